@@ -56,11 +56,13 @@ impl SyncManager {
                             client_count
                         );
                         // Load initial state from server
-                        if let Ok(save_file) = SaveFile::from_reader(Cursor::new(state_bytes)) {
-                            if let Ok(loaded_state) = State::load(save_file) {
+                        match SaveFile::from_reader(Cursor::new(state_bytes)).and_then(State::load)
+                        {
+                            Ok(loaded_state) => {
                                 state_clone.replace(loaded_state);
                                 log::info!("Loaded initial state from server");
                             }
+                            Err(e) => log::error!("Failed to load initial state: {}", e),
                         }
                     }
                     Ok(ServerMessage::Command { client_id, command, rollback, .. }) => {
@@ -76,10 +78,11 @@ impl SyncManager {
                     }
                     Ok(ServerMessage::StateSync { state, version }) => {
                         log::info!("Received state sync (version: {})", version);
-                        if let Ok(save_file) = SaveFile::from_reader(Cursor::new(state)) {
-                            if let Ok(loaded_state) = State::load(save_file) {
+                        match SaveFile::from_reader(Cursor::new(state)).and_then(State::load) {
+                            Ok(loaded_state) => {
                                 state_clone.replace(loaded_state);
                             }
+                            Err(e) => log::error!("Failed to load synced state: {}", e),
                         }
                     }
                     Ok(ServerMessage::ClientJoined { client_id, client_count }) => {
@@ -88,6 +91,9 @@ impl SyncManager {
                     Ok(ServerMessage::ClientLeft { client_id, client_count }) => {
                         log::info!("Client {} left ({} remaining)", client_id, client_count);
                     }
+                    Ok(ServerMessage::Refused { doc_id, reason }) => {
+                        log::error!("Server refused to open document {}: {}", doc_id, reason);
+                    }
                     Err(e) => {
                         log::error!("Failed to parse server message: {:?}", e);
                     }
@@ -134,7 +140,7 @@ impl SyncManager {
         if let CommandType::Select(_) = command {
             return Ok(());
         };
-        let msg = ClientMessage::Command { command: command.clone(), rollback };
+        let msg = ClientMessage::Command { command: Box::new(command.clone()), rollback };
         let json = serde_json::to_string(&msg).map_err(|e| {
             log::error!("{:?}", e);
             JsValue::from_str(&format!("Failed to serialize command: {:?}", e))
@@ -1,5 +1,6 @@
 use pp_core::{
-    clear_cuts::ClearCutsCommand, make_cuts::MakeCutsCommand, select::SelectionActionType,
+    clear_cuts::ClearCutsCommand, delete_faces::DeleteFacesCommand, fill_hole::FillHoleCommand,
+    flip_edge::FlipEdgeCommand, make_cuts::MakeCutsCommand, select::SelectionActionType,
     select_elements::SelectCommand, update_flaps::UpdateFlapsCommand, CommandType,
 };
 use pp_editor::{
@@ -7,7 +8,10 @@ use pp_editor::{
     tool::{select_paint::DEFAULT_RADIUS, SelectBoxTool, SelectPaintTool, Tool},
     Editor,
 };
-use pp_save::save::Saveable;
+use pp_save::{
+    save::Saveable,
+    thumbnail::{Thumbnail, THUMBNAIL_SIZE},
+};
 use wasm_bindgen::{JsCast, JsValue};
 use web_sys::{Blob, Url};
 
@@ -87,11 +91,17 @@ impl EditorEventHandler for Editor {
                 // S: Mark edge as cut or Save (CMD+S)
                 "KeyS" => {
                     if ctx.modifiers.super_pressed() {
-                        // CMD+S: Save state as GLB file download
-                        let _ = ctx
-                            .state
-                            .borrow()
+                        // CMD+S: Save state as GLB file download, with a thumbnail
+                        // of the model as the folding viewport currently sees it
+                        let state = ctx.state.borrow();
+                        let thumbnail = Thumbnail::render(
+                            &state,
+                            self.folding_view_proj(THUMBNAIL_SIZE),
+                            THUMBNAIL_SIZE,
+                        );
+                        let _ = state
                             .save()
+                            .and_then(|save_file| save_file.with_thumbnail(&thumbnail.to_png()?))
                             .and_then(|save_file| save_file.to_binary())
                             .inspect_err(|e| log::error!("Failed to save state: {:?}", e))
                             .inspect(|glb_data| {
@@ -134,6 +144,27 @@ impl EditorEventHandler for Editor {
                 "KeyD" => ctx.history.borrow_mut().add(pp_core::CommandType::UpdateFlaps(
                     UpdateFlapsCommand::swap_flaps(&mut ctx.state.borrow_mut()),
                 )),
+                // F: Flip the selected edges onto their quads' other diagonals
+                "KeyF" => {
+                    let command = FlipEdgeCommand::flip_selected(&mut ctx.state.borrow_mut());
+                    if !command.edges.is_empty() {
+                        ctx.history.borrow_mut().add(pp_core::CommandType::FlipEdge(command));
+                    }
+                }
+                // X / Delete: Delete the selected faces
+                "KeyX" | "Delete" => {
+                    let command = DeleteFacesCommand::delete_selected(&mut ctx.state.borrow_mut());
+                    if !command.meshes.is_empty() {
+                        ctx.history.borrow_mut().add(pp_core::CommandType::DeleteFaces(command));
+                    }
+                }
+                // H: Fill the holes around the selected border edges
+                "KeyH" => {
+                    let command = FillHoleCommand::fill_selected(&mut ctx.state.borrow_mut());
+                    if !command.holes.is_empty() {
+                        ctx.history.borrow_mut().add(pp_core::CommandType::FillHole(command));
+                    }
+                }
                 // .: Frame the selection in every viewport's camera
                 "Period" | "NumpadDecimal" => {
                     // Tools capture camera-derived state when they're created
@@ -4,7 +4,19 @@ use event::{
     ExternalEventHandleSuccess, PressedState, UserEvent,
 };
 use keyboard::ModifierKeys;
-use pp_core::{measures::Dimensions, print::PrintLayoutSettings};
+use pp_core::{
+    measures::Dimensions,
+    mesh::{
+        flap::{FlapSettings, FlapStyle},
+        flap_strategy::FlapStrategy,
+    },
+    print::PrintLayoutSettings,
+    reorder_assembly::ReorderAssemblyCommand,
+    set_flap_settings::SetFlapSettingsCommand,
+    set_flap_style::SetFlapStyleCommand,
+    set_paper_thickness::SetPaperThicknessCommand,
+    update_flaps::UpdateFlapsCommand,
+};
 use pp_editor::SplitId;
 use pp_save::{load::Loadable, SaveFile};
 use serde::{Deserialize, Serialize};
@@ -40,6 +52,16 @@ pub struct MeshBoundsCm {
     pub height: f32,
 }
 
+/// The file formats the unfolded pattern can be exported as.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternFormat {
+    /// Binary glTF, keeping the document's materials and textures
+    Glb,
+    /// Wavefront OBJ, geometry and UVs only
+    Obj,
+}
+
 /// The largest frame delta time-based state will act on, so a tab that stops
 /// rendering (backgrounded, or blocked on a long load) resumes smoothly instead
 /// of jumping by however long it was away.
@@ -114,8 +136,7 @@ impl App {
     /// the `renderer`'s resources are untouched - these will be automatically
     /// synchronized with the new `state`.
     pub fn load_save(&mut self, bytes: &[u8]) -> Result<(), JsError> {
-        let save_file = SaveFile::from_reader(Cursor::new(bytes))
-            .map_err(|_| JsError::new("Failed to load save file."))?;
+        let save_file = SaveFile::from_reader(Cursor::new(bytes))?;
         let state = pp_core::State::load(save_file)?;
         self.state.replace(state);
         self.history.take();
@@ -123,6 +144,27 @@ impl App {
         Ok(())
     }
 
+    /// Reloads the app from a save file like `load_save`, cleaning up any
+    /// plain imports in it as `options` say (see `pp_save::load::ImportOptions`,
+    /// where a missing field keeps its default). Returns what was changed in
+    /// each mesh.
+    pub fn load_save_with_options(
+        &mut self,
+        bytes: &[u8],
+        options: JsValue,
+    ) -> Result<JsValue, JsError> {
+        let options: pp_save::load::ImportOptions = match options.is_undefined() {
+            true => Default::default(),
+            false => serde_wasm_bindgen::from_value(options)?,
+        };
+        let save_file = SaveFile::from_reader(Cursor::new(bytes))?;
+        let (state, report) = pp_core::State::load_with_options(save_file, &options)?;
+        self.state.replace(state);
+        self.history.take();
+        self.editor.reset();
+        Ok(serde_wasm_bindgen::to_value(&report)?)
+    }
+
     /// Attaches the Rust app to a canvas in the DOM. This allocates all the
     /// GPU resources the app might need. Actually drawing frames in a loop
     /// can then be done with `requestAnimationFrame` and the `draw` method.
@@ -195,6 +237,7 @@ impl App {
         let target = pp_draw::print::PrintTarget::new(
             &renderer.ctx,
             &state.printing.page_size,
+            state.printing.bleed,
             &self.editor.preferences.theme,
             dpi,
         )
@@ -205,12 +248,138 @@ impl App {
             target,
             state.printing.page_size.dimensions(),
             pages,
+            print::instruction_pages(&state),
             resolve,
             reject,
         ));
         Ok(())
     }
 
+    /// Downloads the unfolded pattern as flat geometry: every piece and its
+    /// tabs placed as they are on the sheet, with the model's UVs.
+    pub fn export_pattern(&self, format: PatternFormat) -> Result<(), JsError> {
+        let state = self.state.borrow();
+        let pattern = pp_save::pattern::FlatPattern::from_state(&state);
+        let (data, filename, mime) = match format {
+            PatternFormat::Glb => (pattern.to_glb(&state)?, "pattern.glb", "model/gltf-binary"),
+            PatternFormat::Obj => (pattern.to_obj().into_bytes(), "pattern.obj", "model/obj"),
+        };
+        editor::trigger_download(&data, filename, mime)
+            .map_err(|err| JsError::new(&format!("Failed to download pattern: {:?}", err)))
+    }
+
+    /// Downloads the first mesh's cuts, flaps and piece placements as a cut
+    /// set, which can be reapplied to any copy of the same model.
+    pub fn export_cut_set(&self) -> Result<(), JsError> {
+        let state = self.state.borrow();
+        let mesh = state.meshes.values().next().ok_or(AppError::NoMesh)?;
+        let json = pp_save::cut_set::CutSet::from_mesh(mesh).to_json()?;
+        editor::trigger_download(json.as_bytes(), "cuts.json", "application/json")
+            .map_err(|err| JsError::new(&format!("Failed to download cut set: {:?}", err)))
+    }
+
+    /// Reapplies a cut set onto the first mesh, as undoable commands
+    pub fn import_cut_set(&mut self, json: &str) -> Result<(), JsError> {
+        let cut_set = pp_save::cut_set::CutSet::from_json(json)?;
+        let mut state = self.state.borrow_mut();
+        let m_id = state.meshes.keys().next().ok_or(AppError::NoMesh)?;
+        let commands = cut_set.apply(&mut state, m_id)?;
+        let mut history = self.history.borrow_mut();
+        commands.into_iter().for_each(|cmd| history.add(cmd));
+        Ok(())
+    }
+
+    /// Splits every mesh apart wherever it isn't manifold, as one undoable
+    /// command per mesh it changed. Returns what was split in each mesh.
+    pub fn repair_non_manifold(&mut self) -> Result<JsValue, JsValue> {
+        let mut state = self.state.borrow_mut();
+        let mut history = self.history.borrow_mut();
+        let m_ids: Vec<_> = state.meshes.keys().collect();
+        let mut repairs = Vec::new();
+        for m_id in m_ids {
+            let (command, repair) = pp_core::commands::set_geometry::SetGeometryCommand::from_edit(
+                &mut state,
+                m_id,
+                pp_core::mesh::Mesh::split_non_manifold,
+            );
+            if !repair.is_empty() {
+                history.add(pp_core::CommandType::SetGeometry(command));
+                repairs.push(repair);
+            }
+        }
+        Ok(serde_wasm_bindgen::to_value(&repairs)?)
+    }
+
+    /// Winds every mesh's faces consistently and outward, as one undoable
+    /// command per mesh it changed. Returns what was flipped in each mesh.
+    pub fn repair_winding(&mut self) -> Result<JsValue, JsValue> {
+        let mut state = self.state.borrow_mut();
+        let mut history = self.history.borrow_mut();
+        let m_ids: Vec<_> = state.meshes.keys().collect();
+        let mut repairs = Vec::new();
+        for m_id in m_ids {
+            let (command, repair) = pp_core::commands::set_geometry::SetGeometryCommand::from_edit(
+                &mut state,
+                m_id,
+                pp_core::mesh::Mesh::orient_faces,
+            );
+            if repair.flipped > 0 {
+                history.add(pp_core::CommandType::SetGeometry(command));
+                repairs.push(repair);
+            }
+        }
+        Ok(serde_wasm_bindgen::to_value(&repairs)?)
+    }
+
+    /// Adds a decimated copy of every mesh with more than `target_faces`
+    /// triangles, reduced towards that many without moving its surface
+    /// further than `max_error` if given, as one undoable command. The
+    /// originals are left as they were, for reference.
+    pub fn decimate(&mut self, target_faces: usize, max_error: Option<f32>) {
+        let mut state = self.state.borrow_mut();
+        let options = pp_core::mesh::decimate::DecimateOptions { target_faces, max_error };
+        let m_ids: Vec<_> = state.meshes.keys().collect();
+        let command = pp_core::decimate_mesh::DecimateMeshCommand::decimate_meshes(
+            &mut state, &m_ids, &options,
+        );
+        if !command.created.is_empty() {
+            self.history.borrow_mut().add(pp_core::CommandType::DecimateMesh(command));
+        }
+    }
+
+    /// Splits every mesh into one mesh per loose part, as one undoable
+    /// command. Returns how many meshes there are afterwards.
+    pub fn split_loose_parts(&mut self) -> usize {
+        let mut state = self.state.borrow_mut();
+        let m_ids: Vec<_> = state.meshes.keys().collect();
+        let command = pp_core::split_mesh::SplitMeshCommand::split_meshes(&mut state, &m_ids);
+        if !command.meshes.is_empty() {
+            self.history.borrow_mut().add(pp_core::CommandType::SplitMesh(command));
+        }
+        state.meshes.len()
+    }
+
+    /// Joins every mesh with anything selected into one, welding vertices
+    /// within `weld_distance` of each other across the meshes if given, as
+    /// one undoable command.
+    pub fn join_selected_meshes(&mut self, weld_distance: Option<f32>) {
+        let mut state = self.state.borrow_mut();
+        let command =
+            pp_core::join_meshes::JoinMeshesCommand::join_selected(&mut state, weld_distance);
+        if !command.removed.is_empty() {
+            self.history.borrow_mut().add(pp_core::CommandType::JoinMeshes(command));
+        }
+    }
+
+    /// Adds a new mesh built from a primitive, e.g. `{ type: "Cylinder",
+    /// segments: 12 }` or `{ type: "Solid", solid: "Icosahedron" }`, as one
+    /// undoable command
+    pub fn add_primitive(&mut self, primitive: pp_core::mesh::primitives::Primitive) {
+        let mut state = self.state.borrow_mut();
+        let command = pp_core::add_primitive::AddPrimitiveCommand::add(&mut state, primitive);
+        self.history.borrow_mut().add(pp_core::CommandType::AddPrimitive(command));
+    }
+
     /// Returns a snapshot of the editor's state
     pub fn get_editor_snapshot(&self) -> Result<JsValue, JsValue> {
         Ok(serde_wasm_bindgen::to_value(&self.editor)?)
@@ -232,6 +401,7 @@ impl App {
             .unwrap_or(0.0);
         self.last_timestamp = Some(timestamp);
         self.editor.tick_cameras(dt_ms);
+        self.editor.tick_timeline(&mut self.state.borrow_mut(), dt_ms);
 
         let mut renderer = self.renderer.borrow_mut();
         let renderer = renderer.as_mut().ok_or(AppError::NoCanvasAttached)?;
@@ -254,6 +424,10 @@ impl App {
         let renderer = renderer.as_mut().ok_or(AppError::NoCanvasAttached)?;
         let state = state.deref_mut();
         state.fit_pages_to_pieces();
+        // Steps of assembly follow any reordering or recutting as it happens
+        if let Some(step) = self.editor.state.assembly_step {
+            state.fold_to_assembly_step(step);
+        }
         renderer.prepare(state, &mut self.editor);
         renderer.render(state, &self.editor);
         Ok(())
@@ -386,6 +560,148 @@ impl App {
             .expect("set_print_layout command should never fail");
     }
 
+    /// The document's flap settings: how tall and how steep tabs may be, and
+    /// the shortest edge given one. Every mesh shares them, unless none exist
+    /// yet, in which case these are the defaults.
+    pub fn get_flap_settings(&self) -> FlapSettings {
+        let state = self.state.borrow();
+        state.meshes.values().next().map(|mesh| mesh.flap_settings).unwrap_or_default()
+    }
+
+    /// Overwrites the document's flap settings on every mesh, as an undoable
+    /// command. Cuts with settings of their own keep them.
+    pub fn set_flap_settings(&mut self, settings: FlapSettings) {
+        let mut state = self.state.borrow_mut();
+        let command = SetFlapSettingsCommand::set_document(&mut state, settings);
+        self.history.borrow_mut().add(pp_core::CommandType::SetFlapSettings(command));
+    }
+
+    /// How thick the document's paper is, in world units: that of the first
+    /// mesh, or none if there are no meshes yet.
+    pub fn get_paper_thickness(&self) -> f32 {
+        let state = self.state.borrow();
+        state.meshes.values().next().map(|mesh| mesh.paper_thickness).unwrap_or_default()
+    }
+
+    /// Sets the document's paper thickness on every mesh, as an undoable
+    /// command. Unfolding insets faces along mountain folds to make up for it.
+    pub fn set_paper_thickness(&mut self, thickness: f32) {
+        let mut state = self.state.borrow_mut();
+        let command = SetPaperThicknessCommand::set_document(&mut state, thickness);
+        self.history.borrow_mut().add(pp_core::CommandType::SetPaperThickness(command));
+    }
+
+    /// Gives the selected cuts flap settings of their own, or with `undefined`
+    /// puts them back on the document's, as an undoable command.
+    pub fn set_selected_cut_flap_settings(&mut self, settings: Option<FlapSettings>) {
+        let mut state = self.state.borrow_mut();
+        let command = SetFlapSettingsCommand::set_selected_cuts(&mut state, settings);
+        if !command.after.cuts.is_empty() {
+            self.history.borrow_mut().add(pp_core::CommandType::SetFlapSettings(command));
+        }
+    }
+
+    /// Cuts the selected cuts' flaps in `style`, as an undoable command. A
+    /// slot-and-tab flap also slits the face it folds onto.
+    pub fn set_selected_cut_flap_style(&mut self, style: FlapStyle) {
+        let mut state = self.state.borrow_mut();
+        let command = SetFlapStyleCommand::set_selected_cuts(&mut state, style);
+        if !command.before.is_empty() {
+            self.history.borrow_mut().add(pp_core::CommandType::SetFlapStyle(command));
+        }
+    }
+
+    /// Reassigns every flap in the document with `strategy`, as an undoable
+    /// command. Flaps set by hand are overridden too.
+    pub fn reassign_flaps(&mut self, strategy: FlapStrategy) {
+        let mut state = self.state.borrow_mut();
+        let command = UpdateFlapsCommand::reassign(&mut state, strategy);
+        if !command.after.is_empty() {
+            self.history.borrow_mut().add(pp_core::CommandType::UpdateFlaps(command));
+        }
+    }
+
+    /// Steps the folding viewport through assembly, folding up the pieces of
+    /// the first `step` steps and laying the rest flat, or with `undefined`
+    /// folds every piece alike again. A view of the document, not undoable.
+    pub fn set_assembly_step(&mut self, step: Option<usize>) {
+        self.editor.state.assembly_step = step;
+        if step.is_some() {
+            self.editor.state.timeline = None;
+        }
+        if step.is_none() {
+            viewport::folding::fold_all(&mut self.state.borrow_mut(), self.editor.state.t);
+        }
+        self.editor.is_dirty = true;
+    }
+
+    /// How many steps assembling the document takes: one per piece.
+    pub fn get_assembly_step_count(&self) -> usize {
+        self.state.borrow().assembly_step_count()
+    }
+
+    /// Plays the fold timeline, folding each piece up in turn in assembly
+    /// order, from the start if it had already played to the end. Opens the
+    /// timeline if it isn't, ending any step-through.
+    pub fn play_timeline(&mut self) {
+        let steps = self.state.borrow().assembly_step_count();
+        self.editor.state.assembly_step = None;
+        self.editor.state.timeline.get_or_insert_default().play(steps);
+        self.editor.is_dirty = true;
+    }
+
+    /// Pauses the fold timeline where it is.
+    pub fn pause_timeline(&mut self) {
+        if let Some(timeline) = self.editor.state.timeline.as_mut() {
+            timeline.pause();
+            self.editor.is_dirty = true;
+        }
+    }
+
+    /// Scrubs the fold timeline to `position`, in steps of assembly (see
+    /// [`App::get_assembly_step_count`]), opening it paused if it isn't.
+    pub fn seek_timeline(&mut self, position: f32) {
+        let steps = self.state.borrow().assembly_step_count();
+        self.editor.state.assembly_step = None;
+        self.editor.state.timeline.get_or_insert_default().seek(position, steps);
+        self.editor.is_dirty = true;
+    }
+
+    /// Closes the fold timeline, folding every piece alike again.
+    pub fn close_timeline(&mut self) {
+        if self.editor.state.timeline.take().is_some() {
+            viewport::folding::fold_all(&mut self.state.borrow_mut(), self.editor.state.t);
+            self.editor.is_dirty = true;
+        }
+    }
+
+    /// Where the fold timeline's playhead is, in steps of assembly, or
+    /// `undefined` if it isn't open. Polled by the scrubber during playback,
+    /// which doesn't send editor snapshots every frame.
+    pub fn get_timeline_position(&self) -> Option<f32> {
+        self.editor.state.timeline.as_ref().map(|timeline| timeline.position)
+    }
+
+    /// Moves the selected pieces `by` steps later in the assembly order, or
+    /// earlier if negative, as an undoable command.
+    pub fn move_selected_pieces_in_assembly(&mut self, by: i32) {
+        let mut state = self.state.borrow_mut();
+        let command = ReorderAssemblyCommand::move_selected(&mut state, by);
+        if !command.after.is_empty() {
+            self.history.borrow_mut().add(pp_core::CommandType::ReorderAssembly(command));
+        }
+    }
+
+    /// Puts the assembly order back on the suggested one, as an undoable
+    /// command.
+    pub fn reset_assembly_order(&mut self) {
+        let mut state = self.state.borrow_mut();
+        let command = ReorderAssemblyCommand::reset(&mut state);
+        if !command.after.is_empty() {
+            self.history.borrow_mut().add(pp_core::CommandType::ReorderAssembly(command));
+        }
+    }
+
     /// Returns the real-world dimensions of the document's world-space
     /// bounding box, in centimeters (1 world unit = 1 cm). All-zero if there
     /// are no meshes / vertices. Unit formatting (cm vs. m) is left to JS.
@@ -571,6 +887,7 @@ impl App {
 #[derive(Debug, Clone)]
 enum AppError {
     NoCanvasAttached,
+    NoMesh,
 }
 
 impl std::error::Error for AppError {}
@@ -9,13 +9,15 @@
 //!
 //! Each page is the cutting viewport's own render of that sheet - cut lines and
 //! folds included - placed into the PDF as an image. See [`pp_save::pdf`] for why
-//! the lines are rasterized rather than stroked as vector.
+//! the lines are rasterized rather than stroked as vector. The sheets are
+//! followed by the steps to assemble the pieces in, numbered, on pages of text.
 
 use std::collections::VecDeque;
 
 use pp_core::measures::{Dimensions, Rect};
+use pp_core::mesh::assembly::AssemblyStep;
 use pp_draw::print::{PrintPoll, PrintTarget};
-use pp_save::pdf::{self, PdfPage, RasterPage};
+use pp_save::pdf::{self, PageBody, PdfPage, RasterPage};
 use wasm_bindgen::JsValue;
 
 use crate::editor::trigger_download;
@@ -42,6 +44,8 @@ pub(crate) struct PrintJob {
     in_flight: Option<PendingPage>,
     /// The pages finished so far, in reading order.
     done: Vec<PdfPage>,
+    /// The assembly instructions, which follow the sheets.
+    instructions: Vec<PdfPage>,
     resolve: js_sys::Function,
     reject: js_sys::Function,
 }
@@ -61,10 +65,20 @@ impl PrintJob {
         target: PrintTarget,
         page_size: Dimensions<f32>,
         pending: VecDeque<PendingPage>,
+        instructions: Vec<PdfPage>,
         resolve: js_sys::Function,
         reject: js_sys::Function,
     ) -> Self {
-        Self { target, page_size, pending, in_flight: None, done: Vec::new(), resolve, reject }
+        Self {
+            target,
+            page_size,
+            pending,
+            in_flight: None,
+            done: Vec::new(),
+            instructions,
+            resolve,
+            reject,
+        }
     }
 
     /// Advances the run by one step, returning `false` once it is over (either
@@ -87,7 +101,7 @@ impl PrintJob {
                     self.done.push(PdfPage {
                         size: self.page_size,
                         label: page.label,
-                        raster: RasterPage { width, height, rgb },
+                        body: PageBody::Raster(RasterPage { width, height, rgb }),
                     });
                 }
             }
@@ -105,10 +119,12 @@ impl PrintJob {
 
     /// Writes the pages out as one PDF, hands it to the browser, and settles the
     /// promise. Always ends the job.
-    fn finish(&self) -> bool {
-        let write = pdf::write_pdf(&self.done).map_err(|err| JsValue::from_str(&err.to_string()));
+    fn finish(&mut self) -> bool {
+        let mut pages = std::mem::take(&mut self.done);
+        pages.append(&mut self.instructions);
+        let write = pdf::write_pdf(&pages).map_err(|err| JsValue::from_str(&err.to_string()));
         match write.and_then(|bytes| trigger_download(&bytes, DOCUMENT_NAME, "application/pdf")) {
-            Ok(()) => self.settle(&self.resolve, &JsValue::from_f64(self.done.len() as f64)),
+            Ok(()) => self.settle(&self.resolve, &JsValue::from_f64(pages.len() as f64)),
             Err(err) => self.settle(&self.reject, &err),
         }
         false
@@ -144,3 +160,41 @@ pub(crate) fn pages_to_render(state: &pp_core::State) -> VecDeque<PendingPage> {
         })
         .collect()
 }
+
+/// The steps to assemble `state`'s pieces in, numbered, on as many pages of
+/// text as they take. None if there are no pieces to assemble.
+pub(crate) fn instruction_pages(state: &pp_core::State) -> Vec<PdfPage> {
+    let steps = state.assembly_steps();
+    if steps.is_empty() {
+        return Vec::new();
+    }
+    let mut lines = Vec::with_capacity(steps.len());
+    for (i, step) in steps.iter().enumerate() {
+        let first = i == 0 || steps[i - 1].mesh != step.mesh;
+        let last = steps.get(i + 1).is_none_or(|next| next.mesh != step.mesh);
+        // Each model gets a heading, when there is more than one to build
+        if first && state.meshes.len() > 1 {
+            let label = state.meshes[step.mesh].label.as_deref().unwrap_or("Model");
+            lines.extend([String::new(), format!("{label}:")]);
+        }
+        lines.push(format!("{}. {}", i + 1, step_text(step, last && !first)));
+    }
+    let size = state.printing.page_size.dimensions();
+    pdf::text_pages(size, "Assembly", "Assembly instructions", &lines)
+}
+
+/// What to do at `step`, naming pieces and seams by the labels printed on them
+fn step_text(step: &AssemblyStep, closes: bool) -> String {
+    let list = |labels: &[u32]| labels.iter().map(u32::to_string).collect::<Vec<_>>().join(", ");
+    let piece = match step.seams.is_empty() {
+        true => "Fold up the piece with no seams".to_string(),
+        false => format!("Fold up the piece with seams {}", list(&step.seams)),
+    };
+    match (step.joins.is_empty(), closes) {
+        (true, _) => format!("{piece}."),
+        (false, false) => format!("{piece}, and glue it on along {}.", list(&step.joins)),
+        (false, true) => {
+            format!("{piece}, and glue it on along {} to close the model.", list(&step.joins))
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use pp_core::mesh::MeshElementType;
+use pp_core::{
+    commands::reorder_assembly::ReorderAssemblyCommand, mesh::MeshElementType, CommandType, State,
+};
 use pp_editor::viewport::{folding::FoldingViewport, ViewportBounds};
 
 use crate::{event, keyboard};
@@ -22,6 +24,39 @@ impl ViewportEventHandler for FoldingViewport {
                         ..event::EventHandleSuccess::stop_propagation()
                     }));
                 }
+                // [ / ]: Step back / forth through assembly, or with Alt, move
+                // the selected pieces earlier / later in it
+                keyboard::Key::Character(char)
+                    if char == "BracketLeft" || char == "BracketRight" =>
+                {
+                    let later = char == "BracketRight";
+                    let mut state = ctx.state.borrow_mut();
+                    if ctx.modifiers.alt_pressed() {
+                        let by = if later { 1 } else { -1 };
+                        let command = ReorderAssemblyCommand::move_selected(&mut state, by);
+                        if !command.after.is_empty() {
+                            ctx.history.borrow_mut().add(CommandType::ReorderAssembly(command));
+                        }
+                        return Some(Ok(event::EventHandleSuccess::stop_propagation()));
+                    }
+                    let steps = state.assembly_step_count();
+                    // Stepping takes over from a fold timeline
+                    editor_state.timeline = None;
+                    // Stepping back past the start leaves the step-through
+                    editor_state.assembly_step = match (editor_state.assembly_step, later) {
+                        (None, false) | (Some(0), false) => None,
+                        (None, true) => Some(1.min(steps)),
+                        (Some(step), true) => Some((step + 1).min(steps)),
+                        (Some(step), false) => Some(step - 1),
+                    };
+                    if editor_state.assembly_step.is_none() {
+                        fold_all(&mut state, editor_state.t);
+                    }
+                    return Some(Ok(event::EventHandleSuccess {
+                        mark_dirty: true,
+                        ..event::EventHandleSuccess::stop_propagation()
+                    }));
+                }
                 _ => (),
             },
             UserEvent::MouseWheel { delta } => {
@@ -33,14 +68,14 @@ impl ViewportEventHandler for FoldingViewport {
                 } else if ctx.modifiers.ctrl_pressed() {
                     let new_t = (editor_state.t + (delta.y * 0.01)).clamp(0.0, 1.0);
                     editor_state.t = new_t;
-                    state.meshes.iter_mut().for_each(|(_, mesh)| {
-                        mesh.elem_dirty |= MeshElementType::all();
-                        mesh.pieces.iter_mut().for_each(|(_, piece)| {
-                            piece.t = new_t;
-                            piece.elem_dirty = true;
-                        });
-                    });
-                    return Some(Ok(event::EventHandleSuccess::stop_propagation()));
+                    // Folding everything at once ends any step-through or timeline
+                    let stepping = editor_state.assembly_step.take().is_some()
+                        | editor_state.timeline.take().is_some();
+                    fold_all(&mut state, new_t);
+                    return Some(Ok(event::EventHandleSuccess {
+                        mark_dirty: stepping,
+                        ..event::EventHandleSuccess::stop_propagation()
+                    }));
                 } else {
                     self.camera.orbit(delta);
                 }
@@ -50,3 +85,14 @@ impl ViewportEventHandler for FoldingViewport {
         None
     }
 }
+
+/// Unfolds every piece in the document to `t`
+pub(crate) fn fold_all(state: &mut State, t: f32) {
+    state.meshes.iter_mut().for_each(|(_, mesh)| {
+        mesh.elem_dirty |= MeshElementType::all();
+        mesh.pieces.iter_mut().for_each(|(_, piece)| {
+            piece.t = t;
+            piece.elem_dirty = true;
+        });
+    });
+}
@@ -1,10 +1,23 @@
+use std::ops::DerefMut;
+
+use pp_core::{
+    id::{self, Id},
+    measures::Rect,
+    MeshId,
+};
+use pp_draw::select::{SelectionMask, SelectionQueryArea, SelectionQueryResult};
+use pp_editor::state::SelectionMode;
 use pp_editor::viewport::{Viewport, ViewportBounds};
+use slotmap::KeyData;
 
 use crate::{
     event::{self, EventHandleError, EventHandleSuccess},
     keyboard, EventContext, EventHandler, UserEvent,
 };
 
+/// How close the pointer must come to a cut, in CSS pixels, to hover it
+const HOVER_RADIUS: f32 = 6.0;
+
 pub mod cutting;
 pub mod folding;
 
@@ -45,8 +58,62 @@ impl EventHandler for Viewport {
                 "KeyW" => {}
                 _ => (),
             },
+            UserEvent::Pointer(event::PointerEvent::Move(pos)) => {
+                hover_cut(ctx, editor_state, *pos);
+            }
+            UserEvent::Pointer(event::PointerEvent::Exit) => {
+                ctx.state.borrow_mut().hover_edge(None);
+            }
             _ => (),
         }
         None
     }
 }
+
+/// Marks the cut under the pointer as hovered, so that its other side can be
+/// pointed out wherever that is. Only cuts are hovered, and only while edges or
+/// pieces are being selected, where a cut is what's being looked at.
+///
+/// The query covers the whole surface, like paint select's, so that moving the
+/// pointer is served from the one buffer until the view changes.
+fn hover_cut(
+    ctx: &EventContext,
+    editor_state: &pp_editor::state::EditorState,
+    pos: cgmath::Point2<f32>,
+) {
+    if !matches!(editor_state.selection_mode, SelectionMode::Edge | SelectionMode::Piece) {
+        return;
+    }
+    let query = SelectionQueryArea {
+        rect: Rect {
+            x: 0,
+            y: 0,
+            width: (ctx.surface_size.width * ctx.surface_dpi) as u32,
+            height: (ctx.surface_size.height * ctx.surface_dpi) as u32,
+        },
+        mask: SelectionMask::EDGES,
+    };
+    let callback = {
+        let state = ctx.state.clone();
+        let center = pos * ctx.surface_dpi;
+        let radius = HOVER_RADIUS * ctx.surface_dpi;
+        move |_: &SelectionQueryArea, result: &SelectionQueryResult| {
+            let mut state = state.borrow_mut();
+            let hovered = result
+                .pixels_in_circle(center, radius)
+                .map(|pixel| {
+                    let m_id: MeshId = KeyData::from_ffi(pixel.mesh_id).into();
+                    (m_id, id::EdgeId::new(pixel.el_id))
+                })
+                .find(|(m_id, e_id)| {
+                    state.meshes.get(*m_id).is_some_and(|mesh| mesh.edge_is_cut(e_id))
+                });
+            state.hover_edge(hovered);
+        }
+    };
+    let mut renderer = ctx.renderer.borrow_mut();
+    if let Some(renderer) = renderer.deref_mut() {
+        // A query already in flight for something else wins; the next move retries
+        let _ = renderer.select_query(query, editor_state.is_xray, Box::new(callback));
+    }
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    mesh::{primitives::Primitive, Mesh},
+    MeshId,
+};
+
+use super::{Command, CommandError};
+
+/// Adds a new mesh built from a primitive. Undo, redo and peers all build it
+/// from the primitive afresh, under the ID it was first added with.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AddPrimitiveCommand {
+    pub primitive: Primitive,
+    /// The ID the mesh was added under
+    pub mesh: MeshId,
+}
+
+impl AddPrimitiveCommand {
+    /// Builds the primitive into a new mesh at the origin
+    pub fn add(state: &mut crate::State, primitive: Primitive) -> Self {
+        let mesh = state.meshes.insert(Mesh::from_primitive(&primitive));
+        Self { primitive, mesh }
+    }
+}
+
+impl Command for AddPrimitiveCommand {
+    fn execute(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        let mesh = Mesh::from_primitive(&self.primitive);
+        state.attach_mesh(self.mesh, mesh).ok_or(CommandError::Unknown)
+    }
+
+    fn rollback(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        state.detach_mesh(self.mesh).map(|_| ()).ok_or(CommandError::Unknown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+
+    #[test]
+    fn adding_a_primitive_undoes_and_redoes() {
+        let mut state = State::default();
+        let command = AddPrimitiveCommand::add(&mut state, Primitive::Cone { segments: 8 });
+        let m_id = command.mesh;
+        assert_eq!(state.meshes[m_id].faces.num_elements(), 9);
+        assert_eq!(state.meshes[m_id].label.as_deref(), Some("CONE"));
+
+        command.rollback(&mut state).ok().unwrap();
+        assert!(!state.meshes.contains_key(m_id));
+        command.execute(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].faces.num_elements(), 9);
+    }
+
+    /// Peers and the server replay the command on their own copy of the state
+    #[test]
+    fn adding_a_primitive_replays_on_another_state() {
+        let mut state = State::with_cube();
+        let command =
+            AddPrimitiveCommand::add(&mut state, Primitive::IcoSphere { subdivisions: 1 });
+
+        let mut peer = State::with_cube();
+        command.execute(&mut peer).ok().unwrap();
+        assert_eq!(peer.meshes[command.mesh].faces.num_elements(), 80);
+        command.rollback(&mut peer).ok().unwrap();
+        assert_eq!(peer.meshes.len(), 1);
+    }
+}
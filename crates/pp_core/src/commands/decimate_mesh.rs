@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    mesh::{decimate::DecimateOptions, geometry::MeshRecord, Mesh},
+    MeshId,
+};
+
+use super::{Command, CommandError};
+
+/// Adds a decimated copy of meshes alongside them, leaving the originals as
+/// they were for reference.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DecimateMeshCommand {
+    /// The decimated copies, by the IDs they were added under
+    pub created: Vec<(MeshId, MeshRecord)>,
+}
+
+impl DecimateMeshCommand {
+    /// Decimates each of `m_ids` with more triangles than `options` targets,
+    /// skipping any which decimation wouldn't change
+    pub fn decimate_meshes(
+        state: &mut crate::State,
+        m_ids: &[MeshId],
+        options: &DecimateOptions,
+    ) -> Self {
+        let mut created = Vec::new();
+        for m_id in m_ids {
+            let Some(mesh) = state.meshes.get(*m_id) else {
+                continue;
+            };
+            let mut decimated = mesh.decimate(options);
+            if decimated.faces.num_elements() == mesh.faces.num_elements() {
+                continue;
+            }
+            let label = mesh.label.clone().unwrap_or_else(|| "Mesh".to_string());
+            decimated.label = Some(format!("{label} decimated"));
+            let record = decimated.record();
+            created.push((state.meshes.insert(decimated), record));
+        }
+        Self { created }
+    }
+}
+
+impl Command for DecimateMeshCommand {
+    fn execute(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        self.created.iter().try_for_each(|(m_id, record)| {
+            state.attach_mesh(*m_id, Mesh::from_record(record)).ok_or(CommandError::Unknown)
+        })
+    }
+
+    fn rollback(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        self.created.iter().rev().try_for_each(|(m_id, _)| {
+            state.detach_mesh(*m_id).map(|_| ()).ok_or(CommandError::Unknown)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+
+    #[test]
+    fn decimating_adds_a_copy_which_undoes_and_redoes() {
+        let mut state = State::default();
+        let m_id = state.meshes.insert(Mesh::new_icosphere(2));
+        let options = DecimateOptions { target_faces: 80, max_error: None };
+        let command = DecimateMeshCommand::decimate_meshes(&mut state, &[m_id], &options);
+        let (copy_id, _) = command.created[0];
+        assert!(state.meshes[copy_id].faces.num_elements() <= 80);
+        assert_eq!(state.meshes[copy_id].label.as_deref(), Some("ICOSPHERE decimated"));
+        // The original is left as it was
+        assert_eq!(state.meshes[m_id].faces.num_elements(), 320);
+
+        command.rollback(&mut state).ok().unwrap();
+        assert_eq!(state.meshes.len(), 1);
+        command.execute(&mut state).ok().unwrap();
+        assert!(state.meshes[copy_id].faces.num_elements() <= 80);
+
+        // Peers build the very same copy
+        let mut peer = State::default();
+        peer.meshes.insert(Mesh::new_icosphere(2));
+        command.execute(&mut peer).ok().unwrap();
+        assert_eq!(
+            peer.meshes[copy_id].faces.num_elements(),
+            state.meshes[copy_id].faces.num_elements()
+        );
+    }
+}
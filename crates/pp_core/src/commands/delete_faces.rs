@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{mesh::delete::DetachedFaces, MeshId};
+
+use super::{group_by_mesh, set_geometry::forget_selection, Command, CommandError};
+
+/// Deletes faces, along with whatever edges and vertices only they used.
+/// Everything else keeps its ID, so this stores just what was taken out.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeleteFacesCommand {
+    /// What was taken out of each mesh, in the order it was deleted
+    pub meshes: Vec<(MeshId, DetachedFaces)>,
+}
+
+impl DeleteFacesCommand {
+    /// Deletes every selected face
+    pub fn delete_selected(state: &mut crate::State) -> Self {
+        let mut selected: Vec<_> = state.selection.faces.iter().copied().collect();
+        selected.sort();
+        let meshes: Vec<_> = group_by_mesh(&selected)
+            .into_iter()
+            .filter_map(|(m_id, faces)| {
+                let detached = state.meshes.get_mut(m_id)?.delete_faces(&faces);
+                Some((m_id, detached))
+            })
+            .collect();
+        meshes.iter().for_each(|(m_id, _)| forget_selection(state, *m_id));
+        Self { meshes }
+    }
+}
+
+impl Command for DeleteFacesCommand {
+    fn execute(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        for (m_id, detached) in &self.meshes {
+            let mesh = state.meshes.get_mut(*m_id).ok_or(CommandError::Unknown)?;
+            mesh.delete_faces(&detached.face_ids());
+            forget_selection(state, *m_id);
+        }
+        Ok(())
+    }
+
+    fn rollback(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        for (m_id, detached) in self.meshes.iter().rev() {
+            let mesh = state.meshes.get_mut(*m_id).ok_or(CommandError::Unknown)?;
+            mesh.restore_faces(detached).map_err(|_| CommandError::Unknown)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        id::{FaceId, Id},
+        State,
+    };
+
+    #[test]
+    fn deleting_selected_faces_undoes_and_redoes() {
+        let mut state = State::with_cube();
+        let m_id = state.meshes.keys().next().unwrap();
+        let top: Vec<_> = {
+            let mesh = &state.meshes[m_id];
+            mesh.faces.indices().map(FaceId::from_usize).filter(|f| mesh[*f].no[2] > 0.5).collect()
+        };
+        state.selection.faces.extend(top.iter().map(|f| (m_id, *f)));
+
+        let command = DeleteFacesCommand::delete_selected(&mut state);
+        assert_eq!(state.meshes[m_id].faces.num_elements(), 10);
+        assert!(state.selection.faces.is_empty());
+
+        command.rollback(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].faces.num_elements(), 12);
+        assert!(top.iter().all(|f| state.meshes[m_id][*f].no[2] > 0.5));
+
+        command.execute(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].faces.num_elements(), 10);
+        assert_eq!(state.meshes[m_id].edges.num_elements(), 17);
+    }
+}
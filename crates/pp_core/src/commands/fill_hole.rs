@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{mesh::fill::FilledHole, MeshId};
+
+use super::{Command, CommandError};
+
+/// Fills holes in meshes with fans of triangles. The fills are stored as
+/// they were made, so redoing one puts back the same faces under the same IDs.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FillHoleCommand {
+    /// The filled holes, in the order they were filled
+    pub holes: Vec<(MeshId, FilledHole)>,
+}
+
+impl FillHoleCommand {
+    /// Fills the hole next to each selected border edge. Selecting more than
+    /// one edge of the same hole fills it once.
+    pub fn fill_selected(state: &mut crate::State) -> Self {
+        let mut selected: Vec<_> = state.selection.edges.iter().copied().collect();
+        // Which edge of a hole gets there first decides where its fan starts,
+        // so the order has to be the same wherever this is replayed
+        selected.sort();
+        let holes = selected
+            .into_iter()
+            .filter_map(|(m_id, e_id)| {
+                // Once its hole is filled, an edge isn't on the border any more
+                let hole = state.meshes.get_mut(m_id)?.fill_hole(e_id).ok()?;
+                Some((m_id, hole))
+            })
+            .collect();
+        Self { holes }
+    }
+}
+
+impl Command for FillHoleCommand {
+    fn execute(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        for (m_id, hole) in &self.holes {
+            let mesh = state.meshes.get_mut(*m_id).ok_or(CommandError::Unknown)?;
+            mesh.refill_hole(hole).map_err(|_| CommandError::Unknown)?;
+        }
+        Ok(())
+    }
+
+    fn rollback(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        for (m_id, hole) in self.holes.iter().rev() {
+            let mesh = state.meshes.get_mut(*m_id).ok_or(CommandError::Unknown)?;
+            mesh.unfill_hole(hole);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        id::{FaceId, Id, VertexId},
+        State,
+    };
+
+    #[test]
+    fn filling_a_selected_hole_undoes_and_redoes() {
+        let mut state = State::with_cube();
+        let m_id = state.meshes.keys().next().unwrap();
+        let mesh = &mut state.meshes[m_id];
+        let top: Vec<_> =
+            mesh.faces.indices().map(FaceId::from_usize).filter(|f| mesh[*f].no[2] > 0.5).collect();
+        mesh.delete_faces(&top);
+        // Two edges of the same hole
+        for (a, b) in [(4, 5), (5, 6)] {
+            let e = mesh.query_edge(VertexId::from_usize(a), VertexId::from_usize(b)).unwrap();
+            state.selection.edges.insert((m_id, e));
+        }
+
+        let command = FillHoleCommand::fill_selected(&mut state);
+        assert_eq!(command.holes.len(), 1);
+        let fill = command.holes[0].1.faces.face_ids();
+        assert_eq!(state.meshes[m_id].faces.num_elements(), 12);
+
+        command.rollback(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].faces.num_elements(), 10);
+
+        command.execute(&mut state).ok().unwrap();
+        let mesh = &state.meshes[m_id];
+        assert!(fill.iter().all(|f| mesh.faces.has_element_at(f.to_usize())));
+        assert_eq!(mesh.faces.num_elements(), 12);
+    }
+}
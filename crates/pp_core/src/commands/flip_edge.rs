@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{id, mesh::flip::FlippedCorners, MeshId};
+
+use super::{Command, CommandError};
+
+/// Turns edges between two triangles onto their quads' other diagonals. A
+/// flip keeps every ID, so unlike other topology edits this stores just the
+/// edges, and the corners needed to turn each one back.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FlipEdgeCommand {
+    /// The flipped edges, in the order they were flipped
+    pub edges: Vec<((MeshId, id::EdgeId), FlippedCorners)>,
+}
+
+impl FlipEdgeCommand {
+    /// Flips every selected edge which can be flipped. Edges which can't, like
+    /// cuts or the edges between quads, are left as they are.
+    pub fn flip_selected(state: &mut crate::State) -> Self {
+        let mut selected: Vec<_> = state.selection.edges.iter().copied().collect();
+        // Flipping one edge can change whether the next can be, so the order
+        // has to be the same wherever this is replayed
+        selected.sort();
+        let edges = selected
+            .into_iter()
+            .filter_map(|(m_id, e_id)| {
+                let corners = state.meshes.get_mut(m_id)?.flip_edge(e_id).ok()?;
+                Some(((m_id, e_id), corners))
+            })
+            .collect();
+        Self { edges }
+    }
+}
+
+impl Command for FlipEdgeCommand {
+    fn execute(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        for ((m_id, e_id), _) in &self.edges {
+            let mesh = state.meshes.get_mut(*m_id).ok_or(CommandError::Unknown)?;
+            mesh.flip_edge(*e_id).map_err(|_| CommandError::Unknown)?;
+        }
+        Ok(())
+    }
+
+    fn rollback(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        for ((m_id, e_id), corners) in self.edges.iter().rev() {
+            let mesh = state.meshes.get_mut(*m_id).ok_or(CommandError::Unknown)?;
+            mesh.unflip_edge(*e_id, corners).map_err(|_| CommandError::Unknown)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        id::{FaceId, Id, VertexId},
+        State,
+    };
+
+    fn normals(state: &State, m_id: MeshId) -> Vec<[f32; 3]> {
+        let mesh = &state.meshes[m_id];
+        mesh.faces.indices().map(|f| mesh[FaceId::from_usize(f)].no).collect()
+    }
+
+    #[test]
+    fn flipping_a_selected_diagonal_undoes_and_redoes() {
+        let mut state = State::with_cube();
+        let m_id = state.meshes.keys().next().unwrap();
+        let mesh = &state.meshes[m_id];
+        // The bottom's diagonal
+        let diagonal = mesh.query_edge(VertexId::from_usize(0), VertexId::from_usize(2)).unwrap();
+        state.selection.edges.insert((m_id, diagonal));
+        let ends = state.meshes[m_id][diagonal].v;
+        let before = normals(&state, m_id);
+
+        let command = FlipEdgeCommand::flip_selected(&mut state);
+        assert_eq!(command.edges.len(), 1);
+        let flipped = state.meshes[m_id][diagonal].v;
+        assert_ne!(flipped, ends);
+
+        command.rollback(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id][diagonal].v, ends);
+        assert_eq!(normals(&state, m_id), before);
+
+        command.execute(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id][diagonal].v, flipped);
+    }
+
+    #[test]
+    fn edges_which_cant_be_flipped_are_skipped() {
+        let mut state = State::with_cube();
+        let m_id = state.meshes.keys().next().unwrap();
+        let mesh = &mut state.meshes[m_id];
+        let cut = mesh.query_edge(VertexId::from_usize(0), VertexId::from_usize(2)).unwrap();
+        mesh.make_cut(cut, crate::mesh::cut::CutUpdate::Nothing);
+        state.selection.edges.insert((m_id, cut));
+        assert!(FlipEdgeCommand::flip_selected(&mut state).edges.is_empty());
+    }
+}
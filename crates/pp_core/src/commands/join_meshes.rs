@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    mesh::{geometry::MeshRecord, Mesh},
+    MeshId,
+};
+
+use super::{set_geometry::SetGeometryCommand, Command, CommandError};
+
+/// Joins meshes into the first of them, optionally welding them together
+/// where they meet. The others are removed from the document.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JoinMeshesCommand {
+    /// The geometry of the mesh joined into, if anything was joined
+    pub meshes: Vec<SetGeometryCommand>,
+    /// The meshes joined into it, which the join removed, by their IDs
+    pub removed: Vec<(MeshId, MeshRecord)>,
+}
+
+impl JoinMeshesCommand {
+    /// Joins `others` into `into`, in order. See `Mesh::join` for welding.
+    pub fn join(
+        state: &mut crate::State,
+        into: MeshId,
+        others: &[MeshId],
+        weld_distance: Option<f32>,
+    ) -> Self {
+        let others: Vec<_> = others
+            .iter()
+            .copied()
+            .filter(|m_id| *m_id != into && state.meshes.contains_key(*m_id))
+            .collect();
+        if others.is_empty() || !state.meshes.contains_key(into) {
+            return Self { meshes: Vec::new(), removed: Vec::new() };
+        }
+        let removed: Vec<_> =
+            others.iter().map(|m_id| (*m_id, state.meshes[*m_id].record())).collect();
+        // Rebuilt first, as the mesh joined into is borrowed while joining
+        let joining: Vec<_> = removed.iter().map(|(_, record)| Mesh::from_record(record)).collect();
+        let (command, _) = SetGeometryCommand::from_edit(state, into, |mesh| {
+            joining.iter().for_each(|other| mesh.join(other, weld_distance));
+        });
+        others.iter().for_each(|m_id| {
+            state.detach_mesh(*m_id);
+        });
+        Self { meshes: vec![command], removed }
+    }
+
+    /// Joins every mesh with anything selected into the first of them
+    pub fn join_selected(state: &mut crate::State, weld_distance: Option<f32>) -> Self {
+        let selection = &state.selection;
+        let mut m_ids: Vec<_> = (selection.verts.iter().map(|(m_id, _)| *m_id))
+            .chain(selection.edges.iter().map(|(m_id, _)| *m_id))
+            .chain(selection.faces.iter().map(|(m_id, _)| *m_id))
+            .chain(selection.pieces.iter().map(|(m_id, _)| *m_id))
+            .collect();
+        m_ids.sort();
+        m_ids.dedup();
+        match m_ids.split_first() {
+            Some((into, others)) => Self::join(state, *into, others, weld_distance),
+            None => Self { meshes: Vec::new(), removed: Vec::new() },
+        }
+    }
+}
+
+impl Command for JoinMeshesCommand {
+    fn execute(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        self.meshes.iter().try_for_each(|command| command.execute(state))?;
+        self.removed.iter().try_for_each(|(m_id, _)| {
+            state.detach_mesh(*m_id).map(|_| ()).ok_or(CommandError::Unknown)
+        })
+    }
+
+    fn rollback(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        self.removed.iter().rev().try_for_each(|(m_id, record)| {
+            state.attach_mesh(*m_id, Mesh::from_record(record)).ok_or(CommandError::Unknown)
+        })?;
+        self.meshes.iter().rev().try_for_each(|command| command.rollback(state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+
+    #[test]
+    fn joining_two_cubes_undoes_and_redoes() {
+        let mut state = State::with_cube();
+        let first = state.meshes.keys().next().unwrap();
+        let mut cube = Mesh::new_cube();
+        cube.transform = cgmath::Matrix4::from_translation(cgmath::Vector3::new(2.0, 0.0, 0.0));
+        let second = state.meshes.insert(cube);
+
+        let command = JoinMeshesCommand::join(&mut state, first, &[second], Some(1e-4));
+        assert_eq!(command.removed.len(), 1);
+        assert_eq!(state.meshes[first].faces.num_elements(), 24);
+        assert!(!state.meshes.contains_key(second));
+
+        command.rollback(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[first].faces.num_elements(), 12);
+        assert_eq!(state.meshes[second].faces.num_elements(), 12);
+        assert_eq!(state.meshes[second].transform.w.x, 2.0);
+
+        command.execute(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[first].faces.num_elements(), 24);
+        assert!(!state.meshes.contains_key(second));
+
+        // A peer which never saw the join gets the same result
+        let mut peer = State::with_cube();
+        let mut cube = Mesh::new_cube();
+        cube.transform = cgmath::Matrix4::from_translation(cgmath::Vector3::new(2.0, 0.0, 0.0));
+        assert_eq!(peer.meshes.insert(cube), second);
+        command.execute(&mut peer).ok().unwrap();
+        assert_eq!(peer.meshes.len(), 1);
+        assert_eq!(peer.meshes[first].faces.num_elements(), 24);
+    }
+}
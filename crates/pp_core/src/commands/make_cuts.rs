@@ -37,28 +37,46 @@ pub struct MakeCutsCommand {
 
 impl MakeCutsCommand {
     pub fn from_select(state: &mut crate::State) -> Self {
+        let edges = state
+            .selection
+            .edges
+            .iter()
+            .filter(|(m_id, e_id)| {
+                let mesh = &state.meshes[*m_id];
+                mesh.iter_edge_loops(*e_id).is_some_and(|mut walker| {
+                    !walker.all(|l| state.selection.faces.contains(&(*m_id, mesh[l].f)))
+                })
+            })
+            .copied()
+            .collect();
+        Self::from_edges(state, edges, &[])
+    }
+
+    /// Cuts an explicit set of edges, skipping any which are already cut.
+    /// `roots` are faces the resulting pieces should be rooted at instead of
+    /// wherever `make_cut` would put them, e.g. when reapplying a cut set whose
+    /// piece transforms hang off particular faces.
+    pub fn from_edges(
+        state: &mut crate::State,
+        edges: Vec<(MeshId, id::EdgeId)>,
+        roots: &[(MeshId, id::FaceId)],
+    ) -> Self {
         let flaps = snapshot_flaps(state);
         let mut cmd = Self {
             flaps_before: Vec::new(),
             flaps_after: Vec::new(),
             roots_before: Vec::new(),
             roots_after: Vec::new(),
-            edges: state
-                .selection
-                .edges
-                .iter()
+            edges: edges
+                .into_iter()
                 .filter(|(m_id, e_id)| {
-                    let mesh = &state.meshes[*m_id];
-                    mesh.cuts.get(e_id).is_none_or(|cut| cut.is_dead)
-                        && mesh.iter_edge_loops(*e_id).is_some_and(|mut walker| {
-                            !walker.all(|l| state.selection.faces.contains(&(*m_id, mesh[l].f)))
-                        })
+                    state.meshes[*m_id].cuts.get(e_id).is_none_or(|cut| cut.is_dead)
                 })
-                .copied()
                 .collect(),
         };
         cmd.roots_before = snapshot_roots(state, &cmd.edges);
         cmd.cut_forward(state, CutUpdate::PiecesAndFlaps);
+        apply_roots(state, roots);
         cmd.roots_after = snapshot_roots(state, &cmd.edges);
         (cmd.flaps_before, cmd.flaps_after) = diff_flaps(&flaps, state);
         cmd
@@ -1,8 +1,20 @@
+use add_primitive::AddPrimitiveCommand;
+use decimate_mesh::DecimateMeshCommand;
+use delete_faces::DeleteFacesCommand;
+use fill_hole::FillHoleCommand;
+use flip_edge::FlipEdgeCommand;
+use join_meshes::JoinMeshesCommand;
 use make_cuts::MakeCutsCommand;
+use reorder_assembly::ReorderAssemblyCommand;
 use scale_mesh::ScaleMeshCommand;
 use select_elements::SelectCommand;
 use serde::{Deserialize, Serialize};
+use set_flap_settings::SetFlapSettingsCommand;
+use set_flap_style::SetFlapStyleCommand;
+use set_geometry::SetGeometryCommand;
+use set_paper_thickness::SetPaperThicknessCommand;
 use set_print_layout::SetPrintLayoutCommand;
+use split_mesh::SplitMeshCommand;
 use std::collections::HashMap;
 use transform_mesh::TransformMeshCommand;
 use transform_pieces::TransformPiecesCommand;
@@ -10,11 +22,23 @@ use update_flaps::UpdateFlapsCommand;
 
 use crate::{clear_cuts::ClearCutsCommand, id, id::EdgeId, mesh::cut::FlapPosition, MeshId, State};
 
+pub mod add_primitive;
 pub mod clear_cuts;
+pub mod decimate_mesh;
+pub mod delete_faces;
+pub mod fill_hole;
+pub mod flip_edge;
+pub mod join_meshes;
 pub mod make_cuts;
+pub mod reorder_assembly;
 pub mod scale_mesh;
 pub mod select_elements;
+pub mod set_flap_settings;
+pub mod set_flap_style;
+pub mod set_geometry;
+pub mod set_paper_thickness;
 pub mod set_print_layout;
+pub mod split_mesh;
 pub mod transform_mesh;
 pub mod transform_pieces;
 pub mod update_flaps;
@@ -207,6 +231,18 @@ pub enum CommandType {
     MakeCuts(MakeCutsCommand),
     UpdateFlaps(UpdateFlapsCommand),
     SetPrintLayout(SetPrintLayoutCommand),
+    SetGeometry(SetGeometryCommand),
+    FlipEdge(FlipEdgeCommand),
+    DeleteFaces(DeleteFacesCommand),
+    FillHole(FillHoleCommand),
+    SplitMesh(SplitMeshCommand),
+    DecimateMesh(DecimateMeshCommand),
+    JoinMeshes(JoinMeshesCommand),
+    AddPrimitive(AddPrimitiveCommand),
+    SetFlapSettings(SetFlapSettingsCommand),
+    SetFlapStyle(SetFlapStyleCommand),
+    SetPaperThickness(SetPaperThicknessCommand),
+    ReorderAssembly(ReorderAssemblyCommand),
 }
 
 impl Command for CommandType {
@@ -220,6 +256,18 @@ impl Command for CommandType {
             CommandType::MakeCuts(cmd) => cmd.execute(state),
             CommandType::UpdateFlaps(cmd) => cmd.execute(state),
             CommandType::SetPrintLayout(cmd) => cmd.execute(state),
+            CommandType::SetGeometry(cmd) => cmd.execute(state),
+            CommandType::FlipEdge(cmd) => cmd.execute(state),
+            CommandType::DeleteFaces(cmd) => cmd.execute(state),
+            CommandType::FillHole(cmd) => cmd.execute(state),
+            CommandType::SplitMesh(cmd) => cmd.execute(state),
+            CommandType::DecimateMesh(cmd) => cmd.execute(state),
+            CommandType::JoinMeshes(cmd) => cmd.execute(state),
+            CommandType::AddPrimitive(cmd) => cmd.execute(state),
+            CommandType::SetFlapSettings(cmd) => cmd.execute(state),
+            CommandType::SetFlapStyle(cmd) => cmd.execute(state),
+            CommandType::SetPaperThickness(cmd) => cmd.execute(state),
+            CommandType::ReorderAssembly(cmd) => cmd.execute(state),
         }
     }
 
@@ -233,6 +281,18 @@ impl Command for CommandType {
             CommandType::MakeCuts(cmd) => cmd.rollback(state),
             CommandType::UpdateFlaps(cmd) => cmd.rollback(state),
             CommandType::SetPrintLayout(cmd) => cmd.rollback(state),
+            CommandType::SetGeometry(cmd) => cmd.rollback(state),
+            CommandType::FlipEdge(cmd) => cmd.rollback(state),
+            CommandType::DeleteFaces(cmd) => cmd.rollback(state),
+            CommandType::FillHole(cmd) => cmd.rollback(state),
+            CommandType::SplitMesh(cmd) => cmd.rollback(state),
+            CommandType::DecimateMesh(cmd) => cmd.rollback(state),
+            CommandType::JoinMeshes(cmd) => cmd.rollback(state),
+            CommandType::AddPrimitive(cmd) => cmd.rollback(state),
+            CommandType::SetFlapSettings(cmd) => cmd.rollback(state),
+            CommandType::SetFlapStyle(cmd) => cmd.rollback(state),
+            CommandType::SetPaperThickness(cmd) => cmd.rollback(state),
+            CommandType::ReorderAssembly(cmd) => cmd.rollback(state),
         }
     }
 }
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{id::FaceId, MeshId};
+
+use super::{Command, CommandError};
+
+/// Reorders the steps pieces are assembled in. Each mesh's order is
+/// overwritten as a whole, so both sides are kept as snapshots.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReorderAssemblyCommand {
+    /// Each changed mesh's stored order, where empty follows the suggested one
+    pub before: Vec<(MeshId, Vec<FaceId>)>,
+    pub after: Vec<(MeshId, Vec<FaceId>)>,
+}
+
+impl ReorderAssemblyCommand {
+    /// Moves the selected pieces `by` steps later in their meshes' assembly
+    /// order, or earlier if negative. Pieces stop at either end, and keep
+    /// their order among themselves.
+    pub fn move_selected(state: &mut crate::State, by: i32) -> Self {
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+        for (m_id, mesh) in state.meshes.iter() {
+            let original = mesh.assembly_order();
+            let mut order = original.clone();
+            let mut moving: Vec<usize> = (0..order.len())
+                .filter(|i| state.selection.pieces.contains(&(m_id, order[*i])))
+                .collect();
+            // The piece furthest along goes first, so none jumps another
+            if by > 0 {
+                moving.reverse();
+            }
+            let mut bound = if by > 0 { order.len() } else { 0 };
+            for i in moving {
+                let to = (i as i64 + by as i64).clamp(0, order.len() as i64 - 1) as usize;
+                let to = if by > 0 { to.min(bound - 1) } else { to.max(bound) };
+                let root = order.remove(i);
+                order.insert(to, root);
+                bound = if by > 0 { to } else { to + 1 };
+            }
+            if order != original {
+                before.push((m_id, mesh.assembly.clone()));
+                after.push((m_id, order));
+            }
+        }
+        let command = Self { before, after };
+        command.apply(&command.after, state);
+        command
+    }
+
+    /// Puts every mesh back on its suggested assembly order
+    pub fn reset(state: &mut crate::State) -> Self {
+        let before: Vec<_> = state
+            .meshes
+            .iter()
+            .filter(|(_, mesh)| !mesh.assembly.is_empty())
+            .map(|(m_id, mesh)| (m_id, mesh.assembly.clone()))
+            .collect();
+        let after = before.iter().map(|(m_id, _)| (*m_id, Vec::new())).collect();
+        let command = Self { before, after };
+        command.apply(&command.after, state);
+        command
+    }
+
+    fn apply(&self, orders: &[(MeshId, Vec<FaceId>)], state: &mut crate::State) {
+        for (m_id, order) in orders {
+            if let Some(mesh) = state.meshes.get_mut(*m_id) {
+                mesh.assembly = order.clone();
+            }
+        }
+    }
+}
+
+impl Command for ReorderAssemblyCommand {
+    fn execute(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        self.apply(&self.after, state);
+        Ok(())
+    }
+
+    fn rollback(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        self.apply(&self.before, state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mesh::Mesh, State};
+
+    #[test]
+    fn moving_a_piece_undoes_and_redoes() {
+        let mut state = State::default();
+        let m_id = state.meshes.insert(Mesh::cut_quad_cube());
+        let suggested = state.meshes[m_id].assembly_order();
+
+        state.selection.pieces.insert((m_id, suggested[2]));
+        let command = ReorderAssemblyCommand::move_selected(&mut state, -5);
+        let moved = vec![suggested[2], suggested[0], suggested[1]];
+        assert_eq!(state.meshes[m_id].assembly_order(), moved, "and stops at the first step");
+
+        command.rollback(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].assembly_order(), suggested);
+        command.execute(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].assembly_order(), moved);
+
+        let reset = ReorderAssemblyCommand::reset(&mut state);
+        assert_eq!(state.meshes[m_id].assembly_order(), suggested);
+        reset.rollback(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].assembly_order(), moved);
+
+        // Nothing left to move further back makes for an empty command
+        let nowhere = ReorderAssemblyCommand::move_selected(&mut state, -1);
+        assert!(nowhere.after.is_empty());
+    }
+}
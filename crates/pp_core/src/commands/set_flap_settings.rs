@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    id,
+    mesh::{flap::FlapSettings, MeshElementType},
+    MeshId,
+};
+
+use super::{Command, CommandError};
+
+/// Changes the shape limits of flaps, either for the whole document or for
+/// some cuts of their own. Like the print layout, a setting is overwritten
+/// rather than nudged, so both sides are kept as snapshots.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetFlapSettingsCommand {
+    pub before: FlapSettingsSnapshot,
+    pub after: FlapSettingsSnapshot,
+}
+
+/// The flap settings of some meshes and some cuts
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FlapSettingsSnapshot {
+    pub meshes: Vec<(MeshId, FlapSettings)>,
+    /// Cuts' own settings, where `None` is a cut using its mesh's
+    pub cuts: Vec<((MeshId, id::EdgeId), Option<FlapSettings>)>,
+}
+
+impl FlapSettingsSnapshot {
+    fn apply(&self, state: &mut crate::State) {
+        for (m_id, settings) in &self.meshes {
+            if let Some(mesh) = state.meshes.get_mut(*m_id) {
+                mesh.flap_settings = *settings;
+                mesh.elem_dirty |= MeshElementType::FLAPS;
+            }
+        }
+        for ((m_id, e_id), settings) in &self.cuts {
+            if let Some(mesh) = state.meshes.get_mut(*m_id) {
+                mesh.set_cut_flap_settings(*e_id, *settings);
+            }
+        }
+    }
+}
+
+impl SetFlapSettingsCommand {
+    /// Sets every mesh's flap settings, leaving cuts' own settings alone
+    pub fn set_document(state: &mut crate::State, settings: FlapSettings) -> Self {
+        let mut before = FlapSettingsSnapshot::default();
+        let mut after = FlapSettingsSnapshot::default();
+        for (m_id, mesh) in state.meshes.iter() {
+            before.meshes.push((m_id, mesh.flap_settings));
+            after.meshes.push((m_id, settings));
+        }
+        after.apply(state);
+        Self { before, after }
+    }
+
+    /// Gives each selected cut its own flap settings, or with `None` puts it
+    /// back on its mesh's
+    pub fn set_selected_cuts(state: &mut crate::State, settings: Option<FlapSettings>) -> Self {
+        let mut before = FlapSettingsSnapshot::default();
+        let mut after = FlapSettingsSnapshot::default();
+        for (m_id, e_id) in state.selection.edges.iter().copied() {
+            let Some(cut) = state.meshes[m_id].cuts.get(&e_id).filter(|cut| !cut.is_dead) else {
+                continue;
+            };
+            before.cuts.push(((m_id, e_id), cut.flap));
+            after.cuts.push(((m_id, e_id), settings));
+        }
+        after.apply(state);
+        Self { before, after }
+    }
+}
+
+impl Command for SetFlapSettingsCommand {
+    fn execute(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        self.after.apply(state);
+        Ok(())
+    }
+
+    fn rollback(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        self.before.apply(state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        id::{Id, VertexId},
+        mesh::cut::CutUpdate,
+        State,
+    };
+
+    #[test]
+    fn document_and_cut_settings_undo_and_redo() {
+        let mut state = State::with_cube();
+        let m_id = state.meshes.keys().next().unwrap();
+        let mesh = &mut state.meshes[m_id];
+        let e_id = mesh.query_edge(VertexId::from_usize(0), VertexId::from_usize(1)).unwrap();
+        mesh.make_cut(e_id, CutUpdate::PiecesAndFlaps);
+        let thick = FlapSettings { height: 1.0, ..Default::default() };
+        let tiny = FlapSettings { height: 0.1, ..Default::default() };
+
+        let document = SetFlapSettingsCommand::set_document(&mut state, thick);
+        assert_eq!(state.meshes[m_id].cut_flap_settings(e_id), thick);
+
+        state.selection.edges.insert((m_id, e_id));
+        let cut = SetFlapSettingsCommand::set_selected_cuts(&mut state, Some(tiny));
+        assert_eq!(state.meshes[m_id].cut_flap_settings(e_id), tiny);
+        // The mesh's other edges still follow the document
+        assert_eq!(state.meshes[m_id].flap_settings, thick);
+
+        cut.rollback(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].cut_flap_settings(e_id), thick);
+        document.rollback(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].cut_flap_settings(e_id), FlapSettings::default());
+        cut.execute(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].cut_flap_settings(e_id), tiny);
+    }
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{id, mesh::flap::FlapStyle, MeshId};
+
+use super::{Command, CommandError};
+
+/// Changes the style the flaps of some cuts are cut in. Every cut keeps its
+/// own style from before, since a selection can mix them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetFlapStyleCommand {
+    pub before: Vec<((MeshId, id::EdgeId), FlapStyle)>,
+    pub after: FlapStyle,
+}
+
+impl SetFlapStyleCommand {
+    /// Cuts each selected cut's flaps in `style`
+    pub fn set_selected_cuts(state: &mut crate::State, style: FlapStyle) -> Self {
+        let before: Vec<_> = state
+            .selection
+            .edges
+            .iter()
+            .copied()
+            .filter_map(|(m_id, e_id)| {
+                let cut = state.meshes[m_id].cuts.get(&e_id).filter(|cut| !cut.is_dead)?;
+                Some(((m_id, e_id), cut.style))
+            })
+            .collect();
+        let cmd = Self { before, after: style };
+        cmd.apply(state, |_| style);
+        cmd
+    }
+
+    fn apply(&self, state: &mut crate::State, style: impl Fn(FlapStyle) -> FlapStyle) {
+        for ((m_id, e_id), before) in &self.before {
+            if let Some(mesh) = state.meshes.get_mut(*m_id) {
+                mesh.set_cut_flap_style(*e_id, style(*before));
+            }
+        }
+    }
+}
+
+impl Command for SetFlapStyleCommand {
+    fn execute(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        self.apply(state, |_| self.after);
+        Ok(())
+    }
+
+    fn rollback(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        self.apply(state, |before| before);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        id::{Id, VertexId},
+        mesh::cut::CutUpdate,
+        State,
+    };
+
+    #[test]
+    fn styles_undo_to_each_cuts_own() {
+        let mut state = State::with_cube();
+        let m_id = state.meshes.keys().next().unwrap();
+        let mesh = &mut state.meshes[m_id];
+        let v = VertexId::from_usize;
+        let a = mesh.query_edge(v(0), v(1)).unwrap();
+        let b = mesh.query_edge(v(1), v(2)).unwrap();
+        mesh.make_cut(a, CutUpdate::PiecesAndFlaps);
+        mesh.make_cut(b, CutUpdate::PiecesAndFlaps);
+        mesh.set_cut_flap_style(b, FlapStyle::Rounded);
+
+        state.selection.edges.insert((m_id, a));
+        state.selection.edges.insert((m_id, b));
+        let cmd = SetFlapStyleCommand::set_selected_cuts(&mut state, FlapStyle::SlotAndTab);
+        assert_eq!(state.meshes[m_id].cut_flap_style(a), FlapStyle::SlotAndTab);
+        assert_eq!(state.meshes[m_id].cut_flap_style(b), FlapStyle::SlotAndTab);
+
+        cmd.rollback(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].cut_flap_style(a), FlapStyle::Trapezoid);
+        assert_eq!(state.meshes[m_id].cut_flap_style(b), FlapStyle::Rounded);
+        cmd.execute(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].cut_flap_style(b), FlapStyle::SlotAndTab);
+    }
+}
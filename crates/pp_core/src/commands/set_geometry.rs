@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    mesh::{geometry::MeshGeometry, Mesh},
+    MeshId,
+};
+
+use super::{Command, CommandError};
+
+/// Any edit which changes a mesh's topology: splitting it, repairing it, and
+/// so on. Reversing those by hand is its own algorithm each time, so instead
+/// we store the mesh's whole geometry from before and after.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetGeometryCommand {
+    pub mesh: MeshId,
+    pub before: Box<MeshGeometry>,
+    pub after: Box<MeshGeometry>,
+}
+
+impl SetGeometryCommand {
+    /// Runs `edit` on a mesh, recording the geometry either side of it. Also
+    /// returns whatever `edit` does, e.g. a report of what it changed.
+    pub fn from_edit<T>(
+        state: &mut crate::State,
+        m_id: MeshId,
+        edit: impl FnOnce(&mut Mesh) -> T,
+    ) -> (Self, T) {
+        let mesh = &mut state.meshes[m_id];
+        let before = Box::new(mesh.geometry());
+        let result = edit(mesh);
+        let after = Box::new(mesh.geometry());
+        forget_selection(state, m_id);
+        (Self { mesh: m_id, before, after }, result)
+    }
+}
+
+/// Element IDs don't survive a geometry change, so anything selected on the
+/// mesh could now point at the wrong element, or none
+pub(crate) fn forget_selection(state: &mut crate::State, m_id: MeshId) {
+    let selection = &mut state.selection;
+    selection.verts.retain(|(id, _)| *id != m_id);
+    selection.edges.retain(|(id, _)| *id != m_id);
+    selection.faces.retain(|(id, _)| *id != m_id);
+    selection.pieces.retain(|(id, _)| *id != m_id);
+    selection.is_dirty = true;
+}
+
+impl Command for SetGeometryCommand {
+    fn execute(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        let mesh = state.meshes.get_mut(self.mesh).ok_or(CommandError::Unknown)?;
+        mesh.set_geometry(&self.after);
+        forget_selection(state, self.mesh);
+        Ok(())
+    }
+
+    fn rollback(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        let mesh = state.meshes.get_mut(self.mesh).ok_or(CommandError::Unknown)?;
+        mesh.set_geometry(&self.before);
+        forget_selection(state, self.mesh);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        commands::delete_faces::DeleteFacesCommand,
+        id::{FaceId, Id, VertexId},
+        mesh::face::FaceDescriptor,
+        State,
+    };
+
+    #[test]
+    fn splitting_a_bowtie_undoes_and_redoes() {
+        let mut mesh = Mesh::new("BOWTIE".to_string());
+        let v: Vec<_> = [[0.0, 0.0, 0.0], [1.0, 1.0, 0.0], [1.0, -1.0, 0.0]]
+            .into_iter()
+            .chain([[-1.0, 1.0, 0.0], [-1.0, -1.0, 0.0]])
+            .map(|p| mesh.add_vertex(p))
+            .collect();
+        mesh.add_face(&[v[0], v[1], v[2]], &FaceDescriptor::default());
+        mesh.add_face(&[v[0], v[3], v[4]], &FaceDescriptor::default());
+        let mut state = State::default();
+        let m_id = state.meshes.insert(mesh);
+
+        let (command, repair) =
+            SetGeometryCommand::from_edit(&mut state, m_id, Mesh::split_non_manifold);
+        assert_eq!(repair.verts.len(), 1);
+        assert_eq!(state.meshes[m_id].verts.num_elements(), 6);
+
+        command.rollback(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].verts.num_elements(), 5);
+        assert_eq!(state.meshes[m_id].iter_all_vert_edges(VertexId::from_usize(0)).count(), 4);
+
+        command.execute(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].verts.num_elements(), 6);
+        assert_eq!(state.meshes[m_id].iter_all_vert_edges(VertexId::from_usize(0)).count(), 2);
+    }
+
+    #[test]
+    fn turning_an_inside_out_cube_undoes_and_redoes() {
+        let mut cube = Mesh::new_cube();
+        let outward = cube[FaceId::from_usize(0)].no;
+        let mut geometry = cube.geometry();
+        geometry.faces.iter_mut().for_each(|face| face.corners[1..].reverse());
+        cube.set_geometry(&geometry);
+        let mut state = State::default();
+        let m_id = state.meshes.insert(cube);
+
+        let (command, repair) = SetGeometryCommand::from_edit(&mut state, m_id, Mesh::orient_faces);
+        assert_eq!(repair.flipped, 12);
+        assert_eq!(state.meshes[m_id][FaceId::from_usize(0)].no, outward);
+        command.rollback(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id][FaceId::from_usize(0)].no, outward.map(|c| -c));
+        command.execute(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id][FaceId::from_usize(0)].no, outward);
+    }
+
+    /// A repair between a deletion and its undo mustn't move the slots the
+    /// deleted faces go back into
+    #[test]
+    fn undoing_a_repair_leaves_an_earlier_deletion_undoable() {
+        let mut cube = Mesh::new_cube();
+        let mut geometry = cube.geometry();
+        geometry.faces.iter_mut().for_each(|face| face.corners[1..].reverse());
+        cube.set_geometry(&geometry);
+        let (faces, edges, loops) =
+            (cube.faces.num_elements(), cube.edges.num_elements(), cube.loops.num_elements());
+        let mut state = State::default();
+        let m_id = state.meshes.insert(cube);
+
+        state.selection.faces.insert((m_id, FaceId::from_usize(0)));
+        let delete = DeleteFacesCommand::delete_selected(&mut state);
+        let (repair, report) = SetGeometryCommand::from_edit(&mut state, m_id, Mesh::orient_faces);
+        assert!(report.flipped > 0);
+
+        repair.rollback(&mut state).ok().unwrap();
+        delete.rollback(&mut state).ok().unwrap();
+        let mesh = &state.meshes[m_id];
+        assert_eq!(mesh.faces.num_elements(), faces);
+        assert_eq!(mesh.edges.num_elements(), edges);
+        assert_eq!(mesh.loops.num_elements(), loops);
+        assert!(mesh
+            .faces
+            .indices()
+            .all(|f| mesh.iter_face_loops(FaceId::from_usize(f)).count() == 3));
+
+        delete.execute(&mut state).ok().unwrap();
+        repair.execute(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].faces.num_elements(), faces - 1);
+        assert!(!state.meshes[m_id].faces.has_element_at(0));
+    }
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{mesh::MeshElementType, MeshId};
+
+use super::{Command, CommandError};
+
+/// Changes how thick the paper of the whole document is. Like the flap
+/// settings, each mesh's thickness is overwritten, so both sides are kept.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetPaperThicknessCommand {
+    pub before: Vec<(MeshId, f32)>,
+    pub after: Vec<(MeshId, f32)>,
+}
+
+impl SetPaperThicknessCommand {
+    /// Sets every mesh's paper thickness
+    pub fn set_document(state: &mut crate::State, thickness: f32) -> Self {
+        let before: Vec<_> =
+            state.meshes.iter().map(|(m_id, mesh)| (m_id, mesh.paper_thickness)).collect();
+        let after = before.iter().map(|(m_id, _)| (*m_id, thickness.max(0.0))).collect();
+        let cmd = Self { before, after };
+        Self::apply(&cmd.after, state);
+        cmd
+    }
+
+    fn apply(thicknesses: &[(MeshId, f32)], state: &mut crate::State) {
+        for (m_id, thickness) in thicknesses {
+            if let Some(mesh) = state.meshes.get_mut(*m_id) {
+                mesh.paper_thickness = *thickness;
+                // Every unfolded position moves, and every flap shortens
+                mesh.elem_dirty |= MeshElementType::VERTS | MeshElementType::FLAPS;
+            }
+        }
+    }
+}
+
+impl Command for SetPaperThicknessCommand {
+    fn execute(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        Self::apply(&self.after, state);
+        Ok(())
+    }
+
+    fn rollback(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        Self::apply(&self.before, state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+
+    #[test]
+    fn thickness_undoes_and_redoes() {
+        let mut state = State::with_cube();
+        let m_id = state.meshes.keys().next().unwrap();
+
+        let cmd = SetPaperThicknessCommand::set_document(&mut state, 0.03);
+        assert_eq!(state.meshes[m_id].paper_thickness, 0.03);
+        cmd.rollback(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].paper_thickness, 0.0);
+        cmd.execute(&mut state).ok().unwrap();
+        assert_eq!(state.meshes[m_id].paper_thickness, 0.03);
+    }
+}
@@ -52,7 +52,13 @@ mod tests {
         assert_eq!(original.page_size, PageSize::A4);
 
         let cmd = command(
-            PrintLayoutSettings { page_size: PageSize::Letter, margin_x: 1.0, margin_y: 2.0 },
+            PrintLayoutSettings {
+                page_size: PageSize::Letter,
+                margin_x: 1.0,
+                margin_y: 2.0,
+                textured_flaps: true,
+                bleed: 0.2,
+            },
             &state,
         );
         cmd.execute(&mut state).unwrap();
@@ -60,11 +66,15 @@ mod tests {
         assert_eq!(state.printing.page_margin_start.x, 1.0);
         // Margins are symmetric: the end corner tracks the start corner
         assert_eq!(state.printing.page_margin_end.y, 2.0);
+        assert!(state.printing.textured_flaps);
+        assert_eq!(state.printing.bleed, 0.2);
 
         cmd.rollback(&mut state).unwrap();
         assert_eq!(state.printing.settings(), original);
         assert_eq!(state.printing.page_margin_start.x, 0.5 * CM_PER_INCH);
         assert_eq!(state.printing.page_margin_end.x, 0.5 * CM_PER_INCH);
+        assert!(!state.printing.textured_flaps);
+        assert_eq!(state.printing.bleed, 0.0);
     }
 
     /// A smaller page needs more sheets to cover the same pieces, so the grid
@@ -84,7 +94,12 @@ mod tests {
         // Halving the page in both directions should take four sheets to cover
         let half = Dimensions { width: width / 2.0, height: height / 2.0 };
         let cmd = command(
-            PrintLayoutSettings { page_size: PageSize::Custom(half), margin_x: 0.0, margin_y: 0.0 },
+            PrintLayoutSettings {
+                page_size: PageSize::Custom(half),
+                margin_x: 0.0,
+                margin_y: 0.0,
+                ..Default::default()
+            },
             &state,
         );
         cmd.execute(&mut state).unwrap();
@@ -102,7 +117,12 @@ mod tests {
     fn both_directions_mark_the_layout_dirty() {
         let mut state = State::default();
         let cmd = command(
-            PrintLayoutSettings { page_size: PageSize::Letter, margin_x: 0.0, margin_y: 0.0 },
+            PrintLayoutSettings {
+                page_size: PageSize::Letter,
+                margin_x: 0.0,
+                margin_y: 0.0,
+                ..Default::default()
+            },
             &state,
         );
 
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    mesh::{geometry::MeshRecord, Mesh},
+    MeshId,
+};
+
+use super::{set_geometry::SetGeometryCommand, Command, CommandError};
+
+/// Splits meshes into one mesh per loose part. The first part stays in the
+/// mesh it came from, and every other part gets a new mesh with the same
+/// transform and settings, so nothing moves.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SplitMeshCommand {
+    /// The geometry of every mesh split, which each keep their first part
+    pub meshes: Vec<SetGeometryCommand>,
+    /// The meshes made for every other part, by the IDs they were added under
+    pub created: Vec<(MeshId, MeshRecord)>,
+}
+
+impl SplitMeshCommand {
+    /// Splits each of `m_ids` which has more than one loose part
+    pub fn split_meshes(state: &mut crate::State, m_ids: &[MeshId]) -> Self {
+        let mut meshes = Vec::new();
+        let mut created = Vec::new();
+        for m_id in m_ids {
+            let Some(mesh) = state.meshes.get(*m_id) else {
+                continue;
+            };
+            let mut parts = mesh.split_parts();
+            if parts.len() < 2 {
+                continue;
+            }
+            let label = mesh.label.clone().unwrap_or_else(|| "Mesh".to_string());
+            let records: Vec<_> = parts
+                .drain(1..)
+                .enumerate()
+                .map(|(i, geometry)| MeshRecord {
+                    label: Some(format!("{label} part {}", i + 1)),
+                    transform: mesh.transform,
+                    scale: mesh.scale,
+                    flap_settings: mesh.flap_settings,
+                    paper_thickness: mesh.paper_thickness,
+                    geometry,
+                })
+                .collect();
+            let (command, _) =
+                SetGeometryCommand::from_edit(state, *m_id, |mesh| mesh.set_geometry(&parts[0]));
+            meshes.push(command);
+            for record in records {
+                created.push((state.meshes.insert(Mesh::from_record(&record)), record));
+            }
+        }
+        Self { meshes, created }
+    }
+}
+
+impl Command for SplitMeshCommand {
+    fn execute(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        self.meshes.iter().try_for_each(|command| command.execute(state))?;
+        self.created.iter().try_for_each(|(m_id, record)| {
+            state.attach_mesh(*m_id, Mesh::from_record(record)).ok_or(CommandError::Unknown)
+        })
+    }
+
+    fn rollback(&self, state: &mut crate::State) -> Result<(), CommandError> {
+        self.created.iter().rev().try_for_each(|(m_id, _)| {
+            state.detach_mesh(*m_id).map(|_| ()).ok_or(CommandError::Unknown)
+        })?;
+        self.meshes.iter().rev().try_for_each(|command| command.rollback(state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        commands::delete_faces::DeleteFacesCommand,
+        id::{FaceId, Id},
+        State,
+    };
+
+    /// Two cubes side by side, joined into one mesh
+    fn two_cubes() -> (State, MeshId) {
+        let mut state = State::default();
+        let mut mesh = Mesh::new_cube();
+        let mut other = Mesh::new_cube();
+        other.transform = cgmath::Matrix4::from_translation(cgmath::Vector3::new(2.0, 0.0, 0.0));
+        mesh.join(&other, None);
+        let m_id = state.meshes.insert(mesh);
+        (state, m_id)
+    }
+
+    #[test]
+    fn splitting_two_cubes_undoes_and_redoes() {
+        let (mut state, m_id) = two_cubes();
+        let command = SplitMeshCommand::split_meshes(&mut state, &[m_id]);
+        assert_eq!(state.meshes.len(), 2);
+        assert!(state.meshes.values().all(|mesh| mesh.faces.num_elements() == 12));
+        let (part_id, _) = command.created[0];
+        assert_eq!(state.meshes[part_id].label.as_deref(), Some("CUBE part 1"));
+
+        command.rollback(&mut state).ok().unwrap();
+        assert_eq!(state.meshes.len(), 1);
+        assert_eq!(state.meshes[m_id].faces.num_elements(), 24);
+
+        // Redone, the part lands back under the same ID
+        command.execute(&mut state).ok().unwrap();
+        assert_eq!(state.meshes.len(), 2);
+        assert_eq!(state.meshes[part_id].faces.num_elements(), 12);
+    }
+
+    /// Peers and the server replay the command on their own copy of the state
+    #[test]
+    fn splitting_replays_on_another_state() {
+        let (mut state, m_id) = two_cubes();
+        let command = SplitMeshCommand::split_meshes(&mut state, &[m_id]);
+
+        let (mut peer, _) = two_cubes();
+        command.execute(&mut peer).ok().unwrap();
+        let (part_id, _) = command.created[0];
+        assert_eq!(peer.meshes[part_id].faces.num_elements(), 12);
+        command.rollback(&mut peer).ok().unwrap();
+        assert_eq!(peer.meshes.len(), 1);
+        command.execute(&mut peer).ok().unwrap();
+        assert_eq!(peer.meshes.len(), 2);
+    }
+
+    /// Undoing a split mustn't move the slots an earlier deletion fills back in
+    #[test]
+    fn undoing_a_split_leaves_an_earlier_deletion_undoable() {
+        let (mut state, m_id) = two_cubes();
+        state.selection.faces.insert((m_id, FaceId::from_usize(0)));
+        let delete = DeleteFacesCommand::delete_selected(&mut state);
+        let split = SplitMeshCommand::split_meshes(&mut state, &[m_id]);
+        assert_eq!(state.meshes.len(), 2);
+
+        split.rollback(&mut state).ok().unwrap();
+        delete.rollback(&mut state).ok().unwrap();
+        let mesh = &state.meshes[m_id];
+        assert_eq!(mesh.faces.num_elements(), 24);
+        assert_eq!(mesh.loops.num_elements(), 72);
+        assert_eq!(mesh.edges.num_elements(), 36);
+
+        delete.execute(&mut state).ok().unwrap();
+        split.execute(&mut state).ok().unwrap();
+        let faces: usize = state.meshes.values().map(|mesh| mesh.faces.num_elements()).sum();
+        assert_eq!(faces, 23);
+    }
+
+    /// Each part keeps the roots of the stored order which are its own
+    #[test]
+    fn each_part_keeps_its_own_assembly_order() {
+        let mut mesh = Mesh::cut_cube();
+        let mut other = Mesh::cut_cube();
+        other.transform = cgmath::Matrix4::from_translation(cgmath::Vector3::new(2.0, 0.0, 0.0));
+        mesh.join(&other, None);
+        mesh.assembly = mesh.suggested_assembly_order().into_iter().rev().collect();
+        let mut state = State::default();
+        let m_id = state.meshes.insert(mesh);
+
+        let command = SplitMeshCommand::split_meshes(&mut state, &[m_id]);
+        for mesh in state.meshes.values() {
+            assert_eq!(mesh.assembly.len(), mesh.iter_pieces().count());
+            assert!(mesh.assembly.iter().all(|root| mesh[*root].p == Some(*root)));
+        }
+        command.rollback(&mut state).ok().unwrap();
+        let mesh = &state.meshes[m_id];
+        assert_eq!(
+            mesh.assembly,
+            mesh.suggested_assembly_order().into_iter().rev().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn a_mesh_in_one_part_is_left_alone() {
+        let mut state = State::with_cube();
+        let m_ids: Vec<_> = state.meshes.keys().collect();
+        assert!(SplitMeshCommand::split_meshes(&mut state, &m_ids).meshes.is_empty());
+        assert_eq!(state.meshes.len(), 1);
+    }
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     id,
-    mesh::{cut::FlapPosition, MeshElementType},
+    mesh::{cut::FlapPosition, flap_strategy::FlapStrategy, MeshElementType},
     MeshId,
 };
 
@@ -43,6 +43,15 @@ impl UpdateFlapsCommand {
         });
         Self { before, after }
     }
+
+    /// Reassigns every flap in the document with `strategy`, keeping only the
+    /// flaps which moved
+    pub fn reassign(state: &mut crate::State, strategy: FlapStrategy) -> Self {
+        let snapshot = super::snapshot_flaps(state);
+        state.meshes.values_mut().for_each(|mesh| mesh.reassign_flaps(strategy));
+        let (before, after) = super::diff_flaps(&snapshot, state);
+        Self { before, after }
+    }
 }
 
 impl Command for UpdateFlapsCommand {
@@ -64,3 +73,22 @@ impl Command for UpdateFlapsCommand {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mesh::Mesh, State};
+
+    #[test]
+    fn reassigning_every_flap_undoes() {
+        let mut state = State::default();
+        state.meshes.insert(Mesh::cut_quad_cube());
+        let before = super::super::snapshot_flaps(&state);
+
+        let cmd = UpdateFlapsCommand::reassign(&mut state, FlapStrategy::Alternate);
+        assert!(!cmd.after.is_empty());
+        assert_eq!(cmd.before.len(), cmd.after.len());
+        cmd.rollback(&mut state).ok().unwrap();
+        assert!(super::super::diff_flaps(&before, &state).1.is_empty());
+    }
+}
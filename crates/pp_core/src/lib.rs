@@ -1,4 +1,5 @@
 use slotmap::{new_key_type, SlotMap};
+use std::collections::BTreeSet;
 
 pub mod bounds;
 pub mod commands;
@@ -50,6 +51,10 @@ pub struct State {
     /// Default entity IDs to use where providing a link is optional (materials, textures, etc.)
     pub defaults: StateDefaults,
 
+    /// Meshes taken out of the document by a command, whose IDs are held for
+    /// undo / redo to put them back under. See [`State::detach_mesh`].
+    detached_meshes: BTreeSet<MeshId>,
+
     /// User-specific selection state, to be moved out of this struct
     pub selection: select::SelectionState,
 }
@@ -92,6 +97,7 @@ impl Default for State {
             image_boxes: Default::default(),
             selection: Default::default(),
             defaults: StateDefaults { material, texture, sampler, image },
+            detached_meshes: Default::default(),
         }
     }
 }
@@ -103,6 +109,35 @@ impl State {
         state
     }
 
+    /// Takes a mesh out of the document, holding on to its ID so that the
+    /// command which removed it can put it back under the same one.
+    pub fn detach_mesh(&mut self, m_id: MeshId) -> Option<mesh::Mesh> {
+        let mesh = self.meshes.detach(m_id)?;
+        self.detached_meshes.insert(m_id);
+        commands::set_geometry::forget_selection(self, m_id);
+        Some(mesh)
+    }
+
+    /// Puts a mesh into the document under `m_id`: back where it was if it was
+    /// detached, or else as a new mesh, so long as `m_id` is the ID the
+    /// document hands out next. That holds for a peer replaying the command
+    /// which first added the mesh, having run every command before it too.
+    pub fn attach_mesh(&mut self, m_id: MeshId, mesh: mesh::Mesh) -> Option<()> {
+        if self.detached_meshes.remove(&m_id) {
+            self.meshes.reattach(m_id, mesh);
+            return Some(());
+        }
+        if self.meshes.contains_key(m_id) {
+            return None;
+        }
+        let inserted = self.meshes.insert(mesh);
+        if inserted != m_id {
+            self.meshes.remove(inserted);
+            return None;
+        }
+        Some(())
+    }
+
     /// The world-space bounding box of every mesh in the document, or
     /// `Aabb3::EMPTY` if there are no meshes / vertices.
     pub fn world_bounds(&self) -> bounds::Aabb3 {
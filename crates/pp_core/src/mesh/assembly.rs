@@ -0,0 +1,382 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use cgmath::InnerSpace;
+
+use crate::{
+    id::{EdgeId, FaceId, Id},
+    MeshId, State,
+};
+
+use super::{face::polygon_area, MeshElementType};
+
+/// The length of cut each piece shares with each other piece, by root
+type Seams = BTreeMap<FaceId, BTreeMap<FaceId, f32>>;
+
+/// One step of assembling the document: a piece, and the seams gluing it on
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssemblyStep {
+    pub mesh: MeshId,
+    /// The root of the piece this step adds
+    pub root: FaceId,
+    /// The labels of the cuts around the piece which part it from other
+    /// pieces, in order
+    pub seams: Vec<u32>,
+    /// Those of `seams` facing pieces assembled in earlier steps, which this
+    /// step glues shut
+    pub joins: Vec<u32>,
+}
+
+impl super::Mesh {
+    /// A suggested order to assemble the mesh's pieces in, by root.
+    ///
+    /// Starts from the largest piece, which gives the most to hold on to, and
+    /// grows across the seams, always adding the piece already most enclosed by
+    /// those assembled: the one with the largest share of its seams facing
+    /// them, so that each step closes up what it can rather than leaving gaps
+    /// to fill from the inside later. The last piece is held back to close the
+    /// model: one that leaves the others joined without it, as far from the
+    /// first as any is, and with the shortest seam among those, since that seam
+    /// has to be glued shut from the outside.
+    ///
+    /// Ties go to the lower root, so every client suggests the same order.
+    pub fn suggested_assembly_order(&self) -> Vec<FaceId> {
+        let seams: Seams =
+            self.iter_pieces().map(|root| (*root, self.piece_seams(*root))).collect();
+        let mut left: Vec<(FaceId, f32)> =
+            self.iter_pieces().map(|root| (*root, self.piece_area(*root))).collect();
+        let mut order = Vec::with_capacity(left.len());
+        while let Some(start) = left
+            .iter()
+            .copied()
+            .reduce(|best, next| if next.1 > best.1 { next } else { best })
+            .map(|(root, _)| root)
+        {
+            // Pieces not joined to the rest at all start over from their largest
+            let depths = piece_depths(&seams, start, None);
+            left.retain(|(root, _)| !depths.contains_key(root));
+            let closing = closing_piece(&seams, start, &depths);
+
+            let begin = order.len();
+            order.push(start);
+            let mut rest: Vec<FaceId> = depths
+                .keys()
+                .copied()
+                .filter(|root| *root != start && Some(*root) != closing)
+                .collect();
+            while !rest.is_empty() {
+                let placed = &order[begin..];
+                let enclosure = |root: FaceId| {
+                    let seams = &seams[&root];
+                    let shared: f32 = seams
+                        .iter()
+                        .filter(|(other, _)| placed.contains(other))
+                        .map(|(_, length)| *length)
+                        .sum();
+                    let total: f32 = seams.values().sum();
+                    (shared / total.max(f32::EPSILON), shared)
+                };
+                let (i, _) = rest
+                    .iter()
+                    .map(|root| enclosure(*root))
+                    .enumerate()
+                    .reduce(|best, next| if next.1 > best.1 { next } else { best })
+                    .unwrap();
+                order.push(rest.remove(i));
+            }
+            order.extend(closing);
+        }
+        order
+    }
+
+    /// The order to assemble the mesh's pieces in, by root: the user's own, if
+    /// they have put them in one, else the suggested one.
+    ///
+    /// Cutting and joining pieces comes and goes under a stored order, so only
+    /// the pieces it names which are still whole keep their place. Any others
+    /// follow, in the order they are suggested in.
+    pub fn assembly_order(&self) -> Vec<FaceId> {
+        let is_piece = |root: &FaceId| {
+            self.faces.has_element_at(root.to_usize()) && self[*root].p == Some(*root)
+        };
+        let mut order: Vec<FaceId> = self.assembly.iter().copied().filter(is_piece).collect();
+        if order.len() == self.iter_pieces().count() {
+            return order;
+        }
+        let placed: BTreeSet<FaceId> = order.iter().copied().collect();
+        order.extend(
+            self.suggested_assembly_order().into_iter().filter(|root| !placed.contains(root)),
+        );
+        order
+    }
+
+    /// The total area of the piece rooted at `root`'s faces
+    pub(crate) fn piece_area(&self, root: FaceId) -> f32 {
+        self.iter_connected_faces(root).map(|f_id| self.face_area(f_id)).sum()
+    }
+
+    /// The area of a face
+    pub(crate) fn face_area(&self, f_id: FaceId) -> f32 {
+        let positions: Vec<[f32; 3]> =
+            self.iter_face_loops(f_id).map(|l_id| self.vert_pos(self[l_id].v).into()).collect();
+        polygon_area(&positions)
+    }
+
+    /// The cuts on `root`'s boundary which face another piece, each with the
+    /// root of the piece across it
+    fn piece_seam_edges(&self, root: FaceId) -> impl Iterator<Item = (EdgeId, FaceId)> + '_ {
+        self.iter_connected_faces(root)
+            .flat_map(|f_id| self.iter_face_loops(f_id))
+            .filter(|l_id| self[*l_id].radial_next != *l_id && self.edge_is_cut(&self[*l_id].e))
+            .filter_map(move |l_id| {
+                let other = self[self[self[l_id].radial_next].f].p?;
+                (other != root).then_some((self[l_id].e, other))
+            })
+    }
+
+    /// The length of cut `root`'s piece shares with each other piece, by root
+    fn piece_seams(&self, root: FaceId) -> BTreeMap<FaceId, f32> {
+        let mut seams = BTreeMap::new();
+        for (e_id, other) in self.piece_seam_edges(root) {
+            let [v0, v1] = self[e_id].v;
+            *seams.entry(other).or_default() += (self.vert_pos(v1) - self.vert_pos(v0)).magnitude();
+        }
+        seams
+    }
+}
+
+/// How many seams away from `from` each piece joined to it is, leaving out
+/// `without`, if given
+fn piece_depths(seams: &Seams, from: FaceId, without: Option<FaceId>) -> BTreeMap<FaceId, usize> {
+    let mut depths = BTreeMap::from([(from, 0)]);
+    let mut queue = VecDeque::from([from]);
+    while let Some(root) = queue.pop_front() {
+        let depth = depths[&root];
+        for next in seams[&root].keys() {
+            if Some(*next) != without && !depths.contains_key(next) {
+                depths.insert(*next, depth + 1);
+                queue.push_back(*next);
+            }
+        }
+    }
+    depths
+}
+
+/// The piece to close the model with, of those `depths` reaches from `start`.
+/// See [`super::Mesh::suggested_assembly_order`].
+fn closing_piece(seams: &Seams, start: FaceId, depths: &BTreeMap<FaceId, usize>) -> Option<FaceId> {
+    depths
+        .iter()
+        .filter(|(root, _)| {
+            **root != start && piece_depths(seams, start, Some(**root)).len() == depths.len() - 1
+        })
+        .map(|(root, depth)| (*root, *depth, seams[root].values().sum::<f32>()))
+        .reduce(
+            |best, next| {
+                if next.1 > best.1 || (next.1 == best.1 && next.2 < best.2) {
+                    next
+                } else {
+                    best
+                }
+            },
+        )
+        .map(|(root, ..)| root)
+}
+
+impl State {
+    /// Every step of assembling the document: each mesh's pieces in their
+    /// [`super::Mesh::assembly_order`], one mesh after another.
+    pub fn assembly_steps(&self) -> Vec<AssemblyStep> {
+        let mut steps = Vec::new();
+        for (m_id, mesh) in self.meshes.iter() {
+            let mut placed = BTreeSet::new();
+            for root in mesh.assembly_order() {
+                let mut seams = BTreeSet::new();
+                let mut joins = BTreeSet::new();
+                for (e_id, other) in mesh.piece_seam_edges(root) {
+                    let Some(label) = mesh.cut_label(e_id) else {
+                        continue;
+                    };
+                    seams.insert(label);
+                    if placed.contains(&other) {
+                        joins.insert(label);
+                    }
+                }
+                placed.insert(root);
+                steps.push(AssemblyStep {
+                    mesh: m_id,
+                    root,
+                    seams: seams.into_iter().collect(),
+                    joins: joins.into_iter().collect(),
+                });
+            }
+        }
+        steps
+    }
+
+    /// How many steps assembling the document takes: one per piece.
+    pub fn assembly_step_count(&self) -> usize {
+        self.meshes.values().map(|mesh| mesh.iter_pieces().count()).sum()
+    }
+
+    /// The piece each step of assembly adds, by mesh and root: the same order
+    /// as [`Self::assembly_steps`], without working out any seams.
+    pub fn assembly_roots(&self) -> Vec<(MeshId, FaceId)> {
+        self.meshes
+            .iter()
+            .flat_map(|(m_id, mesh)| {
+                mesh.assembly_order().into_iter().map(move |root| (m_id, root))
+            })
+            .collect()
+    }
+
+    /// A hash of each mesh's pieces, their folds and its stored assembly order.
+    /// Cheap enough to take every frame, so a caller holding onto
+    /// [`Self::assembly_roots`] can tell when they, or the folds it set, have
+    /// changed under it.
+    pub fn assembly_fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for (m_id, mesh) in self.meshes.iter() {
+            m_id.hash(&mut hasher);
+            mesh.assembly.hash(&mut hasher);
+            for (root, piece) in mesh.pieces.iter() {
+                root.hash(&mut hasher);
+                piece.t.to_bits().hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Folds up the pieces of the first `step` steps of assembly and lays the
+    /// rest flat, as the folding viewport steps through them.
+    pub fn fold_to_assembly_step(&mut self, step: usize) {
+        let roots = self.assembly_roots();
+        self.fold_assembly_steps(&roots, |i| if i < step { 0.0 } else { 1.0 });
+    }
+
+    /// Unfolds the piece of each step of assembly in `roots`, as from
+    /// [`Self::assembly_roots`], to `t_of_step(step)`. Only pieces whose fold
+    /// changes are marked.
+    pub fn fold_assembly_steps(
+        &mut self,
+        roots: &[(MeshId, FaceId)],
+        t_of_step: impl Fn(usize) -> f32,
+    ) {
+        for (step, (m_id, root)) in roots.iter().enumerate() {
+            let t = t_of_step(step);
+            let Some(mesh) = self.meshes.get_mut(*m_id) else {
+                continue;
+            };
+            let Some(piece) = mesh.pieces.get_mut(root) else {
+                continue;
+            };
+            if piece.t != t {
+                piece.t = t;
+                piece.elem_dirty = true;
+                mesh.elem_dirty |= MeshElementType::VERTS;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::InnerSpace;
+
+    use crate::id::{EdgeId, FaceId, Id, VertexId};
+    use crate::mesh::{cut::CutUpdate, Mesh};
+    use crate::State;
+
+    /// Cutting a quad cube's top and bottom off leaves three pieces: the ring of
+    /// four sides is largest, so assembly starts there and both lids follow.
+    #[test]
+    fn assembly_starts_from_the_largest_piece() {
+        let mesh = Mesh::cut_quad_cube();
+        let order = mesh.suggested_assembly_order();
+        assert_eq!(order.len(), 3);
+        assert_eq!(mesh.iter_connected_faces(order[0]).count(), 4, "the ring comes first");
+        assert_eq!(order, mesh.suggested_assembly_order(), "and the order is stable");
+    }
+
+    /// A cube cut into its six faces goes together like a box: every face
+    /// joins those before it, and the lid opposite the first closes it.
+    #[test]
+    fn a_box_closes_with_the_face_opposite_the_first() {
+        let mut mesh = Mesh::new_quad_cube();
+        let edges: Vec<EdgeId> = mesh.edges.indices().map(EdgeId::from_usize).collect();
+        edges.iter().for_each(|e_id| mesh.make_cut(*e_id, CutUpdate::PiecesAndFlaps));
+        let order = mesh.suggested_assembly_order();
+        assert_eq!(order.len(), 6);
+
+        let normal = |root: FaceId| cgmath::Vector3::from(mesh[root].no);
+        let (first, last) = (order[0], order[5]);
+        assert!((normal(first).dot(normal(last)) + 1.0).abs() < 1e-5, "the last is the lid");
+        for (i, root) in order.iter().enumerate().skip(1) {
+            let joined = mesh.piece_seams(*root).keys().any(|other| order[..i].contains(other));
+            assert!(joined, "step {i} glues onto what came before");
+        }
+        // The second side in goes next to the first, closing a corner
+        let shared = |a, b| mesh.piece_seams(a).contains_key(&b);
+        assert!(order[2..4].iter().all(|side| shared(*side, order[1]) || shared(*side, first)));
+    }
+
+    /// A stored order keeps the pieces it names in place, and the rest follow
+    #[test]
+    fn a_stored_order_outlives_recutting() {
+        let mut mesh = Mesh::cut_quad_cube();
+        let suggested = mesh.suggested_assembly_order();
+        mesh.assembly = vec![suggested[2], suggested[0]];
+        assert_eq!(mesh.assembly_order(), vec![suggested[2], suggested[0], suggested[1]]);
+
+        // Gluing the bottom back onto the ring leaves one piece for the two
+        let v = VertexId::from_usize;
+        let e_id = mesh.query_edge(v(0), v(1)).unwrap();
+        mesh.clear_cut(&e_id, CutUpdate::PiecesAndFlaps);
+        let order = mesh.assembly_order();
+        assert_eq!(order.len(), 2);
+        assert!(order.iter().all(|root| mesh[*root].p == Some(*root)));
+    }
+
+    /// Deleting a root leaves nothing under its ID for the stored order to name
+    #[test]
+    fn a_stored_order_outlives_deleting_its_roots() {
+        let mut mesh = Mesh::cut_quad_cube();
+        let suggested = mesh.suggested_assembly_order();
+        mesh.assembly = suggested.clone();
+
+        let detached = mesh.delete_faces(&[suggested[1]]);
+        assert!(!mesh.assembly.contains(&suggested[1]));
+        let order = mesh.assembly_order();
+        assert_eq!(order.len(), mesh.iter_pieces().count());
+        assert!(order.iter().all(|root| mesh[*root].p == Some(*root)));
+
+        mesh.restore_faces(&detached).unwrap();
+        assert_eq!(mesh.assembly, suggested);
+        assert_eq!(mesh.assembly_order(), suggested);
+
+        // An order naming faces which were never there is passed over
+        mesh.assembly = vec![FaceId::from_usize(mesh.faces.next_push_index())];
+        assert_eq!(mesh.assembly_order(), suggested);
+    }
+
+    #[test]
+    fn steps_name_the_seams_they_glue() {
+        let mut state = State::default();
+        let m_id = state.meshes.insert(Mesh::cut_quad_cube());
+        let steps = state.assembly_steps();
+        assert_eq!(steps.len(), 3);
+        assert!(steps[0].joins.is_empty(), "nothing is glued on the first step");
+        // Each lid glues its four edges onto the ring
+        for step in &steps[1..] {
+            assert_eq!(step.joins.len(), 4);
+            assert_eq!(step.joins, step.seams);
+        }
+
+        state.fold_to_assembly_step(1);
+        let mesh = &state.meshes[m_id];
+        let t: Vec<f32> = steps.iter().map(|step| mesh.pieces[&step.root].t).collect();
+        assert_eq!(t, vec![0.0, 1.0, 1.0]);
+    }
+}
@@ -0,0 +1,118 @@
+//! Artwork printed on past the cut edges of a piece.
+//!
+//! A knife never quite follows a printed line. Carrying each face's texture a
+//! little way out past the piece's silhouette means a cut that wanders outward
+//! lands on artwork rather than on white paper. Each outline edge gets a strip
+//! standing out from it, textured by extrapolating its own face's mapping.
+//!
+//! The strips are only the geometry: how far they reach is a print setting,
+//! and keeping them off other pieces is left to drawing them beneath all other
+//! paper on the sheet, so that any face or tab, whoever's, covers them.
+
+use cgmath::{EuclideanSpace, InnerSpace, Point3, Transform, Vector3};
+
+use crate::{
+    id::{FaceId, LoopId},
+    MaterialId,
+};
+
+/// The bleed beyond one edge of a piece's outline, in piece space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BleedStrip {
+    /// The material of the face the edge belongs to
+    pub m: Option<MaterialId>,
+    pub v0: Point3<f32>,
+    pub v1: Point3<f32>,
+    /// The unit direction away from the face, in its plane
+    pub out: Vector3<f32>,
+    /// The face's texture coordinates at `v0` and `v1`
+    pub uv: [[f32; 2]; 2],
+    /// How the texture coordinates move per unit along `out`
+    pub uv_out: [f32; 2],
+}
+
+impl BleedStrip {
+    /// The texture coordinates `s` of the way along the edge and `h` out from
+    /// it. Neither is bounded, so a strip may reach past either end of its edge
+    /// to close the corner with the next.
+    pub fn uv(&self, [s, h]: [f32; 2]) -> [f32; 2] {
+        let [[u0, v0], [u1, v1]] = self.uv;
+        [u0 + (u1 - u0) * s + self.uv_out[0] * h, v0 + (v1 - v0) * s + self.uv_out[1] * h]
+    }
+}
+
+impl super::Mesh {
+    /// The bleed strips of the piece rooted at `root`: one for every edge of
+    /// its faces on a cut or on the mesh boundary, whichever piece (if any)
+    /// is across it.
+    pub fn piece_bleed(&self, root: FaceId) -> Vec<BleedStrip> {
+        let mut strips = Vec::new();
+        for face in self.iter_piece_faces_unfolded(root) {
+            let unfolded = |l_id: LoopId| {
+                face.affine.transform_point(Point3::from_vec(self.vert_pos(self[l_id].v)))
+            };
+            let corners: Vec<Point3<f32>> = self.iter_face_loops(face.f).map(unfolded).collect();
+            let centroid = Point3::centroid(&corners);
+            let Some(first) = self.iter_face_loops(face.f).next() else {
+                continue;
+            };
+            let Some(uv_at) = self.placed_uv_map(first, face.affine) else {
+                continue;
+            };
+            for l_id in self.iter_face_loops(face.f) {
+                let l = self[l_id];
+                if l.radial_next != l_id && !self.edge_is_cut(&l.e) {
+                    continue;
+                }
+                let (v0, v1) = (unfolded(l_id), unfolded(l.next));
+                let along = v1 - v0;
+                if along.magnitude2() < f32::EPSILON {
+                    continue;
+                }
+                let toward = centroid - v0;
+                let inward = toward - along * (toward.dot(along) / along.magnitude2());
+                if inward.magnitude2() < f32::EPSILON {
+                    continue;
+                }
+                let out = -inward.normalize();
+                let uv = [uv_at(v0), uv_at(v1)];
+                strips.push(BleedStrip {
+                    m: self[face.f].m,
+                    v0,
+                    v1,
+                    out,
+                    uv: uv.map(Into::into),
+                    uv_out: (uv_at(v0 + out) - uv[0]).into(),
+                });
+            }
+        }
+        strips
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{EuclideanSpace, InnerSpace, Vector3};
+
+    use crate::mesh::Mesh;
+
+    /// A quad cube cut into its top, its bottom and the ring of sides between
+    /// has nine cuts, and so eighteen outline edges: one strip for each
+    #[test]
+    fn every_outline_edge_bleeds_outward() {
+        let mesh = Mesh::cut_quad_cube();
+        let mut count = 0;
+        for root in mesh.iter_pieces() {
+            let strips = mesh.piece_bleed(*root);
+            count += strips.len();
+            let middle = strips.iter().map(|strip| strip.v0.to_vec()).sum::<Vector3<f32>>()
+                / strips.len() as f32;
+            for strip in &strips {
+                assert!((strip.out.magnitude() - 1.0).abs() < 1e-5);
+                assert!(strip.out.dot(strip.v1 - strip.v0).abs() < 1e-5);
+                assert!(strip.out.dot(strip.v0.to_vec() - middle) > 0.0, "and faces out");
+            }
+        }
+        assert_eq!(count, 18);
+    }
+}
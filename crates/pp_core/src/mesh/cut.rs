@@ -1,7 +1,13 @@
 use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Point3, SquareMatrix, Transform, Vector3, Zero};
 use serde::{Deserialize, Serialize};
 
-use crate::{id, mesh::MeshElementType};
+use crate::{
+    id,
+    mesh::{
+        flap::{FlapSettings, FlapStyle},
+        MeshElementType,
+    },
+};
 
 /// How far a piece slides out from the seam when a cut splits it off its
 /// parent, in world units (1 unit = 1cm). Far enough to read as two pieces,
@@ -15,6 +21,24 @@ pub struct Cut {
     pub flap_position: FlapPosition,
     /// If `true`, then this `Cut` is inactive, but kept around for undo / redo
     pub is_dead: bool,
+    /// This cut's own flap shape, in place of the mesh's `flap_settings`
+    #[serde(default)]
+    pub flap: Option<FlapSettings>,
+    /// How this cut's flaps are cut
+    #[serde(default)]
+    pub style: FlapStyle,
+    /// The number marking both sides of this cut on the pattern, given when the
+    /// cut is first made. See [`super::label`].
+    #[serde(default)]
+    pub label: Option<u32>,
+}
+
+/// One side of a cut: the loop running along it, and the piece that loop's face
+/// is in, if any
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CutSide {
+    pub l: id::LoopId,
+    pub piece: Option<id::FaceId>,
 }
 
 #[repr(u8)]
@@ -89,8 +113,9 @@ impl CutUpdate {
 
 impl super::Mesh {
     // Extract the adjacent faces to the edge. Technically it's possible for
-    // the mesh to have more than 2 faces per edge, but we can preprocess
-    // that invariant out, so I don't want to try to support that use case.
+    // the mesh to have more than 2 faces per edge, but `split_non_manifold`
+    // preprocesses that invariant out on import, so I don't want to try to
+    // support that use case.
     // Similarly, if the edge had <2 faces, it's either a boundary or
     // dangling, in which case "cutting" doesn't make much sense either.
     // Faces are in radial order, so A then B in the radial link
@@ -110,7 +135,11 @@ impl super::Mesh {
 
     /// Adds / restores a cut on an edge.
     pub fn make_cut(&mut self, e_id: id::EdgeId, update: CutUpdate) {
-        self.cuts.entry(e_id).or_default().is_dead = false;
+        let label = self.next_cut_label();
+        let cut = self.cuts.entry(e_id).or_default();
+        cut.is_dead = false;
+        let label = *cut.label.get_or_insert(label);
+        self.last_cut_label = self.last_cut_label.max(label);
         self.elem_dirty |= MeshElementType::EDGES;
         if !update.touches_pieces() {
             return;
@@ -326,6 +355,24 @@ impl super::Mesh {
         self.cuts.get(id).is_some_and(|cut| !cut.is_dead)
     }
 
+    /// The two sides of a live cut, in radial order, if it has a face on each
+    pub fn cut_sides(&self, e_id: id::EdgeId) -> Option<[CutSide; 2]> {
+        if !self.edge_is_cut(&e_id) {
+            return None;
+        }
+        let l_id = self.iter_edge_loops(e_id)?.next()?;
+        let across = self[l_id].radial_next;
+        let side = |l: id::LoopId| CutSide { l, piece: self[self[l].f].p };
+        (across != l_id).then(|| [side(l_id), side(across)])
+    }
+
+    /// The other side of the cut `l_id` runs along: the loop it is glued back
+    /// to, and which piece that is on. This is what a tab marked on one piece
+    /// has to be matched against, wherever that piece has been laid out.
+    pub fn cut_mate(&self, l_id: id::LoopId) -> Option<CutSide> {
+        self.cut_sides(self[l_id].e)?.into_iter().find(|side| side.l != l_id)
+    }
+
     /// Tells whether the flap for this loop's edge extends over this loop's face.
     /// The two radial loops of an edge start at opposite endpoints, so `l.v` is
     /// what picks out which side of the cut we're on.
@@ -362,11 +409,13 @@ impl super::Mesh {
 
 #[cfg(test)]
 mod tests {
-    use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Point3, SquareMatrix, Transform, Vector3};
+    use cgmath::{
+        EuclideanSpace, InnerSpace, Matrix4, MetricSpace, Point3, SquareMatrix, Transform, Vector3,
+    };
 
     use crate::commands::{make_cuts::MakeCutsCommand, Command};
     use crate::id::{EdgeId, FaceId, Id, VertexId};
-    use crate::mesh::cut::CUT_PIECE_SEPARATION;
+    use crate::mesh::{cut::CUT_PIECE_SEPARATION, Mesh};
     use crate::select::SelectionActionType;
 
     /// The cube's vertices, in the order `new_cube` adds them: the z=0 quad
@@ -375,12 +424,10 @@ mod tests {
         VertexId::from_usize(i)
     }
 
-    /// Frees the bottom quad and the front quad as one four-triangle strip,
-    /// hinged on the edge `v0-v1` they share.
-    const STRIP: [(usize, usize); 6] = [(1, 2), (2, 3), (3, 0), (0, 4), (4, 5), (5, 1)];
-    /// The hinge itself. Cutting it splits the strip into two pieces, and the
-    /// piece which keeps the strip's root is the one on `v0-v1`'s second face,
-    /// so undoing the cut merges back into the piece the cut created.
+    /// The hinge of [`Mesh::CUBE_STRIP`]. Cutting it splits the strip into two
+    /// pieces, and the piece which keeps the strip's root is the one on
+    /// `v0-v1`'s second face, so undoing the cut merges back into the piece
+    /// the cut created.
     const HINGE: [(usize, usize); 1] = [(0, 1)];
 
     fn cube() -> (crate::State, crate::MeshId) {
@@ -422,7 +469,7 @@ mod tests {
     /// command which split them.
     fn split() -> (crate::State, crate::MeshId, FaceId, FaceId, MakeCutsCommand) {
         let (mut state, m_id) = cube();
-        cut(&mut state, m_id, &STRIP);
+        cut(&mut state, m_id, &Mesh::CUBE_STRIP);
         let existing = *state.meshes[m_id].iter_pieces().next().expect("the strip is a piece");
 
         let cmd = cut(&mut state, m_id, &HINGE);
@@ -501,6 +548,32 @@ mod tests {
         );
     }
 
+    /// Each side of the hinge finds the other across it, on the other piece,
+    /// and both run the length of the same seam in their own piece's space
+    #[test]
+    fn the_sides_of_a_cut_are_each_others_mates() {
+        let (state, m_id, existing, created, _) = split();
+        let mesh = &state.meshes[m_id];
+        let hinge = edges(&state, m_id, &HINGE)[0];
+        let [a, b] = mesh.cut_sides(hinge).expect("the hinge is cut");
+        assert_eq!(mesh.cut_mate(a.l), Some(b));
+        assert_eq!(mesh.cut_mate(b.l), Some(a));
+        let mut pieces = vec![a.piece.unwrap(), b.piece.unwrap()];
+        pieces.sort();
+        let mut expected = vec![existing, created];
+        expected.sort();
+        assert_eq!(pieces, expected, "the mate is on the other piece");
+
+        let length = |l_id| {
+            let [from, to] = mesh.loop_piece_span(l_id).expect("the loop is in a piece");
+            from.distance(to)
+        };
+        assert!((length(a.l) - length(b.l)).abs() < 1e-5);
+
+        let uncut = edges(&state, m_id, &[(2, 6)])[0];
+        assert_eq!(mesh.cut_sides(uncut), None);
+    }
+
     /// Where the piece rooted at `root` draws vertex `v_id` of face `f_id`:
     /// the piece transform on top of that face's unfolding.
     fn placed(
@@ -0,0 +1,736 @@
+use cgmath::{InnerSpace, Vector3};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use super::{
+    cut::Cut,
+    flap::FlapStyle,
+    geometry::{GeometryCorner, GeometryFace, MeshGeometry},
+    Mesh,
+};
+use crate::{
+    id::{self, Id},
+    MaterialId,
+};
+
+/// How far to decimate a mesh. Decimation stops at whichever limit it
+/// reaches first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecimateOptions {
+    /// How many triangles to stop at
+    pub target_faces: usize,
+    /// The furthest, in model units, any collapse may move a vertex from the
+    /// plane of a face merged into it, or from the line of a seam it slides
+    /// along
+    pub max_error: Option<f32>,
+}
+
+/// A quadric error metric: the sum of squared distances to a set of planes,
+/// as a symmetric 4x4 matrix stored by its upper triangle
+#[derive(Debug, Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    fn plane(normal: Vector3<f64>, point: Vector3<f64>, weight: f64) -> Self {
+        let [a, b, c] = [normal.x, normal.y, normal.z];
+        let d = -normal.dot(point);
+        let q = [a * a, a * b, a * c, a * d, b * b, b * c, b * d, c * c, c * d, d * d];
+        Self(q.map(|x| x * weight))
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.0.iter_mut().zip(other.0).for_each(|(a, b)| *a += b);
+    }
+
+    /// The summed squared distance of `p` from the planes
+    fn error(&self, p: Vector3<f64>) -> f64 {
+        let [aa, ab, ac, ad, bb, bc, bd, cc, cd, dd] = self.0;
+        let (x, y, z) = (p.x, p.y, p.z);
+        aa * x * x
+            + 2.0 * ab * x * y
+            + 2.0 * ac * x * z
+            + 2.0 * ad * x
+            + bb * y * y
+            + 2.0 * bc * y * z
+            + 2.0 * bd * y
+            + cc * z * z
+            + 2.0 * cd * z
+            + dd
+    }
+}
+
+/// Constraint planes along seams are weighted this much more than the
+/// surface's own, so sliding a seam off its line costs far more than
+/// flattening the surface either side of it
+const SEAM_WEIGHT: f64 = 100.0;
+
+/// Collapses which would turn a face further than this, as the cosine of
+/// the angle between its normals, are refused
+const MIN_NORMAL_DOT: f64 = 0.2;
+
+#[derive(Clone)]
+struct Triangle {
+    v: [usize; 3],
+    uv: [[f32; 2]; 3],
+    no: [[f32; 3]; 3],
+    m: Option<MaterialId>,
+    /// The face of the original mesh this triangle was cut from
+    origin: id::FaceId,
+    alive: bool,
+}
+
+impl Triangle {
+    fn corner(&self, v: usize) -> Option<usize> {
+        self.v.iter().position(|c| *c == v)
+    }
+}
+
+/// A candidate collapse of vertex `u` onto vertex `v`, valid as long as
+/// neither vertex has changed since it was costed
+struct Collapse {
+    cost: f64,
+    /// The summed squared distance `v` would be from the planes bounding the
+    /// error, which is at least the square of its distance from any one
+    error: f64,
+    u: usize,
+    v: usize,
+    stamps: [u32; 2],
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    /// Reversed, so that the heap pops the cheapest collapse first. Ties go to
+    /// the lowest vertices, so that every run decimates the same way.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost).then_with(|| (other.u, other.v).cmp(&(self.u, self.v)))
+    }
+}
+
+/// The triangle soup that decimation works on, with just enough adjacency
+/// to collapse edges: the triangles around each vertex, and the seams.
+struct Decimator {
+    positions: Vec<Vector3<f64>>,
+    alive: Vec<bool>,
+    quadrics: Vec<Quadric>,
+    /// The same planes as `quadrics`, but each at unit weight, for measuring
+    /// collapses against `DecimateOptions::max_error`
+    bounds: Vec<Quadric>,
+    stamps: Vec<u32>,
+    triangles: Vec<Triangle>,
+    vert_triangles: Vec<Vec<usize>>,
+    /// Edges which must survive as edges, by their vertices in order: mesh
+    /// boundaries, non-manifold edges, cuts, UV seams and material borders
+    seams: HashSet<(usize, usize)>,
+    /// The other ends of the seams at each vertex
+    vert_seams: Vec<Vec<usize>>,
+    /// Live cuts, by their seam, with the edge's vertices in the order the
+    /// flap position refers to
+    cuts: HashMap<(usize, usize), ([usize; 2], Cut)>,
+    alive_triangles: usize,
+}
+
+fn key(a: usize, b: usize) -> (usize, usize) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+fn same_uv(a: [f32; 2], b: [f32; 2]) -> bool {
+    (a[0] - b[0]).abs() < 1e-5 && (a[1] - b[1]).abs() < 1e-5
+}
+
+impl Decimator {
+    fn new(mesh: &Mesh) -> Self {
+        let n = mesh.verts.next_push_index();
+        let mut positions = vec![Vector3::new(0.0, 0.0, 0.0); n];
+        let mut alive = vec![false; n];
+        for v in mesh.verts.indices() {
+            positions[v] = Vector3::from(mesh.verts[v].po.map(f64::from));
+            alive[v] = true;
+        }
+
+        // Polygons are fanned into triangles around their first corner
+        let mut triangles = Vec::new();
+        for f_id in mesh.faces.indices().map(id::FaceId::from_usize) {
+            let loops: Vec<_> = mesh.iter_face_loops(f_id).collect();
+            for k in 1..loops.len() - 1 {
+                let corners = [loops[0], loops[k], loops[k + 1]];
+                triangles.push(Triangle {
+                    v: corners.map(|l| mesh[l].v.to_usize()),
+                    uv: corners.map(|l| mesh[l].uv),
+                    no: corners.map(|l| mesh[l].no),
+                    m: mesh[f_id].m,
+                    origin: f_id,
+                    alive: true,
+                });
+            }
+        }
+        let mut vert_triangles = vec![Vec::new(); n];
+        for (t, triangle) in triangles.iter().enumerate() {
+            triangle.v.iter().for_each(|v| vert_triangles[*v].push(t));
+        }
+
+        let mut seams = HashSet::new();
+        let mut cuts = HashMap::new();
+        for e_id in mesh.edges.indices().map(id::EdgeId::from_usize) {
+            let [v0, v1] = mesh[e_id].v.map(|v| v.to_usize());
+            let loops: Vec<_> = mesh.iter_edge_loops(e_id).into_iter().flatten().collect();
+            let is_seam = match loops[..] {
+                [l_a, l_b] => {
+                    // Either loop's next corner should line up with the other's own
+                    mesh[l_a].v == mesh[l_b].v
+                        || mesh[mesh[l_a].f].m != mesh[mesh[l_b].f].m
+                        || !same_uv(mesh[l_a].uv, mesh[mesh[l_b].next].uv)
+                        || !same_uv(mesh[mesh[l_a].next].uv, mesh[l_b].uv)
+                }
+                _ => true,
+            };
+            if let Some(cut) = mesh.cuts.get(&e_id).filter(|cut| !cut.is_dead) {
+                cuts.insert(key(v0, v1), ([v0, v1], *cut));
+                seams.insert(key(v0, v1));
+            } else if is_seam {
+                seams.insert(key(v0, v1));
+            }
+        }
+        let mut vert_seams = vec![Vec::new(); n];
+        for (a, b) in &seams {
+            vert_seams[*a].push(*b);
+            vert_seams[*b].push(*a);
+        }
+
+        let mut decimator = Self {
+            quadrics: vec![Quadric::default(); n],
+            bounds: vec![Quadric::default(); n],
+            stamps: vec![0; n],
+            alive_triangles: triangles.len(),
+            positions,
+            alive,
+            triangles,
+            vert_triangles,
+            seams,
+            vert_seams,
+            cuts,
+        };
+        decimator.build_quadrics();
+        decimator
+    }
+
+    fn normal(&self, v: [usize; 3]) -> Vector3<f64> {
+        let [a, b, c] = v.map(|v| self.positions[v]);
+        (b - a).cross(c - a)
+    }
+
+    fn build_quadrics(&mut self) {
+        for t in 0..self.triangles.len() {
+            let v = self.triangles[t].v;
+            let normal = self.normal(v);
+            if normal.magnitude2() == 0.0 {
+                continue;
+            }
+            let normal = normal.normalize();
+            let plane = Quadric::plane(normal, self.positions[v[0]], 1.0);
+            v.iter().for_each(|v| self.quadrics[*v].add(&plane));
+            v.iter().for_each(|v| self.bounds[*v].add(&plane));
+
+            // A seam is held in place by a plane through it, upright on
+            // each face it borders
+            for k in 0..3 {
+                let (a, b) = (v[k], v[(k + 1) % 3]);
+                if !self.seams.contains(&key(a, b)) {
+                    continue;
+                }
+                let along = self.positions[b] - self.positions[a];
+                let upright = along.cross(normal);
+                if upright.magnitude2() == 0.0 {
+                    continue;
+                }
+                let upright = upright.normalize();
+                let weight = SEAM_WEIGHT * along.magnitude2();
+                let plane = Quadric::plane(upright, self.positions[a], weight);
+                self.quadrics[a].add(&plane);
+                self.quadrics[b].add(&plane);
+                let plane = Quadric::plane(upright, self.positions[a], 1.0);
+                self.bounds[a].add(&plane);
+                self.bounds[b].add(&plane);
+            }
+        }
+    }
+
+    fn live_triangles(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        self.vert_triangles[v].iter().copied().filter(|t| self.triangles[*t].alive)
+    }
+
+    fn neighbours(&self, v: usize) -> HashSet<usize> {
+        self.live_triangles(v).flat_map(|t| self.triangles[t].v).filter(|w| *w != v).collect()
+    }
+
+    fn seam_count(&self, v: usize) -> usize {
+        self.neighbours(v).into_iter().filter(|w| self.seams.contains(&key(v, *w))).count()
+    }
+
+    /// Whether `u` can be collapsed onto `v` without tearing the surface,
+    /// turning a face over, or moving a seam off its line
+    fn can_collapse(&self, u: usize, v: usize) -> bool {
+        if u == v || !self.alive[u] || !self.alive[v] {
+            return false;
+        }
+        // A vertex on a seam can only slide along it, and only if it's in
+        // the middle of one: the ends and crossings of seams stay put
+        let seams = self.seam_count(u);
+        if seams > 0 && (seams != 2 || !self.seams.contains(&key(u, v))) {
+            return false;
+        }
+
+        // The link condition: the only vertices `u` and `v` share are the far
+        // corners of the triangles on their edge, so no edge gets doubled up
+        let shared: Vec<_> =
+            self.live_triangles(u).filter(|t| self.triangles[*t].corner(v).is_some()).collect();
+        if shared.is_empty() || shared.len() > 2 {
+            return false;
+        }
+        let opposite: HashSet<_> = shared
+            .iter()
+            .flat_map(|t| self.triangles[*t].v)
+            .filter(|w| *w != u && *w != v)
+            .collect();
+        if self.neighbours(u).intersection(&self.neighbours(v)).any(|w| !opposite.contains(w)) {
+            return false;
+        }
+
+        self.live_triangles(u).filter(|t| !shared.contains(t)).all(|t| {
+            let before = self.triangles[t].v;
+            let after = before.map(|w| if w == u { v } else { w });
+            let (n_before, n_after) = (self.normal(before), self.normal(after));
+            n_after.magnitude2() > 0.0
+                && n_before.magnitude2() > 0.0
+                && n_before.normalize().dot(n_after.normalize()) > MIN_NORMAL_DOT
+        })
+    }
+
+    /// The cost of collapsing `u` onto `v`, and its error. See [`Collapse`].
+    fn collapse_cost(&self, u: usize, v: usize) -> (f64, f64) {
+        let summed = |quadrics: &[Quadric]| {
+            let mut quadric = quadrics[u];
+            quadric.add(&quadrics[v]);
+            quadric.error(self.positions[v]).max(0.0)
+        };
+        (summed(&self.quadrics), summed(&self.bounds))
+    }
+
+    /// The cheaper way to collapse the edge between `a` and `b`, if either is
+    /// allowed
+    fn best_collapse(&self, a: usize, b: usize) -> Option<Collapse> {
+        [(a, b), (b, a)]
+            .into_iter()
+            .filter(|(u, v)| self.can_collapse(*u, *v))
+            .map(|(u, v)| {
+                let (cost, error) = self.collapse_cost(u, v);
+                Collapse { cost, error, u, v, stamps: [self.stamps[u], self.stamps[v]] }
+            })
+            .min_by(|x, y| x.cost.total_cmp(&y.cost))
+    }
+
+    fn collapse(&mut self, u: usize, v: usize) {
+        let shared: Vec<_> =
+            self.live_triangles(u).filter(|t| self.triangles[*t].corner(v).is_some()).collect();
+        for t in &shared {
+            self.triangles[*t].alive = false;
+            self.alive_triangles -= 1;
+        }
+
+        // Each corner moved from `u` to `v` takes the UV and normal `v` has in
+        // whichever of the collapsed triangles is on its side of any seam,
+        // which is the one where `u` looked the same
+        let moved: Vec<_> = self.live_triangles(u).collect();
+        for t in moved {
+            let k = self.triangles[t].corner(u).unwrap();
+            let uv = self.triangles[t].uv[k];
+            let side = shared
+                .iter()
+                .min_by(|a, b| {
+                    let distance = |s: &usize| {
+                        let s = &self.triangles[*s];
+                        let [x, y] = s.uv[s.corner(u).unwrap()];
+                        (x - uv[0]).powi(2) + (y - uv[1]).powi(2)
+                    };
+                    distance(a).total_cmp(&distance(b))
+                })
+                .map(|s| &self.triangles[*s])
+                .unwrap();
+            let j = side.corner(v).unwrap();
+            let (uv, no) = (side.uv[j], side.no[j]);
+            let triangle = &mut self.triangles[t];
+            triangle.v[k] = v;
+            triangle.uv[k] = uv;
+            triangle.no[k] = no;
+            self.vert_triangles[v].push(t);
+        }
+
+        // Seams and cuts from `u` carry on from `v`
+        let mut seam_neighbours = std::mem::take(&mut self.vert_seams[u]);
+        seam_neighbours.sort();
+        for w in seam_neighbours {
+            let Some(seam) = self.seams.take(&key(u, w)) else {
+                continue;
+            };
+            self.vert_seams[w].retain(|x| *x != u);
+            let cut = self.cuts.remove(&seam);
+            if w == v {
+                continue;
+            }
+            if self.seams.insert(key(v, w)) {
+                self.vert_seams[v].push(w);
+                self.vert_seams[w].push(v);
+            }
+            if let Some((ends, cut)) = cut {
+                let ends = ends.map(|x| if x == u { v } else { x });
+                self.cuts.entry(key(v, w)).or_insert((ends, cut));
+            }
+        }
+
+        let quadric = self.quadrics[u];
+        self.quadrics[v].add(&quadric);
+        let bound = self.bounds[u];
+        self.bounds[v].add(&bound);
+        self.alive[u] = false;
+        self.vert_triangles[u].clear();
+        self.stamps[v] += 1;
+        for w in self.neighbours(v) {
+            self.stamps[w] += 1;
+        }
+    }
+
+    fn run(&mut self, options: &DecimateOptions) {
+        let max_error = options.max_error.map(|e| f64::from(e).powi(2));
+        let mut heap = BinaryHeap::new();
+        let mut edges: Vec<_> = self
+            .triangles
+            .iter()
+            .flat_map(|t| (0..3).map(move |k| key(t.v[k], t.v[(k + 1) % 3])))
+            .collect();
+        edges.sort();
+        edges.dedup();
+        heap.extend(edges.into_iter().filter_map(|(a, b)| self.best_collapse(a, b)));
+
+        while self.alive_triangles > options.target_faces {
+            let Some(collapse) = heap.pop() else {
+                break;
+            };
+            let Collapse { error, u, v, stamps, .. } = collapse;
+            if !self.alive[u] || !self.alive[v] || stamps != [self.stamps[u], self.stamps[v]] {
+                continue;
+            }
+            // Costs weigh seams heavily, so they don't order errors: one over
+            // the bound can come before a cheaper one under it
+            if max_error.is_some_and(|max| error > max) || !self.can_collapse(u, v) {
+                continue;
+            }
+            self.collapse(u, v);
+            // Everything around `v` was restamped, so its edges and those of
+            // its neighbours are costed again
+            let mut around: Vec<_> = self.neighbours(v).into_iter().chain([v]).collect();
+            around.sort();
+            let mut edges: Vec<_> = around
+                .iter()
+                .flat_map(|a| self.neighbours(*a).into_iter().map(|b| key(*a, b)))
+                .collect();
+            edges.sort();
+            edges.dedup();
+            heap.extend(edges.into_iter().filter_map(|(a, b)| self.best_collapse(a, b)));
+        }
+    }
+}
+
+impl Mesh {
+    /// Builds a copy of the mesh with fewer triangles, by collapsing edges in
+    /// order of the quadric error they'd add. Polygons are fanned into
+    /// triangles first, so the copy is all triangles.
+    ///
+    /// Boundaries, UV seams, material borders and cuts are all kept: a vertex
+    /// on one only ever slides along it onto its neighbour, and where seams
+    /// end or meet never moves at all. Collapses move one vertex onto
+    /// another rather than to somewhere new, so every vertex left keeps its
+    /// ID and position, and every corner a UV it had in the original.
+    ///
+    /// Cuts keep their flaps, styles and labels. Pieces are rebuilt from the
+    /// cuts, each rooted at a triangle of its old root face where one is left,
+    /// and keep their transforms.
+    pub fn decimate(&self, options: &DecimateOptions) -> Self {
+        let mut decimator = Decimator::new(self);
+        decimator.run(options);
+
+        let mut geometry =
+            MeshGeometry { verts: vec![None; decimator.positions.len()], ..Default::default() };
+        for triangle in decimator.triangles.iter().filter(|t| t.alive) {
+            triangle.v.iter().for_each(|v| geometry.verts[*v] = Some(self.verts[*v].po));
+            geometry.faces.push(GeometryFace {
+                f: None,
+                m: triangle.m,
+                corners: (0..3)
+                    .map(|k| GeometryCorner {
+                        l: None,
+                        v: id::VertexId::from_usize(triangle.v[k]),
+                        uv: triangle.uv[k],
+                        no: triangle.no[k],
+                    })
+                    .collect(),
+            });
+        }
+        let mut cuts: Vec<_> = decimator.cuts.into_values().collect();
+        cuts.sort_by_key(|(ends, _)| *ends);
+        for (ends, cut) in cuts {
+            let ends = ends.map(id::VertexId::from_usize);
+            geometry.cuts.push((ends, cut.flap_position));
+            if let Some(flap) = cut.flap {
+                geometry.cut_flaps.push((ends, flap));
+            }
+            if cut.style != FlapStyle::default() {
+                geometry.cut_styles.push((ends, cut.style));
+            }
+            if let Some(label) = cut.label {
+                geometry.cut_labels.push((ends, label));
+            }
+        }
+
+        // A piece is rooted at a triangle of its old root face if any are
+        // left, or else at any triangle which was part of it
+        let origins: Vec<_> =
+            decimator.triangles.iter().filter(|t| t.alive).map(|t| t.origin).collect();
+        let mut roots = HashMap::new();
+        for (root, piece) in &self.pieces {
+            let index = origins
+                .iter()
+                .position(|f| f == root)
+                .or_else(|| origins.iter().position(|f| self[*f].p == Some(*root)));
+            if let Some(index) = index {
+                geometry.pieces.push((index, piece.transform));
+                roots.insert(*root, index);
+            }
+        }
+        geometry.assembly =
+            self.assembly.iter().filter_map(|root| roots.get(root).copied()).collect();
+
+        // The copy is the same document object, just coarser, so it keeps the
+        // mesh's own settings
+        let mut decimated = Self {
+            label: self.label.clone(),
+            transform: self.transform,
+            scale: self.scale,
+            flap_settings: self.flap_settings,
+            paper_thickness: self.paper_thickness,
+            ..Default::default()
+        };
+        decimated.set_geometry(&geometry);
+        decimated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::{EdgeId, FaceId, VertexId};
+    use crate::mesh::{cut::CutUpdate, face::FaceDescriptor, flap::FlapSettings};
+
+    /// A flat `w` by `h` grid of squares, each split into two triangles,
+    /// with UVs a quarter of their positions. The vertex at `(i, j)` has ID
+    /// `i * (h + 1) + j`.
+    fn grid(w: usize, h: usize) -> Mesh {
+        let mut mesh = Mesh::new("GRID".to_string());
+        let v: Vec<Vec<_>> = (0..=w)
+            .map(|i| (0..=h).map(|j| mesh.add_vertex([i as f32, j as f32, 0.0])).collect())
+            .collect();
+        let uv = |i: usize, j: usize| [i as f32 / 4.0, j as f32 / 4.0];
+        for i in 0..w {
+            for j in 0..h {
+                for [a, b, c] in [[(0, 0), (1, 0), (1, 1)], [(0, 0), (1, 1), (0, 1)]] {
+                    let corners = [a, b, c].map(|(di, dj)| (i + di, j + dj));
+                    let uvs = corners.map(|(i, j)| uv(i, j));
+                    mesh.add_face(
+                        &corners.map(|(i, j)| v[i][j]),
+                        &FaceDescriptor { uvs: Some(&uvs), ..Default::default() },
+                    );
+                }
+            }
+        }
+        mesh
+    }
+
+    fn options(target_faces: usize) -> DecimateOptions {
+        DecimateOptions { target_faces, max_error: None }
+    }
+
+    /// As far as it'll go without moving the surface, or any of its seams
+    fn lossless() -> DecimateOptions {
+        DecimateOptions { target_faces: 0, max_error: Some(1e-3) }
+    }
+
+    fn boundary_edges(mesh: &Mesh) -> usize {
+        mesh.edges
+            .indices()
+            .filter(|e| mesh.iter_edge_loops(EdgeId::from_usize(*e)).unwrap().count() == 1)
+            .count()
+    }
+
+    #[test]
+    fn a_flat_grid_collapses_to_its_corners() {
+        let mesh = grid(4, 4);
+        let decimated = mesh.decimate(&options(2));
+        assert_eq!(decimated.faces.num_elements(), 2);
+        assert_eq!(decimated.verts.num_elements(), 4);
+        // Only the corners are left, where they were, with their UVs
+        for l in decimated.loops.indices().map(crate::id::LoopId::from_usize) {
+            let po = decimated[decimated[l].v].po;
+            assert_eq!(decimated[l].uv, [po[0] / 4.0, po[1] / 4.0]);
+        }
+        // The original is left alone
+        assert_eq!(mesh.faces.num_elements(), 32);
+    }
+
+    #[test]
+    fn every_face_still_faces_up() {
+        let decimated = grid(6, 6).decimate(&options(10));
+        assert!(decimated.faces.num_elements() <= 10);
+        assert!(decimated.faces.indices().all(|f| decimated[FaceId::from_usize(f)].no[2] > 0.99));
+    }
+
+    #[test]
+    fn a_sphere_stays_closed() {
+        let sphere = Mesh::new_icosphere(2);
+        let decimated = sphere.decimate(&options(80));
+        assert!(decimated.faces.num_elements() <= 80);
+        assert_eq!(boundary_edges(&decimated), 0);
+    }
+
+    /// A cube's corners are all sharp, so any collapse would dent it
+    #[test]
+    fn an_error_bound_keeps_sharp_features() {
+        let cube = Mesh::new_cube();
+        let decimated = cube.decimate(&DecimateOptions { target_faces: 0, max_error: Some(0.01) });
+        assert_eq!(decimated.faces.num_elements(), 12);
+    }
+
+    /// A cut across the grid stays a line of cut edges from side to side
+    #[test]
+    fn cuts_are_kept() {
+        let mut mesh = grid(4, 4);
+        for j in 0..4 {
+            let (a, b) = (VertexId::from_usize(2 * 5 + j), VertexId::from_usize(2 * 5 + j + 1));
+            let e_id = mesh.query_edge(a, b).unwrap();
+            mesh.make_cut(e_id, CutUpdate::Nothing);
+            mesh.set_cut_label(e_id, 42);
+            mesh.set_cut_flap_style(e_id, FlapStyle::Zigzag);
+            mesh.set_cut_flap_settings(
+                e_id,
+                Some(FlapSettings { height: 0.2, ..Default::default() }),
+            );
+        }
+        let decimated = mesh.decimate(&lossless());
+        assert!(!decimated.cuts.is_empty());
+        for (e_id, cut) in &decimated.cuts {
+            assert!(decimated[*e_id].v.iter().all(|v| decimated[*v].po[0] == 2.0));
+            // ...and still looks and reads the same on the pattern
+            assert_eq!(cut.label, Some(42));
+            assert_eq!(cut.style, FlapStyle::Zigzag);
+            assert_eq!(cut.flap.map(|flap| flap.height), Some(0.2));
+        }
+        // Both ends of the cut are still there
+        assert!(decimated.verts.has_element_at(10));
+        assert!(decimated.verts.has_element_at(14));
+        assert_eq!(decimated.faces.num_elements(), 4);
+    }
+
+    #[test]
+    fn the_mesh_keeps_its_settings() {
+        let mut mesh = grid(4, 4);
+        mesh.flap_settings = FlapSettings { height: 1.0, angle: 0.5, min_base: 0.1 };
+        mesh.paper_thickness = 0.05;
+        let decimated = mesh.decimate(&options(2));
+        assert_eq!(decimated.flap_settings, mesh.flap_settings);
+        assert_eq!(decimated.paper_thickness, 0.05);
+    }
+
+    #[test]
+    fn the_boundary_is_kept() {
+        let decimated = grid(4, 4).decimate(&lossless());
+        // Corners are where the boundary turns, so they survive, and the
+        // boundary between them is still unbroken
+        for corner in [0, 4, 20, 24] {
+            assert!(decimated.verts.has_element_at(corner));
+        }
+        assert_eq!(boundary_edges(&decimated), 4);
+    }
+
+    /// Two materials side by side keep their border
+    #[test]
+    fn material_borders_are_kept() {
+        let mut mesh = grid(4, 4);
+        let mut geometry = mesh.geometry();
+        let material = crate::MaterialId::default();
+        for face in &mut geometry.faces {
+            let x = face.corners.iter().map(|c| geometry.verts[c.v.to_usize()].unwrap()[0]);
+            if x.fold(0.0, f32::max) <= 2.0 {
+                face.m = Some(material);
+            }
+        }
+        mesh.set_geometry(&geometry);
+        let decimated = mesh.decimate(&lossless());
+        assert_eq!(decimated.faces.num_elements(), 4);
+        assert!(decimated.faces.indices().all(|f| {
+            let f_id = FaceId::from_usize(f);
+            let left =
+                decimated.iter_face_loops(f_id).all(|l| decimated[decimated[l].v].po[0] <= 2.0);
+            left == decimated[f_id].m.is_some()
+        }));
+    }
+
+    #[test]
+    fn pieces_are_rebuilt_with_their_transforms() {
+        // A strip, as a piece can't have a vertex in its middle
+        let mut mesh = grid(4, 1);
+        let e_id = mesh.query_edge(VertexId::from_usize(4), VertexId::from_usize(5)).unwrap();
+        mesh.make_cut(e_id, CutUpdate::PiecesAndFlaps);
+        mesh.pieces.values_mut().for_each(|piece| piece.transform.w.x = 3.0);
+        assert_eq!(mesh.pieces.len(), 2);
+
+        let decimated = mesh.decimate(&lossless());
+        assert_eq!(decimated.faces.num_elements(), 4);
+        assert_eq!(decimated.pieces.len(), 2);
+        assert!(decimated.pieces.values().all(|piece| piece.transform.w.x == 3.0));
+        assert!(decimated.faces.indices().all(|f| decimated[FaceId::from_usize(f)].p.is_some()));
+    }
+
+    #[test]
+    fn the_assembly_order_follows_its_pieces() {
+        let mut mesh = grid(4, 1);
+        let e_id = mesh.query_edge(VertexId::from_usize(4), VertexId::from_usize(5)).unwrap();
+        mesh.make_cut(e_id, CutUpdate::PiecesAndFlaps);
+        let mut roots: Vec<_> = mesh.iter_pieces().copied().collect();
+        for (i, root) in roots.iter().enumerate() {
+            mesh.pieces.get_mut(root).unwrap().transform.w.x = i as f32;
+        }
+        roots.reverse();
+        mesh.assembly = roots;
+
+        let decimated = mesh.decimate(&lossless());
+        let order = |mesh: &Mesh| -> Vec<f32> {
+            mesh.assembly.iter().map(|root| mesh.pieces[root].transform.w.x).collect()
+        };
+        assert_eq!(order(&decimated), vec![1.0, 0.0]);
+        assert_eq!(order(&decimated), order(&mesh));
+    }
+}
@@ -0,0 +1,392 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+
+use super::{cut::Cut, edge::Edge, face::Face, loop_::Loop, piece::Piece, vertex::Vertex};
+use super::{Mesh, MeshElementType};
+use crate::{
+    id::{self, Id},
+    MaterialId,
+};
+
+/// A face which was taken out of a mesh, with its loops as they were
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DetachedFace {
+    pub f: id::FaceId,
+    pub no: [f32; 3],
+    pub p: Option<id::FaceId>,
+    pub m: Option<MaterialId>,
+    pub corners: Vec<DetachedCorner>,
+}
+
+/// One of a detached face's loops, in the face's winding order
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct DetachedCorner {
+    pub l: id::LoopId,
+    pub v: id::VertexId,
+    pub e: id::EdgeId,
+    pub uv: [f32; 2],
+    pub no: [f32; 3],
+}
+
+/// Why detached faces can't be put back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreFacesError {
+    /// Something else has since taken one of the IDs they'd go back under
+    SlotTaken,
+    /// A vertex or edge they'd be put back onto is gone
+    Missing,
+}
+
+/// Everything `Mesh::delete_faces` took out of a mesh, by ID, so that
+/// `Mesh::restore_faces` can put it back into the very same slots.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DetachedFaces {
+    pub faces: Vec<DetachedFace>,
+    /// Edges left with no faces, by their vertices in order, with their cuts
+    pub edges: Vec<(id::EdgeId, [id::VertexId; 2], Option<Cut>)>,
+    /// Vertices left with no edges
+    pub verts: Vec<(id::VertexId, [f32; 3])>,
+    /// Pieces which were rooted on a deleted face
+    pub pieces: Vec<(id::FaceId, Piece)>,
+    /// Faces left behind by a piece which lost some of its faces, with the
+    /// piece they were in
+    pub members: Vec<(id::FaceId, id::FaceId)>,
+    /// New pieces grown from what was left of a broken piece, with whatever
+    /// entry their root had in `pieces` before
+    pub remnants: Vec<(id::FaceId, Option<Piece>)>,
+    /// The mesh's stored assembly order, if any of the deleted faces were
+    /// roots in it
+    #[serde(default)]
+    pub assembly: Option<Vec<id::FaceId>>,
+}
+
+impl DetachedFaces {
+    /// The IDs of the detached faces, in the order they were detached
+    pub fn face_ids(&self) -> Vec<id::FaceId> {
+        self.faces.iter().map(|face| face.f).collect()
+    }
+}
+
+impl Mesh {
+    /// Deletes faces from the mesh, along with any edges and vertices which
+    /// no other face uses. Every remaining element keeps its ID: the deleted
+    /// ones leave empty slots behind, which `restore_faces` fills back in.
+    ///
+    /// Cuts go with their edges. A piece which loses faces is regrown from
+    /// its root, or from its first remaining face if the root was deleted,
+    /// and any part of it the deletion cut off becomes a piece of its own.
+    /// Each of these keeps the broken piece's transform. Deleted roots drop
+    /// out of the stored assembly order.
+    pub fn delete_faces(&mut self, faces: &[id::FaceId]) -> DetachedFaces {
+        let mut f_ids: Vec<_> =
+            faces.iter().copied().filter(|f| self.faces.has_element_at(f.to_usize())).collect();
+        f_ids.sort();
+        f_ids.dedup();
+        let deleting: HashSet<_> = f_ids.iter().copied().collect();
+        let mut detached = DetachedFaces::default();
+        if self.assembly.iter().any(|root| deleting.contains(root)) {
+            detached.assembly = Some(self.assembly.clone());
+            self.assembly.retain(|root| !deleting.contains(root));
+        }
+
+        // Pieces losing faces come apart first, and are regrown afterwards
+        // from whatever is left of them
+        let broken: BTreeSet<_> = f_ids.iter().filter_map(|f| self[*f].p).collect();
+        detached.members = self
+            .faces
+            .indices()
+            .map(id::FaceId::from_usize)
+            .filter(|f| !deleting.contains(f))
+            .filter_map(|f| Some((f, self[f].p.filter(|p| broken.contains(p))?)))
+            .collect();
+        detached.members.iter().for_each(|(f, _)| self[*f].p = None);
+        let transforms: Vec<_> =
+            broken.iter().map(|root| (*root, self.pieces[root].transform)).collect();
+
+        for f in &f_ids {
+            let face = self[*f];
+            let loops: Vec<_> = self.iter_face_loops(*f).collect();
+            detached.faces.push(DetachedFace {
+                f: *f,
+                no: face.no,
+                p: face.p,
+                m: face.m,
+                corners: loops
+                    .iter()
+                    .map(|l| {
+                        let Loop { v, e, uv, no, .. } = self[*l];
+                        DetachedCorner { l: *l, v, e, uv, no }
+                    })
+                    .collect(),
+            });
+            for l in loops {
+                self.disconnect_loop_from_edge(l, self[l].e);
+                self.loops.remove(l.to_usize());
+            }
+            self.faces.remove(f.to_usize());
+            if let Some(piece) = self.pieces.remove(f) {
+                detached.pieces.push((*f, piece));
+            }
+        }
+
+        let corners = || detached.faces.iter().flat_map(|face| &face.corners);
+        let edges: BTreeSet<_> = corners().map(|c| c.e).collect();
+        for e in edges {
+            if self[e].l.is_some() {
+                continue;
+            }
+            let v = self[e].v;
+            self.disconnect_edge_from_vert(e, v[0]);
+            self.disconnect_edge_from_vert(e, v[1]);
+            self.edges.remove(e.to_usize());
+            detached.edges.push((e, v, self.cuts.remove(&e)));
+        }
+        let verts: BTreeSet<_> = corners().map(|c| c.v).collect();
+        for v in verts {
+            if self[v].e.is_none() {
+                detached.verts.push((v, self[v].po));
+                self.verts.remove(v.to_usize());
+            }
+        }
+
+        for (root, transform) in transforms {
+            let seeds: Vec<_> = std::iter::once(root)
+                .filter(|root| !deleting.contains(root))
+                .chain(detached.members.iter().filter(|(_, p)| *p == root).map(|(f, _)| *f))
+                .collect();
+            for f in seeds {
+                if self[f].p.is_some() {
+                    continue;
+                }
+                if f != root {
+                    let before = self.pieces.insert(f, Piece { transform, ..Default::default() });
+                    detached.remnants.push((f, before));
+                }
+                // What's left of an acyclic piece is acyclic too
+                let _ = self.expand_piece(f);
+            }
+        }
+
+        self.elem_dirty = MeshElementType::all();
+        self.index_dirty = MeshElementType::all();
+        detached
+    }
+
+    /// Puts faces taken out by `delete_faces` back where they were, with the
+    /// same IDs, and the pieces around them as they were before.
+    ///
+    /// Leaves the mesh as it is if any of those IDs has been taken since, or
+    /// anything the faces were attached to has gone.
+    pub fn restore_faces(&mut self, detached: &DetachedFaces) -> Result<(), RestoreFacesError> {
+        self.check_restorable(detached)?;
+        for (f, before) in detached.remnants.iter().rev() {
+            match before {
+                Some(piece) => self.pieces.insert(*f, *piece),
+                None => self.pieces.remove(f),
+            };
+        }
+
+        for (v, po) in &detached.verts {
+            self.verts.reserve_for(v.to_usize());
+            self.verts.insert(v.to_usize(), Vertex { po: *po, e: None });
+        }
+        for (e, v, cut) in &detached.edges {
+            self.edges.reserve_for(e.to_usize());
+            self.edges.insert(e.to_usize(), Edge::new(*e, v[0], v[1]));
+            self.connect_edge_to_vert(*e, v[0]);
+            self.connect_edge_to_vert(*e, v[1]);
+            if let Some(cut) = cut {
+                self.cuts.insert(*e, *cut);
+            }
+        }
+        for face in &detached.faces {
+            let n = face.corners.len();
+            self.faces.reserve_for(face.f.to_usize());
+            self.faces.insert(
+                face.f.to_usize(),
+                Face { no: face.no, l: face.corners[0].l, p: face.p, m: face.m },
+            );
+            for (k, c) in face.corners.iter().enumerate() {
+                self.loops.reserve_for(c.l.to_usize());
+                self.loops.insert(
+                    c.l.to_usize(),
+                    Loop {
+                        v: c.v,
+                        e: c.e,
+                        f: face.f,
+                        uv: c.uv,
+                        no: c.no,
+                        next: face.corners[(k + 1) % n].l,
+                        prev: face.corners[(k + n - 1) % n].l,
+                        ..Default::default()
+                    },
+                );
+                self.connect_loop_to_edge(c.l, c.e);
+            }
+        }
+
+        self.pieces.extend(detached.pieces.iter().copied());
+        detached.members.iter().for_each(|(f, p)| self[*f].p = Some(*p));
+        if let Some(assembly) = &detached.assembly {
+            self.assembly = assembly.clone();
+        }
+        self.elem_dirty = MeshElementType::all();
+        self.index_dirty = MeshElementType::all();
+        Ok(())
+    }
+
+    fn check_restorable(&self, detached: &DetachedFaces) -> Result<(), RestoreFacesError> {
+        let corners = || detached.faces.iter().flat_map(|face| &face.corners);
+        let taken = detached.verts.iter().any(|(v, _)| self.verts.has_element_at(v.to_usize()))
+            || detached.edges.iter().any(|(e, ..)| self.edges.has_element_at(e.to_usize()))
+            || detached.faces.iter().any(|face| self.faces.has_element_at(face.f.to_usize()))
+            || corners().any(|c| self.loops.has_element_at(c.l.to_usize()));
+        if taken {
+            return Err(RestoreFacesError::SlotTaken);
+        }
+
+        let verts: HashSet<_> = detached.verts.iter().map(|(v, _)| *v).collect();
+        let edges: HashSet<_> = detached.edges.iter().map(|(e, ..)| *e).collect();
+        let missing = corners()
+            .map(|c| c.v)
+            .chain(detached.edges.iter().flat_map(|(_, v, _)| *v))
+            .any(|v| !verts.contains(&v) && !self.verts.has_element_at(v.to_usize()))
+            || corners()
+                .any(|c| !edges.contains(&c.e) && !self.edges.has_element_at(c.e.to_usize()));
+        if missing {
+            return Err(RestoreFacesError::Missing);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::{EdgeId, FaceId, VertexId};
+    use crate::mesh::cut::CutUpdate;
+
+    /// Every edge's loops, and every vertex's edges, by ID
+    fn links(mesh: &Mesh) -> (Vec<usize>, Vec<usize>) {
+        let edges = mesh
+            .edges
+            .indices()
+            .map(|e| mesh.iter_edge_loops(EdgeId::from_usize(e)).map_or(0, |l| l.count()))
+            .collect();
+        let verts =
+            mesh.verts.indices().map(|v| mesh.iter_all_vert_edges(VertexId::from_usize(v)).count());
+        (edges, verts.collect())
+    }
+
+    /// The two triangles of the cube's top
+    fn top(cube: &Mesh) -> Vec<FaceId> {
+        cube.faces.indices().map(FaceId::from_usize).filter(|f| cube[*f].no[2] > 0.5).collect()
+    }
+
+    #[test]
+    fn deleting_the_top_opens_the_cube() {
+        let mut cube = Mesh::new_cube();
+        let top = top(&cube);
+        let detached = cube.delete_faces(&top);
+        assert_eq!(detached.face_ids(), top);
+        // The top's diagonal goes, and its four sides are left on the border
+        assert_eq!(detached.edges.len(), 1);
+        assert!(detached.verts.is_empty());
+        assert_eq!(cube.faces.num_elements(), 10);
+        assert_eq!(cube.loops.num_elements(), 30);
+        let (edges, verts) = links(&cube);
+        assert_eq!(edges.iter().filter(|loops| **loops == 1).count(), 4);
+        assert_eq!(edges.iter().filter(|loops| **loops == 2).count(), 13);
+        assert_eq!(verts.iter().sum::<usize>(), 2 * cube.edges.num_elements());
+        // Nothing else moved
+        assert!(!cube.faces.has_element_at(top[0].to_usize()));
+        assert_eq!(cube.faces.next_push_index(), 12);
+    }
+
+    #[test]
+    fn deleted_faces_come_back_with_their_ids() {
+        let mut cube = Mesh::new_cube();
+        let before = links(&cube);
+        let normals: Vec<_> =
+            cube.faces.indices().map(|f| cube[FaceId::from_usize(f)].no).collect();
+        let detached = cube.delete_faces(&top(&cube));
+        cube.restore_faces(&detached).unwrap();
+        assert_eq!(links(&cube), before);
+        let restored: Vec<_> =
+            cube.faces.indices().map(|f| cube[FaceId::from_usize(f)].no).collect();
+        assert_eq!(restored, normals);
+        for f in cube.faces.indices().map(FaceId::from_usize) {
+            assert!(cube.iter_face_loops(f).all(|l| cube[l].f == f));
+        }
+    }
+
+    /// Faces only go back into slots nothing else has taken, onto edges and
+    /// verts which are still there, and otherwise leave the mesh alone
+    #[test]
+    fn faces_are_not_restored_over_others() {
+        let mut cube = Mesh::new_cube();
+        let detached = cube.delete_faces(&top(&cube));
+        cube.restore_faces(&detached).unwrap();
+        let before = links(&cube);
+        assert_eq!(cube.restore_faces(&detached), Err(RestoreFacesError::SlotTaken));
+        assert_eq!(links(&cube), before);
+
+        // With every face gone, the top's sides and corners have gone too
+        let detached = cube.delete_faces(&top(&cube));
+        let rest: Vec<_> = cube.faces.indices().map(FaceId::from_usize).collect();
+        cube.delete_faces(&rest);
+        assert_eq!(cube.restore_faces(&detached), Err(RestoreFacesError::Missing));
+        assert_eq!(cube.faces.num_elements() + cube.edges.num_elements(), 0);
+    }
+
+    #[test]
+    fn a_lone_face_takes_its_edges_and_verts_with_it() {
+        let mut mesh = Mesh::new("TRIANGLE".to_string());
+        let v: Vec<_> = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]
+            .into_iter()
+            .map(|p| mesh.add_vertex(p))
+            .collect();
+        let f = mesh.add_face(&v, &Default::default());
+        let e = mesh.query_edge(v[0], v[1]).unwrap();
+        mesh.make_cut(e, CutUpdate::Nothing);
+
+        let detached = mesh.delete_faces(&[f]);
+        assert_eq!(detached.verts.len(), 3);
+        assert_eq!(mesh.verts.num_elements() + mesh.edges.num_elements(), 0);
+        assert!(mesh.cuts.is_empty());
+
+        mesh.restore_faces(&detached).unwrap();
+        assert_eq!(mesh.verts.num_elements(), 3);
+        assert!(mesh.edge_is_cut(&e));
+    }
+
+    /// A strip of three faces cut loose as one piece, with its middle face
+    /// deleted, leaves two pieces which both keep the strip's transform
+    #[test]
+    fn a_broken_piece_is_regrown_in_parts() {
+        let mut mesh = Mesh::new("STRIP".to_string());
+        let v: Vec<_> = (0..4)
+            .flat_map(|x| [[x as f32, 0.0, 0.0], [x as f32, 1.0, 0.0]])
+            .map(|p| mesh.add_vertex(p))
+            .collect();
+        let f: Vec<_> = (0..3)
+            .map(|x| {
+                let quad = [v[2 * x], v[2 * x + 2], v[2 * x + 3], v[2 * x + 1]];
+                mesh.add_face(&quad, &Default::default())
+            })
+            .collect();
+        mesh.expand_piece(f[0]).unwrap();
+        mesh.pieces.get_mut(&f[0]).unwrap().transform.w.x = 3.0;
+
+        let detached = mesh.delete_faces(&[f[1]]);
+        assert_eq!(mesh[f[0]].p, Some(f[0]));
+        assert_eq!(mesh[f[2]].p, Some(f[2]));
+        assert_eq!(mesh.pieces[&f[2]].transform.w.x, 3.0);
+        assert_eq!(detached.remnants.len(), 1);
+
+        mesh.restore_faces(&detached).unwrap();
+        assert!(f.iter().all(|f_id| mesh[*f_id].p == Some(f[0])));
+        assert!(!mesh.pieces.contains_key(&f[2]));
+        assert_eq!(mesh.iter_pieces().count(), 1);
+    }
+}
@@ -73,7 +73,7 @@ impl super::Mesh {
     }
 
     /// Adds an edge into the disk cycle around a vertex.
-    fn connect_edge_to_vert(&mut self, e: id::EdgeId, v: id::VertexId) {
+    pub(super) fn connect_edge_to_vert(&mut self, e: id::EdgeId, v: id::VertexId) {
         // If the vertex already has an edge, update that edge's DiskLink
         if let Some(e_first) = self[v].e {
             let e_last = self[e_first].disklink(v).prev;
@@ -91,6 +91,21 @@ impl super::Mesh {
         }
     }
 
+    /// Takes an edge out of the disk cycle around a vertex, moving the
+    /// vertex's own edge on if it was this one.
+    pub(super) fn disconnect_edge_from_vert(&mut self, e: id::EdgeId, v: id::VertexId) {
+        let DiskLink { prev, next } = *self[e].disklink(v);
+        if next == e {
+            self[v].e = None;
+            return;
+        }
+        self[prev].disklink_mut(v).next = next;
+        self[next].disklink_mut(v).prev = prev;
+        if self[v].e == Some(e) {
+            self[v].e = Some(next);
+        }
+    }
+
     /// Returns the edge between the supplied vertices, or `None`.
     pub fn query_edge(&self, v_a: id::VertexId, v_b: id::VertexId) -> Option<id::EdgeId> {
         if v_a == v_b {
@@ -9,7 +9,7 @@ use crate::MaterialId;
 use super::loop_::*;
 use super::MeshElementType;
 
-/// A face, formed by three vertices and three edges.
+/// A planar polygon, formed by a cycle of three or more vertices and edges.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Face {
     /// Face normal
@@ -22,23 +22,24 @@ pub struct Face {
     pub m: Option<MaterialId>,
 }
 
-/// Input parameters when creating a face
+/// Input parameters when creating a face. Per-corner data, when given, has one
+/// entry per vertex passed to `add_face`, in the same order.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct FaceDescriptor<'a> {
     pub m: Option<MaterialId>,
-    pub nos: Option<&'a [[f32; 3]; 3]>,
-    pub uvs: Option<&'a [[f32; 2]; 3]>,
+    pub nos: Option<&'a [[f32; 3]]>,
+    pub uvs: Option<&'a [[f32; 2]]>,
 }
 
 impl super::Mesh {
     /// Adds an ngon / face between any number of vertices. If a face already
     /// existed between the verts, returns that face instead.
-    pub fn add_face(
-        &mut self,
-        verts: &[id::VertexId; 3],
-        descriptor: &FaceDescriptor,
-    ) -> id::FaceId {
+    ///
+    /// The vertices should be planar: unfolding lays every face flat as a
+    /// whole, so a warped polygon would tear away from its neighbours.
+    pub fn add_face(&mut self, verts: &[id::VertexId], descriptor: &FaceDescriptor) -> id::FaceId {
         let &FaceDescriptor { uvs, m, nos } = descriptor;
+        assert!(verts.len() >= 3, "a face needs at least three vertices");
         // If face already exists, return it
         if let Some(f_id) = self.query_face(verts) {
             return f_id;
@@ -46,17 +47,13 @@ impl super::Mesh {
 
         // Otherwise, begin creating the face
         let f = id::FaceId::from_usize(self.faces.push(Face { m, ..Default::default() }));
-
-        // Calculate face normal
-        let v0 = cgmath::Vector3::from(self[verts[0]].po);
-        let v1 = cgmath::Vector3::from(self[verts[1]].po);
-        let v2 = cgmath::Vector3::from(self[verts[2]].po);
-        let normal = (v1 - v0).cross(v2 - v0).normalize();
-        self[f].no = normal.into();
+        let positions: Vec<_> = verts.iter().map(|v| self[*v].po).collect();
+        self[f].no = polygon_normal(&positions);
 
         // Create or use existing edges between all adjacent vertices
+        let n = verts.len();
         let edges: Vec<id::EdgeId> =
-            (0..3).map(|i| self.add_edge(verts[i], verts[(i + 1) % 3])).collect();
+            (0..n).map(|i| self.add_edge(verts[i], verts[(i + 1) % n])).collect();
 
         // Create the loops for the face, adding loop + radial data
         let l_start = id::LoopId::from_usize(self.loops.push(Loop {
@@ -70,7 +67,7 @@ impl super::Mesh {
         self.connect_loop_to_edge(l_start, edges[0]);
         self[f].l = l_start;
         let mut l_last = l_start;
-        for i in 1..3 {
+        for i in 1..n {
             let l = id::LoopId::from_usize(self.loops.push(Loop {
                 f,
                 e: edges[i],
@@ -108,7 +105,7 @@ impl super::Mesh {
                 loop {
                     // Cycle 2: Loops (radial) for each edge, aka faces containing e_iter
                     let l_curr = self[l_iter_radial];
-                    if l_curr.v == v0 {
+                    if l_curr.v == v0 && self.iter_face_loops(l_curr.f).count() == len {
                         // First two verts match, so iterate through for remaining verts
                         // Note that loop winding direction is undefined, so we
                         // need to iterate in both directions (next's and prev's).
@@ -164,6 +161,33 @@ impl super::Mesh {
     }
 }
 
+/// Twice the area of a polygon, along its normal, by Newell's method. This
+/// sums the cross products around the whole boundary rather than taking one
+/// corner's, so a polygon with a collinear or reflex first corner still faces
+/// the right way.
+fn newell_normal(positions: &[[f32; 3]]) -> cgmath::Vector3<f32> {
+    positions
+        .iter()
+        .zip(positions.iter().cycle().skip(1))
+        .map(|(a, b)| cgmath::Vector3::from(*a).cross(cgmath::Vector3::from(*b)))
+        .fold(cgmath::Vector3::new(0.0, 0.0, 0.0), |sum, cross| sum + cross)
+}
+
+/// The unit normal of a polygon. A polygon with no area has no direction to
+/// face, and gets a zero normal rather than a NaN one.
+pub(super) fn polygon_normal(positions: &[[f32; 3]]) -> [f32; 3] {
+    let normal = newell_normal(positions);
+    if normal.magnitude2() <= f32::MIN_POSITIVE {
+        return [0.0; 3];
+    }
+    normal.normalize().into()
+}
+
+/// The area of a planar polygon
+pub fn polygon_area(positions: &[[f32; 3]]) -> f32 {
+    newell_normal(positions).magnitude() / 2.0
+}
+
 // --- Section: Loop Cycle ---
 
 /// LoopCycle: Enables walking over the loops within a face
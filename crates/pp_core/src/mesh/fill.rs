@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::cut::{Cut, CutUpdate};
+use super::delete::{DetachedFaces, RestoreFacesError};
+use super::face::{polygon_normal, FaceDescriptor};
+use super::{Mesh, MeshElementType};
+use crate::id::{self, Id};
+
+/// Why a hole can't be filled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillHoleError {
+    /// Only an edge with exactly one face is on the border of a hole
+    NotBorder,
+    /// Following the border from the edge never came back round to it, e.g.
+    /// where it runs into a dangling edge
+    OpenBorder,
+}
+
+/// What `Mesh::fill_hole` added, which `unfill_hole` and `refill_hole` take
+/// back out and put back in
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FilledHole {
+    /// The fan of triangles across the hole, as `restore_faces` puts them back
+    pub faces: DetachedFaces,
+    /// The rim's edges which were cut to keep the fill its own piece, with
+    /// their cuts from before and after
+    pub rim: Vec<(id::EdgeId, Option<Cut>, Cut)>,
+}
+
+impl Mesh {
+    /// The border loops around the hole next to a border edge, in the order
+    /// the hole's own face would wind. These are the loops `LineKind::Border`
+    /// prints: each is the only loop on its edge.
+    pub fn hole_loops(&self, e_id: id::EdgeId) -> Result<Vec<id::LoopId>, FillHoleError> {
+        let l_start = self[e_id].l.ok_or(FillHoleError::NotBorder)?;
+        if self[l_start].radial_next != l_start {
+            return Err(FillHoleError::NotBorder);
+        }
+        // A border loop runs one way along its edge, so the hole runs the
+        // other: from the start loop's vertex on to the border loop which
+        // ends there
+        let mut loops = vec![l_start];
+        let mut visited = HashSet::from([l_start]);
+        loop {
+            let at = self[*loops.last().unwrap()].v;
+            let l_next = self
+                .iter_all_vert_edges(at)
+                .filter_map(|e| self[e].l)
+                .find(|l| self[*l].radial_next == *l && self[self[*l].next].v == at)
+                .ok_or(FillHoleError::OpenBorder)?;
+            if l_next == l_start {
+                return Ok(loops);
+            }
+            if !visited.insert(l_next) {
+                return Err(FillHoleError::OpenBorder);
+            }
+            loops.push(l_next);
+        }
+    }
+
+    /// Fills the hole next to a border edge with a fan of triangles, from the
+    /// first vertex of the edge's border loop. The fan winds against the
+    /// faces around it, so it faces the same way they do.
+    ///
+    /// Each corner borrows the UV its vertex has in the face across the rim,
+    /// and takes the fan's normal. If any face around the rim is in a piece,
+    /// the rim is cut and the fill becomes a piece of its own; joined on, it
+    /// would close a cycle that can't be unfolded.
+    pub fn fill_hole(&mut self, e_id: id::EdgeId) -> Result<FilledHole, FillHoleError> {
+        let loops = self.hole_loops(e_id)?;
+        let verts: Vec<_> = loops.iter().map(|l| self[*l].v).collect();
+        let uvs: Vec<_> = loops.iter().map(|l| self[*l].uv).collect();
+        let positions: Vec<_> = verts.iter().map(|v| self[*v].po).collect();
+        let no = polygon_normal(&positions);
+        let m = self[self[loops[0]].f].m;
+
+        let first = self.faces.next_push_index();
+        let fan: Vec<_> = (1..verts.len() - 1)
+            .map(|k| {
+                let uvs = [uvs[0], uvs[k], uvs[k + 1]];
+                let descriptor = FaceDescriptor { m, uvs: Some(&uvs), nos: Some(&[no; 3]) };
+                self.add_face(&[verts[0], verts[k], verts[k + 1]], &descriptor)
+            })
+            // A triangle which was already a face elsewhere isn't part of the fill
+            .filter(|f| f.to_usize() >= first)
+            .collect();
+
+        let mut rim = Vec::new();
+        if loops.iter().any(|l| self[self[*l].f].p.is_some()) {
+            for l in &loops {
+                let e = self[*l].e;
+                let before = self.cuts.get(&e).copied();
+                self.make_cut(e, CutUpdate::Nothing);
+                rim.push((e, before, Cut::default()));
+            }
+            if let Some(f) = fan.first() {
+                let _ = self.expand_piece(*f);
+                self.assign_piece_flaps(*f);
+            }
+            rim.iter_mut().for_each(|(e, _, after)| *after = self.cuts[e]);
+        }
+
+        // Taking the new faces straight back out records them exactly as
+        // they were made, IDs and all, for a redo to put back
+        let faces = self.delete_faces(&fan);
+        // Their slots were only just emptied, so they go straight back in
+        let _ = self.restore_faces(&faces);
+        Ok(FilledHole { faces, rim })
+    }
+
+    /// Takes a fill back out, leaving the hole and its rim as they were
+    pub fn unfill_hole(&mut self, hole: &FilledHole) {
+        self.delete_faces(&hole.faces.face_ids());
+        for (e, before, _) in &hole.rim {
+            match before {
+                Some(cut) => self.cuts.insert(*e, *cut),
+                None => self.cuts.remove(e),
+            };
+        }
+        self.elem_dirty |= MeshElementType::EDGES;
+    }
+
+    /// Puts a fill taken out by `unfill_hole` back in, with the same IDs
+    pub fn refill_hole(&mut self, hole: &FilledHole) -> Result<(), RestoreFacesError> {
+        self.restore_faces(&hole.faces)?;
+        for (e, _, after) in &hole.rim {
+            self.cuts.insert(*e, *after);
+        }
+        self.elem_dirty |= MeshElementType::EDGES;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::{EdgeId, FaceId, VertexId};
+
+    /// The cube with its top deleted, and one of the top's edges
+    fn open_cube() -> (Mesh, EdgeId) {
+        let mut cube = Mesh::new_cube();
+        let top: Vec<_> =
+            cube.faces.indices().map(FaceId::from_usize).filter(|f| cube[*f].no[2] > 0.5).collect();
+        cube.delete_faces(&top);
+        let e = cube.query_edge(VertexId::from_usize(4), VertexId::from_usize(5)).unwrap();
+        (cube, e)
+    }
+
+    fn is_closed(mesh: &Mesh) -> bool {
+        mesh.edges.indices().all(|e| {
+            let e = EdgeId::from_usize(e);
+            mesh.iter_edge_loops(e).is_some_and(|loops| loops.count() == 2)
+        })
+    }
+
+    #[test]
+    fn the_hole_is_the_open_top() {
+        let (cube, e) = open_cube();
+        let loops = cube.hole_loops(e).unwrap();
+        assert_eq!(loops.len(), 4);
+        // The two sides of an edge between faces aren't a hole
+        let side = cube.query_edge(VertexId::from_usize(0), VertexId::from_usize(4)).unwrap();
+        assert_eq!(cube.hole_loops(side), Err(FillHoleError::NotBorder));
+    }
+
+    #[test]
+    fn filling_the_top_closes_the_cube() {
+        let (mut cube, e) = open_cube();
+        let hole = cube.fill_hole(e).unwrap();
+        assert_eq!(hole.faces.faces.len(), 2);
+        assert!(hole.rim.is_empty());
+        assert!(is_closed(&cube));
+        // Wound like its neighbours, so it faces up and out
+        for f in hole.faces.face_ids() {
+            assert!(cube[f].no[2] > 0.99);
+        }
+        assert_eq!(cube.orient_faces().flipped, 0);
+    }
+
+    #[test]
+    fn a_fill_comes_out_and_goes_back_in() {
+        let (mut cube, e) = open_cube();
+        let hole = cube.fill_hole(e).unwrap();
+        cube.unfill_hole(&hole);
+        assert_eq!(cube.faces.num_elements(), 10);
+        assert_eq!(cube.hole_loops(e).unwrap().len(), 4);
+        cube.refill_hole(&hole).unwrap();
+        assert!(is_closed(&cube));
+        assert_eq!(cube.faces.num_elements(), 12);
+    }
+
+    /// Next to a piece, the fill is cut free as a piece of its own
+    #[test]
+    fn a_fill_next_to_a_piece_is_its_own_piece() {
+        let (mut cube, e) = open_cube();
+        // Open the box up along its vertical edges, making five pieces
+        for (a, b) in [(0, 4), (1, 5), (2, 6), (3, 7)] {
+            let e = cube.query_edge(VertexId::from_usize(a), VertexId::from_usize(b)).unwrap();
+            cube.make_cut(e, CutUpdate::PiecesAndFlaps);
+        }
+        let pieces = cube.iter_pieces().count();
+        assert!(pieces > 0);
+
+        let hole = cube.fill_hole(e).unwrap();
+        assert_eq!(hole.rim.len(), 4);
+        assert!(hole.rim.iter().all(|(e, before, _)| before.is_none() && cube.edge_is_cut(e)));
+        let fill = hole.faces.face_ids();
+        assert!(fill.iter().all(|f| cube[*f].p == Some(fill[0])));
+        assert_eq!(cube.iter_pieces().count(), pieces + 1);
+
+        cube.unfill_hole(&hole);
+        assert!(hole.rim.iter().all(|(e, _, _)| !cube.cuts.contains_key(e)));
+        assert_eq!(cube.iter_pieces().count(), pieces);
+    }
+}
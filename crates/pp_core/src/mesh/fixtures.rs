@@ -0,0 +1,38 @@
+//! Cut-open primitives for tests, in this crate and, behind the `test-utils`
+//! feature, in the crates building on it.
+
+use super::{cut::CutUpdate, Mesh};
+use crate::id::{Id, VertexId};
+
+impl Mesh {
+    /// The edges of [`Mesh::new_cube`] freeing its bottom and front quads as
+    /// one four-triangle strip, hinged on the edge `v0-v1` they share
+    pub const CUBE_STRIP: [(usize, usize); 6] = [(1, 2), (2, 3), (3, 0), (0, 4), (4, 5), (5, 1)];
+
+    /// The edges of [`Mesh::new_quad_cube`] cutting it into its bottom, its
+    /// top and the ring of sides between: the bottom's four, the top's four,
+    /// then one side seam to open the ring
+    pub const QUAD_CUBE_LIDS: [(usize, usize); 9] =
+        [(0, 1), (1, 2), (2, 3), (3, 0), (4, 5), (5, 6), (6, 7), (7, 4), (0, 4)];
+
+    /// A cube with [`Self::CUBE_STRIP`] cut
+    pub fn cut_cube() -> Self {
+        let mut mesh = Self::new_cube();
+        mesh.cut_all(&Self::CUBE_STRIP);
+        mesh
+    }
+
+    /// A quad cube with [`Self::QUAD_CUBE_LIDS`] cut, in three pieces
+    pub fn cut_quad_cube() -> Self {
+        let mut mesh = Self::new_quad_cube();
+        mesh.cut_all(&Self::QUAD_CUBE_LIDS);
+        mesh
+    }
+
+    fn cut_all(&mut self, edges: &[(usize, usize)]) {
+        for (a, b) in edges {
+            let e_id = self.query_edge(VertexId::from_usize(*a), VertexId::from_usize(*b)).unwrap();
+            self.make_cut(e_id, CutUpdate::PiecesAndFlaps);
+        }
+    }
+}
@@ -1,24 +1,284 @@
 use std::collections::BTreeMap;
 
-use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Point3, Transform, Vector3};
+use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Point3, Transform, Vector2, Vector3, Zero};
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
 
 use crate::{
-    id::{FaceId, LoopId, VertexId},
-    mesh::cut::FlapPosition,
+    id::{EdgeId, FaceId, LoopId, VertexId},
+    mesh::{cut::FlapPosition, MeshElementType},
+    MaterialId,
 };
 
-/// How far a flap may reach off the edge it hangs from, in centimeters.
+/// The default for how far a flap may reach off the edge it hangs from, in
+/// centimeters.
 ///
 /// A cap rather than a proportion: tabs are glued by hand, so past a few
 /// millimeters extra reach stops helping and just eats page area.
 pub const MAX_FLAP_HEIGHT: f32 = 0.3;
 
-/// The widest half-angle a flap's corner may take, in radians (45°).
+/// The default for the widest half-angle a flap's corner may take, in radians
+/// (45°).
 ///
 /// The flap is inscribed in the isosceles triangle standing on its base edge,
 /// which for a very obtuse neighbouring face would run away to a long spike.
 pub const MAX_FLAP_APEX_ANGLE: f32 = std::f32::consts::FRAC_PI_4;
 
+/// The shape limits every flap is built within. Each mesh has its own, which
+/// the document's settings are applied to all at once, and each cut can
+/// override them for its own flaps.
+///
+/// Thick cardstock wants bigger tabs to glue, and a tiny model smaller ones.
+#[derive(Debug, Clone, Copy, PartialEq, Tsify, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct FlapSettings {
+    /// How far a flap may reach off the edge it hangs from, in centimeters
+    pub height: f32,
+    /// The widest half-angle a flap's corner may take, in radians
+    pub angle: f32,
+    /// The shortest edge worth a flap, in centimeters. Shorter edges get none:
+    /// a sliver of a tab is more fiddly to fold than it helps to glue.
+    pub min_base: f32,
+}
+
+impl Default for FlapSettings {
+    fn default() -> Self {
+        Self { height: MAX_FLAP_HEIGHT, angle: MAX_FLAP_APEX_ANGLE, min_base: 0.0 }
+    }
+}
+
+/// How a flap's free edge is cut. Every style stays inside the trapezoid
+/// [`flap_corners`] gives, so the limits in [`FlapSettings`] hold for all of
+/// them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Tsify, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum FlapStyle {
+    /// The trapezoid itself
+    #[default]
+    Trapezoid,
+    /// The trapezoid with its top corners rounded off, which are the first
+    /// thing to catch and crease when a tab is tucked under its face
+    Rounded,
+    /// A row of teeth along the top, which bends to follow a curved seam and
+    /// leaves the glue somewhere to go other than out past the edge
+    Zigzag,
+    /// A rounded tongue which tucks through a slit in the face it folds onto,
+    /// hiding the tab and holding the seam in place while its glue sets. The
+    /// tongue is no wider than the slit, so it doesn't lock on its own.
+    SlotAndTab,
+}
+
+/// How many points every [`FlapOutline`] has, whatever its style. A fixed
+/// count lets the GPU take an outline as plain vertex attributes; styles
+/// needing fewer repeat their last point.
+pub const FLAP_OUTLINE_POINTS: usize = 16;
+
+/// The teeth along a [`FlapStyle::Zigzag`] flap's top
+const ZIGZAG_TEETH: usize = 5;
+
+/// How deep a zig-zag's valleys reach, as a fraction of the flap's height
+const ZIGZAG_DEPTH: f32 = 0.4;
+
+/// How much of the base a [`FlapStyle::SlotAndTab`] tongue may span: the
+/// middle, clear of the corners, where the slit across from it is least likely
+/// to run out of its face.
+const TONGUE_SPAN: (f32, f32) = (0.3, 0.7);
+
+/// The narrowest tongue worth a slit, as a fraction of the base. Narrower ones
+/// fall back to a plain trapezoid.
+const TONGUE_MIN_WIDTH: f32 = 0.2;
+
+/// The shape of a flap, as a polyline from one end of its base to the other.
+///
+/// Points are kept in the base's own frame — `[s, h]`, `s` the fraction of the
+/// way from `v0` to `v1` and `h` the distance off the base along `up` — and `s`
+/// never decreases along the outline. That makes the flap the union of one
+/// column per segment, each standing on the base beneath it, which is how both
+/// the GPU and the pattern export fill it in.
+///
+/// A slit is an outline too: a straight one, standing off the base of the
+/// face a slot-and-tab's tongue folds onto.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlapOutline {
+    pub v0: Point3<f32>,
+    pub v1: Point3<f32>,
+    /// Unit direction from the base toward the flap, in the flap's plane. Zero
+    /// for a flap which collapsed onto its base.
+    pub up: Vector3<f32>,
+    pub profile: [[f32; 2]; FLAP_OUTLINE_POINTS],
+}
+
+impl FlapOutline {
+    /// An outline lying flat along its base, which draws nothing
+    fn collapsed(v0: Point3<f32>, v1: Point3<f32>) -> Self {
+        let mut profile = [[1.0, 0.0]; FLAP_OUTLINE_POINTS];
+        profile[0] = [0.0, 0.0];
+        Self { v0, v1, up: Vector3::zero(), profile }
+    }
+
+    /// An outline through `points`, given as `[x, h]` with `x` the distance
+    /// along the base, padded out with the last one
+    fn through(v0: Point3<f32>, v1: Point3<f32>, up: Vector3<f32>, points: &[[f32; 2]]) -> Self {
+        let len = (v1 - v0).magnitude();
+        let mut profile = [[1.0, 0.0]; FLAP_OUTLINE_POINTS];
+        for (i, slot) in profile.iter_mut().enumerate() {
+            let [x, h] = points[i.min(points.len() - 1)];
+            *slot = [x / len, h];
+        }
+        Self { v0, v1, up, profile }
+    }
+
+    /// The outline's point `i`
+    pub fn point(&self, i: usize) -> Point3<f32> {
+        self.base_point(i) + self.up * self.profile[i][1]
+    }
+
+    /// The point on the base beneath the outline's point `i`
+    pub fn base_point(&self, i: usize) -> Point3<f32> {
+        self.v0 + (self.v1 - self.v0) * self.profile[i][0]
+    }
+
+    /// The outline's segments which are lines on the page: those with any
+    /// length, and not lying along the base, which is a fold.
+    pub fn segments(&self) -> impl Iterator<Item = (Point3<f32>, Point3<f32>)> + '_ {
+        (0..FLAP_OUTLINE_POINTS - 1)
+            .filter(|i| {
+                let ([s0, h0], [s1, h1]) = (self.profile[*i], self.profile[*i + 1]);
+                (s0 != s1 || h0 != h1) && (h0 > f32::EPSILON || h1 > f32::EPSILON)
+            })
+            .map(|i| (self.point(i), self.point(i + 1)))
+    }
+}
+
+/// The texture a flap carries on from the face it is glued beneath, so that a
+/// seam which doesn't quite close over its tab shows more of that face rather
+/// than a sliver of white paper.
+///
+/// Texture coordinates are given across a flap's outline, in the same `[s, h]`
+/// its profile points are: `s` along the base from `v0` to `v1`, `h` off it
+/// along `up`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlapTexture {
+    /// The material of the face across the cut, or none for the default
+    pub m: Option<MaterialId>,
+    /// The texture coordinates at the outline's `v0` and `v1`
+    pub base: [[f32; 2]; 2],
+    /// How far the texture coordinates move per unit along the outline's `up`
+    pub up: [f32; 2],
+}
+
+impl FlapTexture {
+    /// The texture coordinates at `[s, h]` on the flap
+    pub fn uv(&self, [s, h]: [f32; 2]) -> [f32; 2] {
+        let [b0, b1] = self.base.map(Vector2::from);
+        (b0 + (b1 - b0) * s + Vector2::from(self.up) * h).into()
+    }
+}
+
+/// The outline of a flap with the given `corners`, as [`flap_corners`] returns
+/// them, cut in `style`.
+///
+/// Like the corners, this is the one definition of a flap's shape: the GPU
+/// draws the outline's points as they come, and the vector print path strokes
+/// its [`FlapOutline::segments`].
+pub fn flap_outline(corners: &[Point3<f32>; 4], style: FlapStyle) -> FlapOutline {
+    let Some((up, len, h, a, b)) = flap_frame(corners) else {
+        return FlapOutline::collapsed(corners[0], corners[1]);
+    };
+    let (v0, v1) = (corners[0], corners[1]);
+    let trapezoid = [[0.0, 0.0], [a, h], [b, h], [len, 0.0]];
+    let points: Vec<[f32; 2]> = match style {
+        FlapStyle::Trapezoid => trapezoid.to_vec(),
+        FlapStyle::Rounded => {
+            // Round each top corner off over up to 40% of the height, or half
+            // of whichever side it joins is shorter
+            let r = 0.4 * h;
+            let top = r.min(0.5 * (b - a));
+            let toward = |from: [f32; 2], to: [f32; 2]| {
+                let (dx, dy) = (to[0] - from[0], to[1] - from[1]);
+                let side = (dx * dx + dy * dy).sqrt();
+                let d = r.min(0.5 * side) / side;
+                [from[0] + dx * d, from[1] + dy * d]
+            };
+            let mut points = vec![[0.0, 0.0]];
+            points.extend(bezier(toward([a, h], [0.0, 0.0]), [a, h], [a + top, h]));
+            points.extend(bezier([b - top, h], [b, h], toward([b, h], [len, 0.0])));
+            points.push([len, 0.0]);
+            points
+        }
+        FlapStyle::Zigzag => {
+            let tooth = (b - a) / ZIGZAG_TEETH as f32;
+            let mut points = vec![[0.0, 0.0], [a, h]];
+            for k in 0..ZIGZAG_TEETH {
+                points.push([a + (k as f32 + 0.5) * tooth, (1.0 - ZIGZAG_DEPTH) * h]);
+                points.push([a + (k as f32 + 1.0) * tooth, h]);
+            }
+            points.push([len, 0.0]);
+            points
+        }
+        FlapStyle::SlotAndTab => match tongue(len, a, b) {
+            Some((x0, x1)) => {
+                let mid = 0.5 * (x0 + x1);
+                let mut points = vec![[0.0, 0.0], [x0, 0.0], [x0, 0.5 * h]];
+                points.extend(bezier([x0, 0.5 * h], [x0, h], [mid, h]).skip(1));
+                points.extend(bezier([mid, h], [x1, h], [x1, 0.5 * h]).skip(1));
+                points.extend([[x1, 0.0], [len, 0.0]]);
+                points
+            }
+            None => trapezoid.to_vec(),
+        },
+    };
+    FlapOutline::through(v0, v1, up, &points)
+}
+
+/// The slit a [`FlapStyle::SlotAndTab`] flap with the given `corners` pushes
+/// its tongue through, or `None` if the flap is too small to have a tongue.
+///
+/// `corners` are the flap's, but in the space of the face it folds onto,
+/// where they lie over that face: the slit runs across the tongue's full
+/// width, halfway up it, where its sides are still straight, so the whole
+/// tongue slides through.
+pub fn slit_outline(corners: &[Point3<f32>; 4]) -> Option<FlapOutline> {
+    let (up, len, h, a, b) = flap_frame(corners)?;
+    let (x0, x1) = tongue(len, a, b)?;
+    Some(FlapOutline::through(corners[0], corners[1], up, &[[x0, 0.5 * h], [x1, 0.5 * h]]))
+}
+
+/// A flap's base frame: the direction it stands up in, its base's length, its
+/// height, and how far along the base its top corners sit. `None` for a flap
+/// collapsed onto its base.
+fn flap_frame(corners: &[Point3<f32>; 4]) -> Option<(Vector3<f32>, f32, f32, f32, f32)> {
+    let [v0, v1, top1, top0] = *corners;
+    let len = (v1 - v0).magnitude();
+    if len <= f32::EPSILON {
+        return None;
+    }
+    let along = (v1 - v0) / len;
+    let rise = top0 - v0;
+    let up = rise - along * rise.dot(along);
+    let h = up.magnitude();
+    if h <= f32::EPSILON {
+        return None;
+    }
+    Some((up / h, len, h, rise.dot(along), (top1 - v0).dot(along)))
+}
+
+/// Where along a base `len` long, with the flap's top running from `a` to
+/// `b`, a slot-and-tab's tongue stands
+fn tongue(len: f32, a: f32, b: f32) -> Option<(f32, f32)> {
+    let (x0, x1) = ((TONGUE_SPAN.0 * len).max(a), (TONGUE_SPAN.1 * len).min(b));
+    (x1 - x0 >= TONGUE_MIN_WIDTH * len).then_some((x0, x1))
+}
+
+/// Five points along the quadratic Bézier from `p0` to `p1` about `c`
+fn bezier(p0: [f32; 2], c: [f32; 2], p1: [f32; 2]) -> impl Iterator<Item = [f32; 2]> {
+    (0..5).map(move |i| {
+        let t = i as f32 / 4.0;
+        let (u, v, w) = ((1.0 - t) * (1.0 - t), 2.0 * t * (1.0 - t), t * t);
+        [u * p0[0] + v * c[0] + w * p1[0], u * p0[1] + v * c[1] + w * p1[1]]
+    })
+}
+
 /// The four corners of the trapezoid flap hanging off the edge `v0 -> v1`,
 /// as `[v0, v1, top1, top0]` — bottom-left, bottom-right, top-right, top-left.
 ///
@@ -28,29 +288,55 @@ pub const MAX_FLAP_APEX_ANGLE: f32 = std::f32::consts::FRAC_PI_4;
 /// apply the piece transform to place the result on a page.
 ///
 /// The flap is inscribed in the isosceles triangle standing on `v0 -> v1`, with
-/// its apex angle capped at [`MAX_FLAP_APEX_ANGLE`] and its height at
-/// [`MAX_FLAP_HEIGHT`], then cut off parallel to the base. Capping the angle
+/// its apex angle capped at `settings.angle` and its height at
+/// `settings.height`, then cut off parallel to the base. Capping the angle
 /// before the height is what keeps the tab inside the facing face: a flap that
 /// leaned out further than the face it folds onto would stick out past the
 /// silhouette once the model is assembled.
 ///
-/// This is the single definition of flap geometry. The GPU reads the corners it
-/// returns rather than deriving its own, and the vector print path strokes their
-/// outline, so the printed tab and the tab on screen cannot drift apart.
+/// This is the single definition of flap geometry. Every [`FlapStyle`] is cut
+/// out of the corners it returns by [`flap_outline`], which the GPU reads rather
+/// than deriving its own and the vector print path strokes, so the printed tab
+/// and the tab on screen cannot drift apart.
 ///
 /// Degenerate input — a zero-length base, or an anchor collinear with it — has
 /// no well-defined lean, so the flap collapses onto its base and renders as
-/// nothing.
-pub fn flap_corners(v0: Vector3<f32>, v1: Vector3<f32>, v2: Vector3<f32>) -> [Point3<f32>; 4] {
+/// nothing, as does a base shorter than `settings.min_base`.
+pub fn flap_corners(
+    v0: Vector3<f32>,
+    v1: Vector3<f32>,
+    v2: Vector3<f32>,
+    settings: &FlapSettings,
+) -> [Point3<f32>; 4] {
+    flap_corners_between(v0, v1, v2, v2, settings)
+}
+
+/// [`flap_corners`] for a facing face of any arity. `a0` and `a1` are that
+/// face's corners next to `v0` and `v1` respectively, which for a triangle are
+/// both its third vertex.
+///
+/// Each side of the flap leans no further than the facing face's angle at its
+/// own end of the base, and the flap stops short of whichever of `a0` / `a1`
+/// is nearer the base, so a long thin quad gets a shallow tab instead of one
+/// running off its far side. A triangle's apex is always at least as far out
+/// as the isosceles triangle the flap is inscribed in, so for triangles that
+/// last limit never bites.
+pub fn flap_corners_between(
+    v0: Vector3<f32>,
+    v1: Vector3<f32>,
+    a0: Vector3<f32>,
+    a1: Vector3<f32>,
+    settings: &FlapSettings,
+) -> [Point3<f32>; 4] {
     let collapsed =
         || [Point3::from_vec(v0), Point3::from_vec(v1), Point3::from_vec(v1), Point3::from_vec(v0)];
 
     // The direction the isosceles triangle extends: perpendicular to the base,
-    // in the plane the base and the anchor span.
+    // in the plane the base and the facing face span.
     let base_vec = v1 - v0;
     let base_len = base_vec.magnitude();
-    let tri_normal = base_vec.cross(v2 - v0);
-    if base_len <= f32::EPSILON || tri_normal.magnitude() <= f32::EPSILON {
+    let tri_normal = base_vec.cross(a0 - v0) + base_vec.cross(a1 - v1);
+    if base_len <= f32::EPSILON.max(settings.min_base) || tri_normal.magnitude() <= f32::EPSILON {
         return collapsed();
     }
     let base_dir = base_vec / base_len;
@@ -59,10 +345,10 @@ pub fn flap_corners(v0: Vector3<f32>, v1: Vector3<f32>, v2: Vector3<f32>) -> [Po
 
     // The apex of the triangle the flap is inscribed in, leaning no further than
     // the shallower of the facing face's two base corners.
-    let angle_at = |a: Vector3<f32>, b: Vector3<f32>| {
-        ((b - a).normalize().dot((v2 - a).normalize())).clamp(-1.0, 1.0).acos()
+    let angle_at = |a: Vector3<f32>, b: Vector3<f32>, anchor: Vector3<f32>| {
+        ((b - a).normalize().dot((anchor - a).normalize())).clamp(-1.0, 1.0).acos()
     };
-    let min_angle = MAX_FLAP_APEX_ANGLE.min(angle_at(v0, v1)).min(angle_at(v1, v0));
+    let min_angle = settings.angle.min(angle_at(v0, v1, a0)).min(angle_at(v1, v0, a1));
     let height = 0.5 * base_len * min_angle.tan();
     if !height.is_finite() || height <= f32::EPSILON {
         return collapsed();
@@ -71,13 +357,45 @@ pub fn flap_corners(v0: Vector3<f32>, v1: Vector3<f32>, v2: Vector3<f32>) -> [Po
 
     // Truncate the triangle to the height cap, keeping the sides' slope so every
     // tab in the document leans the same way its own geometry asks for.
-    let depth_scale = height.min(MAX_FLAP_HEIGHT) / height;
+    let room = (a0 - v0).dot(perp_dir).min((a1 - v1).dot(perp_dir));
+    if room <= f32::EPSILON {
+        return collapsed();
+    }
+    let depth_scale = height.min(settings.height).min(room) / height;
     let top0 = v0 + (apex - v0) * depth_scale;
     let top1 = v1 + (apex - v1) * depth_scale;
     [Point3::from_vec(v0), Point3::from_vec(v1), Point3::from_vec(top1), Point3::from_vec(top0)]
 }
 
 impl super::Mesh {
+    /// The shape limits of the flaps on an edge: its cut's own, if it has
+    /// them, or else the mesh's
+    pub fn cut_flap_settings(&self, e_id: EdgeId) -> FlapSettings {
+        self.cuts.get(&e_id).and_then(|cut| cut.flap).unwrap_or(self.flap_settings)
+    }
+
+    /// Overrides the flap shape of a cut, or with `None` goes back to the
+    /// mesh's. Edges which aren't cut are left alone.
+    pub fn set_cut_flap_settings(&mut self, e_id: EdgeId, settings: Option<FlapSettings>) {
+        if let Some(cut) = self.cuts.get_mut(&e_id) {
+            cut.flap = settings;
+            self.elem_dirty |= MeshElementType::FLAPS;
+        }
+    }
+
+    /// The style a cut's flaps are cut in
+    pub fn cut_flap_style(&self, e_id: EdgeId) -> FlapStyle {
+        self.cuts.get(&e_id).map(|cut| cut.style).unwrap_or_default()
+    }
+
+    /// Sets the style of a cut's flaps. Edges which aren't cut are left alone.
+    pub fn set_cut_flap_style(&mut self, e_id: EdgeId, style: FlapStyle) {
+        if let Some(cut) = self.cuts.get_mut(&e_id) {
+            cut.style = style;
+            self.elem_dirty |= MeshElementType::FLAPS;
+        }
+    }
+
     /// The four corners of the flap hanging over loop `l_id`, or `None` if that
     /// side of the edge carries no flap.
     ///
@@ -86,10 +404,11 @@ impl super::Mesh {
     /// The corners come back in the same piece-local unfolded space the walker
     /// works in, so placing them on a page still needs the piece transform.
     ///
-    /// The anchor a flap leans on is the third vertex of the face *across* the
-    /// cut, which is only in the right place once that face has been rotated onto
-    /// this one — the neighbour belongs to a different piece (or to none), so its
-    /// own unfolding says nothing about where it sits relative to this seam.
+    /// The anchors a flap leans on are the corners of the face *across* the
+    /// cut next to the seam, which are only in the right place once that face
+    /// has been rotated onto this one — the neighbour belongs to a different
+    /// piece (or to none), so its own unfolding says nothing about where it
+    /// sits relative to this seam.
     pub fn piece_flap_corners(
         &self,
         l_id: LoopId,
@@ -99,26 +418,141 @@ impl super::Mesh {
         if !self.loop_has_flap(l_id) {
             return None;
         }
-        let l = self[l_id];
-        let across = self[l.radial_next];
-        let (v0_id, v1_id) = (self[l.e].v[0], self[l.e].v[1]);
-        let (v0, v1) = (self.vert_pos(v0_id), self.vert_pos(v1_id));
-
         // Rotate the facing face onto this one about the shared edge.
         let local = self.unfold_hinge_affine(l_id, t);
+        Some(self.flap_corners_placed(l_id, affine, affine * local))
+    }
+
+    /// The corners of the flap over loop `l_id`, with the edge placed by `base`
+    /// and the facing face by `across`
+    fn flap_corners_placed(
+        &self,
+        l_id: LoopId,
+        base: Matrix4<f32>,
+        across: Matrix4<f32>,
+    ) -> [Point3<f32>; 4] {
+        let l = self[l_id];
+        let facing = self[l.radial_next];
+        let v0_id = self[l.e].v[0];
+        let place = |affine: Matrix4<f32>, v| {
+            affine.transform_point(Point3::from_vec(self.vert_pos(v))).to_vec()
+        };
+
+        // The anchors are the facing face's corners either side of the seam.
+        // `facing` starts at one end of the edge and its `next` at the other,
+        // so the corner before the one and the corner after the other are the
+        // neighbours of each end; for a triangle, both are its third vertex.
+        let before = self[facing.prev].v;
+        let after = self[self[facing.next].next].v;
+        let (a0, a1) = if facing.v == v0_id { (before, after) } else { (after, before) };
+
+        flap_corners_between(
+            place(base, v0_id),
+            place(base, self[l.e].v[1]),
+            place(across, a0),
+            place(across, a1),
+            &self.placed_flap_settings(l.e),
+        )
+    }
+
+    /// The shape limits flaps on an edge are placed with: its
+    /// [`Self::cut_flap_settings`], a paper's thickness shorter. A tab sits
+    /// that far behind the face it is glued to, so the fold at its far end
+    /// comes that much sooner.
+    fn placed_flap_settings(&self, e_id: EdgeId) -> FlapSettings {
+        let settings = self.cut_flap_settings(e_id);
+        FlapSettings { height: (settings.height - self.paper_thickness).max(0.0), ..settings }
+    }
+
+    /// The shape of the flap over loop `l_id`, cut in its cut's style, or
+    /// `None` if that side of the edge carries no flap. Placed like
+    /// [`Self::piece_flap_corners`].
+    pub fn piece_flap_outline(
+        &self,
+        l_id: LoopId,
+        affine: Matrix4<f32>,
+        t: f32,
+    ) -> Option<FlapOutline> {
+        let corners = self.piece_flap_corners(l_id, affine, t)?;
+        Some(flap_outline(&corners, self.cut_flap_style(self[l_id].e)))
+    }
+
+    /// The texture of the face across the cut from loop `l_id`, carried on over
+    /// `outline`, its flap. That face is hinged onto this one as the flap's
+    /// anchors are, where it lies exactly under the flap, so its texture goes
+    /// on across the seam as though the paper had never been cut.
+    pub fn piece_flap_texture(
+        &self,
+        l_id: LoopId,
+        outline: &FlapOutline,
+        affine: Matrix4<f32>,
+        t: f32,
+    ) -> Option<FlapTexture> {
+        let facing_id = self[l_id].radial_next;
+        if facing_id == l_id {
+            return None;
+        }
+        let across = affine * self.unfold_hinge_affine(l_id, t);
+        let uv_at = self.placed_uv_map(facing_id, across)?;
+        let base = [uv_at(outline.v0), uv_at(outline.v1)];
+        let up = uv_at(outline.v0 + outline.up) - base[0];
+        Some(FlapTexture {
+            m: self[self[facing_id].f].m,
+            base: base.map(Into::into),
+            up: up.into(),
+        })
+    }
+
+    /// The texture coordinates anywhere in the plane of loop `l_id`'s face,
+    /// once `placement` has laid that face out, extrapolated past its edges.
+    ///
+    /// The face's texture is taken from its first three corners from `l_id`,
+    /// which carry it exactly when it is mapped affinely, as unwrapped faces
+    /// are. `None` if those corners are degenerate.
+    pub(super) fn placed_uv_map(
+        &self,
+        l_id: LoopId,
+        placement: Matrix4<f32>,
+    ) -> Option<impl Fn(Point3<f32>) -> Vector2<f32>> {
+        let corner = |l_id: LoopId| {
+            let pos = placement.transform_point(Point3::from_vec(self.vert_pos(self[l_id].v)));
+            (pos, Vector2::from(self[l_id].uv))
+        };
+        let l = self[l_id];
+        let [(p0, uv0), (p1, uv1), (p2, uv2)] = [l_id, l.next, self[l.next].next].map(corner);
 
-        // The anchor is the facing face's one vertex that isn't on the seam.
-        let anchor = self
-            .iter_face_loops(across.f)
-            .map(|l| self[l].v)
-            .find(|v| *v != v0_id && *v != v1_id)?;
-        let anchor = (affine * local).transform_point(Point3::from_vec(self.vert_pos(anchor)));
+        // Solves `q - p0 = a * e1 + b * e2` in the plane of the three corners
+        let (e1, e2) = (p1 - p0, p2 - p0);
+        let (d11, d12, d22) = (e1.dot(e1), e1.dot(e2), e2.dot(e2));
+        let det = d11 * d22 - d12 * d12;
+        if det <= f32::EPSILON * d11 * d22 {
+            return None;
+        }
+        Some(move |q: Point3<f32>| {
+            let (r1, r2) = ((q - p0).dot(e1), (q - p0).dot(e2));
+            let (a, b) = ((r1 * d22 - r2 * d12) / det, (r2 * d11 - r1 * d12) / det);
+            uv0 + (uv1 - uv0) * a + (uv2 - uv0) * b
+        })
+    }
 
-        Some(flap_corners(
-            affine.transform_point(Point3::from_vec(v0)).to_vec(),
-            affine.transform_point(Point3::from_vec(v1)).to_vec(),
-            anchor.to_vec(),
-        ))
+    /// The slit in loop `l_id`'s face for the slot-and-tab flap folding onto
+    /// it from across the cut, or `None` if nothing folds onto it that needs
+    /// one. `affine` is the unfolding transform of `l_id`'s face.
+    ///
+    /// The flap is built from this face's corners wherever it sits, so laying
+    /// it out over this face needs no hinge: it is this face's own transform
+    /// that places both the seam and the anchors.
+    pub fn piece_flap_slit(&self, l_id: LoopId, affine: Matrix4<f32>) -> Option<FlapOutline> {
+        let flap_l_id = self[l_id].radial_next;
+        if flap_l_id == l_id
+            || self[flap_l_id].radial_next != l_id
+            || self.loop_has_flap(l_id)
+            || !self.loop_has_flap(flap_l_id)
+            || self.cut_flap_style(self[l_id].e) != FlapStyle::SlotAndTab
+        {
+            return None;
+        }
+        slit_outline(&self.flap_corners_placed(flap_l_id, affine, affine))
     }
 
     /// Chooses which side of each cut on a piece's boundary carries the flap.
@@ -234,7 +668,7 @@ impl super::Mesh {
 }
 
 /// Union-find root of `i`, halving the path as it climbs.
-fn find(parent: &mut [usize], mut i: usize) -> usize {
+pub(super) fn find(parent: &mut [usize], mut i: usize) -> usize {
     while parent[i] != i {
         parent[i] = parent[parent[i]];
         i = parent[i];
@@ -246,7 +680,13 @@ fn find(parent: &mut [usize], mut i: usize) -> usize {
 mod corner_tests {
     use cgmath::{InnerSpace, Vector3};
 
-    use super::{flap_corners, MAX_FLAP_APEX_ANGLE, MAX_FLAP_HEIGHT};
+    use super::{
+        flap_corners, flap_corners_between, flap_outline, slit_outline, FlapSettings, FlapStyle,
+        FLAP_OUTLINE_POINTS, MAX_FLAP_APEX_ANGLE, MAX_FLAP_HEIGHT,
+    };
+
+    const DEFAULT: FlapSettings =
+        FlapSettings { height: MAX_FLAP_HEIGHT, angle: MAX_FLAP_APEX_ANGLE, min_base: 0.0 };
 
     /// A base edge along x, with the anchor `reach` away from it in +y — the
     /// unfolded layout every flap sees, up to a rigid transform.
@@ -263,7 +703,7 @@ mod corner_tests {
     #[test]
     fn the_flap_stands_on_its_base_edge() {
         let [v0, v1, v2] = flap(1.0, 0.4);
-        let corners = flap_corners(v0, v1, v2);
+        let corners = flap_corners(v0, v1, v2, &DEFAULT);
         assert_eq!(corners[0], cgmath::Point3::new(0.0, 0.0, 0.0));
         assert_eq!(corners[1], cgmath::Point3::new(1.0, 0.0, 0.0));
     }
@@ -273,12 +713,12 @@ mod corner_tests {
     #[test]
     fn the_flap_leans_toward_the_anchor() {
         let [v0, v1, v2] = flap(1.0, 0.4);
-        let corners = flap_corners(v0, v1, v2);
+        let corners = flap_corners(v0, v1, v2, &DEFAULT);
         assert!(corners[2].y > 0.0, "top-right should lean toward the anchor");
         assert!(corners[3].y > 0.0, "top-left should lean toward the anchor");
 
         // Mirror the anchor and the flap must follow it.
-        let mirrored = flap_corners(v0, v1, Vector3::new(0.5, -0.4, 0.0));
+        let mirrored = flap_corners(v0, v1, Vector3::new(0.5, -0.4, 0.0), &DEFAULT);
         assert!(mirrored[2].y < 0.0 && mirrored[3].y < 0.0);
     }
 
@@ -287,7 +727,7 @@ mod corner_tests {
     #[test]
     fn a_symmetric_anchor_gives_a_symmetric_flap() {
         let [v0, v1, v2] = flap(2.0, 0.5);
-        let corners = flap_corners(v0, v1, v2);
+        let corners = flap_corners(v0, v1, v2, &DEFAULT);
         let mid = 0.5 * (corners[0].x + corners[1].x);
         assert!(
             ((corners[3].x - mid) + (corners[2].x - mid)).abs() < 1e-5,
@@ -302,7 +742,7 @@ mod corner_tests {
     fn the_flap_height_is_capped() {
         // A base this long would inscribe a 45-degree triangle 5cm tall.
         let [v0, v1, v2] = flap(10.0, 20.0);
-        let corners = flap_corners(v0, v1, v2);
+        let corners = flap_corners(v0, v1, v2, &DEFAULT);
         assert!(
             corners[2].y <= MAX_FLAP_HEIGHT + 1e-6,
             "expected the cap at {MAX_FLAP_HEIGHT}, got {}",
@@ -310,12 +750,26 @@ mod corner_tests {
         );
     }
 
+    /// Settings for thicker card raise the cap, and a minimum base length
+    /// drops the tabs on edges too short to be worth one.
+    #[test]
+    fn the_settings_shape_the_flap() {
+        let [v0, v1, v2] = flap(10.0, 20.0);
+        let thick = FlapSettings { height: 1.0, ..DEFAULT };
+        assert!((flap_corners(v0, v1, v2, &thick)[2].y - 1.0).abs() < 1e-5);
+
+        let [v0, v1, v2] = flap(0.5, 0.4);
+        let corners = flap_corners(v0, v1, v2, &FlapSettings { min_base: 1.0, ..DEFAULT });
+        assert_eq!(corners[3], corners[0], "a short edge should have no tab");
+        assert_eq!(corners[2], corners[1]);
+    }
+
     /// A shallow neighbour caps the tab below the height limit instead: the tab
     /// has to stay inside the face it folds onto.
     #[test]
     fn a_shallow_anchor_shortens_the_flap_below_the_cap() {
         let [v0, v1, v2] = flap(1.0, 0.05);
-        let corners = flap_corners(v0, v1, v2);
+        let corners = flap_corners(v0, v1, v2, &DEFAULT);
         assert!(
             corners[2].y < MAX_FLAP_HEIGHT,
             "a shallow face should bound the flap before the height cap does"
@@ -329,7 +783,7 @@ mod corner_tests {
     fn the_apex_angle_never_exceeds_the_cap() {
         for reach in [0.05, 0.2, 1.0, 5.0, 100.0] {
             let [v0, v1, v2] = flap(1.0, reach);
-            let corners = flap_corners(v0, v1, v2);
+            let corners = flap_corners(v0, v1, v2, &DEFAULT);
             // Re-derive the untruncated apex from the trapezoid's own sides.
             let side = corners[3] - corners[0];
             let base = (corners[1] - corners[0]).normalize();
@@ -348,7 +802,7 @@ mod corner_tests {
         let v0 = Vector3::new(0.0, 0.0, 0.0);
         let v1 = Vector3::new(1.0, 0.0, 0.0);
         for anchor in [Vector3::new(0.5, 0.0, 0.0), Vector3::new(2.0, 0.0, 0.0), v0] {
-            let corners = flap_corners(v0, v1, anchor);
+            let corners = flap_corners(v0, v1, anchor, &DEFAULT);
             assert!(
                 corners.iter().all(|c| c.x.is_finite() && c.y.is_finite() && c.z.is_finite()),
                 "collinear anchor {anchor:?} produced non-finite corners: {corners:?}"
@@ -362,7 +816,7 @@ mod corner_tests {
     #[test]
     fn a_zero_length_base_collapses_the_flap() {
         let v = Vector3::new(1.0, 2.0, 3.0);
-        let corners = flap_corners(v, v, Vector3::new(1.0, 3.0, 3.0));
+        let corners = flap_corners(v, v, Vector3::new(1.0, 3.0, 3.0), &DEFAULT);
         assert!(corners.iter().all(|c| c.x.is_finite() && c.y.is_finite() && c.z.is_finite()));
     }
 
@@ -375,19 +829,131 @@ mod corner_tests {
             Vector3::new(0.0, 0.0, 0.0),
             Vector3::new(1.0, 0.0, 0.0),
             Vector3::new(0.5, 0.0, 0.4),
+            &DEFAULT,
         );
         let flat = flap_corners(
             Vector3::new(0.0, 0.0, 0.0),
             Vector3::new(1.0, 0.0, 0.0),
             Vector3::new(0.5, 0.4, 0.0),
+            &DEFAULT,
         );
         assert!((corners[3].z.abs() - flat[3].y.abs()).abs() < 1e-6);
         assert!(corners[3].y.abs() < 1e-6, "the flap should stay in its own plane");
     }
+
+    /// A square facing face has right angles at both ends of the base, so the
+    /// flap leans at the capped angle, just as a tall triangle's would.
+    #[test]
+    fn a_square_facing_face_leans_like_a_tall_triangle() {
+        let (v0, v1) = (Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        let quad = flap_corners_between(
+            v0,
+            v1,
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+            &DEFAULT,
+        );
+        let tri = flap_corners(v0, v1, Vector3::new(0.5, 10.0, 0.0), &DEFAULT);
+        for (a, b) in quad.iter().zip(tri) {
+            assert!((a - b).magnitude() < 1e-6, "{a:?} != {b:?}");
+        }
+    }
+
+    /// A long thin facing quad has right-angled corners, which alone would let
+    /// the flap stand taller than the quad is wide.
+    #[test]
+    fn a_thin_facing_quad_limits_the_flap_height() {
+        let (v0, v1) = (Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        let corners = flap_corners_between(
+            v0,
+            v1,
+            Vector3::new(0.0, 0.1, 0.0),
+            Vector3::new(1.0, 0.1, 0.0),
+            &DEFAULT,
+        );
+        assert!((corners[2].y - 0.1).abs() < 1e-6, "the flap should reach the far side");
+        assert!((corners[3].y - 0.1).abs() < 1e-6);
+    }
+
+    /// Every style runs from one end of the base to the other without doubling
+    /// back, and stays inside the trapezoid, so the settings' limits hold for
+    /// all of them.
+    #[test]
+    fn every_style_stays_inside_the_trapezoid() {
+        let [v0, v1, v2] = flap(2.0, 1.0);
+        let corners = flap_corners(v0, v1, v2, &DEFAULT);
+        let top = corners[2].y;
+        for style in
+            [FlapStyle::Trapezoid, FlapStyle::Rounded, FlapStyle::Zigzag, FlapStyle::SlotAndTab]
+        {
+            let outline = flap_outline(&corners, style);
+            assert_eq!(outline.point(0), corners[0], "{style:?} should start at v0");
+            assert_eq!(outline.point(FLAP_OUTLINE_POINTS - 1), corners[1], "and end at v1");
+            for i in 0..FLAP_OUTLINE_POINTS {
+                let p = outline.point(i);
+                // Inside the sides' slope, under the top and above the base
+                let slope = corners[3].x / top;
+                assert!(p.y >= -1e-6 && p.y <= top + 1e-6, "{style:?} point {i}: {p:?}");
+                assert!(p.x >= p.y * slope - 1e-5, "{style:?} point {i}: {p:?}");
+                assert!(p.x <= 2.0 - p.y * slope + 1e-5, "{style:?} point {i}: {p:?}");
+                if i > 0 {
+                    assert!(outline.profile[i][0] >= outline.profile[i - 1][0]);
+                }
+            }
+        }
+    }
+
+    /// A trapezoid's outline is its three free sides, the same lines its
+    /// corners give.
+    #[test]
+    fn a_trapezoid_outline_is_the_free_sides() {
+        let [v0, v1, v2] = flap(1.0, 0.4);
+        let corners = flap_corners(v0, v1, v2, &DEFAULT);
+        let segments: Vec<_> = flap_outline(&corners, FlapStyle::Trapezoid).segments().collect();
+        assert_eq!(segments.len(), 3);
+        for ((from, to), side) in segments.iter().zip([[0, 3], [3, 2], [2, 1]]) {
+            assert!((from - corners[side[0]]).magnitude() < 1e-6);
+            assert!((to - corners[side[1]]).magnitude() < 1e-6);
+        }
+    }
+
+    /// The slit runs across the tongue's full width, inside the flap, so the
+    /// tongue fits through it.
+    #[test]
+    fn the_slit_fits_the_tongue() {
+        let [v0, v1, v2] = flap(2.0, 1.0);
+        let corners = flap_corners(v0, v1, v2, &DEFAULT);
+        let tongue = flap_outline(&corners, FlapStyle::SlotAndTab);
+        let slit = slit_outline(&corners).expect("a long edge has room for a tongue");
+        let width = |xs: Vec<f32>| {
+            xs.iter().copied().fold(f32::MIN, f32::max)
+                - xs.iter().copied().fold(f32::MAX, f32::min)
+        };
+        let tongue_xs = (0..FLAP_OUTLINE_POINTS)
+            .map(|i| tongue.point(i))
+            .filter(|p| p.y > 0.0)
+            .map(|p| p.x)
+            .collect();
+        let slit_xs = (0..FLAP_OUTLINE_POINTS).map(|i| slit.point(i).x).collect();
+        assert!((width(tongue_xs) - width(slit_xs)).abs() < 1e-5);
+        assert_eq!(slit.segments().count(), 1, "a slit is one straight cut");
+
+        // An edge too short for a tongue falls back to a plain tab, with no slit
+        let [v0, v1, v2] = flap(0.1, 1.0);
+        let corners = flap_corners(v0, v1, v2, &DEFAULT);
+        assert!(slit_outline(&corners).is_none());
+        assert_eq!(
+            flap_outline(&corners, FlapStyle::SlotAndTab),
+            flap_outline(&corners, FlapStyle::Trapezoid)
+        );
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use cgmath::{EuclideanSpace, InnerSpace, Point3, Transform};
+
+    use super::MAX_FLAP_HEIGHT;
     use crate::commands::{make_cuts::MakeCutsCommand, Command};
     use crate::id::{EdgeId, Id, VertexId};
     use crate::mesh::cut::CutUpdate;
@@ -423,6 +989,48 @@ mod tests {
         }
     }
 
+    /// With every face textured straight from its position, each point of a
+    /// flap should take the texture of the point on the face across the cut it
+    /// is glued to: the one it lands on once that face is hinged flat.
+    #[test]
+    fn flaps_carry_on_the_texture_across_the_cut() {
+        let (mut state, m_id) = cube();
+        let planar = |p: cgmath::Vector3<f32>| [p.x + 0.5 * p.z, p.y - 0.25 * p.z];
+        let mesh = &mut state.meshes[m_id];
+        let loops: Vec<_> = mesh.iter_loops().collect();
+        for l_id in loops {
+            let uv = planar(mesh.vert_pos(mesh[l_id].v));
+            mesh[l_id].uv = uv;
+        }
+        cut(&mut state, m_id, &BOTTOM_RING);
+        let root = only_piece(&state, m_id);
+        let mesh = &state.meshes[m_id];
+
+        let walker = mesh.iter_piece_faces_unfolded(root);
+        let t = walker.t;
+        let mut flaps = 0;
+        for face in walker {
+            for l_id in mesh.iter_face_loops(face.f) {
+                let Some(outline) = mesh.piece_flap_outline(l_id, face.affine, t) else {
+                    continue;
+                };
+                let texture = mesh
+                    .piece_flap_texture(l_id, &outline, face.affine, t)
+                    .expect("a flap has a face across the cut");
+                flaps += 1;
+                let across = face.affine * mesh.unfold_hinge_affine(l_id, t);
+                let into_mesh = across.inverse_transform().unwrap();
+                for i in 0..super::FLAP_OUTLINE_POINTS {
+                    let on_face = into_mesh.transform_point(outline.point(i)).to_vec();
+                    let [u, v] = texture.uv(outline.profile[i]);
+                    let [eu, ev] = planar(on_face);
+                    assert!((u - eu).abs() < 1e-4 && (v - ev).abs() < 1e-4, "{i}: {u},{v}");
+                }
+            }
+        }
+        assert_eq!(flaps, 4);
+    }
+
     /// Whether the flap on `e_id` sits over the face belonging to piece `root`.
     fn flap_is_on(state: &crate::State, m_id: crate::MeshId, e_id: EdgeId, root: FaceId) -> bool {
         let mesh = &state.meshes[m_id];
@@ -539,4 +1147,84 @@ mod tests {
             .collect();
         assert_eq!(after, before, "undo should restore every flap, manual swap included");
     }
+
+    /// Cutting the top ring's path and the four uprights of a quad cube leaves
+    /// the classic cross-shaped net: one piece of six quads, lying flat once
+    /// fully unfolded, with every flap inside the quad it folds onto.
+    #[test]
+    fn a_quad_cube_unfolds_into_a_flat_net() {
+        let mut state = crate::State::default();
+        let m_id = state.meshes.insert(crate::mesh::Mesh::new_quad_cube());
+        cut(&mut state, m_id, &[(4, 5), (5, 6), (6, 7), (0, 4), (1, 5), (2, 6), (3, 7)]);
+        let root = only_piece(&state, m_id);
+        let mesh = &state.meshes[m_id];
+
+        let walker = mesh.iter_piece_faces_unfolded(root);
+        let t = walker.t;
+        let mut faces = 0;
+        let mut flaps = 0;
+        for face in walker {
+            faces += 1;
+            for l_id in mesh.iter_face_loops(face.f) {
+                let p = face.affine.transform_point(Point3::from_vec(mesh.vert_pos(mesh[l_id].v)));
+                assert!(p.z.abs() < 1e-5, "every corner should lie flat, got {p:?}");
+                if let Some(corners) = mesh.piece_flap_corners(l_id, face.affine, t) {
+                    flaps += 1;
+                    // The sides lean at no more than 45°, so they're at most √2
+                    // times the capped height
+                    let side = (corners[3] - corners[0]).magnitude();
+                    let longest = MAX_FLAP_HEIGHT * std::f32::consts::SQRT_2 + 1e-5;
+                    assert!(side > 0.0 && side <= longest, "{corners:?}");
+                    assert!(corners.iter().all(|c| c.z.abs() < 1e-5), "flaps lie flat too");
+                }
+            }
+        }
+        assert_eq!(faces, 6);
+        // Each of the seven cuts is a seam within the piece, flapped on one side
+        assert_eq!(flaps, 7);
+    }
+
+    /// Slot-and-tab flaps slit the face across each seam, inside that face,
+    /// and the other side of the seam — the tab's own — gets no slit.
+    #[test]
+    fn slot_and_tab_flaps_slit_the_face_across() {
+        let mut state = crate::State::default();
+        let m_id = state.meshes.insert(crate::mesh::Mesh::new_quad_cube());
+        cut(&mut state, m_id, &[(4, 5), (5, 6), (6, 7), (0, 4), (1, 5), (2, 6), (3, 7)]);
+        let cuts: Vec<EdgeId> = state.meshes[m_id].cuts.keys().copied().collect();
+        for e_id in cuts {
+            state.meshes[m_id].set_cut_flap_style(e_id, super::FlapStyle::SlotAndTab);
+        }
+        let root = only_piece(&state, m_id);
+        let mesh = &state.meshes[m_id];
+
+        let mut slits = 0;
+        for face in mesh.iter_piece_faces_unfolded(root) {
+            for l_id in mesh.iter_face_loops(face.f) {
+                let Some(slit) = mesh.piece_flap_slit(l_id, face.affine) else { continue };
+                slits += 1;
+                assert!(!mesh.loop_has_flap(l_id), "the tab's own side is not slit");
+                // The net's faces are axis-aligned squares, so inside one is
+                // within its bounds
+                let corner = |v| face.affine.transform_point(Point3::from_vec(mesh.vert_pos(v)));
+                let corners: Vec<_> =
+                    mesh.iter_face_loops(face.f).map(|l| corner(mesh[l].v)).collect();
+                let (min, max) = corners.iter().fold(
+                    (Point3::new(f32::MAX, f32::MAX, 0.0), Point3::new(f32::MIN, f32::MIN, 0.0)),
+                    |(lo, hi), p| {
+                        (
+                            Point3::new(lo.x.min(p.x), lo.y.min(p.y), 0.0),
+                            Point3::new(hi.x.max(p.x), hi.y.max(p.y), 0.0),
+                        )
+                    },
+                );
+                for (from, to) in slit.segments() {
+                    for p in [from, to] {
+                        assert!(p.x > min.x && p.x < max.x && p.y > min.y && p.y < max.y, "{p:?}");
+                    }
+                }
+            }
+        }
+        assert_eq!(slits, 7, "one slit for each tongue");
+    }
 }
@@ -0,0 +1,288 @@
+use std::collections::BTreeMap;
+
+use cgmath::InnerSpace;
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use crate::id::{EdgeId, FaceId, LoopId, VertexId};
+
+use super::cut::FlapPosition;
+
+/// How [`super::Mesh::reassign_flaps`] chooses which side of each cut carries
+/// its flap
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Tsify, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum FlapStrategy {
+    /// The rule cutting follows: every seam's tabs on one side, and on the
+    /// piece side of a seam facing faces in no piece
+    #[default]
+    Runs,
+    /// Tabs take turns along each seam, so both pieces get a grip on it
+    Alternate,
+    /// Tabs on whichever piece comes later in the mesh's assembly order,
+    /// so each new piece brings the tabs it glues on by
+    LaterPiece,
+    /// A single tab per seam, on its longest edge: the least cutting and
+    /// gluing which still holds every seam
+    Fewest,
+    /// Tabs on the larger of the two faces at each edge, which has the most
+    /// paper to fold them from
+    LargerFace,
+}
+
+/// One edge of a seam, as walked along it
+#[derive(Clone, Copy)]
+struct SeamEdge {
+    e: EdgeId,
+    /// The loop running the way the walk does, whose face is on the seam's left
+    left: LoopId,
+    /// The loop running against the walk, on the seam's right
+    right: LoopId,
+}
+
+impl super::Mesh {
+    /// Rechooses the side of every cut's flap with `strategy`.
+    ///
+    /// Unlike the pass cutting runs, this overrides the cuts left with a flap
+    /// on both sides or on neither: it is an explicit request to lay out every
+    /// flap afresh. A seam facing faces in no piece still always takes its
+    /// tabs on the piece side, as there is nothing on the other to carry them.
+    pub fn reassign_flaps(&mut self, strategy: FlapStrategy) {
+        let cuts: Vec<EdgeId> =
+            self.cuts.keys().copied().filter(|e_id| self.cut_sides(*e_id).is_some()).collect();
+        if strategy == FlapStrategy::Runs {
+            // From a clean slate, so that the majority rule keeps nothing
+            cuts.iter().for_each(|e_id| self.set_cut_flap(*e_id, FlapPosition::FirstFace));
+            let roots: Vec<FaceId> = self.iter_pieces().copied().collect();
+            roots.into_iter().for_each(|root| self.assign_piece_flaps(root));
+            return;
+        }
+
+        let order = match strategy {
+            FlapStrategy::LaterPiece => self.assembly_order(),
+            _ => Vec::new(),
+        };
+        let assembled_at =
+            |root: Option<FaceId>| root.and_then(|root| order.iter().position(|r| *r == root));
+
+        for seam in self.seams(&cuts) {
+            let piece = |l_id: LoopId| self[self[l_id].f].p;
+            let (first_left, first_right) = (piece(seam[0].left), piece(seam[0].right));
+            let choice: Vec<Option<LoopId>> = match (first_left, first_right) {
+                (None, None) => continue,
+                (Some(_), None) => seam.iter().map(|edge| Some(edge.left)).collect(),
+                (None, Some(_)) => seam.iter().map(|edge| Some(edge.right)).collect(),
+                _ => match strategy {
+                    FlapStrategy::Runs => unreachable!(),
+                    FlapStrategy::Alternate => seam
+                        .iter()
+                        .enumerate()
+                        .map(|(i, edge)| Some(if i % 2 == 0 { edge.left } else { edge.right }))
+                        .collect(),
+                    FlapStrategy::LaterPiece => seam
+                        .iter()
+                        .map(|edge| {
+                            let later =
+                                assembled_at(piece(edge.right)) > assembled_at(piece(edge.left));
+                            Some(if later { edge.right } else { edge.left })
+                        })
+                        .collect(),
+                    FlapStrategy::Fewest => {
+                        let length = |e: EdgeId| {
+                            let [v0, v1] = self[e].v;
+                            (self.vert_pos(v1) - self.vert_pos(v0)).magnitude2()
+                        };
+                        let longest =
+                            seam.iter()
+                                .enumerate()
+                                .reduce(|best, next| {
+                                    if length(next.1.e) > length(best.1.e) {
+                                        next
+                                    } else {
+                                        best
+                                    }
+                                })
+                                .map(|(i, _)| i);
+                        seam.iter()
+                            .enumerate()
+                            .map(|(i, edge)| (Some(i) == longest).then(|| self.larger_side(edge)))
+                            .collect()
+                    }
+                    FlapStrategy::LargerFace => {
+                        seam.iter().map(|edge| Some(self.larger_side(edge))).collect()
+                    }
+                },
+            };
+            for (edge, on) in seam.iter().zip(choice) {
+                let position = match on {
+                    Some(l_id) => self.flap_position_over_loop(l_id),
+                    None => FlapPosition::None,
+                };
+                self.set_cut_flap(edge.e, position);
+            }
+        }
+    }
+
+    /// The side of `edge` with the larger face, ties going left
+    fn larger_side(&self, edge: &SeamEdge) -> LoopId {
+        let (left, right) = (self.face_area(self[edge.left].f), self.face_area(self[edge.right].f));
+        if right > left {
+            edge.right
+        } else {
+            edge.left
+        }
+    }
+
+    /// `cuts` gathered into seams: runs of cuts joined end to end between the
+    /// same two pieces, or between a piece and faces in none, each in order
+    /// along its length. Seams come out in the order of their lowest edge, and
+    /// walk from the lowest edge at an end of them, so that every client finds
+    /// the same ones.
+    fn seams(&self, cuts: &[EdgeId]) -> Vec<Vec<SeamEdge>> {
+        let pieces = |e_id: EdgeId| {
+            let [a, b] = self.cut_sides(e_id).unwrap();
+            let mut pieces = [a.piece, b.piece];
+            pieces.sort();
+            pieces
+        };
+
+        // Union cuts which meet at a vertex and part the same pieces
+        let mut at_vert: BTreeMap<VertexId, Vec<usize>> = BTreeMap::new();
+        for (i, e_id) in cuts.iter().enumerate() {
+            self[*e_id].v.iter().for_each(|v| at_vert.entry(*v).or_default().push(i));
+        }
+        let mut parent: Vec<usize> = (0..cuts.len()).collect();
+        for meeting in at_vert.values() {
+            for (n, a) in meeting.iter().copied().enumerate() {
+                for b in meeting[n + 1..].iter().copied() {
+                    if pieces(cuts[a]) != pieces(cuts[b]) {
+                        continue;
+                    }
+                    let (root_a, root_b) =
+                        (super::flap::find(&mut parent, a), super::flap::find(&mut parent, b));
+                    if root_a != root_b {
+                        parent[root_a] = root_b;
+                    }
+                }
+            }
+        }
+        let mut runs: BTreeMap<usize, Vec<EdgeId>> = BTreeMap::new();
+        for (i, e_id) in cuts.iter().enumerate() {
+            let root = super::flap::find(&mut parent, i);
+            runs.entry(root).or_default().push(*e_id);
+        }
+        let mut runs: Vec<Vec<EdgeId>> = runs.into_values().collect();
+        runs.sort_by_key(|run| run.iter().min().copied());
+
+        runs.into_iter().map(|run| self.walk_seam(run)).collect()
+    }
+
+    /// Orders a seam's edges end to end, noting which side of the walk each
+    /// of their loops is on
+    fn walk_seam(&self, mut run: Vec<EdgeId>) -> Vec<SeamEdge> {
+        run.sort();
+        let degree = |v: VertexId| run.iter().filter(|e_id| self[**e_id].v.contains(&v)).count();
+        // Start at an end if the seam has one, or anywhere on a closed ring
+        let start = run
+            .iter()
+            .copied()
+            .find_map(|e_id| {
+                let [v0, v1] = self[e_id].v;
+                (degree(v0) == 1).then_some((e_id, v0)).or((degree(v1) == 1).then_some((e_id, v1)))
+            })
+            .unwrap_or((run[0], self[run[0]].v[0]));
+
+        let mut walked: Vec<SeamEdge> = Vec::with_capacity(run.len());
+        let mut next = Some(start);
+        while let Some((e_id, from)) = next {
+            let [a, b] = self.cut_sides(e_id).unwrap().map(|side| side.l);
+            let (left, right) = if self[a].v == from { (a, b) } else { (b, a) };
+            walked.push(SeamEdge { e: e_id, left, right });
+            let to = self[e_id].v.into_iter().find(|v| *v != from).unwrap_or(from);
+            next = run
+                .iter()
+                .copied()
+                .filter(|e_id| !walked.iter().any(|edge| edge.e == *e_id))
+                .find(|e_id| self[*e_id].v.contains(&to))
+                .map(|e_id| (e_id, to));
+            // A seam which branches is walked one branch at a time
+            if next.is_none() {
+                next = run
+                    .iter()
+                    .copied()
+                    .find(|e_id| !walked.iter().any(|edge| edge.e == *e_id))
+                    .map(|e_id| (e_id, self[e_id].v[0]));
+            }
+        }
+        walked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FlapStrategy;
+    use crate::id::{EdgeId, FaceId, Id, VertexId};
+    use crate::mesh::Mesh;
+
+    /// [`Mesh::cut_quad_cube`], and the four cuts around its bottom
+    fn lidded_cube() -> (Mesh, Vec<EdgeId>) {
+        let mesh = Mesh::cut_quad_cube();
+        let v = VertexId::from_usize;
+        let bottom = Mesh::QUAD_CUBE_LIDS[..4]
+            .iter()
+            .map(|(a, b)| mesh.query_edge(v(*a), v(*b)).unwrap())
+            .collect();
+        assert_eq!(mesh.iter_pieces().count(), 3);
+        (mesh, bottom)
+    }
+
+    /// How many of `edges` have their flap over the bottom face
+    fn flaps_on_bottom(mesh: &Mesh, edges: &[EdgeId]) -> usize {
+        let bottom = FaceId::from_usize(0);
+        edges
+            .iter()
+            .flat_map(|e_id| mesh.iter_edge_loops(*e_id).unwrap())
+            .filter(|l_id| mesh[*l_id].f == bottom && mesh.loop_has_flap(*l_id))
+            .count()
+    }
+
+    #[test]
+    fn alternating_tabs_take_turns_around_the_seam() {
+        let (mut mesh, ring) = lidded_cube();
+        mesh.reassign_flaps(FlapStrategy::Alternate);
+        assert_eq!(flaps_on_bottom(&mesh, &ring), 2);
+        let flapped = ring.iter().flat_map(|e_id| mesh.iter_edge_loops(*e_id).unwrap());
+        assert_eq!(flapped.filter(|l_id| mesh.loop_has_flap(*l_id)).count(), 4);
+    }
+
+    /// The ring of sides outweighs the bottom, so the bottom goes on after it
+    /// and brings every tab
+    #[test]
+    fn the_later_piece_takes_the_tabs() {
+        let (mut mesh, ring) = lidded_cube();
+        mesh.reassign_flaps(FlapStrategy::LaterPiece);
+        assert_eq!(flaps_on_bottom(&mesh, &ring), 4);
+    }
+
+    /// An order stored on the mesh wins over the suggested one
+    #[test]
+    fn the_later_piece_follows_the_stored_order() {
+        let (mut mesh, ring) = lidded_cube();
+        mesh.assembly = vec![mesh[FaceId::from_usize(0)].p.unwrap()];
+        mesh.reassign_flaps(FlapStrategy::LaterPiece);
+        assert_eq!(flaps_on_bottom(&mesh, &ring), 0);
+    }
+
+    #[test]
+    fn the_fewest_tabs_are_one_per_seam() {
+        let (mut mesh, ring) = lidded_cube();
+        mesh.reassign_flaps(FlapStrategy::Fewest);
+        let flapped = ring.iter().flat_map(|e_id| mesh.iter_edge_loops(*e_id).unwrap());
+        assert_eq!(flapped.filter(|l_id| mesh.loop_has_flap(*l_id)).count(), 1);
+
+        mesh.reassign_flaps(FlapStrategy::Runs);
+        let flapped = ring.iter().flat_map(|e_id| mesh.iter_edge_loops(*e_id).unwrap());
+        assert_eq!(flapped.filter(|l_id| mesh.loop_has_flap(*l_id)).count(), 4);
+    }
+}
@@ -0,0 +1,309 @@
+use serde::{Deserialize, Serialize};
+
+use super::{face::polygon_normal, Mesh, MeshElementType};
+use crate::id;
+
+/// Why an edge can't be flipped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlipEdgeError {
+    /// Only an edge between exactly two triangles, wound opposite ways along
+    /// it, has another diagonal to turn onto
+    NotBetweenTwoTriangles,
+    /// Cuts are where pieces come apart, so flipping one would move the seam
+    IsCut,
+    /// The other diagonal is already an edge of the mesh, e.g. on a tetrahedron
+    DiagonalExists,
+}
+
+/// The UVs and normals an edge's two corners had before it was flipped. A
+/// flip moves those corners onto the other diagonal, so this is what it
+/// takes to put them back exactly, even across a UV seam.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct FlippedCorners {
+    pub uvs: [[f32; 2]; 2],
+    pub nos: [[f32; 3]; 2],
+}
+
+/// The pieces of the quad around an edge `a -> b` between triangles `a b c`
+/// and `b a d`, by their loops
+struct Quad {
+    /// The edge's loop in the first triangle, starting at `a`
+    la: id::LoopId,
+    /// The edge's loop in the second triangle, starting at `b`
+    lb: id::LoopId,
+    /// The first triangle's other loops, starting at `b` and at `c`
+    la1: id::LoopId,
+    la2: id::LoopId,
+    /// The second triangle's other loops, starting at `a` and at `d`
+    lb1: id::LoopId,
+    lb2: id::LoopId,
+}
+
+impl Mesh {
+    /// Turns an edge between two triangles onto the quad's other diagonal.
+    /// For a quad which isn't flat, this is what decides whether the fold
+    /// across it is a mountain or a valley.
+    ///
+    /// Everything keeps its ID: the edge, the two faces and all six loops are
+    /// relinked in place, so selections, pieces and the cuts around the quad
+    /// carry on as they were. Returns what `unflip_edge` needs to undo it.
+    pub fn flip_edge(&mut self, e_id: id::EdgeId) -> Result<FlippedCorners, FlipEdgeError> {
+        let quad = self.flippable_quad(e_id)?;
+        let Quad { la, lb, la1, la2, lb1, lb2 } = quad;
+        let before = self.edge_corners(&quad);
+        // a b c + b a d  ->  c d b + d c a
+        self.relink_quad(e_id, &quad, [[la, lb2, la1], [lb, la2, lb1]]);
+        Ok(before)
+    }
+
+    /// Reverses `flip_edge`, turning the edge back onto the diagonal it was
+    /// flipped from. Flipping again would give the same diagonal, but with
+    /// the two faces swapped over.
+    pub fn unflip_edge(
+        &mut self,
+        e_id: id::EdgeId,
+        before: &FlippedCorners,
+    ) -> Result<(), FlipEdgeError> {
+        let quad = self.flippable_quad(e_id)?;
+        let Quad { la, lb, la1, la2, lb1, lb2 } = quad;
+        // c d b + d c a  ->  a b c + b a d
+        self.relink_quad(e_id, &quad, [[la, la2, lb1], [lb, lb2, la1]]);
+        for (k, l) in [la, lb].into_iter().enumerate() {
+            self[l].uv = before.uvs[k];
+            self[l].no = before.nos[k];
+        }
+        Ok(())
+    }
+
+    /// The quad around an edge, if it can be flipped
+    fn flippable_quad(&self, e_id: id::EdgeId) -> Result<Quad, FlipEdgeError> {
+        if self.edge_is_cut(&e_id) {
+            return Err(FlipEdgeError::IsCut);
+        }
+        let loops: Vec<_> = self.iter_edge_loops(e_id).into_iter().flatten().collect();
+        let &[l_x, l_y] = &loops[..] else {
+            return Err(FlipEdgeError::NotBetweenTwoTriangles);
+        };
+        let [v_a, v_b] = self[e_id].v;
+        let (la, lb) = if self[l_x].v == v_a { (l_x, l_y) } else { (l_y, l_x) };
+        let is_triangle = |l: id::LoopId| self.iter_face_loops(self[l].f).count() == 3;
+        if self[la].v != v_a
+            || self[lb].v != v_b
+            || self[la].f == self[lb].f
+            || !is_triangle(la)
+            || !is_triangle(lb)
+        {
+            return Err(FlipEdgeError::NotBetweenTwoTriangles);
+        }
+        let quad = Quad {
+            la,
+            lb,
+            la1: self[la].next,
+            la2: self[la].prev,
+            lb1: self[lb].next,
+            lb2: self[lb].prev,
+        };
+        let (v_c, v_d) = (self[quad.la2].v, self[quad.lb2].v);
+        if v_c == v_d || self.query_edge(v_c, v_d).is_some() {
+            return Err(FlipEdgeError::DiagonalExists);
+        }
+        Ok(quad)
+    }
+
+    fn edge_corners(&self, quad: &Quad) -> FlippedCorners {
+        FlippedCorners {
+            uvs: [self[quad.la].uv, self[quad.lb].uv],
+            nos: [self[quad.la].no, self[quad.lb].no],
+        }
+    }
+
+    /// Rebuilds the quad's two triangles from its loops. Each triangle starts
+    /// with one of the edge's own loops, which moves to the corner its
+    /// triangle's last loop ends at; the other loops keep their vertices and
+    /// edges, and only change face.
+    fn relink_quad(&mut self, e_id: id::EdgeId, quad: &Quad, triangles: [[id::LoopId; 3]; 2]) {
+        let faces = [self[quad.la].f, self[quad.lb].f];
+        let [v_a, v_b] = self[e_id].v;
+        // Each of the edge's loops takes its new corner's UV and normal from
+        // whichever loop was at that corner, before the faces are relinked
+        let corner_of = |mesh: &Self, v: id::VertexId| {
+            let l = [quad.la1, quad.la2, quad.lb1, quad.lb2].into_iter().find(|l| mesh[*l].v == v);
+            l.map(|l| (mesh[l].uv, mesh[l].no)).unwrap_or_default()
+        };
+        let starts = triangles.map(|[_, _, last]| self[self[last].e].other_vert(self[last].v));
+        let corners = starts.map(|v| corner_of(self, v));
+
+        // The edge leaves the disks around its old ends for its new ones
+        self.disconnect_edge_from_vert(e_id, v_a);
+        self.disconnect_edge_from_vert(e_id, v_b);
+        self[e_id].v = starts;
+        self.connect_edge_to_vert(e_id, starts[0]);
+        self.connect_edge_to_vert(e_id, starts[1]);
+
+        for ((f_id, loops), (v, (uv, no))) in
+            faces.into_iter().zip(triangles).zip(starts.into_iter().zip(corners))
+        {
+            let first = &mut self[loops[0]];
+            first.v = v;
+            first.uv = uv;
+            first.no = no;
+            for k in 0..3 {
+                let l = loops[k];
+                self[l].f = f_id;
+                self[l].next = loops[(k + 1) % 3];
+                self[l].prev = loops[(k + 2) % 3];
+            }
+            // Faces keep the loop they start from where they can, so that
+            // their corners are walked in the same order as before
+            if !loops.contains(&self[f_id].l) {
+                self[f_id].l = loops[0];
+            }
+            let positions = loops.map(|l| self[self[l].v].po);
+            self[f_id].no = polygon_normal(&positions);
+            if let Some(piece) = self[f_id].p.and_then(|root| self.pieces.get_mut(&root)) {
+                piece.elem_dirty = true;
+            }
+        }
+
+        self.elem_dirty |= MeshElementType::all();
+        self.index_dirty |= MeshElementType::EDGES
+            | MeshElementType::FACES
+            | MeshElementType::LOOPS
+            | MeshElementType::PIECES;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::{EdgeId, FaceId, Id, LoopId, VertexId};
+    use crate::mesh::{cut::CutUpdate, face::FaceDescriptor};
+
+    /// A square folded along its diagonal from corner 0 to corner 2, with
+    /// corners 1 and 3 raised so that the square isn't flat
+    fn bent_square() -> (Mesh, EdgeId) {
+        let mut mesh = Mesh::new("SQUARE".to_string());
+        let v: Vec<_> = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.5], [1.0, 1.0, 0.0], [0.0, 1.0, 0.5]]
+            .into_iter()
+            .map(|p| mesh.add_vertex(p))
+            .collect();
+        let uvs = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]];
+        mesh.add_face(
+            &[v[0], v[1], v[2]],
+            &FaceDescriptor { uvs: Some(&uvs), ..Default::default() },
+        );
+        let uvs = [[0.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+        mesh.add_face(
+            &[v[0], v[2], v[3]],
+            &FaceDescriptor { uvs: Some(&uvs), ..Default::default() },
+        );
+        let e_id = mesh.query_edge(v[0], v[2]).unwrap();
+        (mesh, e_id)
+    }
+
+    /// Every link in the mesh agrees with the links it points back through
+    fn assert_consistent(mesh: &Mesh) {
+        for l in mesh.loops.indices().map(LoopId::from_usize) {
+            assert_eq!(mesh[mesh[l].next].prev, l);
+            assert_eq!(mesh[mesh[l].radial_next].radial_prev, l);
+            assert_eq!(mesh[mesh[l].next].v, mesh[mesh[l].e].other_vert(mesh[l].v));
+            assert!(mesh.iter_face_loops(mesh[l].f).any(|other| other == l));
+        }
+        for v in mesh.verts.indices().map(VertexId::from_usize) {
+            for e in mesh.iter_all_vert_edges(v) {
+                assert!(mesh[e].has_vert(v));
+                assert_eq!(mesh[mesh[e].disklink(v).next].disklink(v).prev, e);
+            }
+        }
+        for e in mesh.edges.indices().map(EdgeId::from_usize) {
+            for v in mesh[e].v {
+                assert!(mesh.iter_all_vert_edges(v).any(|other| other == e));
+            }
+        }
+    }
+
+    fn face_verts(mesh: &Mesh, f: usize) -> Vec<usize> {
+        mesh.iter_face_loops(FaceId::from_usize(f)).map(|l| mesh[l].v.to_usize()).collect()
+    }
+
+    #[test]
+    fn flipping_turns_the_edge_onto_the_other_diagonal() {
+        let (mut mesh, e_id) = bent_square();
+        let fold = mesh.edge_fold_angle(e_id).unwrap();
+        mesh.flip_edge(e_id).unwrap();
+        assert_consistent(&mesh);
+        assert_eq!(mesh[e_id].v.map(|v| v.to_usize()), [1, 3]);
+        assert!(mesh.query_edge(VertexId::from_usize(0), VertexId::from_usize(2)).is_none());
+        assert_eq!(mesh.edges.num_elements(), 5);
+        // The other diagonal folds the other way
+        assert!(mesh.edge_fold_angle(e_id).unwrap() * fold < 0.0);
+        // Both faces still face up, and each corner kept its UV
+        for f in 0..2 {
+            assert!(mesh[FaceId::from_usize(f)].no[2] > 0.0);
+        }
+        for l in mesh.loops.indices().map(LoopId::from_usize) {
+            let expected = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]][mesh[l].v.to_usize()];
+            assert_eq!(mesh[l].uv, expected);
+        }
+    }
+
+    #[test]
+    fn unflipping_puts_everything_back() {
+        let (mut mesh, e_id) = bent_square();
+        let ends = mesh[e_id].v;
+        let faces = [face_verts(&mesh, 0), face_verts(&mesh, 1)];
+        let uvs: Vec<_> = mesh.loops.indices().map(|l| mesh[LoopId::from_usize(l)].uv).collect();
+        let before = mesh.flip_edge(e_id).unwrap();
+        mesh.unflip_edge(e_id, &before).unwrap();
+        assert_consistent(&mesh);
+        assert_eq!(mesh[e_id].v, ends);
+        assert_eq!([face_verts(&mesh, 0), face_verts(&mesh, 1)], faces);
+        let restored: Vec<_> =
+            mesh.loops.indices().map(|l| mesh[LoopId::from_usize(l)].uv).collect();
+        assert_eq!(restored, uvs);
+    }
+
+    #[test]
+    fn cut_and_boundary_edges_are_refused() {
+        let (mut mesh, e_id) = bent_square();
+        let boundary = mesh.query_edge(VertexId::from_usize(0), VertexId::from_usize(1)).unwrap();
+        assert_eq!(mesh.flip_edge(boundary).err(), Some(FlipEdgeError::NotBetweenTwoTriangles));
+        mesh.make_cut(e_id, CutUpdate::Nothing);
+        assert_eq!(mesh.flip_edge(e_id).err(), Some(FlipEdgeError::IsCut));
+    }
+
+    /// Every pair of a tetrahedron's corners already has an edge
+    #[test]
+    fn an_existing_diagonal_is_refused() {
+        let mut mesh = Mesh::new("TETRA".to_string());
+        let v: Vec<_> = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+            .into_iter()
+            .map(|p| mesh.add_vertex(p))
+            .collect();
+        for [a, b, c] in [[0, 2, 1], [0, 1, 3], [1, 2, 3], [0, 3, 2]] {
+            mesh.add_face(&[v[a], v[b], v[c]], &FaceDescriptor::default());
+        }
+        let e_id = mesh.query_edge(v[0], v[1]).unwrap();
+        assert_eq!(mesh.flip_edge(e_id).err(), Some(FlipEdgeError::DiagonalExists));
+    }
+
+    /// A flip inside a piece keeps the piece whole, and the cuts around it
+    #[test]
+    fn a_flip_inside_a_piece_keeps_the_piece() {
+        let mut cube = Mesh::cut_cube();
+        let pieces: Vec<_> = cube.faces.indices().map(|f| cube[FaceId::from_usize(f)].p).collect();
+        let diagonal = cube
+            .edges
+            .indices()
+            .map(EdgeId::from_usize)
+            .find(|e| !cube.edge_is_cut(e) && cube.flippable_quad(*e).is_ok())
+            .unwrap();
+        cube.flip_edge(diagonal).unwrap();
+        assert_consistent(&cube);
+        let after: Vec<_> = cube.faces.indices().map(|f| cube[FaceId::from_usize(f)].p).collect();
+        assert_eq!(after, pieces);
+        assert_eq!(cube.cuts.len(), 6);
+        let root = *cube.iter_pieces().next().unwrap();
+        assert!(cube.expand_piece(root).is_ok(), "the piece is still free of cycles");
+    }
+}
@@ -0,0 +1,372 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::{
+    cut::CutUpdate,
+    cut::FlapPosition,
+    edge::Edge,
+    face::{polygon_normal, Face, FaceDescriptor},
+    flap::{FlapSettings, FlapStyle},
+    loop_::Loop,
+    Mesh, MeshElementType,
+};
+use crate::{
+    id::{self, Id},
+    MaterialId,
+};
+
+/// A plain copy of a mesh's faces, cuts and pieces, free of the BMesh links
+/// between them. Edits which change a mesh's topology record one of these
+/// from before and after, so that undo, redo and peers can put the mesh back
+/// exactly, without having to reverse the edit itself.
+///
+/// Every element goes back under the ID it was recorded with, gaps and all:
+/// commands further along the history keep hold of face, edge and loop IDs,
+/// and have to find them again after an undo. Faces and corners put together
+/// by hand have no ID, and are numbered after the rest.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MeshGeometry {
+    /// Vertex positions, indexed by vertex ID. `None` is an unused ID.
+    pub verts: Vec<Option<[f32; 3]>>,
+    /// Edges by ID, with their vertices in order. Edges the faces need which
+    /// aren't here are numbered after these, and any of these which no face
+    /// uses are left out.
+    #[serde(default)]
+    pub edges: Vec<(id::EdgeId, [id::VertexId; 2])>,
+    pub faces: Vec<GeometryFace>,
+    /// Live cuts, by the vertices of their edge, in the edge's own order so
+    /// that the flap position keeps its meaning
+    pub cuts: Vec<([id::VertexId; 2], FlapPosition)>,
+    /// The cuts among `cuts` with their own flap shape, by the same vertices
+    #[serde(default)]
+    pub cut_flaps: Vec<([id::VertexId; 2], FlapSettings)>,
+    /// The cuts among `cuts` whose flaps aren't plain trapezoids, likewise
+    #[serde(default)]
+    pub cut_styles: Vec<([id::VertexId; 2], FlapStyle)>,
+    /// The labels of the cuts among `cuts`, likewise
+    #[serde(default)]
+    pub cut_labels: Vec<([id::VertexId; 2], u32)>,
+    /// Pieces, by the index in `faces` of their root, with their transform
+    pub pieces: Vec<(usize, cgmath::Matrix4<f32>)>,
+    /// The stored assembly order, by the index in `faces` of each root
+    #[serde(default)]
+    pub assembly: Vec<usize>,
+}
+
+/// A whole mesh as plain data: its geometry, and the document settings which
+/// hang off it. Commands which add or remove meshes record one of these, so
+/// that undo, redo and peers all build the very same mesh.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MeshRecord {
+    pub label: Option<String>,
+    pub transform: cgmath::Matrix4<f32>,
+    pub scale: f32,
+    pub flap_settings: FlapSettings,
+    pub paper_thickness: f32,
+    pub geometry: MeshGeometry,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GeometryFace {
+    /// The face's ID, if it had one
+    #[serde(default)]
+    pub f: Option<id::FaceId>,
+    pub m: Option<MaterialId>,
+    pub corners: Vec<GeometryCorner>,
+}
+
+/// A face's corner: its vertex, and the ID, UV and normal of its loop
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct GeometryCorner {
+    #[serde(default)]
+    pub l: Option<id::LoopId>,
+    pub v: id::VertexId,
+    pub uv: [f32; 2],
+    pub no: [f32; 3],
+}
+
+impl Mesh {
+    /// Copies out the mesh's faces, cuts, pieces and assembly order
+    pub fn geometry(&self) -> MeshGeometry {
+        let mut verts = vec![None; self.verts.next_push_index()];
+        self.verts.indices().for_each(|v| verts[v] = Some(self.verts[v].po));
+
+        let edges = self
+            .edges
+            .indices()
+            .map(id::EdgeId::from_usize)
+            .map(|e_id| (e_id, self[e_id].v))
+            .collect();
+        let face_ids: Vec<_> = self.faces.indices().map(id::FaceId::from_usize).collect();
+        let faces = face_ids
+            .iter()
+            .map(|f_id| GeometryFace {
+                f: Some(*f_id),
+                m: self[*f_id].m,
+                corners: self
+                    .iter_face_loops(*f_id)
+                    .map(|l| GeometryCorner {
+                        l: Some(l),
+                        v: self[l].v,
+                        uv: self[l].uv,
+                        no: self[l].no,
+                    })
+                    .collect(),
+            })
+            .collect();
+        let cuts = self
+            .cuts
+            .iter()
+            .filter(|(_, cut)| !cut.is_dead)
+            .map(|(e_id, cut)| (self[*e_id].v, cut.flap_position))
+            .collect();
+        let cut_flaps = self
+            .cuts
+            .iter()
+            .filter(|(_, cut)| !cut.is_dead)
+            .filter_map(|(e_id, cut)| Some((self[*e_id].v, cut.flap?)))
+            .collect();
+        let cut_styles = self
+            .cuts
+            .iter()
+            .filter(|(_, cut)| !cut.is_dead && cut.style != FlapStyle::default())
+            .map(|(e_id, cut)| (self[*e_id].v, cut.style))
+            .collect();
+        let cut_labels = self
+            .cuts
+            .iter()
+            .filter(|(_, cut)| !cut.is_dead)
+            .filter_map(|(e_id, cut)| Some((self[*e_id].v, cut.label?)))
+            .collect();
+        let index_of = |f_id: &id::FaceId| face_ids.iter().position(|f| f == f_id);
+        let pieces = self
+            .iter_pieces()
+            .filter_map(|root| Some((index_of(root)?, self.pieces[root].transform)))
+            .collect();
+        let assembly = self.assembly.iter().filter_map(index_of).collect();
+        MeshGeometry {
+            verts,
+            edges,
+            faces,
+            cuts,
+            cut_flaps,
+            cut_styles,
+            cut_labels,
+            pieces,
+            assembly,
+        }
+    }
+
+    /// Copies out the whole mesh, settings and all
+    pub fn record(&self) -> MeshRecord {
+        MeshRecord {
+            label: self.label.clone(),
+            transform: self.transform,
+            scale: self.scale,
+            flap_settings: self.flap_settings,
+            paper_thickness: self.paper_thickness,
+            geometry: self.geometry(),
+        }
+    }
+
+    /// Builds a mesh back up from a recorded copy
+    pub fn from_record(record: &MeshRecord) -> Self {
+        let mut mesh = Mesh {
+            label: record.label.clone(),
+            transform: record.transform,
+            scale: record.scale,
+            flap_settings: record.flap_settings,
+            paper_thickness: record.paper_thickness,
+            ..Default::default()
+        };
+        mesh.set_geometry(&record.geometry);
+        mesh
+    }
+
+    /// Replaces the mesh's faces, cuts, pieces and assembly order with a
+    /// recorded copy. The mesh's label, transform, scale, flap settings and
+    /// paper thickness are its own, and are left alone.
+    pub fn set_geometry(&mut self, geometry: &MeshGeometry) {
+        let mut mesh = Mesh {
+            label: self.label.take(),
+            transform: self.transform,
+            scale: self.scale,
+            flap_settings: self.flap_settings,
+            paper_thickness: self.paper_thickness,
+            ..Default::default()
+        };
+        for (v, po) in geometry.verts.iter().enumerate() {
+            if let Some(po) = po {
+                mesh.verts.reserve_for(v);
+                mesh.verts.insert(v, super::Vertex { po: *po, e: None });
+            }
+        }
+
+        for (e_id, [v0, v1]) in &geometry.edges {
+            let placed = [v0, v1].iter().all(|v| mesh.verts.has_element_at(v.to_usize()));
+            if placed && !mesh.edges.has_element_at(e_id.to_usize()) {
+                mesh.edges.reserve_for(e_id.to_usize());
+                mesh.edges.insert(e_id.to_usize(), Edge::new(*e_id, *v0, *v1));
+                mesh.connect_edge_to_vert(*e_id, *v0);
+                mesh.connect_edge_to_vert(*e_id, *v1);
+            }
+        }
+
+        // Faces with IDs go back into their own slots first, so that faces and
+        // loops without one can't be pushed into a slot that's spoken for
+        let mut face_ids: Vec<_> =
+            geometry.faces.iter().map(|face| mesh.place_face(face)).collect();
+        for (face, f_id) in geometry.faces.iter().zip(&mut face_ids) {
+            if f_id.is_some() {
+                continue;
+            }
+            let verts: Vec<_> = face.corners.iter().map(|c| c.v).collect();
+            let uvs: Vec<_> = face.corners.iter().map(|c| c.uv).collect();
+            let nos: Vec<_> = face.corners.iter().map(|c| c.no).collect();
+            *f_id =
+                Some(mesh.add_face(
+                    &verts,
+                    &FaceDescriptor { m: face.m, uvs: Some(&uvs), nos: Some(&nos) },
+                ));
+        }
+        for (e_id, [v0, v1]) in &geometry.edges {
+            if mesh.edges.has_element_at(e_id.to_usize()) && mesh[*e_id].l.is_none() {
+                mesh.disconnect_edge_from_vert(*e_id, *v0);
+                mesh.disconnect_edge_from_vert(*e_id, *v1);
+                mesh.edges.remove(e_id.to_usize());
+            }
+        }
+
+        // Cuts and pieces go back the way a save file's do: flip the cut flags,
+        // then grow each piece out from its root
+        for ([v0, v1], flap_position) in &geometry.cuts {
+            let Some(e_id) = mesh.query_edge(*v0, *v1) else {
+                continue;
+            };
+            mesh.make_cut(e_id, CutUpdate::Nothing);
+            let flap_position = match mesh[e_id].v[0] == *v0 {
+                true => *flap_position,
+                false => flap_position.opposite(),
+            };
+            mesh.set_cut_flap(e_id, flap_position);
+        }
+        for ([v0, v1], settings) in &geometry.cut_flaps {
+            if let Some(e_id) = mesh.query_edge(*v0, *v1) {
+                mesh.set_cut_flap_settings(e_id, Some(*settings));
+            }
+        }
+        for ([v0, v1], style) in &geometry.cut_styles {
+            if let Some(e_id) = mesh.query_edge(*v0, *v1) {
+                mesh.set_cut_flap_style(e_id, *style);
+            }
+        }
+        for ([v0, v1], label) in &geometry.cut_labels {
+            if let Some(e_id) = mesh.query_edge(*v0, *v1) {
+                mesh.set_cut_label(e_id, *label);
+            }
+        }
+        for (i, transform) in &geometry.pieces {
+            let Some(f_id) = face_ids.get(*i).copied().flatten() else {
+                continue;
+            };
+            if mesh.expand_piece(f_id).is_ok() {
+                mesh.pieces.get_mut(&f_id).unwrap().transform = *transform;
+            }
+        }
+        mesh.assembly =
+            geometry.assembly.iter().filter_map(|i| face_ids.get(*i).copied().flatten()).collect();
+
+        mesh.elem_dirty = MeshElementType::all();
+        mesh.index_dirty = MeshElementType::all();
+        *self = mesh;
+    }
+
+    /// Puts a recorded face back under its own ID, with its loops under
+    /// theirs, if it has IDs for all of them and none are taken
+    fn place_face(&mut self, face: &GeometryFace) -> Option<id::FaceId> {
+        let f_id = face.f?;
+        let loops: Vec<_> = face.corners.iter().map(|c| c.l).collect::<Option<_>>()?;
+        let verts: Vec<_> = face.corners.iter().map(|c| c.v).collect();
+        let distinct: HashSet<_> = loops.iter().collect();
+        if self.faces.has_element_at(f_id.to_usize())
+            || distinct.len() < loops.len()
+            || loops.iter().any(|l| self.loops.has_element_at(l.to_usize()))
+            || verts.iter().any(|v| !self.verts.has_element_at(v.to_usize()))
+        {
+            return None;
+        }
+
+        let n = verts.len();
+        let positions: Vec<_> = verts.iter().map(|v| self[*v].po).collect();
+        self.faces.reserve_for(f_id.to_usize());
+        self.faces.insert(
+            f_id.to_usize(),
+            Face { no: polygon_normal(&positions), l: loops[0], p: None, m: face.m },
+        );
+        for (k, c) in face.corners.iter().enumerate() {
+            let e = self.add_edge(verts[k], verts[(k + 1) % n]);
+            self.loops.reserve_for(loops[k].to_usize());
+            self.loops.insert(
+                loops[k].to_usize(),
+                Loop {
+                    v: c.v,
+                    e,
+                    f: f_id,
+                    uv: c.uv,
+                    no: c.no,
+                    next: loops[(k + 1) % n],
+                    prev: loops[(k + n - 1) % n],
+                    ..Default::default()
+                },
+            );
+            self.connect_loop_to_edge(loops[k], e);
+        }
+        Some(f_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::{FaceId, VertexId};
+
+    #[test]
+    fn a_cut_cube_survives_a_round_trip() {
+        let mut cube = Mesh::cut_cube();
+        let root = *cube.iter_pieces().next().unwrap();
+        cube.pieces.get_mut(&root).unwrap().transform.w.x = 4.0;
+        let flaps: Vec<_> =
+            cube.cuts.iter().map(|(e, c)| (cube[*e].v, u8::from(c.flap_position))).collect();
+
+        let mut restored = Mesh::new("EMPTY".to_string());
+        restored.set_geometry(&cube.geometry());
+        assert_eq!(restored.faces.num_elements(), cube.faces.num_elements());
+        assert_eq!(restored.edges.num_elements(), cube.edges.num_elements());
+        let restored_flaps: Vec<_> = restored
+            .cuts
+            .iter()
+            .map(|(e, c)| (restored[*e].v, u8::from(c.flap_position)))
+            .collect();
+        assert_eq!(restored_flaps, flaps);
+        let roots: Vec<_> = restored.iter_pieces().copied().collect();
+        assert_eq!(roots, vec![root]);
+        assert_eq!(restored.pieces[&root].transform.w.x, 4.0);
+        let piece_of = |mesh: &Mesh| -> Vec<_> {
+            mesh.faces.indices().map(|f| mesh[FaceId::from_usize(f)].p).collect()
+        };
+        assert_eq!(piece_of(&restored), piece_of(&cube));
+    }
+
+    /// Gaps in the vertex IDs are kept, so later vertices keep their IDs
+    #[test]
+    fn vertex_ids_are_kept() {
+        let mut mesh = Mesh::new_cube();
+        let mut geometry = mesh.geometry();
+        geometry.verts[0] = None;
+        geometry.faces.retain(|face| face.corners.iter().all(|c| c.v != VertexId::from_usize(0)));
+        mesh.set_geometry(&geometry);
+        assert!(!mesh.verts.has_element_at(0));
+        assert_eq!(mesh.verts.num_elements(), 7);
+        assert_eq!(mesh[VertexId::from_usize(6)].po, [0.5, 0.5, 1.0]);
+    }
+}
@@ -0,0 +1,232 @@
+//! Matching labels on the two sides of every seam.
+//!
+//! A printed pattern numbers its cuts so that the tab marked "17" is glued to
+//! the edge marked "17". Every cut is given its number when it is first made,
+//! and keeps it through being cleared and remade, so labels don't shuffle as
+//! the user carries on cutting. The numbers come from the mesh's own cuts
+//! alone, which every peer replays in the same order, so they agree on them.
+//!
+//! Labels are drawn as strokes rather than with a font, so that the cutting
+//! viewport, the printed sheets and the vector export all letter them alike.
+
+use cgmath::{EuclideanSpace, InnerSpace, Point3, Transform, Vector3};
+
+use crate::id::{EdgeId, FaceId, LoopId};
+
+/// The tallest a label's digits are drawn, in world units (1 unit = 1cm)
+pub const LABEL_HEIGHT: f32 = 0.35;
+
+/// How wide a digit is, as a fraction of its height
+const DIGIT_WIDTH: f32 = 0.55;
+/// How far each digit starts from the last, as a fraction of its height
+const DIGIT_ADVANCE: f32 = 0.8;
+/// How far a label sits in from its seam, as a fraction of its height
+const LABEL_INSET: f32 = 0.3;
+/// The most of its seam a label may run along
+const LABEL_MAX_SPAN: f32 = 0.6;
+
+/// One side of a labelled seam, placed on the face of the piece next to it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CutLabel {
+    pub e: EdgeId,
+    pub label: u32,
+    /// The bottom-left corner of the label's text, in piece space
+    pub origin: Point3<f32>,
+    /// The direction the text reads in, a digit's height long
+    pub right: Vector3<f32>,
+    /// The direction from the bottom of the text to its top, which faces the
+    /// seam, likewise a digit's height long
+    pub up: Vector3<f32>,
+}
+
+impl CutLabel {
+    /// The label's text
+    pub fn text(&self) -> String {
+        self.label.to_string()
+    }
+
+    /// The label's digits as straight strokes, in piece space
+    pub fn strokes(&self) -> Vec<(Point3<f32>, Point3<f32>)> {
+        let place = |i: usize, [x, y]: [f32; 2]| {
+            self.origin + self.right * (i as f32 * DIGIT_ADVANCE + x * DIGIT_WIDTH) + self.up * y
+        };
+        self.text()
+            .chars()
+            .enumerate()
+            .flat_map(|(i, digit)| {
+                digit_strokes(digit).iter().flat_map(move |stroke| {
+                    stroke.windows(2).map(move |pair| (place(i, pair[0]), place(i, pair[1])))
+                })
+            })
+            .collect()
+    }
+}
+
+impl super::Mesh {
+    /// The label of a live cut
+    pub fn cut_label(&self, e_id: EdgeId) -> Option<u32> {
+        self.cuts.get(&e_id).filter(|cut| !cut.is_dead)?.label
+    }
+
+    /// Sets a cut's label outright, for putting back one that was recorded
+    pub fn set_cut_label(&mut self, e_id: EdgeId, label: u32) {
+        if let Some(cut) = self.cuts.get_mut(&e_id) {
+            cut.label = Some(label);
+            self.last_cut_label = self.last_cut_label.max(label);
+            self.elem_dirty |= super::MeshElementType::EDGES;
+        }
+    }
+
+    /// The label the next new cut gets: one past the highest any cut has had,
+    /// dead or alive, so a cut brought back by redo never finds its number
+    /// taken.
+    pub(crate) fn next_cut_label(&self) -> u32 {
+        self.last_cut_label + 1
+    }
+
+    /// The labels of every seam on the piece rooted at `root`, one for each of
+    /// its faces along a cut. Both sides of a seam are labelled, whichever
+    /// piece the other is on, at the middle of the seam and just inside the
+    /// face, reading along it with the tops of their digits towards it. A
+    /// label too long for its seam is shrunk to fit.
+    pub fn piece_cut_labels(&self, root: FaceId) -> Vec<CutLabel> {
+        let walker = self.iter_piece_faces_unfolded(root);
+        let mut labels = Vec::new();
+        for face in walker {
+            let unfolded = |l_id: LoopId| {
+                face.affine.transform_point(Point3::from_vec(self.vert_pos(self[l_id].v)))
+            };
+            let corners: Vec<Point3<f32>> = self.iter_face_loops(face.f).map(unfolded).collect();
+            let centroid = Point3::centroid(&corners);
+            for l_id in self.iter_face_loops(face.f) {
+                let l = self[l_id];
+                if l.radial_next == l_id {
+                    continue;
+                }
+                let Some(label) = self.cut_label(l.e) else {
+                    continue;
+                };
+                let (from, to) = (unfolded(l_id), unfolded(l.next));
+                if let Some(placed) = place_label(l.e, label, from, to, centroid) {
+                    labels.push(placed);
+                }
+            }
+        }
+        labels
+    }
+}
+
+/// Places a label along the seam from `from` to `to`, on the side of it
+/// `inside` is on
+fn place_label(
+    e: EdgeId,
+    label: u32,
+    from: Point3<f32>,
+    to: Point3<f32>,
+    inside: Point3<f32>,
+) -> Option<CutLabel> {
+    let along = to - from;
+    let length = along.magnitude();
+    if length < f32::EPSILON {
+        return None;
+    }
+    let along = along / length;
+    let middle = from.midpoint(to);
+    let toward = inside - middle;
+    let inward = toward - along * toward.dot(along);
+    if inward.magnitude2() < f32::EPSILON {
+        return None;
+    }
+    let inward = inward.normalize();
+
+    let digits = label.to_string().len() as f32;
+    let em_width = (digits - 1.0) * DIGIT_ADVANCE + DIGIT_WIDTH;
+    let height = LABEL_HEIGHT.min(length * LABEL_MAX_SPAN / em_width);
+    // Facing the seam, the text reads against the face's own winding
+    let (right, up) = (-along * height, -inward * height);
+    let origin = middle - right * (em_width / 2.0) - up * (1.0 + LABEL_INSET);
+    Some(CutLabel { e, label, origin, right, up })
+}
+
+/// A digit as polylines, in a box one wide and one tall from its bottom-left.
+/// Six and nine are underlined, since a label can be read upside down.
+fn digit_strokes(digit: char) -> &'static [&'static [[f32; 2]]] {
+    const UNDERLINE: &[[f32; 2]] = &[[0.0, -0.15], [1.0, -0.15]];
+    match digit {
+        '0' => &[&[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]]],
+        '1' => &[&[[0.2, 0.8], [0.5, 1.0], [0.5, 0.0]], &[[0.2, 0.0], [0.8, 0.0]]],
+        '2' => &[&[[0.0, 1.0], [1.0, 1.0], [1.0, 0.5], [0.0, 0.5], [0.0, 0.0], [1.0, 0.0]]],
+        '3' => &[&[[0.0, 1.0], [1.0, 1.0], [1.0, 0.0], [0.0, 0.0]], &[[0.0, 0.5], [1.0, 0.5]]],
+        '4' => &[&[[0.0, 1.0], [0.0, 0.5], [1.0, 0.5]], &[[1.0, 1.0], [1.0, 0.0]]],
+        '5' => &[&[[1.0, 1.0], [0.0, 1.0], [0.0, 0.5], [1.0, 0.5], [1.0, 0.0], [0.0, 0.0]]],
+        '6' => {
+            &[&[[1.0, 1.0], [0.0, 1.0], [0.0, 0.0], [1.0, 0.0], [1.0, 0.5], [0.0, 0.5]], UNDERLINE]
+        }
+        '7' => &[&[[0.0, 1.0], [1.0, 1.0], [0.3, 0.0]]],
+        '8' => &[
+            &[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]],
+            &[[0.0, 0.5], [1.0, 0.5]],
+        ],
+        '9' => {
+            &[&[[1.0, 0.5], [0.0, 0.5], [0.0, 1.0], [1.0, 1.0], [1.0, 0.0], [0.0, 0.0]], UNDERLINE]
+        }
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, MetricSpace};
+
+    use crate::id::{Id, VertexId};
+    use crate::mesh::{cut::CutUpdate, Mesh};
+
+    #[test]
+    fn cuts_keep_their_labels_through_being_remade() {
+        let mut mesh = Mesh::cut_quad_cube();
+        let labels: Vec<u32> = mesh.cuts.keys().filter_map(|e| mesh.cut_label(*e)).collect();
+        let mut sorted = labels.clone();
+        sorted.sort();
+        assert_eq!(sorted, (1..=9).collect::<Vec<_>>(), "every cut has its own number");
+
+        let e_id = *mesh.cuts.keys().next().unwrap();
+        let label = mesh.cut_label(e_id);
+        mesh.clear_cut(&e_id, CutUpdate::PiecesAndFlaps);
+        assert_eq!(mesh.cut_label(e_id), None);
+        let v = VertexId::from_usize;
+        let other = mesh.query_edge(v(1), v(5)).unwrap();
+        mesh.make_cut(other, CutUpdate::PiecesAndFlaps);
+        mesh.make_cut(e_id, CutUpdate::PiecesAndFlaps);
+        assert_eq!(mesh.cut_label(e_id), label);
+        assert_eq!(mesh.cut_label(other), Some(10));
+
+        // Numbering carries on past a label put back by hand
+        mesh.set_cut_label(other, 20);
+        let last = mesh.query_edge(v(2), v(6)).unwrap();
+        mesh.make_cut(last, CutUpdate::PiecesAndFlaps);
+        assert_eq!(mesh.cut_label(last), Some(21));
+    }
+
+    /// Both faces along a seam carry its label, beside the seam's middle and
+    /// inside their own face
+    #[test]
+    fn both_sides_of_a_seam_are_labelled() {
+        let mesh = Mesh::cut_quad_cube();
+        let labels: Vec<_> =
+            mesh.iter_pieces().flat_map(|root| mesh.piece_cut_labels(*root)).collect();
+        assert_eq!(labels.len(), 18);
+        for e_id in mesh.cuts.keys() {
+            assert_eq!(labels.iter().filter(|label| label.e == *e_id).count(), 2);
+        }
+        for label in &labels {
+            assert!((label.up.magnitude() - super::LABEL_HEIGHT).abs() < 1e-5);
+            assert!(label.right.dot(label.up).abs() < 1e-5);
+            let strokes = label.strokes();
+            assert!(!strokes.is_empty());
+            // Every stroke stays close by the label, on a face a unit across
+            assert!(strokes
+                .iter()
+                .all(|(a, b)| a.distance(label.origin) < 1.0 && b.distance(label.origin) < 1.0));
+        }
+    }
+}
@@ -47,4 +47,19 @@ impl super::Mesh {
         // Point the loop back at the edge itself
         self[l].e = e;
     }
+
+    /// Takes a loop out of the radial loop cycle around an edge, moving the
+    /// edge's own loop on if it was this one.
+    pub(super) fn disconnect_loop_from_edge(&mut self, l: id::LoopId, e: id::EdgeId) {
+        let (prev, next) = (self[l].radial_prev, self[l].radial_next);
+        if next == l {
+            self[e].l = None;
+            return;
+        }
+        self[prev].radial_next = next;
+        self[next].radial_prev = prev;
+        if self[e].l == Some(l) {
+            self[e].l = Some(next);
+        }
+    }
 }
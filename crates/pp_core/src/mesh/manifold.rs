@@ -0,0 +1,278 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use super::{geometry::MeshGeometry, Mesh};
+use crate::id::{self, Id};
+
+/// What `Mesh::split_non_manifold` changed
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ManifoldRepair {
+    /// Edges which had more than two faces, by their vertices
+    pub edges: Vec<[id::VertexId; 2]>,
+    /// Vertices which were split, each with the copies made of it
+    pub verts: Vec<(id::VertexId, Vec<id::VertexId>)>,
+    /// Edges which still have more than two faces after splitting. These are
+    /// pinched shut elsewhere around both their ends, so there was no way to
+    /// split them by vertex.
+    pub unresolved: Vec<[id::VertexId; 2]>,
+}
+
+impl ManifoldRepair {
+    /// Whether the mesh was manifold already
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty() && self.verts.is_empty()
+    }
+}
+
+/// An edge's vertices, in a fixed order, for looking an edge up from either face
+fn edge_key(a: id::VertexId, b: id::VertexId) -> (id::VertexId, id::VertexId) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// The faces around every edge, by index in `geometry.faces`
+fn edge_faces(geometry: &MeshGeometry) -> BTreeMap<(id::VertexId, id::VertexId), Vec<usize>> {
+    let mut edges: BTreeMap<_, Vec<usize>> = BTreeMap::new();
+    for (f, face) in geometry.faces.iter().enumerate() {
+        let n = face.corners.len();
+        for k in 0..n {
+            let key = edge_key(face.corners[k].v, face.corners[(k + 1) % n].v);
+            edges.entry(key).or_default().push(f);
+        }
+    }
+    edges
+}
+
+/// The root of `i`'s set in a union-find forest
+fn find(parents: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[i] = root;
+    root
+}
+
+impl Mesh {
+    /// Splits the mesh apart wherever it isn't manifold, so that every edge
+    /// has at most two faces and can be cut and unfolded.
+    ///
+    /// The faces around each vertex are grouped into fans, joined across the
+    /// vertex's edges which have two faces or fewer. A vertex with more than
+    /// one fan is where separate sheets meet: a "book" of three or more faces
+    /// on one edge, or two cones touching at their tips. The first fan keeps
+    /// the vertex and every other fan gets a copy of it, which also splits the
+    /// edges between them.
+    ///
+    /// Leaves the mesh untouched, IDs and all, if it was manifold already.
+    pub fn split_non_manifold(&mut self) -> ManifoldRepair {
+        let mut geometry = self.geometry();
+        let repair = split_geometry(&mut geometry);
+        if !repair.is_empty() {
+            self.set_geometry(&geometry);
+        }
+        repair
+    }
+}
+
+fn split_geometry(geometry: &mut MeshGeometry) -> ManifoldRepair {
+    let edges = edge_faces(geometry);
+    let mut repair = ManifoldRepair {
+        edges: edges.iter().filter(|(_, faces)| faces.len() > 2).map(|(k, _)| [k.0, k.1]).collect(),
+        ..Default::default()
+    };
+
+    let mut vert_faces: BTreeMap<id::VertexId, Vec<usize>> = BTreeMap::new();
+    for (f, face) in geometry.faces.iter().enumerate() {
+        for corner in &face.corners {
+            let faces = vert_faces.entry(corner.v).or_default();
+            if faces.last() != Some(&f) {
+                faces.push(f);
+            }
+        }
+    }
+
+    // The new vertex of each `(face, vertex)` corner that moves to a copy
+    let mut remap: HashMap<(usize, id::VertexId), id::VertexId> = HashMap::new();
+    for (v, faces) in &vert_faces {
+        // Union-find over the faces around `v`, joined across its manifold edges
+        let mut fan: Vec<usize> = (0..faces.len()).collect();
+        for (i, f) in faces.iter().enumerate() {
+            let corners = &geometry.faces[*f].corners;
+            let n = corners.len();
+            let k = corners.iter().position(|c| c.v == *v).unwrap();
+            for other in [corners[(k + 1) % n].v, corners[(k + n - 1) % n].v] {
+                let neighbours = &edges[&edge_key(*v, other)];
+                if neighbours.len() != 2 {
+                    continue;
+                }
+                let Some(j) = neighbours.iter().find(|g| *g != f) else {
+                    continue;
+                };
+                let j = faces.binary_search(j).unwrap();
+                let (a, b) = (find(&mut fan, i), find(&mut fan, j));
+                // The lower root wins, so the first fan is the one with the
+                // first face, which keeps the vertex
+                fan[a.max(b)] = a.min(b);
+            }
+        }
+
+        let mut copies: BTreeMap<usize, id::VertexId> = BTreeMap::new();
+        for (i, f) in faces.iter().enumerate() {
+            let root = find(&mut fan, i);
+            if root == 0 {
+                continue;
+            }
+            let copy = *copies.entry(root).or_insert_with(|| {
+                geometry.verts.push(geometry.verts[v.to_usize()]);
+                id::VertexId::from_usize(geometry.verts.len() - 1)
+            });
+            remap.insert((*f, *v), copy);
+        }
+        if !copies.is_empty() {
+            repair.verts.push((*v, copies.into_values().collect()));
+        }
+    }
+
+    // Each cut moves to the copies of its edge, in whichever faces it had
+    let cuts = std::mem::take(&mut geometry.cuts);
+    for ([v0, v1], flap_position) in cuts {
+        let mut copies: Vec<[id::VertexId; 2]> = edges
+            .get(&edge_key(v0, v1))
+            .into_iter()
+            .flatten()
+            .map(|f| {
+                let at = |v| remap.get(&(*f, v)).copied().unwrap_or(v);
+                [at(v0), at(v1)]
+            })
+            .collect();
+        copies.sort();
+        copies.dedup();
+        if copies.is_empty() {
+            copies.push([v0, v1]);
+        }
+        geometry.cuts.extend(copies.into_iter().map(|edge| (edge, flap_position)));
+    }
+    for (f, face) in geometry.faces.iter_mut().enumerate() {
+        for corner in &mut face.corners {
+            if let Some(copy) = remap.get(&(f, corner.v)) {
+                corner.v = *copy;
+            }
+        }
+    }
+
+    repair.unresolved = edge_faces(geometry)
+        .into_iter()
+        .filter(|(_, faces)| faces.len() > 2)
+        .map(|(k, _)| [k.0, k.1])
+        .collect();
+    repair
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::{EdgeId, VertexId};
+    use crate::mesh::face::FaceDescriptor;
+
+    fn max_faces_per_edge(mesh: &Mesh) -> usize {
+        mesh.edges
+            .indices()
+            .map(|e| mesh.iter_edge_loops(EdgeId::from_usize(e)).map_or(0, |l| l.count()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn a_manifold_mesh_is_left_alone() {
+        for mut mesh in [Mesh::new_cube(), Mesh::new_cylinder(12), Mesh::new_icosphere(2)] {
+            let edges = mesh.edges.num_elements();
+            assert!(mesh.split_non_manifold().is_empty());
+            assert_eq!(mesh.edges.num_elements(), edges);
+        }
+    }
+
+    /// Three triangles hinged on one edge, like the pages of a book
+    #[test]
+    fn a_book_of_faces_is_split_into_sheets() {
+        let mut mesh = Mesh::new("BOOK".to_string());
+        let spine = [mesh.add_vertex([0.0, 0.0, 0.0]), mesh.add_vertex([0.0, 0.0, 1.0])];
+        for p in [[1.0, 0.0, 0.5], [-1.0, 0.0, 0.5], [0.0, 1.0, 0.5]] {
+            let page = mesh.add_vertex(p);
+            mesh.add_face(&[spine[0], spine[1], page], &FaceDescriptor::default());
+        }
+        assert_eq!(max_faces_per_edge(&mesh), 3);
+
+        let repair = mesh.split_non_manifold();
+        assert_eq!(repair.edges, vec![[spine[0], spine[1]]]);
+        assert_eq!(repair.verts.len(), 2, "both ends of the spine are split");
+        assert!(repair.verts.iter().all(|(_, copies)| copies.len() == 2));
+        assert!(repair.unresolved.is_empty());
+        assert_eq!(max_faces_per_edge(&mesh), 1);
+        // Each end of the spine is copied for the second and third pages
+        assert_eq!(mesh.verts.num_elements(), 9);
+    }
+
+    /// Two cubes sharing an edge: each cube stays whole, but they come apart
+    #[test]
+    fn cubes_sharing_an_edge_come_apart_whole() {
+        let mut mesh = Mesh::new_cube();
+        let mut geometry = mesh.geometry();
+        // A second cube, moved one along X, sharing the first cube's edge
+        // between verts 2 (0.5, 0.5, 0) and 6 (0.5, 0.5, 1)
+        let offset = geometry.verts.len();
+        let shifted: Vec<_> =
+            geometry.verts.iter().map(|p| p.map(|p| [p[0] + 1.0, p[1] + 1.0, p[2]])).collect();
+        geometry.verts.extend(shifted);
+        let second: Vec<_> = geometry
+            .faces
+            .iter()
+            .cloned()
+            .map(|mut face| {
+                face.corners.iter_mut().for_each(|c| {
+                    // The second cube's verts 0 and 4 sit on the first's 2 and 6
+                    c.v = match c.v.to_usize() {
+                        0 => VertexId::from_usize(2),
+                        4 => VertexId::from_usize(6),
+                        v => VertexId::from_usize(v + offset),
+                    };
+                });
+                face
+            })
+            .collect();
+        geometry.faces.extend(second);
+        mesh.set_geometry(&geometry);
+        assert_eq!(max_faces_per_edge(&mesh), 4);
+
+        let repair = mesh.split_non_manifold();
+        assert_eq!(repair.verts.len(), 2);
+        assert!(repair.unresolved.is_empty());
+        assert_eq!(max_faces_per_edge(&mesh), 2);
+        // Still two closed cubes: every edge has exactly two faces
+        assert!(mesh
+            .edges
+            .indices()
+            .all(|e| { mesh.iter_edge_loops(EdgeId::from_usize(e)).unwrap().count() == 2 }));
+    }
+
+    /// Two triangles touching only at a corner
+    #[test]
+    fn a_bowtie_vertex_is_split() {
+        let mut mesh = Mesh::new("BOWTIE".to_string());
+        let v: Vec<_> = [[0.0, 0.0, 0.0], [1.0, 1.0, 0.0], [1.0, -1.0, 0.0], [-1.0, 1.0, 0.0]]
+            .into_iter()
+            .chain([[-1.0, -1.0, 0.0]])
+            .map(|p| mesh.add_vertex(p))
+            .collect();
+        mesh.add_face(&[v[0], v[1], v[2]], &FaceDescriptor::default());
+        mesh.add_face(&[v[0], v[3], v[4]], &FaceDescriptor::default());
+
+        let repair = mesh.split_non_manifold();
+        assert!(repair.edges.is_empty());
+        assert_eq!(repair.verts, vec![(v[0], vec![VertexId::from_usize(5)])]);
+        assert_eq!(mesh.verts.num_elements(), 6);
+    }
+}
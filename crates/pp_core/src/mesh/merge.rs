@@ -0,0 +1,283 @@
+use cgmath::{InnerSpace, Vector3};
+use std::collections::{HashMap, HashSet};
+
+use super::{face::FaceDescriptor, Mesh};
+use crate::id::{self, Id};
+
+/// The largest angle, in radians, between two faces' normals for them to count
+/// as one flat polygon. Small enough that a gently curved surface is never
+/// flattened, large enough to absorb the float noise of an exported quad.
+pub const COPLANAR_ANGLE: f32 = 0.1_f32.to_radians();
+
+impl Mesh {
+    /// Builds a copy of the mesh in which each patch of adjacent, coplanar
+    /// faces is merged into a single polygon. Most exporters triangulate, so
+    /// this is how an imported quad becomes one face again: it is selected as
+    /// a whole, and its diagonal can't be cut.
+    ///
+    /// Faces are only merged across edges which keep the result a simple
+    /// polygon: manifold, uncut, consistently wound, with the same material
+    /// and continuous UVs either side. Every face is compared with the first
+    /// face of its patch rather than its neighbour, so a curved surface never
+    /// creeps into one polygon a degree at a time.
+    ///
+    /// Vertices keep their IDs, except those left inside a polygon, which are
+    /// dropped. Cuts and pieces are not carried over, so this is meant for
+    /// meshes fresh from an import.
+    pub fn merge_coplanar_faces(&self, max_angle: f32) -> Self {
+        let mut grouped: HashSet<id::FaceId> = HashSet::new();
+        let mut polygons = Vec::new();
+        for f_id in self.faces.indices().map(id::FaceId::from_usize) {
+            if grouped.contains(&f_id) {
+                continue;
+            }
+            let group = self.coplanar_group(f_id, max_angle, &grouped);
+            grouped.extend(group.iter().copied());
+            polygons.push((self[f_id].m, self.group_boundary(&group)));
+        }
+
+        let mut merged = Self { label: self.label.clone(), ..Default::default() };
+        merged.transform = self.transform;
+        merged.scale = self.scale;
+        let used: HashSet<_> =
+            polygons.iter().flat_map(|(_, c)| c.iter().map(|l| self[*l].v)).collect();
+        for v_id in self.verts.indices().filter(|v| used.contains(&id::VertexId::from_usize(*v))) {
+            merged.verts.reserve_for(v_id);
+            merged.verts.insert(v_id, super::Vertex { po: self.verts[v_id].po, e: None });
+        }
+        for (m, corners) in polygons {
+            let verts: Vec<_> = corners.iter().map(|l| self[*l].v).collect();
+            let uvs: Vec<_> = corners.iter().map(|l| self[*l].uv).collect();
+            let nos: Vec<_> = corners.iter().map(|l| self[*l].no).collect();
+            merged.add_face(&verts, &FaceDescriptor { m, uvs: Some(&uvs), nos: Some(&nos) });
+        }
+        merged
+    }
+
+    /// Grows a patch of coplanar faces outward from `seed`, in breadth-first
+    /// order. A face only joins if the patch stays a disk: the edges it shares
+    /// with the patch must be one unbroken run, and its other corners must be
+    /// new to the patch.
+    fn coplanar_group(
+        &self,
+        seed: id::FaceId,
+        max_angle: f32,
+        grouped: &HashSet<id::FaceId>,
+    ) -> Vec<id::FaceId> {
+        let seed_no = Vector3::from(self[seed].no);
+        let mut group = vec![seed];
+        let mut members = HashSet::from([seed]);
+        let mut group_verts: HashSet<_> = self.iter_face_loops(seed).map(|l| self[l].v).collect();
+        let mut i = 0;
+        while i < group.len() {
+            for l in self.iter_face_loops(group[i]).collect::<Vec<_>>() {
+                let Some(f_next) = self.mergeable_neighbour(l) else {
+                    continue;
+                };
+                if members.contains(&f_next)
+                    || grouped.contains(&f_next)
+                    || self[f_next].m != self[seed].m
+                    || Vector3::from(self[f_next].no).angle(seed_no).0 > max_angle
+                {
+                    continue;
+                }
+
+                // Which of the candidate's edges it would share with the patch
+                let loops: Vec<_> = self.iter_face_loops(f_next).collect();
+                let shared: Vec<_> = loops
+                    .iter()
+                    .map(|l| {
+                        self.iter_edge_loops(self[*l].e)
+                            .into_iter()
+                            .flatten()
+                            .any(|other| other != *l && members.contains(&self[other].f))
+                    })
+                    .collect();
+                let runs = (0..shared.len())
+                    .filter(|k| shared[*k] && !shared[(k + shared.len() - 1) % shared.len()])
+                    .count();
+                let all_mergeable = loops
+                    .iter()
+                    .zip(&shared)
+                    .all(|(l, shared)| !shared || self.mergeable_neighbour(*l).is_some());
+                // Corners touched by a shared edge, which may already be in the patch
+                let on_shared =
+                    |k: usize| shared[k] || shared[(k + shared.len() - 1) % shared.len()];
+                let new_corners_are_new = (0..loops.len())
+                    .all(|k| on_shared(k) || !group_verts.contains(&self[loops[k]].v));
+                if runs != 1 || !all_mergeable || !new_corners_are_new {
+                    continue;
+                }
+
+                group_verts.extend(loops.iter().map(|l| self[*l].v));
+                members.insert(f_next);
+                group.push(f_next);
+            }
+            i += 1;
+        }
+        group
+    }
+
+    /// The face across a loop's edge, if the two could be one polygon: the
+    /// edge joins exactly two faces, isn't cut, runs opposite ways in each,
+    /// and has the same UVs at each end on both sides.
+    fn mergeable_neighbour(&self, l: id::LoopId) -> Option<id::FaceId> {
+        let e = self[l].e;
+        let mut loops = self.iter_edge_loops(e)?;
+        let (l_a, l_b) = (loops.next()?, loops.next()?);
+        if loops.next().is_some() || self.cuts.contains_key(&e) {
+            return None;
+        }
+        let other = if l_a == l { l_b } else { l_a };
+        if self[other].v == self[l].v {
+            return None;
+        }
+        // `l` runs v0 -> v1, so `other` runs v1 -> v0
+        let same_uv = |a: id::LoopId, b: id::LoopId| {
+            let (a, b) = (self[a].uv, self[b].uv);
+            (a[0] - b[0]).abs() < 1e-5 && (a[1] - b[1]).abs() < 1e-5
+        };
+        if !same_uv(l, self[other].next) || !same_uv(self[l].next, other) {
+            return None;
+        }
+        Some(self[other].f)
+    }
+
+    /// The corners around the outside of a patch of faces, in winding order.
+    /// Each is the loop that starts a boundary edge, so it carries the UV and
+    /// normal the merged polygon should have at that corner.
+    fn group_boundary(&self, group: &[id::FaceId]) -> Vec<id::LoopId> {
+        let members: HashSet<_> = group.iter().copied().collect();
+        let is_interior = |l: id::LoopId| {
+            self.iter_edge_loops(self[l].e)
+                .into_iter()
+                .flatten()
+                .any(|other| other != l && members.contains(&self[other].f))
+        };
+        let outgoing: HashMap<id::VertexId, id::LoopId> = group
+            .iter()
+            .flat_map(|f| self.iter_face_loops(*f))
+            .filter(|l| !is_interior(*l))
+            .map(|l| (self[l].v, l))
+            .collect();
+
+        // Start from the first boundary corner in face order, so that merging
+        // the same mesh always gives the same first corners
+        let start = group
+            .iter()
+            .flat_map(|f| self.iter_face_loops(*f))
+            .find(|l| !is_interior(*l))
+            .expect("a patch of faces that never closes has a boundary");
+        let mut corners = vec![start];
+        let mut l = start;
+        while let Some(&next) = outgoing.get(&self[self[l].next].v) {
+            if next == start {
+                break;
+            }
+            corners.push(next);
+            l = next;
+        }
+        corners
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::{EdgeId, FaceId, VertexId};
+    use crate::mesh::cut::CutUpdate;
+
+    fn face_sizes(mesh: &Mesh) -> Vec<usize> {
+        let mut sizes: Vec<_> = mesh
+            .faces
+            .indices()
+            .map(|f| mesh.iter_face_loops(FaceId::from_usize(f)).count())
+            .collect();
+        sizes.sort();
+        sizes
+    }
+
+    #[test]
+    fn a_triangulated_cube_merges_into_quads() {
+        let merged = Mesh::new_cube().merge_coplanar_faces(COPLANAR_ANGLE);
+        assert_eq!(face_sizes(&merged), vec![4; 6]);
+        assert_eq!(merged.edges.num_elements(), 12);
+        assert_eq!(merged.verts.num_elements(), 8);
+    }
+
+    /// The merged faces should still face outward, like the triangles did
+    #[test]
+    fn merging_keeps_the_winding() {
+        let cube = Mesh::new_cube();
+        let merged = cube.merge_coplanar_faces(COPLANAR_ANGLE);
+        for f in merged.faces.indices().map(FaceId::from_usize) {
+            let centre: Vector3<f32> =
+                merged.iter_face_loops(f).map(|l| merged.vert_pos(merged[l].v)).sum::<Vector3<_>>()
+                    / 4.0;
+            let outward = centre - Vector3::new(0.0, 0.0, 0.5);
+            assert!(Vector3::from(merged[f].no).dot(outward) > 0.0);
+        }
+    }
+
+    #[test]
+    fn cut_diagonals_are_kept() {
+        let mut cube = Mesh::new_cube();
+        // The bottom's diagonal
+        let e_id = cube.query_edge(VertexId::from_usize(0), VertexId::from_usize(2)).unwrap();
+        cube.make_cut(e_id, CutUpdate::Nothing);
+        let merged = cube.merge_coplanar_faces(COPLANAR_ANGLE);
+        assert_eq!(face_sizes(&merged), vec![3, 3, 4, 4, 4, 4, 4]);
+    }
+
+    /// A fan of triangles all the way around a vertex is a flat cap, like a
+    /// cylinder's end, and becomes one polygon without its centre vertex.
+    #[test]
+    fn a_closed_fan_merges_around_its_centre() {
+        let mut mesh = Mesh::new("FAN".to_string());
+        let centre = mesh.add_vertex([0.0, 0.0, 0.0]);
+        let rim: Vec<_> = (0..6)
+            .map(|i| {
+                let a = (i as f32) * std::f32::consts::TAU / 6.0;
+                mesh.add_vertex([a.cos(), a.sin(), 0.0])
+            })
+            .collect();
+        for i in 0..6 {
+            mesh.add_face(&[centre, rim[i], rim[(i + 1) % 6]], &FaceDescriptor::default());
+        }
+        let merged = mesh.merge_coplanar_faces(COPLANAR_ANGLE);
+        assert_eq!(face_sizes(&merged), vec![6]);
+        assert_eq!(merged.verts.num_elements(), 6);
+        assert!(!merged.verts.has_element_at(centre.to_usize()));
+        assert!(merged
+            .edges
+            .indices()
+            .all(|e| { merged.iter_edge_loops(EdgeId::from_usize(e)).unwrap().count() == 1 }));
+    }
+
+    #[test]
+    fn faces_at_an_angle_are_not_merged() {
+        let merged = Mesh::new_cube().merge_coplanar_faces(COPLANAR_ANGLE);
+        let twice = merged.merge_coplanar_faces(std::f32::consts::FRAC_PI_4);
+        assert_eq!(face_sizes(&twice), vec![4; 6]);
+    }
+
+    #[test]
+    fn a_uv_seam_is_kept() {
+        let mut mesh = Mesh::new("SEAM".to_string());
+        let v: Vec<_> = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]]
+            .into_iter()
+            .map(|p| mesh.add_vertex(p))
+            .collect();
+        let uvs_a = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]];
+        let uvs_b = [[0.5, 0.5], [1.0, 1.0], [0.0, 1.0]];
+        mesh.add_face(
+            &[v[0], v[1], v[2]],
+            &FaceDescriptor { uvs: Some(&uvs_a), ..Default::default() },
+        );
+        mesh.add_face(
+            &[v[0], v[2], v[3]],
+            &FaceDescriptor { uvs: Some(&uvs_b), ..Default::default() },
+        );
+        assert_eq!(face_sizes(&mesh.merge_coplanar_faces(COPLANAR_ANGLE)), vec![3, 3]);
+    }
+}
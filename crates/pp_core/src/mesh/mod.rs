@@ -5,20 +5,38 @@ use std::{collections::BTreeMap, ops};
 
 use crate::id::{EdgeId, FaceId, Id, LoopId, VertexId};
 
+pub mod assembly;
+pub mod bleed;
 mod bounds;
 pub mod cut;
+pub mod decimate;
+pub mod delete;
 pub mod edge;
 pub mod face;
+pub mod fill;
+#[cfg(any(test, feature = "test-utils"))]
+mod fixtures;
 pub mod flap;
+pub mod flap_strategy;
+pub mod flip;
+pub mod geometry;
+pub mod label;
 pub mod loop_;
+pub mod manifold;
+pub mod merge;
+pub mod parts;
 pub mod piece;
-mod primitives;
+pub mod primitives;
+pub mod solids;
+pub mod thickness;
 mod transform;
 mod vertex;
+pub mod winding;
 
 use cut::*;
 use edge::*;
 use face::*;
+use flap::FlapSettings;
 use loop_::*;
 use piece::*;
 use vertex::*;
@@ -80,6 +98,16 @@ pub struct Mesh {
     /// Uniform scale factor, applied across all axes. Affects the mesh's own
     /// geometry as well as its derived pieces.
     pub scale: f32,
+    /// The shape limits of the mesh's flaps, where a cut doesn't override them
+    pub flap_settings: FlapSettings,
+    /// How thick the paper the mesh is built from is, in world units, which
+    /// unfolding insets faces along mountain folds for. See [`thickness`].
+    pub paper_thickness: f32,
+    /// The order the user has put the mesh's pieces in to assemble them, by
+    /// root. Empty to follow the suggested one. See [`Mesh::assembly_order`].
+    pub assembly: Vec<FaceId>,
+    /// The highest label any of the mesh's cuts has had, dead or alive
+    pub(crate) last_cut_label: u32,
     /// Indicates if this mesh's model-matrix uniform data has changed
     pub uniform_dirty: bool,
 }
@@ -98,6 +126,10 @@ impl Default for Mesh {
             index_dirty: Default::default(),
             transform: cgmath::Matrix4::identity(),
             scale: 1.0,
+            flap_settings: Default::default(),
+            paper_thickness: 0.0,
+            assembly: Vec::new(),
+            last_cut_label: 0,
             uniform_dirty: true,
         }
     }
@@ -169,6 +201,68 @@ mod test {
         assert_eq!(mesh.faces.num_elements(), 1);
     }
 
+    #[test]
+    fn add_quad_face() {
+        let mut mesh = Mesh::new(String::from("test"));
+        let v: Vec<_> = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]]
+            .into_iter()
+            .map(|po| mesh.add_vertex(po))
+            .collect();
+        let f = mesh.add_face(&v, &Default::default());
+        assert_eq!(mesh.edges.num_elements(), 4);
+        assert_eq!(mesh.loops.num_elements(), 4);
+        assert_eq!(mesh.iter_face_loops(f).count(), 4);
+        assert_eq!(mesh[f].no, [0.0, 0.0, 1.0]);
+        // The same corners from another start, wound the other way
+        assert_eq!(mesh.add_face(&[v[2], v[1], v[0], v[3]], &Default::default()), f);
+    }
+
+    /// Three of a quad's corners are not the quad, so asking for that triangle
+    /// makes a new face across the quad's diagonal.
+    #[test]
+    fn a_triangle_is_not_a_quad_it_shares_corners_with() {
+        let mut mesh = Mesh::new(String::from("test"));
+        let v: Vec<_> = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]]
+            .into_iter()
+            .map(|po| mesh.add_vertex(po))
+            .collect();
+        let quad = mesh.add_face(&v, &Default::default());
+        let tri = mesh.add_face(&v[..3], &Default::default());
+        assert_ne!(quad, tri);
+        assert_eq!(mesh.faces.num_elements(), 2);
+    }
+
+    /// A polygon's normal comes from its whole outline, so an L-shaped hexagon
+    /// whose first corner is reflex still faces the way it's wound.
+    #[test]
+    fn a_reflex_first_corner_does_not_flip_the_normal() {
+        let mut mesh = Mesh::new(String::from("test"));
+        let outline = [
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [2.0, 0.0, 0.0],
+            [2.0, 2.0, 0.0],
+            [1.0, 2.0, 0.0],
+        ];
+        let v: Vec<_> = outline.into_iter().map(|po| mesh.add_vertex(po)).collect();
+        let f = mesh.add_face(&v, &Default::default());
+        assert_eq!(mesh[f].no, [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn quad_cube_topology() {
+        let mesh = Mesh::new_quad_cube();
+        assert_eq!(mesh.verts.num_elements(), 8);
+        assert_eq!(mesh.edges.num_elements(), 12);
+        assert_eq!(mesh.loops.num_elements(), 24);
+        assert_eq!(mesh.faces.num_elements(), 6);
+        // Every edge is shared by exactly two faces
+        assert!(mesh.edges.indices().all(|e| {
+            mesh.iter_edge_loops(EdgeId::from_usize(e)).is_some_and(|walker| walker.count() == 2)
+        }));
+    }
+
     #[test]
     fn scale_mesh_affects_vert_pos_and_dirties_verts_and_pieces() {
         let mut mesh = Mesh::new(String::from("test"));
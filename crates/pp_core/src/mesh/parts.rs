@@ -0,0 +1,308 @@
+use cgmath::{EuclideanSpace, InnerSpace, Point3, SquareMatrix, Transform, Vector3};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::geometry::{GeometryCorner, GeometryFace, MeshGeometry};
+use super::Mesh;
+use crate::id::{self, Id};
+
+impl Mesh {
+    /// The mesh's faces grouped into parts, each the faces which can be
+    /// reached from one another across uncut edges, in order of their first
+    /// face. A mesh already cut into pieces has one part per piece.
+    pub fn loose_parts(&self) -> Vec<Vec<id::FaceId>> {
+        let mut seen: HashSet<id::FaceId> = HashSet::new();
+        let mut parts = Vec::new();
+        for f_id in self.faces.indices().map(id::FaceId::from_usize) {
+            if seen.contains(&f_id) {
+                continue;
+            }
+            let mut part: Vec<_> = self.iter_connected_faces(f_id).collect();
+            part.sort();
+            seen.extend(part.iter().copied());
+            parts.push(part);
+        }
+        parts
+    }
+
+    /// The geometry of each of the mesh's loose parts, ready to be set on a
+    /// mesh of its own. Each part keeps its faces' order and its vertices'
+    /// positions, but its vertex IDs start again from zero.
+    ///
+    /// Pieces go with the part their root is in. Cuts go with the part on
+    /// both sides of them; a cut between two parts ends up on the border of
+    /// each, where there's nothing left to cut.
+    pub fn split_parts(&self) -> Vec<MeshGeometry> {
+        let geometry = self.geometry();
+        let face_ids: Vec<_> = self.faces.indices().map(id::FaceId::from_usize).collect();
+        let index_of: HashMap<_, _> = face_ids.iter().enumerate().map(|(i, f)| (*f, i)).collect();
+
+        self.loose_parts()
+            .into_iter()
+            .map(|part| {
+                let mut remap: BTreeMap<id::VertexId, id::VertexId> = BTreeMap::new();
+                let mut split = MeshGeometry::default();
+                let in_part: HashSet<_> = part.iter().copied().collect();
+                let mut new_index: HashMap<usize, usize> = HashMap::new();
+                for f_id in &part {
+                    let i = index_of[f_id];
+                    // A part is numbered afresh, so its faces go in as new ones
+                    let mut face = GeometryFace { f: None, ..geometry.faces[i].clone() };
+                    for corner in &mut face.corners {
+                        corner.l = None;
+                        corner.v = *remap.entry(corner.v).or_insert_with(|| {
+                            split.verts.push(geometry.verts[corner.v.to_usize()]);
+                            id::VertexId::from_usize(split.verts.len() - 1)
+                        });
+                    }
+                    new_index.insert(i, split.faces.len());
+                    split.faces.push(face);
+                }
+                split.cuts = self
+                    .cuts
+                    .iter()
+                    .filter(|(e_id, cut)| {
+                        !cut.is_dead
+                            && self.iter_edge_loops(**e_id).is_some_and(|mut loops| {
+                                loops.all(|l| in_part.contains(&self[l].f))
+                            })
+                    })
+                    .map(|(e_id, cut)| (self[*e_id].v.map(|v| remap[&v]), cut.flap_position))
+                    .collect();
+                split.cut_flaps = geometry
+                    .cut_flaps
+                    .iter()
+                    .filter(|(v, _)| v.iter().all(|v| remap.contains_key(v)))
+                    .map(|(v, settings)| (v.map(|v| remap[&v]), *settings))
+                    .collect();
+                split.cut_styles = geometry
+                    .cut_styles
+                    .iter()
+                    .filter(|(v, _)| v.iter().all(|v| remap.contains_key(v)))
+                    .map(|(v, style)| (v.map(|v| remap[&v]), *style))
+                    .collect();
+                split.cut_labels = geometry
+                    .cut_labels
+                    .iter()
+                    .filter(|(v, _)| v.iter().all(|v| remap.contains_key(v)))
+                    .map(|(v, label)| (v.map(|v| remap[&v]), *label))
+                    .collect();
+                split.pieces = geometry
+                    .pieces
+                    .iter()
+                    .filter_map(|(i, transform)| Some((*new_index.get(i)?, *transform)))
+                    .collect();
+                split.assembly =
+                    geometry.assembly.iter().filter_map(|i| new_index.get(i).copied()).collect();
+                split
+            })
+            .collect()
+    }
+
+    /// Joins another mesh's faces, cuts and pieces into this one. The other
+    /// mesh's vertices are moved into this mesh's space, through both meshes'
+    /// transforms and scales, so the joined faces stay where they were seen.
+    ///
+    /// With a `weld_distance`, each of the other mesh's vertices which comes
+    /// within that distance of one of this mesh's is welded onto it, so that
+    /// the faces either side of the seam share its edges: the seam can then
+    /// be cut, or left as a fold. Faces which welding collapses are dropped.
+    /// Vertices are never welded within the same mesh.
+    pub fn join(&mut self, other: &Mesh, weld_distance: Option<f32>) {
+        let mut geometry = self.geometry();
+        let joining = other.geometry();
+
+        // From the other mesh's local space into this one's
+        let to_local = self.transform.invert().unwrap_or(cgmath::Matrix4::identity());
+        let moved = |po: [f32; 3]| -> [f32; 3] {
+            let world =
+                other.transform.transform_point(Point3::from_vec(Vector3::from(po) * other.scale));
+            (to_local.transform_point(world).to_vec() / self.scale).into()
+        };
+
+        let welder = weld_distance.map(|distance| VertexGrid::new(&geometry.verts, distance));
+        let mut remap: Vec<Option<id::VertexId>> = vec![None; joining.verts.len()];
+        for (v, po) in joining.verts.iter().enumerate() {
+            let Some(po) = po.map(moved) else {
+                continue;
+            };
+            let welded = welder.as_ref().and_then(|grid| grid.nearest(&geometry.verts, po));
+            remap[v] = Some(welded.unwrap_or_else(|| {
+                geometry.verts.push(Some(po));
+                id::VertexId::from_usize(geometry.verts.len() - 1)
+            }));
+        }
+        let at = |v: id::VertexId| remap[v.to_usize()].unwrap();
+
+        let mut new_index: HashMap<usize, usize> = HashMap::new();
+        for (i, face) in joining.faces.iter().enumerate() {
+            let corners: Vec<_> =
+                face.corners.iter().map(|c| GeometryCorner { l: None, v: at(c.v), ..*c }).collect();
+            let distinct: HashSet<_> = corners.iter().map(|c| c.v).collect();
+            if distinct.len() < corners.len() {
+                continue;
+            }
+            new_index.insert(i, geometry.faces.len());
+            geometry.faces.push(GeometryFace { f: None, m: face.m, corners });
+        }
+        geometry.cuts.extend(joining.cuts.iter().map(|(v, flap)| (v.map(at), *flap)));
+        geometry.cut_flaps.extend(joining.cut_flaps.iter().map(|(v, flap)| (v.map(at), *flap)));
+        geometry.cut_styles.extend(joining.cut_styles.iter().map(|(v, style)| (v.map(at), *style)));
+        // The other mesh numbers its cuts from one too, so its labels carry on
+        // from this mesh's
+        let offset = self.next_cut_label() - 1;
+        geometry
+            .cut_labels
+            .extend(joining.cut_labels.iter().map(|(v, label)| (v.map(at), label + offset)));
+        geometry.pieces.extend(
+            joining
+                .pieces
+                .iter()
+                .filter_map(|(i, transform)| Some((*new_index.get(i)?, *transform))),
+        );
+        // Any order the other mesh stored carries on after this one's
+        geometry.assembly.extend(joining.assembly.iter().filter_map(|i| new_index.get(i)));
+        self.set_geometry(&geometry);
+    }
+}
+
+/// Vertex positions bucketed into cubes as wide as the weld distance, so
+/// that any vertex within that distance of a point is in one of the 27 cubes
+/// around it
+struct VertexGrid {
+    distance: f32,
+    cells: HashMap<[i32; 3], Vec<id::VertexId>>,
+}
+
+impl VertexGrid {
+    fn new(verts: &[Option<[f32; 3]>], distance: f32) -> Self {
+        let mut grid = Self { distance: distance.max(f32::EPSILON), cells: HashMap::new() };
+        for (v, po) in verts.iter().enumerate() {
+            if let Some(po) = po {
+                grid.cells.entry(grid.cell(*po)).or_default().push(id::VertexId::from_usize(v));
+            }
+        }
+        grid
+    }
+
+    fn cell(&self, po: [f32; 3]) -> [i32; 3] {
+        po.map(|c| (c / self.distance).floor() as i32)
+    }
+
+    /// The closest vertex within the weld distance of `po`, if any
+    fn nearest(&self, verts: &[Option<[f32; 3]>], po: [f32; 3]) -> Option<id::VertexId> {
+        let [x, y, z] = self.cell(po);
+        let neighbours = (-1..=1).flat_map(|dx| {
+            (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| [x + dx, y + dy, z + dz]))
+        });
+        neighbours
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .map(|v| {
+                let other = verts[v.to_usize()].unwrap();
+                (*v, (Vector3::from(other) - Vector3::from(po)).magnitude())
+            })
+            .filter(|(_, d)| *d <= self.distance)
+            .min_by(|(v_a, d_a), (v_b, d_b)| d_a.total_cmp(d_b).then(v_a.cmp(v_b)))
+            .map(|(v, _)| v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::{EdgeId, FaceId, VertexId};
+
+    /// Two cubes side by side in one mesh, touching nowhere
+    fn two_cubes() -> Mesh {
+        let mut mesh = Mesh::new_cube();
+        let mut other = Mesh::new_cube();
+        other.transform = cgmath::Matrix4::from_translation(Vector3::new(2.0, 0.0, 0.0));
+        mesh.join(&other, None);
+        mesh
+    }
+
+    fn border_edges(mesh: &Mesh) -> usize {
+        mesh.edges
+            .indices()
+            .filter(|e| mesh.iter_edge_loops(EdgeId::from_usize(*e)).unwrap().count() == 1)
+            .count()
+    }
+
+    #[test]
+    fn joining_keeps_where_the_other_mesh_was() {
+        let mesh = two_cubes();
+        assert_eq!(mesh.faces.num_elements(), 24);
+        assert_eq!(mesh.verts.num_elements(), 16);
+        assert_eq!(mesh[VertexId::from_usize(8)].po, [1.5, -0.5, 0.0]);
+        assert_eq!(mesh.loose_parts().len(), 2);
+    }
+
+    #[test]
+    fn a_mesh_splits_into_its_loose_parts() {
+        let mesh = two_cubes();
+        let parts = mesh.split_parts();
+        assert_eq!(parts.len(), 2);
+        for geometry in &parts {
+            let mut part = Mesh::new("PART".to_string());
+            part.set_geometry(geometry);
+            assert_eq!(part.faces.num_elements(), 12);
+            assert_eq!(part.verts.num_elements(), 8);
+            assert_eq!(border_edges(&part), 0);
+        }
+        assert!(parts[1].verts.iter().all(|po| po.unwrap()[0] > 1.0));
+    }
+
+    /// Two open boxes, mouth to mouth, welded shut along their rims
+    #[test]
+    fn welding_closes_the_seam_between_two_meshes() {
+        let open_box = |flip: bool| {
+            let mut mesh = Mesh::new_cube();
+            let top: Vec<_> = mesh
+                .faces
+                .indices()
+                .map(FaceId::from_usize)
+                .filter(|f| mesh[*f].no[2] > 0.5)
+                .collect();
+            mesh.delete_faces(&top);
+            if flip {
+                // Upside down on top of the first, so the open sides meet
+                mesh.transform = cgmath::Matrix4::from_translation(Vector3::new(0.0, 0.0, 2.0))
+                    * cgmath::Matrix4::from_angle_x(cgmath::Deg(180.0));
+            }
+            mesh
+        };
+        let mut mesh = open_box(false);
+        let mut unwelded = open_box(false);
+        unwelded.join(&open_box(true), None);
+        assert_eq!(border_edges(&unwelded), 8);
+
+        mesh.join(&open_box(true), Some(1e-4));
+        assert_eq!(border_edges(&mesh), 0);
+        assert_eq!(mesh.verts.num_elements(), 12);
+        assert_eq!(mesh.loose_parts().len(), 1);
+        // And the seam can be cut apart again
+        let seam = mesh.query_edge(VertexId::from_usize(4), VertexId::from_usize(5)).unwrap();
+        assert_eq!(mesh.iter_edge_loops(seam).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn cuts_and_pieces_go_with_their_part() {
+        let mut mesh = Mesh::cut_cube();
+        let pieces = mesh.iter_pieces().count();
+        let mut other = Mesh::new_cube();
+        other.transform = cgmath::Matrix4::from_translation(Vector3::new(2.0, 0.0, 0.0));
+        mesh.join(&other, None);
+        // Each of the cut cube's pieces is a part of its own
+        let parts = mesh.split_parts();
+        assert!(parts.len() > pieces);
+        assert_eq!(parts.iter().map(|part| part.pieces.len()).sum::<usize>(), pieces);
+        // No part is left with a cut on its border
+        for geometry in &parts {
+            let mut part = Mesh::new("PART".to_string());
+            part.set_geometry(geometry);
+            assert_eq!(part.iter_pieces().count(), geometry.pieces.len());
+            assert!(part.cuts.keys().all(|e| part.iter_edge_loops(*e).unwrap().count() == 2));
+        }
+        assert!(parts.last().unwrap().cuts.is_empty());
+    }
+}
@@ -117,10 +117,27 @@ impl super::Mesh {
         UnfoldedPieceFaceWalker::new(self, f_id)
     }
 
+    /// Where `l_id`'s edge lies in its piece, in piece space (the piece's own
+    /// transform not applied), from the loop's vertex to the next one's
+    pub fn loop_piece_span(&self, l_id: LoopId) -> Option<[Point3<f32>; 2]> {
+        let l = self[l_id];
+        let root = self[l.f].p?;
+        let face = self.iter_piece_faces_unfolded(root).find(|face| face.f == l.f)?;
+        let unfolded = |l_id: LoopId| {
+            face.affine.transform_point(Point3::from_vec(self.vert_pos(self[l_id].v)))
+        };
+        Some([unfolded(l_id), unfolded(l.next)])
+    }
+
     /// The rotation which brings the face across `l_id`'s edge onto `l_id`'s own
     /// face, about the seam the two share, by `t` of the signed angle between
     /// their normals. `t` is a piece's unfoldedness, so `t = 0` leaves the
     /// neighbour folded where it sits in 3D and `t = 1` makes it coplanar.
+    ///
+    /// For thick paper, both faces are inset from the seam (see
+    /// [`super::thickness`]), and this takes the neighbour's inset face onto
+    /// this one's: as it unfolds it slides in by both insets, so that flat, the
+    /// two meet along their inset edges.
     pub(crate) fn unfold_hinge_affine(&self, l_id: LoopId, t: f32) -> Matrix4<f32> {
         let l = self[l_id];
         let across = self[l.radial_next];
@@ -130,9 +147,21 @@ impl super::Mesh {
         // Signed angle taking the neighbour's normal onto ours, about that axis.
         let (n_here, n_across) = (Vector3::from(self[l.f].no), Vector3::from(self[across.f].no));
         let angle = axis.dot(n_across.cross(n_here)).atan2(n_across.dot(n_here)) * t;
-        Matrix4::from_translation(v0)
+        let hinge = Matrix4::from_translation(v0)
             * Matrix4::from_axis_angle(axis, Rad(angle))
-            * Matrix4::from_translation(-v0)
+            * Matrix4::from_translation(-v0);
+
+        if self.paper_thickness <= 0.0 {
+            return hinge;
+        }
+        let inset = self.edge_thickness_inset(l.e);
+        // Loops run counter-clockwise about their face, so inward is to the left
+        let along = self.vert_pos(self[l.next].v) - self.vert_pos(l.v);
+        let inward = n_here.cross(along).normalize();
+        let here = self.face_inset_affine(l.f).invert().unwrap_or(Matrix4::identity());
+        here * Matrix4::from_translation(inward * (2.0 * inset * t))
+            * hinge
+            * self.face_inset_affine(across.f)
     }
 
     /// Moves the piece, updating its transformation
@@ -205,7 +234,7 @@ impl<'mesh> UnfoldedPieceFaceWalker<'mesh> {
             t: *t,
             affine_final,
             visited: HashSet::from([f]),
-            frontier: VecDeque::from([UnfoldedFace { f, affine: Matrix4::identity() }]),
+            frontier: VecDeque::from([UnfoldedFace { f, affine: mesh.face_inset_affine(f) }]),
         }
     }
 }
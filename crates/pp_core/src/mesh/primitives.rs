@@ -1,4 +1,69 @@
-use super::{face::FaceDescriptor, Mesh};
+use cgmath::{InnerSpace, Vector3};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f32::consts::{PI, TAU};
+use tsify::Tsify;
+
+use super::face::{polygon_normal, FaceDescriptor};
+use super::solids::{hull_faces, Solid};
+use super::Mesh;
+use crate::id;
+
+/// The most segments a round primitive can be built from
+const MAX_SEGMENTS: u32 = 256;
+/// The most times an icosphere can be subdivided: five is already 20480 faces
+const MAX_SUBDIVISIONS: u32 = 5;
+
+/// A parametric shape to start a model from. Every primitive is closed, wound
+/// outwards, centred over the origin, resting on the ground, and one unit
+/// across at its widest, like the cube.
+#[derive(Clone, Copy, Debug, PartialEq, Tsify, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+#[serde(tag = "type")]
+pub enum Primitive {
+    /// A round column, smooth around its sides
+    Cylinder {
+        segments: u32,
+    },
+    /// A round cone, smooth around its side
+    Cone {
+        segments: u32,
+    },
+    /// A column with flat sides
+    Prism {
+        sides: u32,
+    },
+    /// A point over a flat-sided base
+    Pyramid {
+        sides: u32,
+    },
+    /// A sphere of quads between lines of latitude, with triangles at the poles
+    UvSphere {
+        segments: u32,
+        rings: u32,
+    },
+    /// A sphere of near-equal triangles, from a subdivided icosahedron
+    IcoSphere {
+        subdivisions: u32,
+    },
+    Solid {
+        solid: Solid,
+    },
+}
+
+impl Primitive {
+    pub fn label(&self) -> String {
+        match self {
+            Primitive::Cylinder { .. } => "CYLINDER".to_string(),
+            Primitive::Cone { .. } => "CONE".to_string(),
+            Primitive::Prism { .. } => "PRISM".to_string(),
+            Primitive::Pyramid { .. } => "PYRAMID".to_string(),
+            Primitive::UvSphere { .. } => "UV SPHERE".to_string(),
+            Primitive::IcoSphere { .. } => "ICOSPHERE".to_string(),
+            Primitive::Solid { solid } => format!("{solid:?}").to_uppercase(),
+        }
+    }
+}
 
 impl Mesh {
     pub fn new_tri() -> Self {
@@ -44,4 +109,452 @@ impl Mesh {
         mesh.add_face(&[v[1], v[6], v[2]], &FaceDescriptor::default());
         mesh
     }
+
+    /// The same cube as `new_cube`, built from six quads rather than twelve
+    /// triangles, with its vertices in the same order.
+    pub fn new_quad_cube() -> Self {
+        let mut mesh = Self::new("CUBE".to_string());
+        let v = &[
+            mesh.add_vertex([-0.5, -0.5, 0.0]),
+            mesh.add_vertex([-0.5, 0.5, 0.0]),
+            mesh.add_vertex([0.5, 0.5, 0.0]),
+            mesh.add_vertex([0.5, -0.5, 0.0]),
+            mesh.add_vertex([-0.5, -0.5, 1.0]),
+            mesh.add_vertex([-0.5, 0.5, 1.0]),
+            mesh.add_vertex([0.5, 0.5, 1.0]),
+            mesh.add_vertex([0.5, -0.5, 1.0]),
+        ];
+        mesh.add_face(&[v[0], v[1], v[2], v[3]], &FaceDescriptor::default()); // Bottom
+        mesh.add_face(&[v[4], v[7], v[6], v[5]], &FaceDescriptor::default()); // Top
+        mesh.add_face(&[v[0], v[4], v[5], v[1]], &FaceDescriptor::default()); // Front
+        mesh.add_face(&[v[3], v[2], v[6], v[7]], &FaceDescriptor::default()); // Back
+        mesh.add_face(&[v[0], v[3], v[7], v[4]], &FaceDescriptor::default()); // Left
+        mesh.add_face(&[v[1], v[5], v[6], v[2]], &FaceDescriptor::default()); // Right
+        mesh
+    }
+
+    /// Builds any of the primitives
+    pub fn from_primitive(primitive: &Primitive) -> Self {
+        let shape = match *primitive {
+            Primitive::Cylinder { segments } => Shape::column(segments, true),
+            Primitive::Cone { segments } => Shape::cone(segments, true),
+            Primitive::Prism { sides } => Shape::column(sides, false),
+            Primitive::Pyramid { sides } => Shape::cone(sides, false),
+            Primitive::UvSphere { segments, rings } => Shape::uv_sphere(segments, rings),
+            Primitive::IcoSphere { subdivisions } => Shape::icosphere(subdivisions),
+            Primitive::Solid { solid } => Shape::solid(solid),
+        };
+        shape.into_mesh(primitive.label())
+    }
+
+    pub fn new_cylinder(segments: u32) -> Self {
+        Self::from_primitive(&Primitive::Cylinder { segments })
+    }
+
+    pub fn new_cone(segments: u32) -> Self {
+        Self::from_primitive(&Primitive::Cone { segments })
+    }
+
+    pub fn new_prism(sides: u32) -> Self {
+        Self::from_primitive(&Primitive::Prism { sides })
+    }
+
+    pub fn new_pyramid(sides: u32) -> Self {
+        Self::from_primitive(&Primitive::Pyramid { sides })
+    }
+
+    pub fn new_uv_sphere(segments: u32, rings: u32) -> Self {
+        Self::from_primitive(&Primitive::UvSphere { segments, rings })
+    }
+
+    pub fn new_icosphere(subdivisions: u32) -> Self {
+        Self::from_primitive(&Primitive::IcoSphere { subdivisions })
+    }
+
+    pub fn new_solid(solid: Solid) -> Self {
+        Self::from_primitive(&Primitive::Solid { solid })
+    }
+}
+
+/// One face of a `Shape`, by index into its positions. Without normals, the
+/// face is flat shaded.
+struct ShapeFace {
+    verts: Vec<usize>,
+    uvs: Vec<[f32; 2]>,
+    nos: Option<Vec<[f32; 3]>>,
+}
+
+/// A primitive's geometry before it's fitted into place and made a mesh
+#[derive(Default)]
+struct Shape {
+    positions: Vec<Vector3<f32>>,
+    faces: Vec<ShapeFace>,
+}
+
+impl Shape {
+    fn push(&mut self, po: Vector3<f32>) -> usize {
+        self.positions.push(po);
+        self.positions.len() - 1
+    }
+
+    /// A cylinder or prism of radius one half and height one. The sides take
+    /// the bottom half of the UV square, going once around; the caps are
+    /// discs side by side in the top half.
+    fn column(segments: u32, smooth: bool) -> Self {
+        let n = segments.clamp(3, MAX_SEGMENTS) as usize;
+        let mut shape = Shape::default();
+        let ring = |shape: &mut Shape, z: f32| -> Vec<usize> {
+            (0..n).map(|i| shape.push(around(i, n, 0.5) + Vector3::unit_z() * z)).collect()
+        };
+        let (bottom, top) = (ring(&mut shape, 0.0), ring(&mut shape, 1.0));
+        for i in 0..n {
+            let j = (i + 1) % n;
+            let (u_i, u_j) = (i as f32 / n as f32, (i + 1) as f32 / n as f32);
+            let nos = smooth.then(|| {
+                let (no_i, no_j) = (around(i, n, 1.0).into(), around(j, n, 1.0).into());
+                vec![no_i, no_j, no_j, no_i]
+            });
+            shape.faces.push(ShapeFace {
+                verts: vec![bottom[i], bottom[j], top[j], top[i]],
+                uvs: vec![[u_i, 0.0], [u_j, 0.0], [u_j, 0.5], [u_i, 0.5]],
+                nos,
+            });
+        }
+        shape.faces.push(ShapeFace {
+            verts: bottom.iter().rev().copied().collect(),
+            uvs: (0..n).rev().map(|i| disc_uv(i, n, [0.25, 0.75])).collect(),
+            nos: None,
+        });
+        shape.faces.push(ShapeFace {
+            verts: top,
+            uvs: (0..n).map(|i| disc_uv(i, n, [0.75, 0.75])).collect(),
+            nos: None,
+        });
+        shape
+    }
+
+    /// A cone or pyramid of radius one half and height one, laid out in UV
+    /// space like `column`, with the tip along the middle of the square
+    fn cone(segments: u32, smooth: bool) -> Self {
+        let n = segments.clamp(3, MAX_SEGMENTS) as usize;
+        let mut shape = Shape::default();
+        let base: Vec<_> = (0..n).map(|i| shape.push(around(i, n, 0.5))).collect();
+        let tip = shape.push(Vector3::unit_z());
+        // The side slopes in by half a unit over its height of one
+        let slope = |t: f32| -> [f32; 3] {
+            let out = Vector3::new(t.cos(), t.sin(), 0.0);
+            (out + Vector3::unit_z() * 0.5).normalize().into()
+        };
+        for i in 0..n {
+            let j = (i + 1) % n;
+            let (u_i, u_j) = (i as f32 / n as f32, (i + 1) as f32 / n as f32);
+            let nos = smooth.then(|| {
+                let t = |k: f32| k * TAU / n as f32;
+                vec![slope(t(i as f32)), slope(t(i as f32 + 1.0)), slope(t(i as f32 + 0.5))]
+            });
+            shape.faces.push(ShapeFace {
+                verts: vec![base[i], base[j], tip],
+                uvs: vec![[u_i, 0.0], [u_j, 0.0], [(u_i + u_j) / 2.0, 0.5]],
+                nos,
+            });
+        }
+        shape.faces.push(ShapeFace {
+            verts: base.iter().rev().copied().collect(),
+            uvs: (0..n).rev().map(|i| disc_uv(i, n, [0.5, 0.75])).collect(),
+            nos: None,
+        });
+        shape
+    }
+
+    /// A sphere with `segments` around and `rings` from pole to pole, mapped
+    /// onto the UV square by longitude and latitude
+    fn uv_sphere(segments: u32, rings: u32) -> Self {
+        let n = segments.clamp(3, MAX_SEGMENTS) as usize;
+        let rings = rings.clamp(2, MAX_SEGMENTS) as usize;
+        let mut shape = Shape::default();
+        let south = shape.push(-Vector3::unit_z());
+        let latitudes: Vec<Vec<usize>> = (1..rings)
+            .map(|r| {
+                let polar = PI * r as f32 / rings as f32;
+                let z = Vector3::unit_z() * -polar.cos();
+                (0..n).map(|i| shape.push(around(i, n, polar.sin()) + z)).collect()
+            })
+            .collect();
+        let north = shape.push(Vector3::unit_z());
+
+        let uv = |i: usize, r: usize| [i as f32 / n as f32, r as f32 / rings as f32];
+        let pole_uv = |i: usize, r: usize| [(i as f32 + 0.5) / n as f32, r as f32 / rings as f32];
+        for i in 0..n {
+            let j = (i + 1) % n;
+            shape.faces.push(ShapeFace {
+                verts: vec![south, latitudes[0][j], latitudes[0][i]],
+                uvs: vec![pole_uv(i, 0), uv(i + 1, 1), uv(i, 1)],
+                nos: None,
+            });
+            for r in 1..rings - 1 {
+                let (below, above) = (&latitudes[r - 1], &latitudes[r]);
+                shape.faces.push(ShapeFace {
+                    verts: vec![below[i], below[j], above[j], above[i]],
+                    uvs: vec![uv(i, r), uv(i + 1, r), uv(i + 1, r + 1), uv(i, r + 1)],
+                    nos: None,
+                });
+            }
+            let last = &latitudes[rings - 2];
+            shape.faces.push(ShapeFace {
+                verts: vec![last[i], last[j], north],
+                uvs: vec![uv(i, rings - 1), uv(i + 1, rings - 1), pole_uv(i, rings)],
+                nos: None,
+            });
+        }
+        shape.smooth_sphere();
+        shape
+    }
+
+    /// An icosahedron with each triangle split into four, `subdivisions`
+    /// times over, and pushed out onto its sphere
+    fn icosphere(subdivisions: u32) -> Self {
+        let mut positions: Vec<_> =
+            Solid::Icosahedron.vertices().into_iter().map(|po| po.normalize()).collect();
+        let mut triangles: Vec<Vec<usize>> = hull_faces(&positions);
+        for _ in 0..subdivisions.min(MAX_SUBDIVISIONS) {
+            let mut midpoints: HashMap<(usize, usize), usize> = HashMap::new();
+            let mut midpoint = |a: usize, b: usize| -> usize {
+                *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                    positions.push((positions[a] + positions[b]).normalize());
+                    positions.len() - 1
+                })
+            };
+            triangles = triangles
+                .iter()
+                .flat_map(|t| {
+                    let (a, b, c) = (t[0], t[1], t[2]);
+                    let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+                    [vec![a, ab, ca], vec![ab, b, bc], vec![ca, bc, c], vec![ab, bc, ca]]
+                })
+                .collect();
+        }
+        let mut shape = Shape::from_hull(positions, triangles);
+        shape.smooth_sphere();
+        shape
+    }
+
+    /// One of the uniform solids, flat shaded
+    fn solid(solid: Solid) -> Self {
+        let positions = solid.vertices();
+        let faces = hull_faces(&positions);
+        Shape::from_hull(positions, faces)
+    }
+
+    /// Faces around the origin, mapped onto the UV square by the longitude
+    /// and latitude of their corners
+    fn from_hull(positions: Vec<Vector3<f32>>, faces: Vec<Vec<usize>>) -> Self {
+        let faces = faces
+            .into_iter()
+            .map(|verts| {
+                let uvs = spherical_uvs(&verts.iter().map(|v| positions[*v]).collect::<Vec<_>>());
+                ShapeFace { verts, uvs, nos: None }
+            })
+            .collect();
+        Shape { positions, faces }
+    }
+
+    /// Gives every corner the normal of a sphere around the origin
+    fn smooth_sphere(&mut self) {
+        for face in &mut self.faces {
+            let nos = face.verts.iter().map(|v| self.positions[*v].normalize().into());
+            face.nos = Some(nos.collect());
+        }
+    }
+
+    /// Fits the shape into place, centred over the origin on the ground and
+    /// one unit across at its widest, and builds its mesh
+    fn into_mesh(self, label: String) -> Mesh {
+        let (mut min, mut max) = (Vector3::from([f32::MAX; 3]), Vector3::from([f32::MIN; 3]));
+        for po in &self.positions {
+            min = Vector3::new(min.x.min(po.x), min.y.min(po.y), min.z.min(po.z));
+            max = Vector3::new(max.x.max(po.x), max.y.max(po.y), max.z.max(po.z));
+        }
+        let size = max - min;
+        let scale = 1.0 / size.x.max(size.y).max(size.z);
+        let offset = Vector3::new((min.x + max.x) / 2.0, (min.y + max.y) / 2.0, min.z);
+
+        let mut mesh = Mesh::new(label);
+        let verts: Vec<id::VertexId> = self
+            .positions
+            .iter()
+            .map(|po| mesh.add_vertex(((po - offset) * scale).into()))
+            .collect();
+        for face in &self.faces {
+            let face_verts: Vec<_> = face.verts.iter().map(|v| verts[*v]).collect();
+            let nos = face.nos.clone().unwrap_or_else(|| {
+                let positions: Vec<_> =
+                    face.verts.iter().map(|v| self.positions[*v].into()).collect();
+                vec![polygon_normal(&positions); face.verts.len()]
+            });
+            let descriptor = FaceDescriptor { m: None, uvs: Some(&face.uvs), nos: Some(&nos) };
+            mesh.add_face(&face_verts, &descriptor);
+        }
+        mesh
+    }
+}
+
+/// The `i`th of `n` points evenly around a circle on the ground
+fn around(i: usize, n: usize, radius: f32) -> Vector3<f32> {
+    let t = i as f32 * TAU / n as f32;
+    Vector3::new(t.cos(), t.sin(), 0.0) * radius
+}
+
+/// Where the `i`th of `n` points around a cap goes in UV space, on a disc a
+/// quarter of the square across
+fn disc_uv(i: usize, n: usize, centre: [f32; 2]) -> [f32; 2] {
+    let t = i as f32 * TAU / n as f32;
+    [centre[0] + t.cos() * 0.25, centre[1] + t.sin() * 0.25]
+}
+
+/// Longitude and latitude UVs for the corners of one face. A face which
+/// straddles the seam at the back has its corners before the seam moved on
+/// past it, and a corner on a pole, where longitude means nothing, takes the
+/// average of the face's others.
+fn spherical_uvs(corners: &[Vector3<f32>]) -> Vec<[f32; 2]> {
+    let on_pole = |po: &Vector3<f32>| po.x.hypot(po.y) < po.magnitude() * 1e-4;
+    let mut uvs: Vec<[f32; 2]> = corners
+        .iter()
+        .map(|po| {
+            let u = 0.5 + po.y.atan2(po.x) / TAU;
+            let v = 0.5 + (po.z / po.magnitude()).clamp(-1.0, 1.0).asin() / PI;
+            [u, v]
+        })
+        .collect();
+    let off_pole = |uvs: &[[f32; 2]]| -> Vec<f32> {
+        uvs.iter().zip(corners).filter(|(_, po)| !on_pole(po)).map(|(uv, _)| uv[0]).collect()
+    };
+    let us = off_pole(&uvs);
+    let spread =
+        us.iter().fold(f32::MIN, |a, b| a.max(*b)) - us.iter().fold(f32::MAX, |a, b| a.min(*b));
+    if spread > 0.5 {
+        uvs.iter_mut().filter(|uv| uv[0] < 0.5).for_each(|uv| uv[0] += 1.0);
+    }
+    let us = off_pole(&uvs);
+    if us.len() < corners.len() && !us.is_empty() {
+        let average = us.iter().sum::<f32>() / us.len() as f32;
+        uvs.iter_mut()
+            .zip(corners)
+            .filter(|(_, po)| on_pole(po))
+            .for_each(|(uv, _)| uv[0] = average);
+    }
+    uvs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::{EdgeId, Id};
+
+    fn all_primitives() -> Vec<Primitive> {
+        let mut primitives = vec![
+            Primitive::Cylinder { segments: 12 },
+            Primitive::Cone { segments: 12 },
+            Primitive::Prism { sides: 5 },
+            Primitive::Pyramid { sides: 4 },
+            Primitive::UvSphere { segments: 16, rings: 8 },
+            Primitive::IcoSphere { subdivisions: 2 },
+        ];
+        primitives.extend(Solid::ALL.map(|solid| Primitive::Solid { solid }));
+        primitives
+    }
+
+    fn edge_lengths(mesh: &Mesh) -> Vec<f32> {
+        mesh.edges
+            .indices()
+            .map(|e| {
+                let [a, b] = mesh[EdgeId::from_usize(e)].v.map(|v| Vector3::from(mesh[v].po));
+                (a - b).magnitude()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_primitive_is_a_closed_outward_sphere() {
+        for primitive in all_primitives() {
+            let mut mesh = Mesh::from_primitive(&primitive);
+            let (v, e, f) =
+                (mesh.verts.num_elements(), mesh.edges.num_elements(), mesh.faces.num_elements());
+            assert_eq!(v + f, e + 2, "{primitive:?} isn't a sphere");
+            assert!(
+                (0..e).all(|e| mesh.iter_edge_loops(EdgeId::from_usize(e)).unwrap().count() == 2),
+                "{primitive:?} isn't closed"
+            );
+            assert_eq!(mesh.orient_faces().flipped, 0, "{primitive:?} is wound inconsistently");
+            // Wound outwards, so the centre is behind every face
+            let centre = Vector3::new(0.0, 0.0, 0.0);
+            let sum: Vector3<f32> = mesh.verts.values().map(|v| Vector3::from(v.po)).sum();
+            let centre = centre + sum / v as f32;
+            for face in mesh.faces.values() {
+                let corner = Vector3::from(mesh[mesh.loops[face.l.to_usize()].v].po);
+                assert!(Vector3::from(face.no).dot(corner - centre) > 0.0, "{primitive:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn every_primitive_fits_the_unit_cube_on_the_ground() {
+        for primitive in all_primitives() {
+            let mesh = Mesh::from_primitive(&primitive);
+            let positions: Vec<_> = mesh.verts.values().map(|v| v.po).collect();
+            let min = |k: usize| positions.iter().map(|po| po[k]).fold(f32::MAX, f32::min);
+            let max = |k: usize| positions.iter().map(|po| po[k]).fold(f32::MIN, f32::max);
+            assert!(min(2).abs() < 1e-5, "{primitive:?}");
+            assert!((0..3).all(|k| max(k) - min(k) < 1.0 + 1e-5), "{primitive:?}");
+            assert!((0..3).any(|k| max(k) - min(k) > 1.0 - 1e-5), "{primitive:?}");
+            assert!((0..2).all(|k| (min(k) + max(k)).abs() < 1e-5), "{primitive:?}");
+            assert!(mesh.loops.values().all(|l| l.uv.iter().all(|c| (-1e-5..=1.5).contains(c))));
+        }
+    }
+
+    #[test]
+    fn solids_have_their_counts_and_equal_edges() {
+        let counts = [
+            (4, 6, 4),
+            (8, 12, 6),
+            (6, 12, 8),
+            (20, 30, 12),
+            (12, 30, 20),
+            (12, 18, 8),
+            (12, 24, 14),
+            (24, 36, 14),
+            (24, 36, 14),
+            (24, 48, 26),
+            (48, 72, 26),
+            (24, 60, 38),
+            (30, 60, 32),
+            (60, 90, 32),
+            (60, 90, 32),
+            (60, 120, 62),
+            (120, 180, 62),
+            (60, 150, 92),
+        ];
+        for (solid, (v, e, f)) in Solid::ALL.into_iter().zip(counts) {
+            let mesh = Mesh::new_solid(solid);
+            assert_eq!(mesh.verts.num_elements(), v, "{solid:?}");
+            assert_eq!(mesh.edges.num_elements(), e, "{solid:?}");
+            assert_eq!(mesh.faces.num_elements(), f, "{solid:?}");
+            let lengths = edge_lengths(&mesh);
+            let shortest = lengths.iter().copied().fold(f32::MAX, f32::min);
+            assert!(lengths.iter().all(|l| *l < shortest * 1.001), "{solid:?}");
+        }
+    }
+
+    #[test]
+    fn round_primitives_have_their_counts() {
+        let cylinder = Mesh::new_cylinder(12);
+        assert_eq!((cylinder.verts.num_elements(), cylinder.faces.num_elements()), (24, 14));
+        let cone = Mesh::new_cone(12);
+        assert_eq!((cone.verts.num_elements(), cone.faces.num_elements()), (13, 13));
+        let sphere = Mesh::new_uv_sphere(16, 8);
+        assert_eq!((sphere.verts.num_elements(), sphere.faces.num_elements()), (114, 128));
+        let icosphere = Mesh::new_icosphere(2);
+        assert_eq!((icosphere.verts.num_elements(), icosphere.faces.num_elements()), (162, 320));
+        // Too few segments makes the smallest shape that's still closed
+        assert_eq!(Mesh::new_prism(1).faces.num_elements(), 5);
+    }
 }
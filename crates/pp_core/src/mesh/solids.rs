@@ -0,0 +1,248 @@
+use cgmath::{InnerSpace, Vector3};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tsify::Tsify;
+
+/// The golden ratio
+const PHI: f32 = 1.618_034;
+/// The tribonacci constant, which sets the snub cube's twist
+const TRIBONACCI: f32 = 1.839_286_8;
+
+/// The Platonic and Archimedean solids: the convex polyhedra whose faces are
+/// all regular polygons and whose vertices are all alike
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Tsify, Serialize, Deserialize)]
+pub enum Solid {
+    Tetrahedron,
+    Cube,
+    Octahedron,
+    Dodecahedron,
+    Icosahedron,
+    TruncatedTetrahedron,
+    Cuboctahedron,
+    TruncatedCube,
+    TruncatedOctahedron,
+    Rhombicuboctahedron,
+    TruncatedCuboctahedron,
+    SnubCube,
+    Icosidodecahedron,
+    TruncatedDodecahedron,
+    TruncatedIcosahedron,
+    Rhombicosidodecahedron,
+    TruncatedIcosidodecahedron,
+    SnubDodecahedron,
+}
+
+impl Solid {
+    pub const ALL: [Solid; 18] = [
+        Solid::Tetrahedron,
+        Solid::Cube,
+        Solid::Octahedron,
+        Solid::Dodecahedron,
+        Solid::Icosahedron,
+        Solid::TruncatedTetrahedron,
+        Solid::Cuboctahedron,
+        Solid::TruncatedCube,
+        Solid::TruncatedOctahedron,
+        Solid::Rhombicuboctahedron,
+        Solid::TruncatedCuboctahedron,
+        Solid::SnubCube,
+        Solid::Icosidodecahedron,
+        Solid::TruncatedDodecahedron,
+        Solid::TruncatedIcosahedron,
+        Solid::Rhombicosidodecahedron,
+        Solid::TruncatedIcosidodecahedron,
+        Solid::SnubDodecahedron,
+    ];
+
+    /// The solid's vertices, centred on the origin, at whatever size their
+    /// textbook coordinates give
+    pub(super) fn vertices(self) -> Vec<Vector3<f32>> {
+        use Parity::*;
+        use Signs::*;
+        let phi2 = PHI * PHI;
+        let phi3 = phi2 * PHI;
+        let sqrt2 = std::f32::consts::SQRT_2;
+        let points: Vec<[f32; 3]> = match self {
+            Solid::Tetrahedron => spread(&[[1.0, 1.0, 1.0]], Cyclic, EvenMinus),
+            Solid::Cube => spread(&[[1.0, 1.0, 1.0]], Cyclic, Any),
+            Solid::Octahedron => spread(&[[1.0, 0.0, 0.0]], Cyclic, Any),
+            Solid::Dodecahedron => spread(&[[1.0, 1.0, 1.0], [0.0, 1.0 / PHI, PHI]], Cyclic, Any),
+            Solid::Icosahedron => spread(&[[0.0, 1.0, PHI]], Cyclic, Any),
+            Solid::TruncatedTetrahedron => spread(&[[3.0, 1.0, 1.0]], All, EvenMinus),
+            Solid::Cuboctahedron => spread(&[[1.0, 1.0, 0.0]], Cyclic, Any),
+            Solid::TruncatedCube => spread(&[[sqrt2 - 1.0, 1.0, 1.0]], Cyclic, Any),
+            Solid::TruncatedOctahedron => spread(&[[0.0, 1.0, 2.0]], All, Any),
+            Solid::Rhombicuboctahedron => spread(&[[1.0, 1.0, 1.0 + sqrt2]], Cyclic, Any),
+            Solid::TruncatedCuboctahedron => {
+                spread(&[[1.0, 1.0 + sqrt2, 1.0 + 2.0 * sqrt2]], All, Any)
+            }
+            Solid::SnubCube => {
+                let t = TRIBONACCI;
+                let base = [[1.0, 1.0 / t, t]];
+                let mut points = spread(&base, Cyclic, EvenPlus);
+                points.extend(spread(&base, Odd, OddPlus));
+                points
+            }
+            Solid::Icosidodecahedron => {
+                spread(&[[0.0, 0.0, PHI], [0.5, PHI / 2.0, phi2 / 2.0]], Cyclic, Any)
+            }
+            Solid::TruncatedDodecahedron => spread(
+                &[[0.0, 1.0 / PHI, 2.0 + PHI], [1.0 / PHI, PHI, 2.0 * PHI], [PHI, 2.0, PHI + 1.0]],
+                Cyclic,
+                Any,
+            ),
+            Solid::TruncatedIcosahedron => spread(
+                &[[0.0, 1.0, 3.0 * PHI], [1.0, 2.0 + PHI, 2.0 * PHI], [PHI, 2.0, phi3]],
+                Cyclic,
+                Any,
+            ),
+            Solid::Rhombicosidodecahedron => spread(
+                &[[1.0, 1.0, phi3], [phi2, PHI, 2.0 * PHI], [2.0 + PHI, 0.0, phi2]],
+                Cyclic,
+                Any,
+            ),
+            Solid::TruncatedIcosidodecahedron => spread(
+                &[
+                    [1.0 / PHI, 1.0 / PHI, 3.0 + PHI],
+                    [2.0 / PHI, PHI, 1.0 + 2.0 * PHI],
+                    [1.0 / PHI, phi2, 3.0 * PHI - 1.0],
+                    [2.0 * PHI - 1.0, 2.0, 2.0 + PHI],
+                    [PHI, 3.0, 2.0 * PHI],
+                ],
+                Cyclic,
+                Any,
+            ),
+            Solid::SnubDodecahedron => {
+                // ξ is the real root of ξ³ - 2ξ = φ
+                let mut xi = 1.7_f32;
+                for _ in 0..20 {
+                    xi -= (xi.powi(3) - 2.0 * xi - PHI) / (3.0 * xi * xi - 2.0);
+                }
+                let alpha = xi - 1.0 / xi;
+                let beta = xi * PHI + phi2 + PHI / xi;
+                let (a, b) = (alpha, beta);
+                let base = [
+                    [2.0 * a, 2.0, 2.0 * b],
+                    [a + b / PHI + PHI, -a * PHI + b + 1.0 / PHI, a / PHI + b * PHI - 1.0],
+                    [a + b / PHI - PHI, a * PHI - b + 1.0 / PHI, a / PHI + b * PHI + 1.0],
+                    [-a / PHI + b * PHI + 1.0, -a + b / PHI - PHI, a * PHI + b - 1.0 / PHI],
+                    [-a / PHI + b * PHI - 1.0, a - b / PHI - PHI, a * PHI + b + 1.0 / PHI],
+                ];
+                spread(&base, Cyclic, EvenPlus)
+            }
+        };
+        dedup(points.into_iter().map(Vector3::from).collect())
+    }
+}
+
+/// Which orderings of a point's coordinates a solid has
+#[derive(Clone, Copy)]
+enum Parity {
+    /// The even permutations, i.e. the cyclic ones
+    Cyclic,
+    /// The odd permutations
+    Odd,
+    All,
+}
+
+/// Which combinations of signs a point's coordinates take
+#[derive(Clone, Copy)]
+enum Signs {
+    Any,
+    EvenMinus,
+    EvenPlus,
+    OddPlus,
+}
+
+/// Every permutation and sign combination of each of `base`'s points
+fn spread(base: &[[f32; 3]], parity: Parity, signs: Signs) -> Vec<[f32; 3]> {
+    let cyclic = [[0, 1, 2], [1, 2, 0], [2, 0, 1]];
+    let odd = [[1, 0, 2], [0, 2, 1], [2, 1, 0]];
+    let orders: Vec<[usize; 3]> = match parity {
+        Parity::Cyclic => cyclic.to_vec(),
+        Parity::Odd => odd.to_vec(),
+        Parity::All => cyclic.into_iter().chain(odd).collect(),
+    };
+    let mut points = Vec::new();
+    for p in base {
+        for order in &orders {
+            for flips in 0..8u32 {
+                let minus = flips.count_ones();
+                let keep = match signs {
+                    Signs::Any => true,
+                    Signs::EvenMinus => minus % 2 == 0,
+                    Signs::EvenPlus => (3 - minus) % 2 == 0,
+                    Signs::OddPlus => (3 - minus) % 2 == 1,
+                };
+                if keep {
+                    let sign = |k: usize| if flips & (1 << k) != 0 { -1.0 } else { 1.0 };
+                    points.push([0, 1, 2].map(|k| p[order[k]] * sign(k)));
+                }
+            }
+        }
+    }
+    points
+}
+
+/// Drops repeated points, which flipping the sign of a zero makes
+fn dedup(points: Vec<Vector3<f32>>) -> Vec<Vector3<f32>> {
+    let mut unique: Vec<Vector3<f32>> = Vec::new();
+    for p in points {
+        if unique.iter().all(|q| (p - *q).magnitude2() > 1e-6) {
+            unique.push(p);
+        }
+    }
+    unique
+}
+
+/// The faces of a convex polyhedron with all its edges the same length, by
+/// index into `points`, wound counter-clockwise seen from outside. Each face
+/// is found by walking around it from one of its edges: at each corner the
+/// next vertex is the neighbour which keeps every point behind the face.
+pub(super) fn hull_faces(points: &[Vector3<f32>]) -> Vec<Vec<usize>> {
+    let n = points.len();
+    let shortest = (0..n)
+        .flat_map(|a| (a + 1..n).map(move |b| (a, b)))
+        .map(|(a, b)| (points[a] - points[b]).magnitude())
+        .fold(f32::MAX, f32::min);
+    let neighbours: Vec<Vec<usize>> = (0..n)
+        .map(|a| {
+            (0..n)
+                .filter(|b| *b != a && (points[a] - points[*b]).magnitude() < shortest * 1.01)
+                .collect()
+        })
+        .collect();
+    let tolerance = shortest * 1e-3;
+    let centre = points.iter().sum::<Vector3<f32>>() / n as f32;
+
+    let mut walked: HashSet<(usize, usize)> = HashSet::new();
+    let mut faces = Vec::new();
+    for a in 0..n {
+        for b in neighbours[a].iter().copied() {
+            if walked.contains(&(a, b)) {
+                continue;
+            }
+            let mut face = vec![a];
+            let (mut from, mut at) = (a, b);
+            while at != a && face.len() <= n {
+                face.push(at);
+                walked.insert((from, at));
+                let next = neighbours[at].iter().copied().find(|c| {
+                    let normal = (points[at] - points[from]).cross(points[*c] - points[at]);
+                    *c != from
+                        && normal.dot(points[at] - centre) > 0.0
+                        && points.iter().all(|p| normal.normalize().dot(p - points[at]) < tolerance)
+                });
+                let Some(next) = next else {
+                    break;
+                };
+                (from, at) = (at, next);
+            }
+            walked.insert((from, at));
+            if at == a {
+                faces.push(face);
+            }
+        }
+    }
+    faces
+}
@@ -0,0 +1,240 @@
+//! Compensating for the thickness of the paper.
+//!
+//! A pattern unfolded from the model's surface assumes paper with no thickness.
+//! Cardstock folded at a mountain fold wraps its far side around the corner,
+//! so every face comes out a little too big and the model as a whole swells by
+//! a few paper thicknesses across. Tabs, tucked a paper's thickness behind the
+//! faces they are glued to, run into the folds around them.
+//!
+//! Each face is inset along its mountain folds by the length thick paper adds
+//! there, and the faces of a piece are hinged together along their inset edges
+//! rather than the model's, so the flat pattern shrinks to match. Everything
+//! placed through a face's unfolding transform - the faces themselves, their
+//! lines, flaps, labels and bleed - follows along. Folded, the insets show as
+//! the faces drawing back from the mountain folds between them.
+
+use cgmath::{
+    EuclideanSpace, InnerSpace, Matrix, Matrix2, Matrix3, Matrix4, Point3, SquareMatrix, Vector2,
+};
+
+use crate::id::{EdgeId, FaceId};
+
+/// The sharpest fold compensated for in full, in radians (135°). Thick paper
+/// can't be folded much tighter than this anyway, and past it the inset runs
+/// away to infinity as the fold closes flat.
+pub const MAX_COMPENSATED_FOLD: f32 = 3.0 * std::f32::consts::FRAC_PI_4;
+
+impl super::Mesh {
+    /// How far each face beside an edge is inset from it for the paper's
+    /// thickness: nothing unless the edge is a mountain fold.
+    ///
+    /// This is half the bend deduction of a sharp fold whose bend follows the
+    /// middle of the sheet: of the two faces' lengths out to the corner on the
+    /// outside of the fold, `2 * thickness * tan(angle / 2)`, the bend itself
+    /// takes back `thickness * angle / 2` round the middle of the sheet.
+    pub fn edge_thickness_inset(&self, e_id: EdgeId) -> f32 {
+        if self.paper_thickness <= 0.0 {
+            return 0.0;
+        }
+        match self.edge_fold_angle(e_id) {
+            Some(angle) if angle > 0.0 => {
+                let angle = angle.min(MAX_COMPENSATED_FOLD);
+                self.paper_thickness * ((angle / 2.0).tan() - angle / 4.0)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// The transform insetting a face, in its own plane, by each of its edges'
+    /// [`Self::edge_thickness_inset`]. The identity for paper with no thickness.
+    ///
+    /// The inset corners are where the face's edges meet once each is moved in,
+    /// and the transform is the affine map fitting the corners onto them best,
+    /// which is exact for triangles and parallelograms.
+    pub fn face_inset_affine(&self, f_id: FaceId) -> Matrix4<f32> {
+        if self.paper_thickness <= 0.0 {
+            return Matrix4::identity();
+        }
+        let corners: Vec<(Point3<f32>, f32)> = self
+            .iter_face_loops(f_id)
+            .map(|l_id| {
+                let l = self[l_id];
+                (Point3::from_vec(self.vert_pos(l.v)), self.edge_thickness_inset(l.e))
+            })
+            .collect();
+        let n = cgmath::Vector3::from(self[f_id].no);
+        let count = corners.len();
+        // A sliver's normal can vanish, leaving no plane to inset it in
+        if count < 3
+            || n.magnitude2() < f32::EPSILON
+            || corners.iter().all(|(_, inset)| *inset == 0.0)
+        {
+            return Matrix4::identity();
+        }
+
+        // Moves each corner in by the insets of the edges into and out of it
+        let inward = |from: Point3<f32>, to: Point3<f32>| n.cross(to - from).normalize();
+        let inset: Vec<Point3<f32>> = (0..count)
+            .map(|i| {
+                let (prev, (p, d_out), next) =
+                    (corners[(i + count - 1) % count], corners[i], corners[(i + 1) % count]);
+                let (n_in, n_out) = (inward(prev.0, p), inward(p, next.0));
+                let (d_in, c) = (prev.1, n_in.dot(n_out));
+                let det = 1.0 - c * c;
+                if det < 1e-6 {
+                    return p + n_in * d_in;
+                }
+                p + n_in * ((d_in - c * d_out) / det) + n_out * ((d_out - c * d_in) / det)
+            })
+            .collect();
+
+        // Fits a 2D affine map onto the inset corners, in a basis on the face
+        let origin = Point3::centroid(&corners.iter().map(|(p, _)| *p).collect::<Vec<_>>());
+        let u = (corners[1].0 - corners[0].0).normalize();
+        let basis = Matrix3::from_cols(u, n.cross(u), n);
+        let flat = |p: Point3<f32>| Vector2::new((p - origin).dot(u), (p - origin).dot(n.cross(u)));
+        let shift = inset.iter().map(|q| flat(*q)).sum::<Vector2<f32>>() / count as f32;
+        let (mut xx, mut yx) = (Matrix2::from_value(0.0), Matrix2::from_value(0.0));
+        for ((p, _), q) in corners.iter().zip(&inset) {
+            let (x, y) = (flat(*p), flat(*q) - shift);
+            xx += Matrix2::from_cols(x * x.x, x * x.y);
+            yx += Matrix2::from_cols(y * x.x, y * x.y);
+        }
+        let Some(linear) = xx.invert().map(|xx_inv| yx * xx_inv) else {
+            return Matrix4::identity();
+        };
+        let in_plane = Matrix3::from_cols(
+            linear.x.extend(0.0),
+            linear.y.extend(0.0),
+            cgmath::Vector3::unit_z(),
+        );
+        let linear = basis * in_plane * basis.transpose();
+        let shift = basis * shift.extend(0.0);
+        Matrix4::from_translation(origin.to_vec() + shift)
+            * Matrix4::from(linear)
+            * Matrix4::from_translation(-origin.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{
+        EuclideanSpace, InnerSpace, Matrix4, MetricSpace, Point3, SquareMatrix, Transform,
+    };
+
+    use crate::id::{EdgeId, FaceId, Id};
+    use crate::mesh::Mesh;
+
+    /// [`Mesh::cut_quad_cube`], folded from paper `thickness` thick
+    fn lidded_cube(thickness: f32) -> Mesh {
+        let mut mesh = Mesh::cut_quad_cube();
+        mesh.paper_thickness = thickness;
+        mesh
+    }
+
+    /// The unfolded corners of every face, in piece space
+    fn unfolded_corners(mesh: &Mesh) -> Vec<Vec<Point3<f32>>> {
+        mesh.iter_pieces()
+            .flat_map(|root| mesh.iter_piece_faces_unfolded(*root))
+            .map(|face| {
+                mesh.iter_face_loops(face.f)
+                    .map(|l_id| {
+                        face.affine.transform_point(Point3::from_vec(mesh.vert_pos(mesh[l_id].v)))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn thin_paper_changes_nothing() {
+        let mesh = lidded_cube(0.0);
+        assert!(mesh
+            .edges
+            .indices()
+            .map(EdgeId::from_usize)
+            .all(|e_id| mesh.edge_thickness_inset(e_id) == 0.0));
+        assert!(mesh
+            .faces
+            .indices()
+            .map(FaceId::from_usize)
+            .all(|f_id| mesh.face_inset_affine(f_id).is_identity()));
+    }
+
+    /// Every edge of a cube is a right-angled mountain fold, so each face of
+    /// thick paper shrinks by the same amount from all four sides
+    #[test]
+    fn faces_inset_along_mountain_folds() {
+        let thickness = 0.05;
+        let mesh = lidded_cube(thickness);
+        let inset = thickness * (1.0 - std::f32::consts::FRAC_PI_8);
+        for e_id in mesh.edges.indices().map(EdgeId::from_usize) {
+            assert!((mesh.edge_thickness_inset(e_id) - inset).abs() < 1e-5);
+        }
+
+        let bottom = FaceId::from_usize(0);
+        let affine = mesh.face_inset_affine(bottom);
+        let corners: Vec<Point3<f32>> = mesh
+            .iter_face_loops(bottom)
+            .map(|l_id| Point3::from_vec(mesh.vert_pos(mesh[l_id].v)))
+            .collect();
+        for (i, p) in corners.iter().enumerate() {
+            let next = corners[(i + 1) % corners.len()];
+            let moved = affine.transform_point(*p);
+            // Each edge keeps its direction, and is shorter by both insets
+            let length = moved.distance(affine.transform_point(next));
+            assert!((length - (p.distance(next) - 2.0 * inset)).abs() < 1e-5);
+        }
+    }
+
+    /// The faces of a piece stay joined, edge to edge, along their inset folds
+    #[test]
+    fn pieces_stay_joined_along_their_insets() {
+        let thin = unfolded_corners(&lidded_cube(0.0));
+        let thick = unfolded_corners(&lidded_cube(0.05));
+        for (thin, thick) in thin.iter().zip(&thick) {
+            let side = |corners: &[Point3<f32>]| corners[0].distance(corners[1]);
+            assert!(side(thick) < side(thin), "every face is smaller");
+        }
+        // The ring of sides unrolls into a strip whose faces still share edges
+        let mesh = lidded_cube(0.05);
+        let ring = mesh.iter_pieces().find(|root| mesh.iter_connected_faces(**root).count() == 4);
+        let faces: Vec<_> = mesh.iter_piece_faces_unfolded(*ring.unwrap()).collect();
+        for face in &faces {
+            for l_id in mesh.iter_face_loops(face.f) {
+                let l = mesh[l_id];
+                if mesh.edge_is_cut(&l.e) {
+                    continue;
+                }
+                let across = faces.iter().find(|other| other.f == mesh[l.radial_next].f).unwrap();
+                let place = |affine: cgmath::Matrix4<f32>, l_id: crate::id::LoopId| {
+                    affine.transform_point(Point3::from_vec(mesh.vert_pos(mesh[l_id].v)))
+                };
+                let (a, b) = (place(face.affine, l_id), place(face.affine, l.next));
+                let other = mesh[l.radial_next];
+                let (c, d) =
+                    (place(across.affine, other.next), place(across.affine, l.radial_next));
+                // The two faces' sides of the fold lie along the same line
+                let along = (b - a).normalize();
+                for p in [c, d] {
+                    let off = (p - a) - along * (p - a).dot(along);
+                    assert!(off.magnitude() < 1e-4, "{off:?}");
+                }
+            }
+        }
+    }
+
+    /// A face whose normal has vanished, as a sliver's can, is left as it is
+    /// rather than inset by NaNs
+    #[test]
+    fn a_face_without_a_normal_isnt_inset() {
+        let mut mesh = lidded_cube(0.05);
+        let faces: Vec<FaceId> = mesh.faces.indices().map(FaceId::from_usize).collect();
+        for f_id in faces {
+            let no = mesh[f_id].no;
+            mesh[f_id].no = no.map(|x| x * 1e-25);
+            assert_eq!(mesh.face_inset_affine(f_id), Matrix4::identity());
+            mesh[f_id].no = no;
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use cgmath::{InnerSpace, Vector3};
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+
+use super::{geometry::MeshGeometry, Mesh};
+use crate::id::{self, Id};
+
+/// What `Mesh::orient_faces` changed
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct WindingRepair {
+    /// How many faces were turned over
+    pub flipped: usize,
+    /// How many separate pieces of surface the mesh is made of
+    pub components: usize,
+    /// Components which can't be wound consistently, like a Möbius strip.
+    /// These are wound as consistently as their first faces allow.
+    pub non_orientable: usize,
+}
+
+impl Mesh {
+    /// Winds every face of the mesh consistently with its neighbours, and
+    /// turns each connected component so that its faces point outward.
+    ///
+    /// Consistency spreads out from each component's first face across edges
+    /// with exactly two faces, which should run opposite ways in each. Which
+    /// way is out is then decided by the component's signed volume, so a
+    /// closed model turned inside out comes back the right way round. Open
+    /// sheets with next to no volume keep their first face's winding.
+    ///
+    /// Face normals are recomputed from the new winding, and the loop normals
+    /// of flipped faces are turned with them if they'd face away. Every face,
+    /// edge and loop keeps its ID, so commands further back in the history
+    /// still find theirs; and if nothing needed flipping, the mesh is left
+    /// untouched.
+    pub fn orient_faces(&mut self) -> WindingRepair {
+        let mut geometry = self.geometry();
+        let repair = orient_geometry(&mut geometry);
+        if repair.flipped > 0 {
+            self.set_geometry(&geometry);
+        }
+        repair
+    }
+}
+
+/// Each edge's faces, with whether the face runs along the edge from its
+/// lower vertex ID to its higher
+fn directed_edges(
+    geometry: &MeshGeometry,
+) -> BTreeMap<(id::VertexId, id::VertexId), Vec<(usize, bool)>> {
+    let mut edges: BTreeMap<_, Vec<(usize, bool)>> = BTreeMap::new();
+    for (f, face) in geometry.faces.iter().enumerate() {
+        let n = face.corners.len();
+        for k in 0..n {
+            let (a, b) = (face.corners[k].v, face.corners[(k + 1) % n].v);
+            let key = if a < b { (a, b) } else { (b, a) };
+            edges.entry(key).or_default().push((f, a < b));
+        }
+    }
+    edges
+}
+
+fn orient_geometry(geometry: &mut MeshGeometry) -> WindingRepair {
+    let edges = directed_edges(geometry);
+    let mut neighbours: Vec<Vec<(usize, bool)>> = vec![Vec::new(); geometry.faces.len()];
+    for faces in edges.values() {
+        if let [(f, f_forward), (g, g_forward)] = faces[..] {
+            // Neighbours which run the same way along their edge disagree
+            neighbours[f].push((g, f_forward == g_forward));
+            neighbours[g].push((f, f_forward == g_forward));
+        }
+    }
+
+    let mut repair = WindingRepair::default();
+    let mut flip: Vec<Option<bool>> = vec![None; geometry.faces.len()];
+    for seed in 0..geometry.faces.len() {
+        if flip[seed].is_some() {
+            continue;
+        }
+        repair.components += 1;
+        let mut component = vec![seed];
+        let mut consistent = true;
+        flip[seed] = Some(false);
+        let mut queue = VecDeque::from([seed]);
+        while let Some(f) = queue.pop_front() {
+            let f_flip = flip[f].unwrap();
+            for (g, disagree) in &neighbours[f] {
+                let g_flip = f_flip ^ disagree;
+                match flip[*g] {
+                    Some(existing) => consistent &= existing == g_flip,
+                    None => {
+                        flip[*g] = Some(g_flip);
+                        component.push(*g);
+                        queue.push_back(*g);
+                    }
+                }
+            }
+        }
+        if !consistent {
+            repair.non_orientable += 1;
+        }
+
+        // Six times the signed volume, of the component as it will be wound,
+        // measured from its centroid so that it doesn't matter where it sits
+        let position =
+            |v: id::VertexId| Vector3::from(geometry.verts[v.to_usize()].unwrap_or_default());
+        let corners = || component.iter().flat_map(|f| &geometry.faces[*f].corners);
+        let count = corners().count().max(1) as f32;
+        let centroid = corners().map(|c| position(c.v)).sum::<Vector3<f32>>() / count;
+        let volume: f32 = component
+            .iter()
+            .map(|f| {
+                let p: Vec<_> =
+                    geometry.faces[*f].corners.iter().map(|c| position(c.v) - centroid).collect();
+                let fan: f32 = (1..p.len() - 1).map(|k| p[0].dot(p[k].cross(p[k + 1]))).sum();
+                if flip[*f].unwrap() {
+                    -fan
+                } else {
+                    fan
+                }
+            })
+            .sum();
+        // Compared with the cube of the component's radius, so that a flat
+        // sheet's float noise never turns it over
+        let extent = corners().map(|c| (position(c.v) - centroid).magnitude()).fold(0.0, f32::max);
+        if volume < -1e-4 * extent.powi(3) {
+            component.iter().for_each(|f| flip[*f] = flip[*f].map(|flip| !flip));
+        }
+    }
+
+    for (face, flip) in geometry.faces.iter_mut().zip(flip) {
+        if flip != Some(true) {
+            continue;
+        }
+        repair.flipped += 1;
+        // Reversed around the first corner, which stays first
+        face.corners[1..].reverse();
+        let positions: Vec<_> = face
+            .corners
+            .iter()
+            .map(|c| geometry.verts[c.v.to_usize()].unwrap_or_default())
+            .collect();
+        let no = Vector3::from(super::face::polygon_normal(&positions));
+        for corner in &mut face.corners {
+            if Vector3::from(corner.no).dot(no) < 0.0 {
+                corner.no = corner.no.map(|c| -c);
+            }
+        }
+    }
+    repair
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::{FaceId, VertexId};
+
+    fn normals(mesh: &Mesh) -> Vec<[f32; 3]> {
+        mesh.faces.indices().map(|f| mesh[FaceId::from_usize(f)].no).collect()
+    }
+
+    fn flip_faces(mesh: &mut Mesh, faces: &[usize]) {
+        let mut geometry = mesh.geometry();
+        faces.iter().for_each(|f| geometry.faces[*f].corners[1..].reverse());
+        mesh.set_geometry(&geometry);
+    }
+
+    #[test]
+    fn a_consistent_cube_is_left_alone() {
+        let mut cube = Mesh::new_cube();
+        assert_eq!(cube.orient_faces(), WindingRepair { components: 1, ..Default::default() });
+    }
+
+    #[test]
+    fn flipped_faces_are_turned_back() {
+        let mut cube = Mesh::new_cube();
+        let before = normals(&cube);
+        flip_faces(&mut cube, &[1, 4, 7]);
+        assert_ne!(normals(&cube), before);
+
+        let repair = cube.orient_faces();
+        assert_eq!(repair.flipped, 3);
+        assert_eq!(repair.non_orientable, 0);
+        assert_eq!(normals(&cube), before);
+    }
+
+    /// With every face flipped the cube is consistent, but inside out
+    #[test]
+    fn an_inside_out_cube_is_turned_outward() {
+        let mut cube = Mesh::new_cube();
+        let before = normals(&cube);
+        flip_faces(&mut cube, &(0..12).collect::<Vec<_>>());
+
+        assert_eq!(cube.orient_faces().flipped, 12);
+        assert_eq!(normals(&cube), before);
+        // The fold angle of a cube's edge is a mountain again
+        let e_id = cube.query_edge(VertexId::from_usize(0), VertexId::from_usize(1)).unwrap();
+        assert!(cube.edge_fold_angle(e_id).unwrap() > 0.0);
+    }
+
+    /// Likewise for a rounder shape, where no face's normal lines up with an axis
+    #[test]
+    fn an_inside_out_sphere_is_turned_outward() {
+        let mut sphere = Mesh::new_uv_sphere(12, 6);
+        let before = normals(&sphere);
+        let faces = sphere.faces.num_elements();
+        flip_faces(&mut sphere, &(0..faces).collect::<Vec<_>>());
+
+        assert_eq!(sphere.orient_faces().flipped, faces);
+        assert_eq!(normals(&sphere), before);
+    }
+
+    /// The first face of the first component is flipped too, so that it's
+    /// the volume and not the seed that decides which way is out
+    #[test]
+    fn the_first_face_can_be_the_wrong_one() {
+        let mut cube = Mesh::new_cube();
+        let before = normals(&cube);
+        flip_faces(&mut cube, &[0]);
+        assert_eq!(cube.orient_faces().flipped, 1);
+        assert_eq!(normals(&cube), before);
+    }
+
+    /// Flipped faces keep their IDs, gaps and all, along with their loops' and
+    /// the edges around them
+    #[test]
+    fn turned_faces_keep_their_ids() {
+        let mut cube = Mesh::new_cube();
+        flip_faces(&mut cube, &[1, 4, 7]);
+        cube.delete_faces(&[FaceId::from_usize(0)]);
+        let verts_of = |mesh: &Mesh| -> Vec<(usize, Vec<VertexId>)> {
+            mesh.faces
+                .indices()
+                .map(|f| {
+                    let mut verts: Vec<_> =
+                        mesh.iter_face_loops(FaceId::from_usize(f)).map(|l| mesh[l].v).collect();
+                    verts.sort();
+                    (f, verts)
+                })
+                .collect()
+        };
+        let faces = verts_of(&cube);
+        let edges: Vec<_> = cube.edges.indices().map(|e| cube.edges[e].v).collect();
+        let loops: Vec<_> = cube.loops.indices().map(|l| (l, cube.loops[l].f)).collect();
+
+        assert_eq!(cube.orient_faces().flipped, 3);
+        assert_eq!(verts_of(&cube), faces);
+        assert_eq!(cube.edges.indices().map(|e| cube.edges[e].v).collect::<Vec<_>>(), edges);
+        assert_eq!(cube.loops.indices().map(|l| (l, cube.loops[l].f)).collect::<Vec<_>>(), loops);
+    }
+
+    /// An exporter computes loop normals from the winding it has, so a
+    /// flipped face's loops point the wrong way too
+    #[test]
+    fn loop_normals_follow_their_faces() {
+        let mut cube = Mesh::new_cube();
+        let mut geometry = cube.geometry();
+        geometry.faces.iter_mut().for_each(|face| {
+            face.corners.iter_mut().for_each(|c| c.no = [0.0, 0.0, -1.0]);
+        });
+        geometry.faces[0].corners[1..].reverse();
+        geometry.faces[0].corners.iter_mut().for_each(|c| c.no = [0.0, 0.0, 1.0]);
+        cube.set_geometry(&geometry);
+
+        cube.orient_faces();
+        let l = cube[FaceId::from_usize(0)].l;
+        assert_eq!(cube[l].no, [0.0, 0.0, -1.0]);
+        // Faces which weren't flipped keep their loop normals as they were
+        let top = cube.faces.indices().map(FaceId::from_usize).find(|f| cube[*f].no[2] > 0.5);
+        let l = cube[top.unwrap()].l;
+        assert_eq!(cube[l].no, [0.0, 0.0, -1.0]);
+    }
+}
@@ -71,6 +71,15 @@ pub struct PrintLayoutSettings {
     pub margin_x: f32,
     /// Top and bottom margin, in centimeters
     pub margin_y: f32,
+    /// Whether tabs are printed with the texture of the face they are glued
+    /// beneath, so that a seam printed slightly out of register doesn't show
+    /// white paper
+    #[serde(default)]
+    pub textured_flaps: bool,
+    /// How far each piece's artwork is printed on past its outline, in
+    /// centimeters, so that a cut made slightly wide lands on artwork
+    #[serde(default)]
+    pub bleed: f32,
 }
 
 impl Default for PrintLayoutSettings {
@@ -113,6 +122,10 @@ pub struct PrintLayout {
     pub page_margin_start: cgmath::Point2<f32>,
     /// Margins at the bottom right of pages
     pub page_margin_end: cgmath::Point2<f32>,
+    /// Whether tabs carry the texture of the face they are glued beneath
+    pub textured_flaps: bool,
+    /// How far artwork is printed past each piece's outline, in centimeters
+    pub bleed: f32,
 
     /// Page-specific configuration
     pub pages: SlotMap<PageId, Page>,
@@ -138,6 +151,8 @@ impl Default for PrintLayout {
             page_size: Default::default(),
             page_margin_start: cgmath::Point2 { x: 0.5 * CM_PER_INCH, y: 0.5 * CM_PER_INCH },
             page_margin_end: cgmath::Point2 { x: 0.5 * CM_PER_INCH, y: 0.5 * CM_PER_INCH },
+            textured_flaps: false,
+            bleed: 0.0,
             pages,
             cols: 1,
             rows: 1,
@@ -154,6 +169,8 @@ impl PrintLayout {
             page_size: self.page_size,
             margin_x: self.page_margin_start.x,
             margin_y: self.page_margin_start.y,
+            textured_flaps: self.textured_flaps,
+            bleed: self.bleed,
         }
     }
 
@@ -169,6 +186,8 @@ impl PrintLayout {
         self.page_size = settings.page_size;
         self.page_margin_start = cgmath::Point2 { x: settings.margin_x, y: settings.margin_y };
         self.page_margin_end = cgmath::Point2 { x: settings.margin_x, y: settings.margin_y };
+        self.textured_flaps = settings.textured_flaps;
+        self.bleed = settings.bleed.max(0.0);
         self.is_dirty = true;
     }
 
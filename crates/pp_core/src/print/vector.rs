@@ -40,12 +40,18 @@ use crate::{
 /// `docs/cut-contours.md`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LineKind {
-    /// One of the three free sides of a glue tab. Behind every face.
+    /// One of the free sides of a glue tab. Behind every face.
     FlapOutline,
     /// A convex fold: the paper folds away from you.
     Mountain,
     /// A concave fold: the paper folds toward you.
     Valley,
+    /// A stroke of the number matching the two sides of a seam. Printed, but
+    /// neither cut nor folded.
+    Label,
+    /// A slit through a face for a slot-and-tab's tongue to push through. Cut,
+    /// though the piece doesn't come free along it.
+    Slit,
     /// The mesh's own boundary, on a model that isn't closed. Also a cut, but it
     /// was never joined to anything, so it reads as the sheet's outline.
     Border,
@@ -57,10 +63,10 @@ impl LineKind {
     /// Whether this is part of a piece's outline rather than something scored
     /// inside it — the lines a cutting machine would follow.
     ///
-    /// Tab outlines count: three of a tab's four sides are cut, and only its base
-    /// is folded.
+    /// Tab outlines count: all of a tab is cut but its base, which is folded.
+    /// So do slits, which are cut even though they sit inside the piece.
     pub fn is_silhouette(&self) -> bool {
-        matches!(self, Self::Cut | Self::Border | Self::FlapOutline)
+        matches!(self, Self::Cut | Self::Border | Self::FlapOutline | Self::Slit)
     }
 }
 
@@ -121,6 +127,16 @@ fn piece_lines(mesh: &Mesh, root: FaceId, fold_lines: bool, out: &mut Vec<WorldL
     // which draws its own copy of the seam somewhere else on the page.
     let mut seen: Vec<EdgeId> = Vec::new();
 
+    for label in mesh.piece_cut_labels(root) {
+        for (from, to) in label.strokes() {
+            out.push((
+                piece.transform.transform_point(from),
+                piece.transform.transform_point(to),
+                LineKind::Label,
+            ));
+        }
+    }
+
     for face in walker {
         for l_id in mesh.iter_face_loops(face.f) {
             let place = |p: Point3<f32>| piece.transform.transform_point(p);
@@ -128,16 +144,15 @@ fn piece_lines(mesh: &Mesh, root: FaceId, fold_lines: bool, out: &mut Vec<WorldL
                 place(face.affine.transform_point(Point3::new(0.0, 0.0, 0.0) + mesh.vert_pos(v)))
             };
 
-            if let Some(corners) = mesh.piece_flap_corners(l_id, face.affine, t) {
-                // Side 0 of the trapezoid is its base, which lies on the seam the
-                // tab folds along; that line is emitted as a fold below. Only the
-                // three free sides are the tab's own outline.
-                for side in 1..4 {
-                    out.push((
-                        place(corners[side]),
-                        place(corners[(side + 1) % 4]),
-                        LineKind::FlapOutline,
-                    ));
+            // The tab's base lies on the seam it folds along, and is emitted
+            // as a fold below, so its outline's segments leave it out.
+            if let Some(outline) = mesh.piece_flap_outline(l_id, face.affine, t) {
+                for (from, to) in outline.segments() {
+                    out.push((place(from), place(to), LineKind::FlapOutline));
+                }
+            } else if let Some(slit) = mesh.piece_flap_slit(l_id, face.affine) {
+                for (from, to) in slit.segments() {
+                    out.push((place(from), place(to), LineKind::Slit));
                 }
             }
 
@@ -241,7 +256,10 @@ mod tests {
     use super::*;
     use crate::{
         id::{Id, VertexId},
-        mesh::cut::{CutUpdate, FlapPosition},
+        mesh::{
+            cut::{CutUpdate, FlapPosition},
+            flap::FlapStyle,
+        },
         print::{Page, PageSize},
         MeshId,
     };
@@ -314,6 +332,7 @@ mod tests {
             0,
             "a cube unfolds outward, so every fold on it reads convex"
         );
+        let lines: Vec<_> = lines.into_iter().filter(|l| l.kind != LineKind::Label).collect();
         assert_eq!(lines.len(), 16, "and nothing else, the flat diagonal included: {lines:#?}");
     }
 
@@ -330,9 +349,34 @@ mod tests {
         assert_eq!(of_kind(&lines, LineKind::FlapOutline), 9, "and one tab fewer");
     }
 
+    /// A tab cut in another style prints its own outline rather than the
+    /// trapezoid's: here five teeth, each two strokes, between the two sides.
+    #[test]
+    fn a_styled_tab_prints_its_own_outline() {
+        let (mut state, m_id, _) = cube_with_one_piece();
+        let cuts: Vec<EdgeId> = state.meshes[m_id].cuts.keys().copied().collect();
+        for e_id in cuts {
+            state.meshes[m_id].set_cut_flap_style(e_id, FlapStyle::Zigzag);
+        }
+        let lines = page_lines(&state, page(0.0, 0.0), true);
+        assert_eq!(of_kind(&lines, LineKind::FlapOutline), 4 * 12);
+        assert_eq!(of_kind(&lines, LineKind::Mountain), 4, "the folds don't change");
+    }
+
     /// Every interior edge is reached from both of its faces. Stroking it twice
     /// would double the ink and, for a dashed fold, fill in the gaps and print a
     /// solid line.
+    /// Each of the ring's cuts is numbered, 1 to 4, on the side of the seam the
+    /// piece has. The other side is on no piece, so prints nowhere.
+    #[test]
+    fn every_seam_is_numbered_on_the_sheet() {
+        let (state, ..) = cube_with_one_piece();
+        let lines = page_lines(&state, page(0.0, 0.0), false);
+        // "1" is three strokes long, "2" five, "3" four and "4" three
+        assert_eq!(of_kind(&lines, LineKind::Label), 3 + 5 + 4 + 3);
+        assert!(!LineKind::Label.is_silhouette());
+    }
+
     #[test]
     fn an_interior_edge_is_stroked_only_once() {
         let (state, ..) = cube_with_one_piece();
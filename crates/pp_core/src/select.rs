@@ -37,6 +37,10 @@ pub struct SelectionState {
     pub edges: HashSet<(MeshId, id::EdgeId)>,
     pub faces: HashSet<(MeshId, id::FaceId)>,
     pub pieces: HashSet<(MeshId, id::FaceId)>,
+    /// The edge under the pointer. Only ever shown, never acted on, so it isn't
+    /// kept with the rest of the selection.
+    #[serde(skip)]
+    pub hovered_edge: Option<(MeshId, id::EdgeId)>,
     pub is_dirty: bool,
 }
 
@@ -307,6 +311,15 @@ impl State {
         true
     }
 
+    /// Sets the edge under the pointer, only dirtying the selection if it moved
+    /// to another
+    pub fn hover_edge(&mut self, id: Option<(MeshId, id::EdgeId)>) {
+        if self.selection.hovered_edge != id {
+            self.selection.hovered_edge = id;
+            self.selection.is_dirty = true;
+        }
+    }
+
     /// Returns all the pieces which have at least one face selected in the mesh
     pub fn get_selected_pieces(&self) -> Vec<(MeshId, id::FaceId)> {
         self.selection
@@ -34,39 +34,77 @@ bitflags! {
     #[derive(Debug, Copy, Clone)]
     pub struct EdgeFlapFlags: u32 {
         const EXISTS = 1 << 0;
+        /// The outline is the slit a slot-and-tab folding onto this loop's
+        /// face pushes through: stroked, but not filled
+        const SLIT = 1 << 1;
     }
 }
 
-/// A flap's short edge, in the same unfolded piece-local space as the edge
+/// A flap's outline, in the same unfolded piece-local space as the edge
 /// positions VBO.
 ///
-/// Only the two *top* corners travel: the bottom two are the flap's base edge,
-/// which the edge positions VBO already carries. The trapezoid itself is shaped
-/// by [`pp_core::mesh::flap::flap_corners`] on the CPU rather than in the vertex
-/// shader, so the tab the printer strokes and the tab on screen are the same
-/// four points.
+/// Only what sits *off* the base travels: the base is the flap's edge, which
+/// the edge positions VBO already carries, and each outline point is a
+/// fraction along it plus a distance `up` from it. The outline itself is
+/// shaped by [`pp_core::mesh::flap::flap_outline`] on the CPU rather than in
+/// the vertex shader, so the tab the printer strokes and the tab on screen are
+/// the same points.
 #[repr(C)]
 #[derive(Debug, Default, Copy, Clone, bytemuck::Zeroable, bytemuck::Pod)]
 pub struct EdgeFlapInfo {
-    pub top0: [f32; 3],
-    pub top1: [f32; 3],
+    pub up: [f32; 3],
     pub flags: u32,
+    pub profile: [[f32; 2]; pp_core::mesh::flap::FLAP_OUTLINE_POINTS],
+}
+
+/// The bleed beyond one edge of a piece's outline. See
+/// [`pp_core::mesh::bleed::BleedStrip`].
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, bytemuck::Zeroable, bytemuck::Pod)]
+pub struct BleedInfo {
+    pub v0: [f32; 3],
+    pub v1: [f32; 3],
+    pub out: [f32; 3],
+    pub uv: [[f32; 2]; 2],
+    pub uv_out: [f32; 2],
+}
+
+/// A flap drawn with the texture of the face across its cut. Unlike
+/// [`EdgeFlapInfo`] this carries its own base, since these are grouped by
+/// material rather than laid out alongside the piecewise edge VBOs.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, bytemuck::Zeroable, bytemuck::Pod)]
+pub struct FlapTextureInfo {
+    pub v0: [f32; 3],
+    pub v1: [f32; 3],
+    pub up: [f32; 3],
+    /// Texture coordinates at `v0` and `v1`
+    pub uv_base: [[f32; 2]; 2],
+    /// How the texture coordinates move per unit along `up`
+    pub uv_up: [f32; 2],
+    pub profile: [[f32; 2]; pp_core::mesh::flap::FLAP_OUTLINE_POINTS],
 }
 
 /// Helper functions for extracting VBOs from a Mesh
 pub mod vbo {
+    use std::collections::{BTreeSet, HashMap};
+    use std::ops::Range;
+
     use cgmath::{EuclideanSpace, Transform};
     use pp_core::{
         id::{self, EdgeId, Id, LoopId},
         mesh::edge::FLAT_EDGE_ANGLE_EPSILON,
         select::SelectionActiveElement,
-        MeshId,
+        MaterialId, MeshId,
     };
-    use slotmap::Key;
+    use slotmap::{Key, SecondaryMap};
 
-    use crate::{cache::mesh::extract::EdgeFlags, gpu};
+    use crate::{
+        cache::mesh::{extract::EdgeFlags, MaterialGPUVBORange},
+        gpu,
+    };
 
-    use super::{EdgeFlapFlags, EdgeFlapInfo, VertFlags};
+    use super::{BleedInfo, EdgeFlapFlags, EdgeFlapInfo, FlapTextureInfo, VertFlags};
 
     /// Reloads the pos VBO from the mesh's data
     pub fn pos(ctx: &gpu::Context, mesh: &pp_core::mesh::Mesh, vbo: &mut gpu::VertBuf) {
@@ -347,8 +385,9 @@ pub mod vbo {
         vbo.update(ctx, data.as_slice())
     }
 
-    /// Extracts the flap hanging over each of the piece's edge loops: the two
-    /// top corners of its trapezoid, and whether it exists at all.
+    /// Extracts the flap hanging over each of the piece's edge loops: its
+    /// outline, and whether it exists at all. A loop without one carries the
+    /// slit of a slot-and-tab folding onto it instead, if there is one.
     ///
     /// The shape itself comes from `pp_core`, which owns the flap geometry so the
     /// printed tab and the on-screen tab agree; this only walks the loops and
@@ -361,32 +400,317 @@ pub mod vbo {
                 let t = walker.t;
                 walker.flat_map(move |item| {
                     mesh.iter_face_loops(item.f).map(move |l_id| {
-                        // A loop with no flap keeps the default, whose cleared
-                        // EXISTS flag the shader clips away.
-                        mesh.piece_flap_corners(l_id, item.affine, t)
-                            .map(|corners| EdgeFlapInfo {
-                                top0: corners[3].into(),
-                                top1: corners[2].into(),
-                                flags: EdgeFlapFlags::EXISTS.bits(),
-                            })
-                            .unwrap_or_default()
+                        // A loop with neither keeps the default, whose cleared
+                        // flags the shader clips away.
+                        let flap = mesh
+                            .piece_flap_outline(l_id, item.affine, t)
+                            .map(|outline| (outline, EdgeFlapFlags::EXISTS));
+                        flap.or_else(|| {
+                            mesh.piece_flap_slit(l_id, item.affine)
+                                .map(|slit| (slit, EdgeFlapFlags::SLIT))
+                        })
+                        .map(|(outline, flags)| EdgeFlapInfo {
+                            up: outline.up.into(),
+                            flags: flags.bits(),
+                            profile: outline.profile,
+                        })
+                        .unwrap_or_default()
                     })
                 })
             })
             .collect();
         vbo.update(ctx, data.as_slice());
     }
+
+    /// Reloads the flaps which carry on the texture of the face across their
+    /// cuts, grouped by that face's material and then by piece, and records
+    /// each group's range against its material.
+    pub fn piece_flap_texture(
+        ctx: &gpu::Context,
+        mesh: &pp_core::mesh::Mesh,
+        default_mat: &MaterialId,
+        vbo: &mut gpu::VertBuf,
+        mats: &mut SecondaryMap<MaterialId, MaterialGPUVBORange>,
+    ) {
+        let mut flaps: Vec<(MaterialId, id::FaceId, FlapTextureInfo)> = Vec::new();
+        for root in mesh.iter_pieces() {
+            let walker = mesh.iter_piece_faces_unfolded(*root);
+            let t = walker.t;
+            for item in walker {
+                for l_id in mesh.iter_face_loops(item.f) {
+                    let Some(outline) = mesh.piece_flap_outline(l_id, item.affine, t) else {
+                        continue;
+                    };
+                    let Some(texture) = mesh.piece_flap_texture(l_id, &outline, item.affine, t)
+                    else {
+                        continue;
+                    };
+                    flaps.push((
+                        texture.m.unwrap_or(*default_mat),
+                        *root,
+                        FlapTextureInfo {
+                            v0: outline.v0.into(),
+                            v1: outline.v1.into(),
+                            up: outline.up.into(),
+                            uv_base: texture.base,
+                            uv_up: texture.up,
+                            profile: outline.profile,
+                        },
+                    ));
+                }
+            }
+        }
+        let data = group_by_material(flaps, mats, |mat| &mut mat.flap_ranges);
+        vbo.update(ctx, data.as_slice());
+    }
+
+    /// Reloads the bleed strips around every piece's outline, grouped by the
+    /// material of their faces and then by piece, and records each group's
+    /// range against its material.
+    pub fn piece_bleed(
+        ctx: &gpu::Context,
+        mesh: &pp_core::mesh::Mesh,
+        default_mat: &MaterialId,
+        vbo: &mut gpu::VertBuf,
+        mats: &mut SecondaryMap<MaterialId, MaterialGPUVBORange>,
+    ) {
+        let strips: Vec<(MaterialId, id::FaceId, BleedInfo)> = mesh
+            .iter_pieces()
+            .flat_map(|root| {
+                mesh.piece_bleed(*root).into_iter().map(|strip| {
+                    let info = BleedInfo {
+                        v0: strip.v0.into(),
+                        v1: strip.v1.into(),
+                        out: strip.out.into(),
+                        uv: strip.uv,
+                        uv_out: strip.uv_out,
+                    };
+                    (strip.m.unwrap_or(*default_mat), *root, info)
+                })
+            })
+            .collect();
+        let data = group_by_material(strips, mats, |mat| &mut mat.bleed_ranges);
+        vbo.update(ctx, data.as_slice());
+    }
+
+    /// Sorts per-piece instances by material and then by piece, recording the
+    /// range each piece's instances of each material take up in `ranges`
+    fn group_by_material<T>(
+        mut items: Vec<(MaterialId, id::FaceId, T)>,
+        mats: &mut SecondaryMap<MaterialId, MaterialGPUVBORange>,
+        ranges: impl Fn(&mut MaterialGPUVBORange) -> &mut HashMap<id::FaceId, Range<u32>>,
+    ) -> Vec<T> {
+        // Stable, so the instances of one piece keep their order within a material
+        items.sort_by(|(m_a, p_a, _), (m_b, p_b, _)| m_a.cmp(m_b).then(p_a.cmp(p_b)));
+        mats.iter_mut().for_each(|(_, mat)| ranges(mat).clear());
+        for (i, (m_id, p_id, _)) in items.iter().enumerate() {
+            let mat = mats.entry(*m_id).unwrap().or_default();
+            let range = ranges(mat).entry(*p_id).or_insert(i as u32..i as u32);
+            range.end = i as u32 + 1;
+        }
+        items.into_iter().map(|(_, _, item)| item).collect()
+    }
+
+    /// Reloads the strokes of every piece's seam labels, and returns the range
+    /// of them each piece has, by its root. As with the flaps, `pp_core` lays the
+    /// labels out, so that the printed pattern numbers its seams the same way.
+    pub fn piece_labels(
+        ctx: &gpu::Context,
+        mesh: &pp_core::mesh::Mesh,
+        vbo: &mut gpu::VertBuf,
+    ) -> Vec<(id::FaceId, std::ops::Range<u32>)> {
+        let mut data: Vec<[[f32; 3]; 2]> = Vec::new();
+        let mut ranges = Vec::new();
+        for root in mesh.iter_pieces() {
+            let start = data.len() as u32;
+            for label in mesh.piece_cut_labels(*root) {
+                data.extend(label.strokes().into_iter().map(|(from, to)| [from.into(), to.into()]));
+            }
+            ranges.push((*root, start..data.len() as u32));
+        }
+        vbo.update(ctx, data.as_slice());
+        ranges
+    }
+
+    /// The cuts whose other sides are pointed out: every selected cut, and the
+    /// one under the pointer. Ordered, so the strokes don't reshuffle.
+    fn highlighted_cuts(
+        m_id: MeshId,
+        mesh: &pp_core::mesh::Mesh,
+        selection: &pp_core::select::SelectionState,
+    ) -> BTreeSet<EdgeId> {
+        selection
+            .edges
+            .iter()
+            .chain(selection.hovered_edge.as_ref())
+            .filter(|(mesh_id, e_id)| *mesh_id == m_id && mesh.edge_is_cut(e_id))
+            .map(|(_, e_id)| *e_id)
+            .collect()
+    }
+
+    /// Reloads the highlighted cuts themselves, in mesh space. On the folded
+    /// model both sides of a cut are the one edge, so that is all there is to
+    /// show.
+    pub fn mates(
+        ctx: &gpu::Context,
+        m_id: MeshId,
+        mesh: &pp_core::mesh::Mesh,
+        selection: &pp_core::select::SelectionState,
+        vbo: &mut gpu::VertBuf,
+    ) {
+        let data: Vec<[[f32; 3]; 2]> = highlighted_cuts(m_id, mesh, selection)
+            .into_iter()
+            .map(|e_id| mesh[e_id].v.map(|v_id| mesh.vert_pos(v_id).into()))
+            .collect();
+        vbo.update(ctx, data.as_slice());
+    }
+
+    /// Reloads both sides of each highlighted cut on the pieces, and a
+    /// connector from the middle of one side to the middle of the other,
+    /// however far apart the two have been laid out. Everything is in the space
+    /// of the piece it's drawn with: the connector goes with the cut's first
+    /// side, and reaches across into the other piece's placement.
+    pub fn piece_mates(
+        ctx: &gpu::Context,
+        m_id: MeshId,
+        mesh: &pp_core::mesh::Mesh,
+        selection: &pp_core::select::SelectionState,
+        vbo: &mut gpu::VertBuf,
+    ) -> Vec<(id::FaceId, std::ops::Range<u32>)> {
+        let cuts: Vec<_> = highlighted_cuts(m_id, mesh, selection)
+            .into_iter()
+            .filter_map(|e_id| mesh.cut_sides(e_id))
+            .collect();
+        let mut data: Vec<[[f32; 3]; 2]> = Vec::new();
+        let mut ranges = Vec::new();
+        for root in mesh.iter_pieces() {
+            let start = data.len() as u32;
+            for (i, side) in cuts.iter().flat_map(|sides| sides.iter().enumerate()) {
+                if side.piece != Some(*root) {
+                    continue;
+                }
+                let Some(span) = mesh.loop_piece_span(side.l) else { continue };
+                data.push(span.map(Into::into));
+                let mate = mesh.cut_mate(side.l);
+                let Some((other, mate_span)) = mate
+                    .filter(|_| i == 0)
+                    .and_then(|mate| Some((mate.piece?, mesh.loop_piece_span(mate.l)?)))
+                else {
+                    continue;
+                };
+                let Some(into_here) = mesh.pieces[root].transform.inverse_transform() else {
+                    continue;
+                };
+                let across = into_here * mesh.pieces[&other].transform;
+                let there = across.transform_point(mate_span[0].midpoint(mate_span[1]));
+                data.push([span[0].midpoint(span[1]).into(), there.into()]);
+            }
+            ranges.push((*root, start..data.len() as u32));
+        }
+        vbo.update(ctx, data.as_slice());
+        ranges
+    }
 }
 
 pub mod ibo {
+    use cgmath::{InnerSpace, Vector2, Vector3};
     use pp_core::{
-        id::{self},
+        id::{self, Id},
         MaterialId,
     };
     use slotmap::SecondaryMap;
 
     use crate::{cache::mesh::MaterialGPUVBORange, gpu};
 
+    /// Splits a face into triangles, as offsets into its loops in loop-cycle
+    /// order. This is the only place faces become triangles: the mesh itself
+    /// keeps whole polygons, so a quad unfolds and prints as one face with no
+    /// diagonal to hide.
+    ///
+    /// Faces are planar but not necessarily convex, so this clips ears in the
+    /// face's own plane rather than fanning, which would cover an L-shape's
+    /// notch. A polygon too degenerate to have an ear is fanned as a fallback.
+    fn triangulate(mesh: &pp_core::mesh::Mesh, f_id: id::FaceId) -> Vec<[u32; 3]> {
+        let n = Vector3::from(mesh[f_id].no);
+        let u =
+            if n.x.abs() < 0.9 { n.cross(Vector3::unit_x()) } else { n.cross(Vector3::unit_y()) };
+        let (u, w) = (u.normalize(), n.cross(u.normalize()));
+        // Wound counter-clockwise about the normal, so ears turn left
+        let points: Vec<_> = mesh
+            .iter_face_loops(f_id)
+            .map(|l| {
+                let p = mesh.vert_pos(mesh[l].v);
+                Vector2::new(p.dot(u), p.dot(w))
+            })
+            .collect();
+        let cross = |a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>| {
+            (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+        };
+
+        let mut remaining: Vec<u32> = (0..points.len() as u32).collect();
+        let mut tris = Vec::with_capacity(points.len().saturating_sub(2));
+        while remaining.len() > 3 {
+            let len = remaining.len();
+            let corner = |i: usize| remaining[(i + len) % len];
+            let ear = (0..len).find(|i| {
+                let [a, b, c] = [corner(*i + len - 1), corner(*i), corner(*i + 1)];
+                let [pa, pb, pc] = [a, b, c].map(|i| points[i as usize]);
+                cross(pa, pb, pc) > f32::EPSILON
+                    && remaining.iter().filter(|j| ![a, b, c].contains(j)).all(|j| {
+                        let p = points[*j as usize];
+                        cross(pa, pb, p) < 0.0 || cross(pb, pc, p) < 0.0 || cross(pc, pa, p) < 0.0
+                    })
+            });
+            let Some(i) = ear else { break };
+            tris.push([corner(i + len - 1), corner(i), corner(i + 1)]);
+            remaining.remove(i);
+        }
+        tris.extend(
+            (1..remaining.len().saturating_sub(1))
+                .map(|i| [remaining[0], remaining[i], remaining[i + 1]]),
+        );
+        tris
+    }
+
+    /// Triangulates `faces`, whose loops sit consecutively in that order in
+    /// the VBOs, yielding each triangle as VBO indices along with its face.
+    fn face_triangles<'a>(
+        mesh: &'a pp_core::mesh::Mesh,
+        faces: impl Iterator<Item = id::FaceId> + 'a,
+    ) -> impl Iterator<Item = (id::FaceId, [u32; 3])> + 'a {
+        let mut first = 0;
+        faces.flat_map(move |f_id| {
+            let base = first;
+            first += mesh.iter_face_loops(f_id).count() as u32;
+            triangulate(mesh, f_id).into_iter().map(move |tri| (f_id, tri.map(|i| base + i)))
+        })
+    }
+
+    /// The faces of every piece, in the order the piecewise VBOs lay them out
+    fn piece_faces(mesh: &pp_core::mesh::Mesh) -> impl Iterator<Item = id::FaceId> + '_ {
+        mesh.iter_pieces().flat_map(|p_id| mesh.iter_connected_faces(*p_id))
+    }
+
+    /// Gets an IBO of every face's triangles, for drawing the whole mesh
+    /// regardless of material.
+    pub fn tri_indices(ctx: &gpu::Context, mesh: &pp_core::mesh::Mesh, ibo: &mut gpu::IndexBuf) {
+        let faces = mesh.faces.indices().map(id::FaceId::from_usize);
+        let data: Vec<u32> = face_triangles(mesh, faces).flat_map(|(_, tri)| tri).collect();
+        ibo.update(ctx, data.as_slice());
+    }
+
+    /// Gets an IBO of every piece's triangles, piece by piece, so each piece's
+    /// triangles are a contiguous range within it. See `PieceGPU::tri_range`.
+    pub fn piece_tri_indices(
+        ctx: &gpu::Context,
+        mesh: &pp_core::mesh::Mesh,
+        ibo: &mut gpu::IndexBuf,
+    ) {
+        let data: Vec<u32> =
+            face_triangles(mesh, piece_faces(mesh)).flat_map(|(_, tri)| tri).collect();
+        ibo.update(ctx, data.as_slice());
+    }
+
     /// Gets an ordered IBO for rendering materials. We sort it so each material's
     /// surface tris can be drawn from a contiguous range within this IBO.
     pub fn mat_indices(
@@ -396,12 +720,13 @@ pub mod ibo {
         ibo: &mut gpu::IndexBuf,
         ranges: &mut SecondaryMap<MaterialId, MaterialGPUVBORange>,
     ) {
-        let mut data: Vec<_> = mesh
-            .iter_loops()
-            .zip(0u32..)
-            .map(|(l, i)| (i, mesh[mesh[l].f].m.unwrap_or(*default_mat)))
+        let faces = mesh.faces.indices().map(id::FaceId::from_usize);
+        // Sorted a whole triangle at a time, so none is split across materials
+        let mut tris: Vec<_> = face_triangles(mesh, faces)
+            .map(|(f_id, tri)| (tri, mesh[f_id].m.unwrap_or(*default_mat)))
             .collect();
-        data.sort_by(|(_, m_a), (_, m_b)| m_a.cmp(m_b));
+        tris.sort_by_key(|(_, m)| *m);
+        let data: Vec<_> = tris.iter().flat_map(|(tri, m_id)| tri.map(|i| (i, *m_id))).collect();
         let mut i_prev: u32 = 0;
         let mut m_prev: Option<MaterialId> = None;
         let final_data: Vec<_> = data
@@ -435,14 +760,15 @@ pub mod ibo {
         ibo: &mut gpu::IndexBuf,
         mats: &mut SecondaryMap<MaterialId, MaterialGPUVBORange>,
     ) {
-        let mut data: Vec<_> = mesh
-            .iter_pieces()
-            .flat_map(|p_id| mesh.iter_connected_faces(*p_id).map(move |f_id| (f_id, *p_id)))
-            .flat_map(|(f_id, p_id)| mesh.iter_face_loops(f_id).map(move |l_id| (l_id, p_id)))
-            .zip(0u32..)
-            .map(|((l, p), i)| (i, mesh[mesh[l].f].m.unwrap_or(*default_mat), p))
+        let mut tris: Vec<_> = face_triangles(mesh, piece_faces(mesh))
+            .map(|(f_id, tri)| {
+                let f = mesh[f_id];
+                (tri, f.m.unwrap_or(*default_mat), f.p.unwrap_or(f_id))
+            })
             .collect();
-        data.sort_by(|(_, m_a, p_a), (_, m_b, p_b)| m_a.cmp(m_b).then(p_a.cmp(p_b)));
+        tris.sort_by(|(_, m_a, p_a), (_, m_b, p_b)| m_a.cmp(m_b).then(p_a.cmp(p_b)));
+        let data: Vec<_> =
+            tris.iter().flat_map(|(tri, m_id, p_id)| tri.map(|i| (i, *m_id, *p_id))).collect();
         let mut i_prev: u32 = 0;
         let mut m_prev: Option<MaterialId> = None;
         let mut p_prev: Option<id::FaceId> = None;
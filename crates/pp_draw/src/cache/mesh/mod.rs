@@ -1,4 +1,4 @@
-use crate::gpu;
+use crate::gpu::{self, shared::buffers::FLAP_SEGMENT_VERTS};
 use extract::{ibo, vbo};
 use piece::PieceGPU;
 use pp_core::id;
@@ -21,6 +21,10 @@ pub(crate) struct MaterialGPUVBORange {
     /// This material's range of elements in the IBO for piecewise VBOs
     /// A missing entry indicates that the piece doesn't use the given material.
     pub piece_ranges: HashMap<id::FaceId, Range<u32>>,
+    /// This material's flaps in the piecewise textured flap VBO, by piece
+    pub flap_ranges: HashMap<id::FaceId, Range<u32>>,
+    /// This material's strips in the piecewise bleed VBO, by piece
+    pub bleed_ranges: HashMap<id::FaceId, Range<u32>>,
 }
 
 /// All the possible VBOs a mesh might need to use.
@@ -38,7 +42,18 @@ pub struct MeshGPUVBOs {
     pub edge_idx: gpu::VertBuf,
     pub edge_flags: gpu::VertBuf,
     pub edge_flap: gpu::VertBuf,
-
+    // For flaps textured like the face across their cut, grouped by material
+    pub flap_texture: gpu::VertBuf,
+    // For the artwork printed past pieces' outlines, grouped by material
+    pub bleed: gpu::VertBuf,
+
+    // For the strokes of seam labels, two endpoints each
+    pub label_pos: gpu::VertBuf,
+    // For pointing out the other sides of hovered and selected cuts, likewise
+    pub mate_pos: gpu::VertBuf,
+
+    // For triangulated faces, which the VBOs above hold as whole polygons
+    pub tri_indices: gpu::IndexBuf,
     // For per-material indexing
     pub mat_indices: gpu::IndexBuf,
 }
@@ -55,6 +70,11 @@ impl MeshGPUVBOs {
             edge_idx: gpu::VertBuf::new(format!("{label}.edge_idx")),
             edge_flags: gpu::VertBuf::new(format!("{label}.edge_flags")),
             edge_flap: gpu::VertBuf::new(format!("{label}.edge_flap")),
+            flap_texture: gpu::VertBuf::new(format!("{label}.flap_texture")),
+            bleed: gpu::VertBuf::new(format!("{label}.bleed")),
+            label_pos: gpu::VertBuf::new(format!("{label}.label_pos")),
+            mate_pos: gpu::VertBuf::new(format!("{label}.mate_pos")),
+            tri_indices: gpu::IndexBuf::new(format!("{label}.tri_indices")),
             mat_indices: gpu::IndexBuf::new(format!("{label}.mat_indices")),
         }
     }
@@ -129,7 +149,12 @@ impl MeshGPU {
             vbo::piece_vnor(ctx, mesh, &mut self.vbo_pieces.nor);
             vbo::piece_edge_pos(ctx, mesh, &mut self.vbo_pieces.edge_pos);
             vbo::piece_edge_flap(ctx, mesh, &mut self.vbo_pieces.edge_flap);
-            // Material slot IBOs
+            let ranges = &mut self.mat_ranges;
+            let flap_texture = &mut self.vbo_pieces.flap_texture;
+            vbo::piece_flap_texture(ctx, mesh, default_mat, flap_texture, ranges);
+            vbo::piece_bleed(ctx, mesh, default_mat, &mut self.vbo_pieces.bleed, ranges);
+            // Triangle and material slot IBOs
+            ibo::tri_indices(ctx, mesh, &mut self.vbo.tri_indices);
             let ranges = &mut self.mat_ranges;
             ibo::mat_indices(ctx, mesh, default_mat, &mut self.vbo.mat_indices, ranges);
             ibo::piece_mat_indices(
@@ -146,9 +171,15 @@ impl MeshGPU {
             // instances, which the PIECES branch below won't catch if the cut
             // didn't restructure any piece.
             vbo::piece_edge_flags(ctx, m_id, mesh, selection, &mut self.vbo_pieces.edge_flags);
+            // Likewise its faces' edges join or leave their pieces' outlines
+            let ranges = &mut self.mat_ranges;
+            vbo::piece_bleed(ctx, mesh, default_mat, &mut self.vbo_pieces.bleed, ranges);
         }
         if elem_dirty.intersects(MeshElementType::FLAPS) {
             vbo::piece_edge_flap(ctx, mesh, &mut self.vbo_pieces.edge_flap);
+            let ranges = &mut self.mat_ranges;
+            let flap_texture = &mut self.vbo_pieces.flap_texture;
+            vbo::piece_flap_texture(ctx, mesh, default_mat, flap_texture, ranges);
             // Moving a flap to the other side of a cut moves its HAS_FLAP flag too
             vbo::piece_edge_flags(ctx, m_id, mesh, selection, &mut self.vbo_pieces.edge_flags);
         }
@@ -172,7 +203,12 @@ impl MeshGPU {
             vbo::piece_edge_idx(ctx, m_id, mesh, &mut self.vbo_pieces.edge_idx);
             vbo::piece_edge_flap(ctx, mesh, &mut self.vbo_pieces.edge_flap);
             vbo::piece_edge_flags(ctx, m_id, mesh, selection, &mut self.vbo_pieces.edge_flags);
-            // Material slot IBOs
+            let ranges = &mut self.mat_ranges;
+            let flap_texture = &mut self.vbo_pieces.flap_texture;
+            vbo::piece_flap_texture(ctx, mesh, default_mat, flap_texture, ranges);
+            vbo::piece_bleed(ctx, mesh, default_mat, &mut self.vbo_pieces.bleed, ranges);
+            // Triangle and material slot IBOs
+            ibo::piece_tri_indices(ctx, mesh, &mut self.vbo_pieces.tri_indices);
             let ranges = &mut self.mat_ranges;
             ibo::piece_mat_indices(
                 ctx,
@@ -182,20 +218,25 @@ impl MeshGPU {
                 ranges,
             );
             // Ensure each piece has up-to-date ranges of elements to render
-            let mut i = 0;
+            let (mut i, mut tri_i) = (0, 0);
             let mut seen_pieces: HashSet<_> = HashSet::new();
             mesh.iter_pieces().for_each(|f_id| {
                 let piece = self
                     .pieces
                     .entry(*f_id)
                     .or_insert_with(|| PieceGPU::new(ctx, format!("{f_id:?}").as_str()));
-                let n_els = mesh
+                let corners: Vec<_> = mesh
                     .iter_connected_faces(*f_id)
-                    .flat_map(|f_id| mesh.iter_face_loops(f_id))
-                    .count() as u32;
+                    .map(|f_id| mesh.iter_face_loops(f_id).count() as u32)
+                    .collect();
+                let n_els = corners.iter().sum::<u32>();
+                // Every face triangulates into two fewer triangles than it has corners
+                let n_tri_els = corners.iter().map(|n| 3 * (n - 2)).sum::<u32>();
                 piece.range = i..(i + n_els);
+                piece.tri_range = tri_i..(tri_i + n_tri_els);
                 seen_pieces.insert(f_id);
                 i += n_els;
+                tri_i += n_tri_els;
             });
             // Delete pieces no longer being used
             if seen_pieces.len() != self.pieces.len() {
@@ -211,6 +252,38 @@ impl MeshGPU {
             }
         }
 
+        // Labels follow their seams' vertices, which cuts come and go on, and
+        // which piece each falls in
+        if elem_dirty.intersects(MeshElementType::VERTS | MeshElementType::EDGES)
+            || index_dirty.intersects(MeshElementType::PIECES)
+        {
+            let ranges = vbo::piece_labels(ctx, mesh, &mut self.vbo_pieces.label_pos);
+            for (p_id, range) in ranges {
+                if let Some(piece) = self.pieces.get_mut(&p_id) {
+                    piece.label_range = range;
+                }
+            }
+        }
+
+        // A cut's other side moves with either piece, as well as with what is
+        // hovered and selected
+        if self.is_dirty
+            || selection.is_dirty
+            || elem_dirty.intersects(
+                MeshElementType::VERTS | MeshElementType::EDGES | MeshElementType::PIECES,
+            )
+            || index_dirty.intersects(MeshElementType::PIECES)
+        {
+            vbo::mates(ctx, m_id, mesh, selection, &mut self.vbo.mate_pos);
+            let ranges =
+                vbo::piece_mates(ctx, m_id, mesh, selection, &mut self.vbo_pieces.mate_pos);
+            for (p_id, range) in ranges {
+                if let Some(piece) = self.pieces.get_mut(&p_id) {
+                    piece.mate_range = range;
+                }
+            }
+        }
+
         // If piece transforms have changed, make sure we sync all of them.
         // Iterate the GPU-side pieces rather than the mesh's: those are the
         // live ones (the mesh keeps deleted pieces around for undo), and their
@@ -270,7 +343,38 @@ impl MeshGPUVBOs {
     vertex_format!(edge_idx Uint32x4); // _ / Edge / Mesh(x2) idx
 
     // For flaps
+    vertex_format!(flap_up Float32x3);
     vertex_format!(flap_flags Uint32);
+    vertex_format!(flap_profile Float32x4); // Two outline points
+    vertex_format!(flap_uv_base Float32x4); // Texture coordinates at both ends
+    vertex_format!(flap_uv_up Float32x2);
+}
+
+/// Where a textured flap's texture coordinates start: after its base and `up`
+const FLAP_TEXTURE_UV_OFFSET: u64 =
+    MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS.size() * 2 + MeshGPUVBOs::VERTEX_FORMAT_FLAP_UP.size();
+
+/// The attribute carrying points `2k` and `2k + 1` of a textured flap's outline
+const fn flap_texture_profile_attribute(k: u32) -> wgpu::VertexAttribute {
+    wgpu::VertexAttribute {
+        format: MeshGPUVBOs::VERTEX_FORMAT_FLAP_PROFILE,
+        offset: FLAP_TEXTURE_UV_OFFSET
+            + MeshGPUVBOs::VERTEX_FORMAT_FLAP_UV_BASE.size()
+            + MeshGPUVBOs::VERTEX_FORMAT_FLAP_UV_UP.size()
+            + MeshGPUVBOs::VERTEX_FORMAT_FLAP_PROFILE.size() * k as u64,
+        shader_location: 6 + k,
+    }
+}
+
+/// The attribute carrying points `2k` and `2k + 1` of a flap's outline
+const fn flap_profile_attribute(k: u32) -> wgpu::VertexAttribute {
+    wgpu::VertexAttribute {
+        format: MeshGPUVBOs::VERTEX_FORMAT_FLAP_PROFILE,
+        offset: MeshGPUVBOs::VERTEX_FORMAT_FLAP_UP.size()
+            + MeshGPUVBOs::VERTEX_FORMAT_FLAP_FLAGS.size()
+            + MeshGPUVBOs::VERTEX_FORMAT_FLAP_PROFILE.size() * k as u64,
+        shader_location: 5 + k,
+    }
 }
 
 impl MeshGPU {
@@ -314,17 +418,23 @@ impl MeshGPU {
     ];
 
     pub fn draw_tris(&self, _: &gpu::Context, render_pass: &mut wgpu::RenderPass) {
+        if self.vbo.tri_indices.len == 0 {
+            return;
+        }
+        render_pass.set_index_buffer(self.vbo.tri_indices.slice(), wgpu::IndexFormat::Uint32);
         render_pass.set_vertex_buffer(0, self.vbo.pos.slice());
         render_pass.set_vertex_buffer(1, self.vbo.nor.slice());
         render_pass.set_vertex_buffer(2, self.vbo.vert_flags.slice());
         render_pass.set_vertex_buffer(3, self.vbo.vert_idx.slice());
-        render_pass.draw(0..self.vbo.vert_idx.len, 0..1);
+        render_pass.draw_indexed(0..self.vbo.tri_indices.len, 0, 0..1);
     }
 
     pub fn draw_piece_tris(&self, _: &gpu::Context, render_pass: &mut wgpu::RenderPass) {
         if self.pieces.is_empty() {
             return;
         };
+        render_pass
+            .set_index_buffer(self.vbo_pieces.tri_indices.slice(), wgpu::IndexFormat::Uint32);
         render_pass.set_vertex_buffer(0, self.vbo_pieces.pos.slice());
         render_pass.set_vertex_buffer(1, self.vbo_pieces.nor.slice());
         render_pass.set_vertex_buffer(2, self.vbo_pieces.vert_flags.slice());
@@ -332,7 +442,7 @@ impl MeshGPU {
         // Draw ranges from the buffers by binding each uniform
         self.pieces.values().for_each(|piece| {
             piece.bind(render_pass);
-            render_pass.draw(piece.range.clone(), 0..1);
+            render_pass.draw_indexed(piece.tri_range.clone(), 0, 0..1);
         })
     }
 
@@ -507,28 +617,31 @@ impl MeshGPU {
                 },
             ],
         },
-        // `EdgeFlapInfo`: the flap's two top corners, then its flags. The bottom
-        // two corners are the base edge, which the buffer above already carries.
+        // `EdgeFlapInfo`: the direction the flap stands up in, its flags, then
+        // its outline two points at a time. The base is the edge, which the
+        // buffer above already carries.
         wgpu::VertexBufferLayout {
-            array_stride: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS.size() * 2
-                + MeshGPUVBOs::VERTEX_FORMAT_FLAP_FLAGS.size(),
+            array_stride: std::mem::size_of::<extract::EdgeFlapInfo>() as u64,
             step_mode: wgpu::VertexStepMode::Instance,
             attributes: &[
                 wgpu::VertexAttribute {
-                    format: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS,
+                    format: MeshGPUVBOs::VERTEX_FORMAT_FLAP_UP,
                     offset: 0,
                     shader_location: 3,
                 },
-                wgpu::VertexAttribute {
-                    format: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS,
-                    offset: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS.size(),
-                    shader_location: 4,
-                },
                 wgpu::VertexAttribute {
                     format: MeshGPUVBOs::VERTEX_FORMAT_FLAP_FLAGS,
-                    offset: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS.size() * 2,
-                    shader_location: 5,
+                    offset: MeshGPUVBOs::VERTEX_FORMAT_FLAP_UP.size(),
+                    shader_location: 4,
                 },
+                flap_profile_attribute(0),
+                flap_profile_attribute(1),
+                flap_profile_attribute(2),
+                flap_profile_attribute(3),
+                flap_profile_attribute(4),
+                flap_profile_attribute(5),
+                flap_profile_attribute(6),
+                flap_profile_attribute(7),
             ],
         },
         wgpu::VertexBufferLayout {
@@ -537,7 +650,7 @@ impl MeshGPU {
             attributes: &[wgpu::VertexAttribute {
                 format: MeshGPUVBOs::VERTEX_FORMAT_EDGE_FLAGS,
                 offset: 0,
-                shader_location: 6,
+                shader_location: 13,
             }],
         },
         wgpu::VertexBufferLayout {
@@ -546,7 +659,7 @@ impl MeshGPU {
             attributes: &[wgpu::VertexAttribute {
                 format: MeshGPUVBOs::VERTEX_FORMAT_EDGE_IDX,
                 offset: 0,
-                shader_location: 7,
+                shader_location: 14,
             }],
         },
     ];
@@ -559,14 +672,162 @@ impl MeshGPU {
         if self.pieces.is_empty() {
             return;
         };
-        render_pass.set_vertex_buffer(0, ctx.shared.buffers.rect.slice(..));
+        render_pass.set_vertex_buffer(0, ctx.shared.buffers.flap_segments.slice(..));
         render_pass.set_vertex_buffer(1, self.vbo_pieces.edge_pos.slice());
         render_pass.set_vertex_buffer(2, self.vbo_pieces.edge_flap.slice());
         render_pass.set_vertex_buffer(3, self.vbo_pieces.edge_flags.slice());
         render_pass.set_vertex_buffer(4, self.vbo_pieces.edge_idx.slice());
         self.pieces.values().for_each(|piece| {
             piece.bind(render_pass);
-            render_pass.draw(0..4, piece.range.clone());
+            render_pass.draw(0..FLAP_SEGMENT_VERTS, piece.range.clone());
+        })
+    }
+
+    // `FlapTextureInfo`: the flap's base, the direction it stands up in, its
+    // texture coordinates, then its outline two points at a time
+    pub const BATCH_BUFFER_LAYOUT_FLAPS_TEXTURED_INSTANCED: &[wgpu::VertexBufferLayout<'static>] =
+        &[
+            wgpu::VertexBufferLayout {
+                array_stride: wgpu::VertexFormat::Float32x2.size(),
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &[wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Float32x2,
+                    offset: 0,
+                    shader_location: 0,
+                }],
+            },
+            wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<extract::FlapTextureInfo>() as u64,
+                step_mode: wgpu::VertexStepMode::Instance,
+                attributes: &[
+                    wgpu::VertexAttribute {
+                        format: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS,
+                        offset: 0,
+                        shader_location: 1,
+                    },
+                    wgpu::VertexAttribute {
+                        format: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS,
+                        offset: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS.size(),
+                        shader_location: 2,
+                    },
+                    wgpu::VertexAttribute {
+                        format: MeshGPUVBOs::VERTEX_FORMAT_FLAP_UP,
+                        offset: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS.size() * 2,
+                        shader_location: 3,
+                    },
+                    wgpu::VertexAttribute {
+                        format: MeshGPUVBOs::VERTEX_FORMAT_FLAP_UV_BASE,
+                        offset: FLAP_TEXTURE_UV_OFFSET,
+                        shader_location: 4,
+                    },
+                    wgpu::VertexAttribute {
+                        format: MeshGPUVBOs::VERTEX_FORMAT_FLAP_UV_UP,
+                        offset: FLAP_TEXTURE_UV_OFFSET
+                            + MeshGPUVBOs::VERTEX_FORMAT_FLAP_UV_BASE.size(),
+                        shader_location: 5,
+                    },
+                    flap_texture_profile_attribute(0),
+                    flap_texture_profile_attribute(1),
+                    flap_texture_profile_attribute(2),
+                    flap_texture_profile_attribute(3),
+                    flap_texture_profile_attribute(4),
+                    flap_texture_profile_attribute(5),
+                    flap_texture_profile_attribute(6),
+                    flap_texture_profile_attribute(7),
+                ],
+            },
+        ];
+
+    /// Draws the flaps textured like the faces across their cuts which use the
+    /// bound material
+    pub fn draw_piece_material_flaps_instanced(
+        &self,
+        ctx: &gpu::Context,
+        render_pass: &mut wgpu::RenderPass,
+        material_id: &MaterialId,
+    ) {
+        if self.pieces.is_empty() || self.vbo_pieces.flap_texture.len == 0 {
+            return;
+        };
+        let Some(material) = self.mat_ranges.get(*material_id) else {
+            return;
+        };
+        render_pass.set_vertex_buffer(0, ctx.shared.buffers.flap_segments.slice(..));
+        render_pass.set_vertex_buffer(1, self.vbo_pieces.flap_texture.slice());
+        material.flap_ranges.iter().for_each(|(p_id, range)| {
+            if let Some(piece) = self.pieces.get(p_id) {
+                piece.bind(render_pass);
+                render_pass.draw(0..FLAP_SEGMENT_VERTS, range.clone());
+            }
+        })
+    }
+
+    // `BleedInfo`: the edge, the direction out from its face, and its texture
+    // coordinates, laid out like the head of a `FlapTextureInfo`
+    pub const BATCH_BUFFER_LAYOUT_BLEED_INSTANCED: &[wgpu::VertexBufferLayout<'static>] = &[
+        wgpu::VertexBufferLayout {
+            array_stride: wgpu::VertexFormat::Float32x2.size(),
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[wgpu::VertexAttribute {
+                format: wgpu::VertexFormat::Float32x2,
+                offset: 0,
+                shader_location: 0,
+            }],
+        },
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<extract::BleedInfo>() as u64,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    format: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS,
+                    offset: 0,
+                    shader_location: 1,
+                },
+                wgpu::VertexAttribute {
+                    format: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS,
+                    offset: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS.size(),
+                    shader_location: 2,
+                },
+                wgpu::VertexAttribute {
+                    format: MeshGPUVBOs::VERTEX_FORMAT_FLAP_UP,
+                    offset: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS.size() * 2,
+                    shader_location: 3,
+                },
+                wgpu::VertexAttribute {
+                    format: MeshGPUVBOs::VERTEX_FORMAT_FLAP_UV_BASE,
+                    offset: FLAP_TEXTURE_UV_OFFSET,
+                    shader_location: 4,
+                },
+                wgpu::VertexAttribute {
+                    format: MeshGPUVBOs::VERTEX_FORMAT_FLAP_UV_UP,
+                    offset: FLAP_TEXTURE_UV_OFFSET + MeshGPUVBOs::VERTEX_FORMAT_FLAP_UV_BASE.size(),
+                    shader_location: 5,
+                },
+            ],
+        },
+    ];
+
+    /// Draws the bleed around the outlines of pieces' faces which use the
+    /// bound material
+    pub fn draw_piece_material_bleed_instanced(
+        &self,
+        ctx: &gpu::Context,
+        render_pass: &mut wgpu::RenderPass,
+        material_id: &MaterialId,
+    ) {
+        if self.pieces.is_empty() || self.vbo_pieces.bleed.len == 0 {
+            return;
+        };
+        let Some(material) = self.mat_ranges.get(*material_id) else {
+            return;
+        };
+        render_pass.set_vertex_buffer(0, ctx.shared.buffers.rect.slice(..));
+        render_pass.set_vertex_buffer(1, self.vbo_pieces.bleed.slice());
+        material.bleed_ranges.iter().for_each(|(p_id, range)| {
+            if let Some(piece) = self.pieces.get(p_id) {
+                piece.bind(render_pass);
+                render_pass.draw(0..4, range.clone());
+            }
         })
     }
 
@@ -578,14 +839,83 @@ impl MeshGPU {
         if self.pieces.is_empty() {
             return;
         };
-        render_pass.set_vertex_buffer(0, ctx.shared.buffers.rect_outline.slice(..));
+        render_pass.set_vertex_buffer(0, ctx.shared.buffers.flap_segments.slice(..));
         render_pass.set_vertex_buffer(1, self.vbo_pieces.edge_pos.slice());
         render_pass.set_vertex_buffer(2, self.vbo_pieces.edge_flap.slice());
         render_pass.set_vertex_buffer(3, self.vbo_pieces.edge_flags.slice());
         render_pass.set_vertex_buffer(4, self.vbo_pieces.edge_idx.slice());
         self.pieces.values().for_each(|piece| {
             piece.bind(render_pass);
-            render_pass.draw(0..24, piece.range.clone());
+            render_pass.draw(0..FLAP_SEGMENT_VERTS, piece.range.clone());
+        })
+    }
+
+    pub const BATCH_BUFFER_LAYOUT_STROKES_INSTANCED: &[wgpu::VertexBufferLayout<'static>] = &[
+        wgpu::VertexBufferLayout {
+            array_stride: wgpu::VertexFormat::Float32x2.size(),
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[wgpu::VertexAttribute {
+                format: wgpu::VertexFormat::Float32x2,
+                offset: 0,
+                shader_location: 0,
+            }],
+        },
+        wgpu::VertexBufferLayout {
+            array_stride: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS.size() * 2,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    format: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS,
+                    offset: 0,
+                    shader_location: 1,
+                },
+                wgpu::VertexAttribute {
+                    format: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS,
+                    offset: MeshGPUVBOs::VERTEX_FORMAT_EDGE_POS.size(),
+                    shader_location: 2,
+                },
+            ],
+        },
+    ];
+
+    pub fn draw_piece_labels_instanced(
+        &self,
+        ctx: &gpu::Context,
+        render_pass: &mut wgpu::RenderPass,
+    ) {
+        if self.pieces.is_empty() || self.vbo_pieces.label_pos.len == 0 {
+            return;
+        };
+        render_pass.set_vertex_buffer(0, ctx.shared.buffers.rect.slice(..));
+        render_pass.set_vertex_buffer(1, self.vbo_pieces.label_pos.slice());
+        self.pieces.values().filter(|piece| !piece.label_range.is_empty()).for_each(|piece| {
+            piece.bind(render_pass);
+            render_pass.draw(0..4, piece.label_range.clone());
+        })
+    }
+
+    pub fn draw_mates_instanced(&self, ctx: &gpu::Context, render_pass: &mut wgpu::RenderPass) {
+        if self.vbo.mate_pos.len == 0 {
+            return;
+        };
+        render_pass.set_vertex_buffer(0, ctx.shared.buffers.rect.slice(..));
+        render_pass.set_vertex_buffer(1, self.vbo.mate_pos.slice());
+        render_pass.draw(0..4, 0..self.vbo.mate_pos.len);
+    }
+
+    pub fn draw_piece_mates_instanced(
+        &self,
+        ctx: &gpu::Context,
+        render_pass: &mut wgpu::RenderPass,
+    ) {
+        if self.pieces.is_empty() || self.vbo_pieces.mate_pos.len == 0 {
+            return;
+        };
+        render_pass.set_vertex_buffer(0, ctx.shared.buffers.rect.slice(..));
+        render_pass.set_vertex_buffer(1, self.vbo_pieces.mate_pos.slice());
+        self.pieces.values().filter(|piece| !piece.mate_range.is_empty()).for_each(|piece| {
+            piece.bind(render_pass);
+            render_pass.draw(0..4, piece.mate_range.clone());
         })
     }
 
@@ -620,10 +950,14 @@ impl MeshGPU {
     ];
 
     pub fn draw_surface(&self, _: &gpu::Context, render_pass: &mut wgpu::RenderPass) {
+        if self.vbo.tri_indices.len == 0 {
+            return;
+        }
+        render_pass.set_index_buffer(self.vbo.tri_indices.slice(), wgpu::IndexFormat::Uint32);
         render_pass.set_vertex_buffer(0, self.vbo.pos.slice());
         render_pass.set_vertex_buffer(1, self.vbo.nor.slice());
         render_pass.set_vertex_buffer(2, self.vbo.uv.slice());
-        render_pass.draw(0..self.vbo.vert_idx.len, 0..1);
+        render_pass.draw_indexed(0..self.vbo.tri_indices.len, 0, 0..1);
     }
 
     pub fn draw_material_surface(
@@ -48,6 +48,12 @@ pub(crate) struct PieceGPU {
 
     /// The range of elements in this piece in non-material piecewise VBOs
     pub range: Range<u32>,
+    /// The range of this piece's triangle corners in the piecewise triangle IBO
+    pub tri_range: Range<u32>,
+    /// The range of this piece's seam label strokes in the piecewise label VBO
+    pub label_range: Range<u32>,
+    /// The range of this piece's cut highlights in the piecewise mate VBO
+    pub mate_range: Range<u32>,
 }
 
 impl PieceGPU {
@@ -69,6 +75,9 @@ impl PieceGPU {
             buf,
             depth_slot: f32::NAN,
             range: 0..0,
+            tri_range: 0..0,
+            label_range: 0..0,
+            mate_range: 0..0,
         }
     }
 
@@ -62,7 +62,9 @@ impl DrawCache {
     }
 
     pub(crate) fn prepare_meshes(&mut self, ctx: &gpu::Context, state: &mut pp_core::State) {
-        // Ensure state meshes are all synced in the DrawCache
+        // Ensure state meshes are all synced in the DrawCache, dropping any
+        // removed from the document
+        self.meshes.retain(|m_id, _| state.meshes.contains_key(m_id));
         state.meshes.iter_mut().for_each(|(m_id, mesh)| {
             self.meshes.entry(m_id).unwrap().or_insert(MeshGPU::new(ctx, mesh)).sync(
                 ctx,
@@ -74,7 +76,6 @@ impl DrawCache {
         });
 
         state.selection.is_dirty = false;
-        // TODO: Remove unused meshes from the DrawCache
     }
 
     pub(crate) fn prepare_materials(&mut self, ctx: &gpu::Context, state: &mut pp_core::State) {
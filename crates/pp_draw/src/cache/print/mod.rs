@@ -11,6 +11,8 @@ pub struct PrintLayoutGPU {
     bind_group: wgpu::BindGroup,
     /// Page-specific information
     pub pages: gpu::VertBuf,
+    /// Whether flaps are drawn with the texture of the face across their cut
+    pub textured_flaps: bool,
 }
 
 impl PrintLayoutGPU {
@@ -35,6 +37,7 @@ impl PrintLayoutGPU {
             }),
             buf,
             pages: gpu::VertBuf::new("print_layout.pages".to_string()),
+            textured_flaps: false,
         }
     }
 
@@ -42,6 +45,7 @@ impl PrintLayoutGPU {
         // Uniform needs re-allocation
         if other.is_dirty {
             self.buf.update(ctx, &[PrintLayoutUniform::from(other)]);
+            self.textured_flaps = other.textured_flaps;
             other.is_dirty = false;
         }
         // Page VBO needs re-allocation
@@ -51,11 +51,14 @@ pub struct ThemeOverrides {
     /// what tells it apart from a fold, and washing it out would cost more than
     /// the softer look gains.
     pub ink: Option<HexColor>,
+    /// How far each piece's artwork is printed past its outline, in world
+    /// units. Only print has any.
+    pub bleed: f32,
 }
 
 impl Default for ThemeOverrides {
     fn default() -> Self {
-        Self { stroke_scale: 1.0, selection: true, ink: None }
+        Self { stroke_scale: 1.0, selection: true, ink: None, bleed: 0.0 }
     }
 }
 
@@ -80,8 +83,10 @@ pub struct ThemeSizesUniform {
     stroke_scale: f32,
     /// [`ThemeOverrides::selection`], as a boolean.
     selection: f32,
+    /// [`ThemeOverrides::bleed`]. In world units, so not scaled with strokes.
+    bleed: f32,
     /// Pads the struct out to the 16-byte alignment `ThemeColorsUniform` needs.
-    _pad: [f32; 2],
+    _pad: [f32; 1],
 }
 
 impl ThemeSizesUniform {
@@ -94,7 +99,8 @@ impl ThemeSizesUniform {
             fold_lines: if value.fold_lines { 1.0 } else { 0.0 },
             stroke_scale: k,
             selection: if overrides.selection { 1.0 } else { 0.0 },
-            _pad: [0.0; 2],
+            bleed: overrides.bleed,
+            _pad: [0.0; 1],
         }
     }
 }
@@ -72,6 +72,9 @@ impl<'window> Renderer<'window> {
             mat.bind(render_pass);
             draw_cache.meshes.values().for_each(|mesh| {
                 engine_ink.draw_piece_mesh_for_material(ctx, render_pass, mesh, &id);
+                if draw_cache.printing.textured_flaps {
+                    engine_ink.draw_piece_flaps_for_material(ctx, render_pass, mesh, &id);
+                }
             });
         });
         draw_cache.meshes.values().for_each(|mesh| {
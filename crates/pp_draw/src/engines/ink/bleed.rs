@@ -0,0 +1,76 @@
+use pp_core::MaterialId;
+
+use crate::cache;
+use crate::gpu;
+
+use super::DepthClass;
+
+/// Artwork printed past the outlines of pieces. Only print sets a bleed, so
+/// everywhere else the strips collapse to nothing.
+#[derive(Debug)]
+pub(super) struct BleedProgram {
+    pipeline: wgpu::RenderPipeline,
+}
+
+impl BleedProgram {
+    pub(super) fn new(ctx: &gpu::Context, sample_count: u32) -> Self {
+        let shader = ctx.device.create_shader_module(wgpu::include_wgsl!("./shaders/bleed.wgsl"));
+        Self {
+            pipeline: ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("ink3.bleed"),
+                layout: Some(&ctx.shared.pipeline_layouts.mesh_surface),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: cache::MeshGPU::BATCH_BUFFER_LAYOUT_BLEED_INSTANCED,
+                    compilation_options: DepthClass::Bleed.compilation_options(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: ctx.view_format,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: gpu::Texture::DEPTH_FORMAT,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::Less,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: sample_count,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+                cache: None,
+            }),
+        }
+    }
+
+    /// Writes draw commands for the bleed of the faces using the bound material
+    pub(super) fn draw_piece_mesh_with_material(
+        &self,
+        ctx: &gpu::Context,
+        render_pass: &mut wgpu::RenderPass,
+        mesh: &cache::MeshGPU,
+        material_id: &MaterialId,
+    ) {
+        render_pass.set_pipeline(&self.pipeline);
+        mesh.draw_piece_material_bleed_instanced(ctx, render_pass, material_id);
+    }
+}
@@ -1,3 +1,5 @@
+use pp_core::MaterialId;
+
 use crate::cache;
 use crate::gpu;
 
@@ -6,11 +8,15 @@ use super::DepthClass;
 #[derive(Debug)]
 pub(super) struct FlapsProgram {
     pipeline: wgpu::RenderPipeline,
+    /// Flaps textured like the face across their cut, drawn per material
+    textured: wgpu::RenderPipeline,
 }
 
 impl FlapsProgram {
     pub(super) fn new(ctx: &gpu::Context, sample_count: u32) -> Self {
         let shader = ctx.device.create_shader_module(wgpu::include_wgsl!("./shaders/flaps.wgsl"));
+        let textured_shader =
+            ctx.device.create_shader_module(wgpu::include_wgsl!("./shaders/flaps_textured.wgsl"));
         Self {
             pipeline: ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
                 label: Some("ink3.flaps"),
@@ -32,7 +38,50 @@ impl FlapsProgram {
                     compilation_options: wgpu::PipelineCompilationOptions::default(),
                 }),
                 primitive: wgpu::PrimitiveState {
-                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: gpu::Texture::DEPTH_FORMAT,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::Less,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: sample_count,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+                cache: None,
+            }),
+            textured: ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("ink3.flaps.textured"),
+                layout: Some(&ctx.shared.pipeline_layouts.mesh_surface),
+                vertex: wgpu::VertexState {
+                    module: &textured_shader,
+                    entry_point: Some("vs_main"),
+                    buffers: cache::MeshGPU::BATCH_BUFFER_LAYOUT_FLAPS_TEXTURED_INSTANCED,
+                    compilation_options: DepthClass::FlapFill.compilation_options(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &textured_shader,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: ctx.view_format,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
                     strip_index_format: None,
                     front_face: wgpu::FrontFace::Ccw,
                     cull_mode: None,
@@ -68,4 +117,16 @@ impl FlapsProgram {
         render_pass.set_pipeline(&self.pipeline);
         mesh.draw_piece_flaps_instanced(ctx, render_pass);
     }
+
+    /// Writes draw commands for the textured flaps using the bound material
+    pub(super) fn draw_piece_mesh_with_material(
+        &self,
+        ctx: &gpu::Context,
+        render_pass: &mut wgpu::RenderPass,
+        mesh: &cache::MeshGPU,
+        material_id: &MaterialId,
+    ) {
+        render_pass.set_pipeline(&self.textured);
+        mesh.draw_piece_material_flaps_instanced(ctx, render_pass, material_id);
+    }
 }
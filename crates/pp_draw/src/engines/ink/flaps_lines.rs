@@ -4,58 +4,87 @@ use crate::gpu;
 #[derive(Debug)]
 pub(super) struct FlapsLinesProgram {
     pipeline: wgpu::RenderPipeline,
+    /// Slits share the flaps' buffers, but lie on top of a face where tab
+    /// outlines are tucked beneath one, so they are drawn in a class of their own
+    slits: wgpu::RenderPipeline,
 }
 
 impl FlapsLinesProgram {
     pub(super) fn new(ctx: &gpu::Context, sample_count: u32) -> Self {
         let shader = ctx.device.create_shader_module(wgpu::include_wgsl!("./shaders/flaps.wgsl"));
         Self {
-            pipeline: ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                label: Some("ink3.flaps_lines"),
-                layout: Some(&ctx.shared.pipeline_layouts.mesh_overlays),
-                vertex: wgpu::VertexState {
-                    module: &shader,
-                    entry_point: Some("vs_edge"),
-                    buffers: cache::MeshGPU::BATCH_BUFFER_LAYOUT_FLAPS_INSTANCED,
-                    compilation_options: super::DepthClass::FlapOutline.compilation_options(),
-                },
-                fragment: Some(wgpu::FragmentState {
-                    module: &shader,
-                    entry_point: Some("fs_main"),
-                    targets: &[Some(wgpu::ColorTargetState {
-                        format: ctx.view_format,
-                        blend: Some(wgpu::BlendState::REPLACE),
-                        write_mask: wgpu::ColorWrites::ALL,
-                    })],
-                    compilation_options: wgpu::PipelineCompilationOptions::default(),
-                }),
-                primitive: wgpu::PrimitiveState {
-                    topology: wgpu::PrimitiveTopology::TriangleList,
-                    strip_index_format: None,
-                    front_face: wgpu::FrontFace::Ccw,
-                    cull_mode: None,
-                    polygon_mode: wgpu::PolygonMode::Fill,
-                    unclipped_depth: false,
-                    conservative: false,
-                },
-                depth_stencil: Some(wgpu::DepthStencilState {
-                    format: gpu::Texture::DEPTH_FORMAT,
-                    depth_write_enabled: true,
-                    depth_compare: wgpu::CompareFunction::Less,
-                    stencil: wgpu::StencilState::default(),
-                    bias: wgpu::DepthBiasState::default(),
-                }),
-                multisample: wgpu::MultisampleState {
-                    count: sample_count,
-                    mask: !0,
-                    alpha_to_coverage_enabled: false,
-                },
-                multiview: None,
-                cache: None,
-            }),
+            pipeline: Self::pipeline(
+                ctx,
+                &shader,
+                sample_count,
+                "ink3.flaps_lines",
+                "vs_edge",
+                super::DepthClass::FlapOutline,
+            ),
+            slits: Self::pipeline(
+                ctx,
+                &shader,
+                sample_count,
+                "ink3.flaps_slits",
+                "vs_slit",
+                super::DepthClass::CutLine,
+            ),
         }
     }
 
+    fn pipeline(
+        ctx: &gpu::Context,
+        shader: &wgpu::ShaderModule,
+        sample_count: u32,
+        label: &str,
+        entry_point: &str,
+        depth_class: super::DepthClass,
+    ) -> wgpu::RenderPipeline {
+        ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&ctx.shared.pipeline_layouts.mesh_overlays),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some(entry_point),
+                buffers: cache::MeshGPU::BATCH_BUFFER_LAYOUT_FLAPS_INSTANCED,
+                compilation_options: depth_class.compilation_options(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: ctx.view_format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: gpu::Texture::DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        })
+    }
+
     /// Writes geometry draw commands for all the materials in a mesh
     pub(super) fn draw_piece_mesh(
         &self,
@@ -65,5 +94,7 @@ impl FlapsLinesProgram {
     ) {
         render_pass.set_pipeline(&self.pipeline);
         mesh.draw_piece_flaps_outline_instanced(ctx, render_pass);
+        render_pass.set_pipeline(&self.slits);
+        mesh.draw_piece_flaps_outline_instanced(ctx, render_pass);
     }
 }
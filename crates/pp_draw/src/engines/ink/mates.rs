@@ -0,0 +1,81 @@
+use crate::cache;
+use crate::gpu;
+
+use super::DepthClass;
+
+/// Points out the other side of each hovered or selected cut, wherever it is
+#[derive(Debug)]
+pub(super) struct MatesProgram {
+    pipeline: wgpu::RenderPipeline,
+}
+
+impl MatesProgram {
+    pub(super) fn new(ctx: &gpu::Context, sample_count: u32) -> Self {
+        let shader = ctx.device.create_shader_module(wgpu::include_wgsl!("./shaders/mates.wgsl"));
+        Self {
+            pipeline: ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("ink3.mates"),
+                layout: Some(&ctx.shared.pipeline_layouts.mesh_overlays),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: cache::MeshGPU::BATCH_BUFFER_LAYOUT_STROKES_INSTANCED,
+                    compilation_options: DepthClass::Highlight.compilation_options(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: ctx.view_format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: gpu::Texture::DEPTH_FORMAT,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::Less,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: sample_count,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+                cache: None,
+            }),
+        }
+    }
+
+    pub(super) fn draw_mesh(
+        &self,
+        ctx: &gpu::Context,
+        render_pass: &mut wgpu::RenderPass,
+        mesh: &cache::MeshGPU,
+    ) {
+        render_pass.set_pipeline(&self.pipeline);
+        mesh.draw_mates_instanced(ctx, render_pass);
+    }
+
+    pub(super) fn draw_piece_mesh(
+        &self,
+        ctx: &gpu::Context,
+        render_pass: &mut wgpu::RenderPass,
+        mesh: &cache::MeshGPU,
+    ) {
+        render_pass.set_pipeline(&self.pipeline);
+        mesh.draw_piece_mates_instanced(ctx, render_pass);
+    }
+}
@@ -3,10 +3,13 @@ use pp_editor::state::SelectionMode;
 
 use crate::{cache, gpu};
 
+mod bleed;
 mod flaps;
 mod flaps_lines;
+mod labels;
 mod lines;
 mod lines_cut;
+mod mates;
 mod points;
 mod surface;
 mod tris;
@@ -33,21 +36,26 @@ mod tris;
 #[repr(u32)]
 #[derive(Debug, Clone, Copy, Default)]
 pub enum DepthClass {
+    /// Artwork printed past a piece's outline. Beneath all paper, so that any
+    /// piece it runs under covers it.
+    Bleed = 0,
     /// Tab paper. Always beneath any face.
-    FlapFill = 0,
+    FlapFill = 1,
     /// Tab borders. Above their own fill, still beneath any face.
-    FlapOutline = 1,
+    FlapOutline = 2,
     /// Textured faces — the reference plane everything else is placed against.
     #[default]
-    Surface = 2,
+    Surface = 3,
     /// The translucent selection tint drawn over the surface.
-    FaceOverlay = 3,
+    FaceOverlay = 4,
     /// Interior fold annotations.
-    FoldLine = 4,
+    FoldLine = 5,
     /// Cut and boundary lines: the piece silhouette.
-    CutLine = 5,
+    CutLine = 6,
     /// Vertex points.
-    Vertex = 6,
+    Vertex = 7,
+    /// Transient pointers, like the other side of a hovered cut. Over all else.
+    Highlight = 8,
 }
 
 impl DepthClass {
@@ -57,13 +65,15 @@ impl DepthClass {
     pub fn compilation_options(&self) -> wgpu::PipelineCompilationOptions<'static> {
         wgpu::PipelineCompilationOptions {
             constants: match self {
-                Self::FlapFill => &[("depth_class", 0.0)],
-                Self::FlapOutline => &[("depth_class", 1.0)],
-                Self::Surface => &[("depth_class", 2.0)],
-                Self::FaceOverlay => &[("depth_class", 3.0)],
-                Self::FoldLine => &[("depth_class", 4.0)],
-                Self::CutLine => &[("depth_class", 5.0)],
-                Self::Vertex => &[("depth_class", 6.0)],
+                Self::Bleed => &[("depth_class", 0.0)],
+                Self::FlapFill => &[("depth_class", 1.0)],
+                Self::FlapOutline => &[("depth_class", 2.0)],
+                Self::Surface => &[("depth_class", 3.0)],
+                Self::FaceOverlay => &[("depth_class", 4.0)],
+                Self::FoldLine => &[("depth_class", 5.0)],
+                Self::CutLine => &[("depth_class", 6.0)],
+                Self::Vertex => &[("depth_class", 7.0)],
+                Self::Highlight => &[("depth_class", 8.0)],
             },
             ..Default::default()
         }
@@ -80,6 +90,9 @@ pub struct InkEngine {
     surface: surface::SurfaceProgram,
     flaps: flaps::FlapsProgram,
     flaps_lines: flaps_lines::FlapsLinesProgram,
+    labels: labels::LabelsProgram,
+    mates: mates::MatesProgram,
+    bleed: bleed::BleedProgram,
 }
 
 impl InkEngine {
@@ -98,6 +111,9 @@ impl InkEngine {
             surface: surface::SurfaceProgram::new(ctx, sample_count),
             flaps: flaps::FlapsProgram::new(ctx, sample_count),
             flaps_lines: flaps_lines::FlapsLinesProgram::new(ctx, sample_count),
+            bleed: bleed::BleedProgram::new(ctx, sample_count),
+            labels: labels::LabelsProgram::new(ctx, sample_count),
+            mates: mates::MatesProgram::new(ctx, sample_count),
         }
     }
 
@@ -122,6 +138,32 @@ impl InkEngine {
         self.surface.draw_piece_mesh_with_material(ctx, render_pass, mesh, material_id);
     }
 
+    /// Draws the flaps which carry on the texture of the face across their
+    /// cuts, for the bound material. Drawn ahead of [`Self::draw_piece_mesh`],
+    /// they win the depth test over the plain flaps drawn there.
+    pub fn draw_piece_flaps_for_material(
+        &self,
+        ctx: &gpu::Context,
+        render_pass: &mut wgpu::RenderPass,
+        mesh: &cache::MeshGPU,
+        material_id: &MaterialId,
+    ) {
+        self.flaps.draw_piece_mesh_with_material(ctx, render_pass, mesh, material_id);
+    }
+
+    /// Draws the artwork printed past the outlines of pieces' faces using the
+    /// bound material. It lies beneath all paper, so it may be drawn in any
+    /// order with the rest.
+    pub fn draw_piece_bleed_for_material(
+        &self,
+        ctx: &gpu::Context,
+        render_pass: &mut wgpu::RenderPass,
+        mesh: &cache::MeshGPU,
+        material_id: &MaterialId,
+    ) {
+        self.bleed.draw_piece_mesh_with_material(ctx, render_pass, mesh, material_id);
+    }
+
     pub fn draw_mesh(
         &self,
         ctx: &gpu::Context,
@@ -151,6 +193,7 @@ impl InkEngine {
         if *selection_mode == SelectionMode::Vert {
             self.points.draw_mesh(ctx, render_pass, mesh);
         }
+        self.mates.draw_mesh(ctx, render_pass, mesh);
     }
 
     pub fn draw_piece_mesh(
@@ -166,6 +209,8 @@ impl InkEngine {
         // you need to see and click on.
         if *selection_mode == SelectionMode::Piece {
             self.lines.draw_piece_mesh_folds(ctx, render_pass, mesh);
+            // Seam labels are assembly instructions, like the folds
+            self.labels.draw_piece_mesh(ctx, render_pass, mesh);
         } else {
             self.lines.draw_piece_mesh(ctx, render_pass, mesh);
         }
@@ -174,5 +219,6 @@ impl InkEngine {
         if *selection_mode == SelectionMode::Vert {
             self.points.draw_piece_mesh(ctx, render_pass, mesh);
         }
+        self.mates.draw_piece_mesh(ctx, render_pass, mesh);
     }
 }
@@ -9,6 +9,9 @@ struct ThemeSizes {
   // Whether selected / active elements are highlighted at all. Off for
   // print, which must not bake transient editor state into the page.
   selection: f32,
+  // How far artwork is printed past each piece's outline, in world units.
+  // Zero everywhere but print.
+  bleed: f32,
 };
 struct ThemeColors { 
   background: vec4<f32>,
@@ -68,11 +71,19 @@ struct VertexInput {
     @location(0) offset: vec2<f32>,
     @location(1) v0_pos: vec3<f32>,
     @location(2) v1_pos: vec3<f32>,
-    @location(3) top0_pos: vec3<f32>,
-    @location(4) top1_pos: vec3<f32>,
-    @location(5) flap_flags: u32,
-    @location(6) flags: u32,
-    @location(7) select_idx: vec4<u32>,
+    @location(3) up: vec3<f32>,
+    @location(4) flap_flags: u32,
+    // The outline, two `(s, h)` points at a time
+    @location(5) profile_0: vec4<f32>,
+    @location(6) profile_1: vec4<f32>,
+    @location(7) profile_2: vec4<f32>,
+    @location(8) profile_3: vec4<f32>,
+    @location(9) profile_4: vec4<f32>,
+    @location(10) profile_5: vec4<f32>,
+    @location(11) profile_6: vec4<f32>,
+    @location(12) profile_7: vec4<f32>,
+    @location(13) flags: u32,
+    @location(14) select_idx: vec4<u32>,
     @builtin(vertex_index) vertex_index: u32
 };
 
@@ -91,17 +102,33 @@ const E_FLAG_CUT: u32 = (u32(1) << 4);
 
 // Flap flags
 const F_FLAG_EXISTS: u32 = (u32(1) << 0);
+const F_FLAG_SLIT: u32 = (u32(1) << 1);
 
-// The 4 corners of the trapezoid flap: bottom-left, bottom-right, top-right,
-// top-left.
+// Point `i` of the flap's outline, as `(s, h)`: `s` the fraction of the way
+// along the base from v0 to v1, `h` the distance off it along `up`.
 //
-// The shape is decided on the CPU by `pp_core::mesh::flap::flap_corners` — the
-// bottom pair is the base edge this flap folds along, the top pair arrives in
-// its own buffer. Keep it that way: the vector print path strokes these same
-// four points, so a copy of the trapezoid math living here too would let the
-// printed tab drift from the one on screen.
-fn _flap_corners(in: VertexInput) -> array<vec3<f32>, 4> {
-    return array<vec3<f32>, 4>(in.v0_pos, in.v1_pos, in.top1_pos, in.top0_pos);
+// The shape is decided on the CPU by `pp_core::mesh::flap::flap_outline` — the
+// base is the edge this flap folds along, the outline arrives in its own
+// buffer. Keep it that way: the vector print path strokes these same points,
+// so a copy of the outline math living here too would let the printed tab
+// drift from the one on screen.
+fn _profile(in: VertexInput, i: u32) -> vec2<f32> {
+    let pairs = array<vec4<f32>, 8>(
+        in.profile_0, in.profile_1, in.profile_2, in.profile_3,
+        in.profile_4, in.profile_5, in.profile_6, in.profile_7,
+    );
+    let pair = pairs[i / 2u];
+    return select(pair.xy, pair.zw, i % 2u == 1u);
+}
+
+// The point on the base beneath outline point `i`
+fn _base_point(in: VertexInput, i: u32) -> vec3<f32> {
+    return mix(in.v0_pos, in.v1_pos, _profile(in, i).x);
+}
+
+// Outline point `i`
+fn _outline_point(in: VertexInput, i: u32) -> vec3<f32> {
+    return _base_point(in, i) + in.up * _profile(in, i).y;
 }
 
 // Calculates the colors of flaps as would be seen on-screen.
@@ -135,47 +162,51 @@ fn _vs_color_edge(in: VertexInput, _out: VertexOutput) -> VertexOutput {
     return out;
 }
 
-// Calculates the clip position of edge vertices based on the width of the line
+// Calculates the clip position of fill vertices. The flap is filled as one
+// column per segment of its outline, standing on the base beneath it.
 fn _vs_clip_pos(in: VertexInput, _out: VertexOutput) -> VertexOutput {
     var out = _out;
-    
-    // Compute corners of the flap trapezoid
-    let corners = _flap_corners(in);
-  
-    // Interpolate between corners of the flap based on input verts
-    let base_pos = mix(corners[0], corners[1], in.offset.x);
-    let top_pos = mix(corners[3], corners[2], in.offset.x);
-    let pos = mix(base_pos, top_pos, in.offset.y);
-    out.clip_position =
-        _apply_depth_offset(camera.view_proj * piece.affine * vec4<f32>(pos, 1.0));
 
-    // If flap doesn't exist, push it offscreen to avoid rasterization
-    if (bool(in.flap_flags ^ F_FLAG_EXISTS)) {
+    // If flap doesn't exist, push it offscreen to avoid rasterization. Slits
+    // are only ever stroked.
+    if (!bool(in.flap_flags & F_FLAG_EXISTS)) {
         out.clip_position.z = -100.0;
+        return out;
     }
 
+    // Interpolate across the column based on input verts
+    let segment = in.vertex_index / 6u;
+    let base_pos =
+        mix(_base_point(in, segment), _base_point(in, segment + 1u), in.offset.x);
+    let top_pos =
+        mix(_outline_point(in, segment), _outline_point(in, segment + 1u), in.offset.x);
+    let pos = mix(base_pos, top_pos, in.offset.y);
+    out.clip_position =
+        _apply_depth_offset(camera.view_proj * piece.affine * vec4<f32>(pos, 1.0));
+
     return out;
 }
 
-// Calculates the clip position of edge vertices based on the width of the line
-fn _vs_clip_pos_edge(in: VertexInput, _out: VertexOutput) -> VertexOutput {
+// Calculates the clip position of edge vertices based on the width of the line,
+// for outlines carrying `flag`.
+fn _vs_clip_pos_edge(in: VertexInput, _out: VertexOutput, flag: u32) -> VertexOutput {
     var out = _out;
 
-    // Side 0 of the trapezoid is its base, which lies right on the piece edge
-    // the flap hangs off of. That seam is a fold rather than a cut, so leave it
-    // to the edge line pipeline and only outline the three free sides.
-    let side = u32(in.vertex_index / 6);
-    if (side == 0u || !bool(in.flap_flags & F_FLAG_EXISTS)) {
+    // Segments lying along the base are on the piece edge the flap hangs off
+    // of. That seam is a fold rather than a cut, so leave it to the edge line
+    // pipeline and only outline the flap's free edge. Zero-length segments are
+    // the padding the outline ends with.
+    let segment = in.vertex_index / 6u;
+    let a = _profile(in, segment);
+    let b = _profile(in, segment + 1u);
+    if (!bool(in.flap_flags & flag) || all(a == b) || (a.y <= 0.0 && b.y <= 0.0)) {
         out.clip_position.z = -100.0;
         return out;
     }
 
-    // Get the corners of the flap trapezoid
-    let corners = _flap_corners(in);
-
     // Get the current vertex and the next vertex
-    let p0 = corners[side];
-    let p1 = corners[(side + 1u) % 4u];
+    let p0 = _outline_point(in, segment);
+    let p1 = _outline_point(in, segment + 1u);
 
     // Find screen-space positions of each vertex
     var clip_v0 = camera.view_proj * piece.affine * vec4<f32>(p0, 1.0);
@@ -208,7 +239,16 @@ fn vs_main(in: VertexInput) -> VertexOutput {
 fn vs_edge(in: VertexInput) -> VertexOutput {
     var out: VertexOutput;
     out = _vs_color_edge(in, out);
-    out = _vs_clip_pos_edge(in, out);
+    out = _vs_clip_pos_edge(in, out, F_FLAG_EXISTS);
+    return out;
+}
+
+// [VS.3] Slits, which lie on a face rather than tucked beneath one
+@vertex
+fn vs_slit(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out = _vs_color_edge(in, out);
+    out = _vs_clip_pos_edge(in, out, F_FLAG_SLIT);
     return out;
 }
 
@@ -0,0 +1,144 @@
+struct ThemeSizes {
+  line_width: f32,
+  line_width_thick: f32,
+  point_size: f32,
+  fold_lines: f32,
+  // Scales lengths this shader hardcodes in pixels, so they keep a
+  // constant physical size as the pixel density changes.
+  stroke_scale: f32,
+  // Whether selected / active elements are highlighted at all. Off for
+  // print, which must not bake transient editor state into the page.
+  selection: f32,
+  // How far artwork is printed past each piece's outline, in world units.
+  // Zero everywhere but print.
+  bleed: f32,
+};
+struct ThemeColors { 
+  background: vec4<f32>,
+  grid: vec4<f32>,
+  grid_axis_x: vec4<f32>,
+  grid_axis_y: vec4<f32>,
+  element_active: vec4<f32>,
+  element_selected: vec4<f32>,
+  edge_cut: vec4<f32>,
+  edge_boundary: vec4<f32>,
+  // The default stroke color for edges carrying no annotation of their own.
+  ink: vec4<f32>,
+  padding: vec4<f32>,
+};
+struct Theme { sizes: ThemeSizes, colors: ThemeColors };
+@group(0) @binding(0) var<uniform> theme: Theme;
+struct Viewport { position: vec2<f32>, dimensions: vec2<f32> };
+struct Camera { view_proj: mat4x4<f32>, eye: vec4<f32> };
+@group(1) @binding(0) var<uniform> viewport: Viewport;
+@group(1) @binding(1) var<uniform> camera: Camera;
+struct Piece { affine: mat4x4<f32>, depth_slot: f32 };
+@group(2) @binding(0) var<uniform> piece: Piece;
+
+// Where this pipeline's geometry sits in the stack of coplanar geometry, as a
+// `DepthClass` discriminant. Set per-pipeline; see `engines::ink::DepthClass`.
+override depth_class: f32 = 0.0;
+
+// How far one class lifts geometry toward the eye, as a fraction of that
+// geometry's own distance from the camera. Being *relative* is the point: it
+// holds at any zoom and on any model scale.
+const DEPTH_CLASS_STEP: f32 = 1.0 / 4096.0;
+
+// How much of a class step a piece's slot may use. Well under 1, so a slot only
+// ever breaks ties inside its own class and can't promote a piece into the next.
+const DEPTH_SLOT_SPAN: f32 = 0.5;
+
+// Lifts a projected position toward the viewer by its class, so that coplanar
+// geometry resolves by what it *is* rather than by draw order or by whichever
+// polygon happened to win the depth test.
+//
+// The lift is a fixed fraction of view depth, not a fixed amount of NDC depth.
+// NDC depth goes as ~1/z, so with this projection (near 0.1, far 100+) the whole
+// model lands in the top few percent of the depth range: a constant NDC offset
+// that looks tiny is in fact a large part of the model's depth extent, and it
+// stays constant as the camera dollies out while that extent keeps shrinking —
+// so far-side geometry punches through. `1 - ndc_z` is proportional to
+// `near / z_view`, so scaling by it turns the offset back into a constant
+// relative step, small against real depth differences at every distance.
+fn _apply_depth_offset(clip: vec4<f32>) -> vec4<f32> {
+    let ndc_z = clip.z / clip.w;
+    let units = depth_class + piece.depth_slot * DEPTH_SLOT_SPAN;
+    let offset = units * DEPTH_CLASS_STEP * max(1.0 - ndc_z, 0.0);
+    return vec4<f32>(clip.xy, (ndc_z - offset) * clip.w, clip.w);
+}
+
+struct Material { base_color_factor: vec4<f32> };
+@group(3) @binding(0) var<uniform> material: Material;
+@group(3) @binding(1) var t_diffuse: texture_2d<f32>;
+@group(3) @binding(2) var s_diffuse: sampler;
+
+struct VertexInput {
+    @location(0) offset: vec2<f32>,
+    @location(1) v0_pos: vec3<f32>,
+    @location(2) v1_pos: vec3<f32>,
+    @location(3) up: vec3<f32>,
+    // Texture coordinates at v0 and v1
+    @location(4) uv_base: vec4<f32>,
+    // How the texture coordinates move per unit along `up`
+    @location(5) uv_up: vec2<f32>,
+    // The outline, two `(s, h)` points at a time
+    @location(6) profile_0: vec4<f32>,
+    @location(7) profile_1: vec4<f32>,
+    @location(8) profile_2: vec4<f32>,
+    @location(9) profile_3: vec4<f32>,
+    @location(10) profile_4: vec4<f32>,
+    @location(11) profile_5: vec4<f32>,
+    @location(12) profile_6: vec4<f32>,
+    @location(13) profile_7: vec4<f32>,
+    @builtin(vertex_index) vertex_index: u32
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+// Point `i` of the flap's outline, as `(s, h)`. See `flaps.wgsl`: the shape
+// comes from the CPU, and this only places it.
+fn _profile(in: VertexInput, i: u32) -> vec2<f32> {
+    let pairs = array<vec4<f32>, 8>(
+        in.profile_0, in.profile_1, in.profile_2, in.profile_3,
+        in.profile_4, in.profile_5, in.profile_6, in.profile_7,
+    );
+    let pair = pairs[i / 2u];
+    return select(pair.xy, pair.zw, i % 2u == 1u);
+}
+
+// Where `(s, h)` is on the flap, and its texture coordinates there. The
+// texture carries on from the face across the cut, as if it were folded flat
+// onto the flap.
+fn _flap_pos(in: VertexInput, p: vec2<f32>) -> vec3<f32> {
+    return mix(in.v0_pos, in.v1_pos, p.x) + in.up * p.y;
+}
+fn _flap_uv(in: VertexInput, p: vec2<f32>) -> vec2<f32> {
+    return mix(in.uv_base.xy, in.uv_base.zw, p.x) + in.uv_up * p.y;
+}
+
+// [VS.1] Flap, filled one column per outline segment as in `flaps.wgsl`
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    let segment = in.vertex_index / 6u;
+    let a = _profile(in, segment);
+    let b = _profile(in, segment + 1u);
+    let base = mix(vec2<f32>(a.x, 0.0), vec2<f32>(b.x, 0.0), in.offset.x);
+    let top = mix(a, b, in.offset.x);
+    let p = mix(base, top, in.offset.y);
+
+    out.tex_coords = _flap_uv(in, p);
+    out.clip_position = _apply_depth_offset(
+        camera.view_proj * piece.affine * vec4<f32>(_flap_pos(in, p), 1.0));
+    return out;
+}
+
+// [FS.1] Normal rendering
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(t_diffuse, s_diffuse, in.tex_coords);
+}
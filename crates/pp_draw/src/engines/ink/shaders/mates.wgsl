@@ -0,0 +1,111 @@
+struct ThemeSizes {
+  line_width: f32,
+  line_width_thick: f32,
+  point_size: f32,
+  fold_lines: f32,
+  // Scales lengths this shader hardcodes in pixels, so they keep a
+  // constant physical size as the pixel density changes.
+  stroke_scale: f32,
+  // Whether selected / active elements are highlighted at all. Off for
+  // print, which must not bake transient editor state into the page.
+  selection: f32,
+  // How far artwork is printed past each piece's outline, in world units.
+  // Zero everywhere but print.
+  bleed: f32,
+};
+struct ThemeColors {
+  background: vec4<f32>,
+  grid: vec4<f32>,
+  grid_axis_x: vec4<f32>,
+  grid_axis_y: vec4<f32>,
+  element_active: vec4<f32>,
+  element_selected: vec4<f32>,
+  edge_cut: vec4<f32>,
+  edge_boundary: vec4<f32>,
+  // The default stroke color for edges carrying no annotation of their own.
+  ink: vec4<f32>,
+  padding: vec4<f32>,
+};
+struct Theme { sizes: ThemeSizes, colors: ThemeColors };
+@group(0) @binding(0) var<uniform> theme: Theme;
+struct Viewport { position: vec2<f32>, dimensions: vec2<f32> };
+struct Camera { view_proj: mat4x4<f32>, eye: vec4<f32> };
+@group(1) @binding(0) var<uniform> viewport: Viewport;
+@group(1) @binding(1) var<uniform> camera: Camera;
+struct Piece { affine: mat4x4<f32>, depth_slot: f32 };
+@group(2) @binding(0) var<uniform> piece: Piece;
+
+// Where this pipeline's geometry sits in the stack of coplanar geometry, as a
+// `DepthClass` discriminant. Set per-pipeline; see `engines::ink::DepthClass`.
+override depth_class: f32 = 0.0;
+
+// How far one class lifts geometry toward the eye, as a fraction of that
+// geometry's own distance from the camera. Being *relative* is the point: it
+// holds at any zoom and on any model scale.
+const DEPTH_CLASS_STEP: f32 = 1.0 / 4096.0;
+
+// How much of a class step a piece's slot may use. Well under 1, so a slot only
+// ever breaks ties inside its own class and can't promote a piece into the next.
+const DEPTH_SLOT_SPAN: f32 = 0.5;
+
+// Lifts a projected position toward the viewer by its class, so that coplanar
+// geometry resolves by what it *is* rather than by draw order or by whichever
+// polygon happened to win the depth test.
+//
+// The lift is a fixed fraction of view depth, not a fixed amount of NDC depth.
+// NDC depth goes as ~1/z, so with this projection (near 0.1, far 100+) the whole
+// model lands in the top few percent of the depth range: a constant NDC offset
+// that looks tiny is in fact a large part of the model's depth extent, and it
+// stays constant as the camera dollies out while that extent keeps shrinking —
+// so far-side geometry punches through. `1 - ndc_z` is proportional to
+// `near / z_view`, so scaling by it turns the offset back into a constant
+// relative step, small against real depth differences at every distance.
+fn _apply_depth_offset(clip: vec4<f32>) -> vec4<f32> {
+    let ndc_z = clip.z / clip.w;
+    let units = depth_class + piece.depth_slot * DEPTH_SLOT_SPAN;
+    let offset = units * DEPTH_CLASS_STEP * max(1.0 - ndc_z, 0.0);
+    return vec4<f32>(clip.xy, (ndc_z - offset) * clip.w, clip.w);
+}
+
+struct VertexInput {
+    @location(0) offset: vec2<f32>,
+    @location(1) v0_pos: vec3<f32>,
+    @location(2) v1_pos: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+};
+
+// Expands one highlighted stroke into a screen-space quad, like a label's
+// stroke but drawn thick, so that it stands out from the cut lines it covers.
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    let size = theme.sizes.line_width_thick;
+
+    let clip_v0 = camera.view_proj * piece.affine * vec4<f32>(in.v0_pos, 1.0);
+    let clip_v1 = camera.view_proj * piece.affine * vec4<f32>(in.v1_pos, 1.0);
+    let screen_v0 = viewport.dimensions * (0.5 * clip_v0.xy / clip_v0.w + 0.5);
+    let screen_v1 = viewport.dimensions * (0.5 * clip_v1.xy / clip_v1.w + 0.5);
+
+    let basis_x = screen_v1 - screen_v0;
+    let dir = normalize(basis_x);
+    let basis_y = vec2<f32>(-dir.y, dir.x);
+    let pt = screen_v0 + in.offset.x * basis_x
+        + (in.offset.x - 0.5) * dir * size
+        + (0.5 - in.offset.y) * basis_y * size;
+    let clip = mix(clip_v0, clip_v1, in.offset.x);
+    out.clip_position = _apply_depth_offset(
+        vec4<f32>(clip.w * (2.0 * pt / viewport.dimensions - 1.0), clip.z, clip.w));
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // What's hovered and selected never goes to print
+    if (theme.sizes.selection == 0.0) {
+        discard;
+    }
+    return theme.colors.element_active;
+}
@@ -9,6 +9,9 @@ struct ThemeSizes {
   // Whether selected / active elements are highlighted at all. Off for
   // print, which must not bake transient editor state into the page.
   selection: f32,
+  // How far artwork is printed past each piece's outline, in world units.
+  // Zero everywhere but print.
+  bleed: f32,
 };
 struct ThemeColors {
   background: vec4<f32>,
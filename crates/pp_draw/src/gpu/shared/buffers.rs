@@ -4,6 +4,8 @@ use wgpu::util::DeviceExt;
 pub struct SharedBuffers {
     pub rect: wgpu::Buffer,
     pub rect_outline: wgpu::Buffer,
+    /// A quad per segment of a flap outline, in order
+    pub flap_segments: wgpu::Buffer,
 }
 
 impl SharedBuffers {
@@ -19,6 +21,11 @@ impl SharedBuffers {
                 contents: bytemuck::bytes_of(&BUF_RECT_OUTLINE_CONTENTS),
                 usage: wgpu::BufferUsages::VERTEX,
             }),
+            flap_segments: device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("ctx.buf_flap_segments"),
+                contents: bytemuck::cast_slice(&flap_segments_contents()),
+                usage: wgpu::BufferUsages::VERTEX,
+            }),
         }
     }
 }
@@ -58,3 +65,12 @@ const BUF_RECT_OUTLINE_CONTENTS: [[f32; 2]; 24] = [
     [1.0, 0.0],
     [1.0, 1.0],
 ];
+
+/// The vertices `flap_segments` holds: a segment quad like the rect outline's
+/// for each segment of a flap outline. Each is filled as the column beneath it, or
+/// stroked as a line.
+pub const FLAP_SEGMENT_VERTS: u32 = (pp_core::mesh::flap::FLAP_OUTLINE_POINTS as u32 - 1) * 6;
+
+fn flap_segments_contents() -> Vec<[f32; 2]> {
+    BUF_RECT_OUTLINE_CONTENTS.iter().copied().cycle().take(FLAP_SEGMENT_VERTS as usize).collect()
+}
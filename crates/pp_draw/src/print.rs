@@ -142,8 +142,10 @@ pub struct PrintTarget {
     /// in the user's MSAA level.
     engine_ink: InkEngine,
     /// The theme the print pass renders under: strokes scaled to the page's
-    /// pixel density, and no selection highlighting.
+    /// pixel density, no selection highlighting, and the layout's bleed.
     settings: SettingsGPU,
+    /// How far artwork is printed past each piece's outline, in centimeters
+    bleed: f32,
 
     camera_buf: gpu::UniformBuf,
     bind_group: wgpu::BindGroup,
@@ -161,6 +163,7 @@ impl PrintTarget {
     pub fn new(
         ctx: &gpu::Context,
         page_size: &PageSize,
+        bleed: f32,
         theme: &Theme,
         dpi: f32,
     ) -> Result<Self, PrintError> {
@@ -251,6 +254,7 @@ impl PrintTarget {
             stroke_scale: dpi / PRINT_STROKE_REFERENCE_DPI,
             selection: false,
             ink: Some(PRINT_INK),
+            bleed,
         };
         Ok(Self {
             size,
@@ -265,6 +269,7 @@ impl PrintTarget {
             state: Rc::new(RefCell::new(PageState::Idle)),
             engine_ink: InkEngine::new(ctx, PRINT_SAMPLE_COUNT),
             settings: SettingsGPU::new_with_overrides(ctx, theme, &overrides),
+            bleed,
             camera_buf,
             bind_group,
         })
@@ -325,6 +330,21 @@ impl<'window> Renderer<'window> {
             );
             target.settings.bind(&mut render_pass);
             render_pass.set_bind_group(BindGroup::Viewport.value(), &target.bind_group, &[]);
+            // The bleed goes beneath everything, so whatever paper it runs under
+            // - its own piece's, or a neighbour's - covers it.
+            if target.bleed > 0.0 {
+                draw_cache.materials.iter().for_each(|(id, mat)| {
+                    mat.bind(&mut render_pass);
+                    draw_cache.meshes.values().for_each(|mesh| {
+                        target.engine_ink.draw_piece_bleed_for_material(
+                            ctx,
+                            &mut render_pass,
+                            mesh,
+                            &id,
+                        );
+                    });
+                });
+            }
             // Piece mode is what the printout is: fold annotations rather than
             // the editable wireframe, and no vertex handles.
             Self::draw_pieces(
@@ -10,6 +10,7 @@ pub mod layout;
 pub mod preferences;
 pub mod scene;
 pub mod state;
+pub mod timeline;
 pub mod tool;
 pub mod viewport;
 pub mod windowing;
@@ -186,11 +187,36 @@ impl Editor {
             });
     }
 
+    /// The view-projection of the first folding viewport's camera, for a square
+    /// image `size` pixels across, or `None` if no viewport is folding. Used to
+    /// render a save file's thumbnail from what the user is looking at.
+    pub fn folding_view_proj(&self, size: u32) -> Option<cgmath::Matrix4<f32>> {
+        use viewport::camera::Camera;
+        let dims = Dimensions { width: size as f32, height: size as f32 };
+        self.layout.viewports.values().find_map(|viewport| match &viewport.content {
+            viewport::ViewportContent::Folding(folding) => Some(folding.camera.view_proj(dims)),
+            viewport::ViewportContent::Cutting(_) => None,
+        })
+    }
+
     /// Advances every viewport camera's in-flight framing move. Called once per
     /// rendered frame with the elapsed milliseconds since the last one.
     pub fn tick_cameras(&mut self, dt_ms: f32) {
         self.layout.viewports.values_mut().for_each(|viewport| viewport.tick_camera(dt_ms));
     }
+
+    /// Advances the fold timeline, if one is open, and folds each piece to
+    /// where the playhead puts it. Called once per frame alongside
+    /// [`Self::tick_cameras`]; the fold follows any reordering or recutting
+    /// even while paused.
+    pub fn tick_timeline(&mut self, state: &mut pp_core::State, dt_ms: f32) {
+        let Some(timeline) = self.state.timeline.as_mut() else {
+            return;
+        };
+        if timeline.tick(state, dt_ms) {
+            self.is_dirty = true;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -411,4 +437,30 @@ mod tests {
         assert!(folding_camera(&editor).animation.is_some());
         assert!(cutting_camera(&editor).animation.is_some());
     }
+
+    /// The whole path playback runs: a playing timeline ticked each frame folds
+    /// the document's piece up, then stops at the end and tells JS.
+    #[test]
+    fn a_playing_timeline_folds_pieces_up_and_stops() {
+        use timeline::{Timeline, STEP_DURATION_MS};
+
+        let mut state = pp_core::State::default();
+        let m_id = state.meshes.insert(pp_core::mesh::Mesh::new_tri());
+        let root = FaceId::from_usize(0);
+        state.meshes[m_id].expand_piece(root).unwrap();
+
+        let mut editor = editor();
+        let mut timeline = Timeline::default();
+        timeline.play(state.assembly_step_count());
+        editor.state.timeline = Some(timeline);
+
+        editor.tick_timeline(&mut state, STEP_DURATION_MS / 2.0);
+        assert_eq!(state.meshes[m_id].pieces[&root].t, 0.5);
+        assert!(!editor.is_dirty);
+
+        editor.tick_timeline(&mut state, STEP_DURATION_MS);
+        assert_eq!(state.meshes[m_id].pieces[&root].t, 0.0);
+        assert!(editor.is_dirty, "stopping at the end should reach JS");
+        assert!(!editor.state.timeline.as_ref().unwrap().is_playing);
+    }
 }
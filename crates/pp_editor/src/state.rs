@@ -2,6 +2,8 @@ use serde::Serialize;
 use tsify::Tsify;
 use wasm_bindgen::prelude::*;
 
+use crate::timeline::Timeline;
+
 #[wasm_bindgen]
 #[derive(Default, Debug, Clone, PartialEq, PartialOrd, Serialize)]
 pub enum SelectionMode {
@@ -39,6 +41,12 @@ pub struct EditorState {
     pub select_tool: SelectTool,
     /// The fold-progress scalar, 0.0-1.0, driving the folding animation
     pub t: f32,
+    /// How many steps of assembly the folding viewport is stepped through to,
+    /// if it is: the pieces of those steps are folded up, and the rest lie flat
+    pub assembly_step: Option<usize>,
+    /// The fold timeline previewing assembly, if one is open: while it is, it
+    /// owns every piece's fold, as the assembly step-through does
+    pub timeline: Option<Timeline>,
 }
 
 impl Default for EditorState {
@@ -49,6 +57,8 @@ impl Default for EditorState {
             selection_mode: Default::default(),
             select_tool: Default::default(),
             t: 1.0,
+            assembly_step: None,
+            timeline: None,
         }
     }
 }
@@ -0,0 +1,160 @@
+//! A playable preview of how the document folds together: each piece folds up
+//! in turn, in its mesh's assembly order, as a playhead sweeps over them.
+//!
+//! Like the camera animations, nothing here reads the clock: callers hand in a
+//! frame delta, which comes from `requestAnimationFrame` by way of
+//! `App::update`. The playhead is measured in steps of assembly rather than
+//! time, so scrubbing to a position means the same thing however long each
+//! step plays for.
+
+use pp_core::{id::FaceId, MeshId, State};
+use serde::Serialize;
+use tsify::Tsify;
+
+/// How long each piece takes to fold up during playback, in milliseconds.
+pub const STEP_DURATION_MS: f32 = 800.0;
+
+/// Smoothstep: eases both into and out of each piece's fold, so consecutive
+/// pieces read as separate moves instead of one continuous sweep.
+fn ease_in_out(s: f32) -> f32 {
+    s * s * (3.0 - 2.0 * s)
+}
+
+/// The playhead of the fold timeline.
+#[derive(Debug, Clone, Default, PartialEq, Tsify, Serialize)]
+pub struct Timeline {
+    /// Whether the playhead is advancing on its own
+    pub is_playing: bool,
+    /// The playhead, in steps of assembly: at 2.5 the first two pieces are
+    /// folded up and the third is halfway there. Only sent to JS when playback
+    /// starts or stops; poll `get_timeline_position` to follow it.
+    pub position: f32,
+    /// The piece of each step, worked out when the timeline opens and again
+    /// whenever the pieces change under it
+    #[serde(skip)]
+    roots: Vec<(MeshId, FaceId)>,
+    /// The playhead and [`State::assembly_fingerprint`] as of the last fold,
+    /// so frames where neither moved leave the document alone
+    #[serde(skip)]
+    folded: Option<(f32, u64)>,
+}
+
+impl Timeline {
+    /// Starts playback, from the beginning if the playhead is already at the
+    /// end of a timeline `steps` long.
+    pub fn play(&mut self, steps: usize) {
+        if self.position >= steps as f32 {
+            self.position = 0.0;
+        }
+        self.is_playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.is_playing = false;
+    }
+
+    /// Moves the playhead to `position`, kept within a timeline `steps` long.
+    pub fn seek(&mut self, position: f32, steps: usize) {
+        self.position = position.clamp(0.0, steps as f32);
+    }
+
+    /// Advances playback by a frame delta, returning whether this reached the
+    /// end of a timeline `steps` long and stopped playing.
+    pub fn advance(&mut self, dt_ms: f32, steps: usize) -> bool {
+        // Steps can disappear under a playing timeline, e.g. by undoing a cut
+        self.seek(self.position, steps);
+        if !self.is_playing {
+            return false;
+        }
+        self.seek(self.position + dt_ms / STEP_DURATION_MS, steps);
+        if self.position >= steps as f32 {
+            self.is_playing = false;
+            return true;
+        }
+        false
+    }
+
+    /// Advances playback by a frame delta and folds each piece of `state` to
+    /// where the playhead puts it, returning whether playback reached the end.
+    /// The assembly order is only worked out again when the document's pieces
+    /// change, and nothing is folded unless the playhead or the document moved.
+    pub fn tick(&mut self, state: &mut State, dt_ms: f32) -> bool {
+        let fingerprint = state.assembly_fingerprint();
+        let changed = self.folded.is_none_or(|(_, folded)| folded != fingerprint);
+        if changed {
+            self.roots = state.assembly_roots();
+        }
+        let ended = self.advance(dt_ms, self.roots.len());
+        if changed || self.folded.is_none_or(|(position, _)| position != self.position) {
+            state.fold_assembly_steps(&self.roots, |step| self.t_at(step));
+            self.folded = Some((self.position, state.assembly_fingerprint()));
+        }
+        ended
+    }
+
+    /// The unfold `t` of the piece assembled at `step`: flat until the
+    /// playhead reaches it, then easing into fully folded over the step.
+    pub fn t_at(&self, step: usize) -> f32 {
+        1.0 - ease_in_out((self.position - step as f32).clamp(0.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pieces_fold_in_turn() {
+        let timeline = Timeline { position: 1.5, ..Default::default() };
+        assert_eq!(timeline.t_at(0), 0.0);
+        assert_eq!(timeline.t_at(1), 0.5);
+        assert_eq!(timeline.t_at(2), 1.0);
+    }
+
+    #[test]
+    fn playback_stops_at_the_end() {
+        let mut timeline = Timeline::default();
+        timeline.play(2);
+        assert!(!timeline.advance(STEP_DURATION_MS, 2));
+        assert_eq!(timeline.position, 1.0);
+        assert!(timeline.advance(STEP_DURATION_MS * 10.0, 2));
+        assert_eq!(timeline.position, 2.0);
+        assert!(!timeline.is_playing);
+        // Playing again from the end starts over
+        timeline.play(2);
+        assert_eq!(timeline.position, 0.0);
+    }
+
+    #[test]
+    fn a_paused_playhead_stays_put_within_the_steps() {
+        let mut timeline = Timeline::default();
+        timeline.seek(5.0, 3);
+        assert_eq!(timeline.position, 3.0);
+        assert!(!timeline.advance(STEP_DURATION_MS, 3));
+        assert_eq!(timeline.position, 3.0);
+        // Losing a step pulls the playhead back onto the timeline
+        timeline.advance(0.0, 2);
+        assert_eq!(timeline.position, 2.0);
+    }
+
+    #[test]
+    fn a_paused_timeline_refolds_pieces_changed_under_it() {
+        use pp_core::{id::Id, mesh::Mesh};
+
+        let mut state = State::default();
+        let m_id = state.meshes.insert(Mesh::new_tri());
+        let root = FaceId::from_usize(0);
+        state.meshes[m_id].expand_piece(root).unwrap();
+
+        let mut timeline = Timeline::default();
+        timeline.seek(0.5, 1);
+        timeline.tick(&mut state, STEP_DURATION_MS);
+        assert_eq!(state.meshes[m_id].pieces[&root].t, 0.5);
+
+        // Recutting lays the piece flat again, which the next frame undoes
+        state.meshes[m_id].pieces.get_mut(&root).unwrap().t = 1.0;
+        timeline.tick(&mut state, STEP_DURATION_MS);
+        assert_eq!(state.meshes[m_id].pieces[&root].t, 0.5);
+        assert_eq!(timeline.position, 0.5);
+    }
+}
@@ -8,8 +8,9 @@ pub enum ClientMessage {
     /// Join a document session
     Join { doc_id: String },
 
-    /// Send a command from the user interacting on the client
-    Command { command: CommandType, rollback: bool },
+    /// Send a command from the user interacting on the client. Boxed, as
+    /// commands carrying whole mesh geometries dwarf the other messages.
+    Command { command: Box<CommandType>, rollback: bool },
 
     /// Request the current full state
     RequestSync,
@@ -23,7 +24,7 @@ pub enum ServerMessage {
     Joined { doc_id: String, state: Vec<u8>, version: u64, client_count: usize },
 
     /// An operation from another client that should be applied
-    Command { client_id: String, command: CommandType, rollback: bool, version: u64 },
+    Command { client_id: String, command: Box<CommandType>, rollback: bool, version: u64 },
 
     /// Full state sync response
     StateSync { state: Vec<u8>, version: u64 },
@@ -33,4 +34,7 @@ pub enum ServerMessage {
 
     /// Client left the session
     ClientLeft { client_id: String, client_count: usize },
+
+    /// The document couldn't be opened, e.g. because its save file is corrupt
+    Refused { doc_id: String, reason: String },
 }
@@ -0,0 +1,328 @@
+//! A mesh's cuts, flaps and piece placements, without the mesh itself.
+//!
+//! Everything in a cut set is keyed by vertex positions rather than by the
+//! ids of the mesh it came from, so it can be shared for a public model
+//! without redistributing the model or its textures, and reapplied after the
+//! model is re-exported from a DCC tool, which is free to renumber every
+//! vertex, edge and face. Positions are the mesh's own, before its scale and
+//! transform, so moving or resizing a model in the editor doesn't break the
+//! match.
+
+use cgmath::{Matrix4, SquareMatrix};
+use pp_core::{
+    commands::{
+        make_cuts::MakeCutsCommand, transform_pieces::TransformPiecesCommand,
+        update_flaps::UpdateFlapsCommand,
+    },
+    id::{EdgeId, FaceId, Id, VertexId},
+    mesh::{cut::FlapPosition, Mesh},
+    CommandType, MeshId, State,
+};
+use serde::{Deserialize, Serialize};
+
+/// The cut set format version written by this build of the app
+pub const CUT_SET_VERSION: u32 = 1;
+
+/// How far apart two positions can be and still name the same vertex, in the
+/// mesh's own units. Loose enough to survive a round trip through a DCC tool's
+/// float formatting, tight enough not to merge neighbouring vertices.
+const MATCH_TOLERANCE: f32 = 1e-4;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CutSetError {
+    #[error("The cut set is not valid JSON: {0}")]
+    Malformed(String),
+    #[error("Cut set version {found} is newer than the supported version {supported}")]
+    UnsupportedVersion { found: u32, supported: u32 },
+    #[error("There is no mesh {0:?} to apply the cut set to")]
+    MissingMesh(MeshId),
+    #[error("No vertex of the mesh is at {0:?}")]
+    UnmatchedVertex([f32; 3]),
+    #[error("No edge of the mesh runs from {0:?} to {1:?}")]
+    UnmatchedEdge([f32; 3], [f32; 3]),
+    #[error("No face of the mesh has corners at {0:?}")]
+    UnmatchedFace(Vec<[f32; 3]>),
+}
+
+/// A single cut edge, and which side of it the flap is on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CutSetCut {
+    /// The edge's endpoints, in the edge's own vertex order, which is the
+    /// order `flap_position` is relative to
+    pub vertices: [[f32; 3]; 2],
+    pub flap_position: FlapPosition,
+}
+
+/// Where a piece was moved to, keyed by the corners of its root face
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CutSetPiece {
+    pub face: Vec<[f32; 3]>,
+    pub transform: [[f32; 4]; 4],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CutSet {
+    pub version: u32,
+    pub cuts: Vec<CutSetCut>,
+    pub pieces: Vec<CutSetPiece>,
+}
+
+impl CutSet {
+    /// Records the live cuts and pieces of a mesh
+    pub fn from_mesh(mesh: &Mesh) -> Self {
+        let cuts = mesh
+            .cuts
+            .iter()
+            .filter(|(_, cut)| !cut.is_dead)
+            .map(|(e_id, cut)| CutSetCut {
+                vertices: mesh[*e_id].v.map(|v| mesh[v].po),
+                flap_position: cut.flap_position,
+            })
+            .collect();
+        let pieces = mesh
+            .iter_pieces()
+            .map(|f_id| CutSetPiece {
+                face: mesh.iter_face_loops(*f_id).map(|l| mesh[mesh[l].v].po).collect(),
+                transform: mesh.pieces[f_id].transform.into(),
+            })
+            .collect();
+        Self { version: CUT_SET_VERSION, cuts, pieces }
+    }
+
+    pub fn to_json(&self) -> Result<String, CutSetError> {
+        serde_json::to_string_pretty(self).map_err(|err| CutSetError::Malformed(err.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, CutSetError> {
+        let cut_set: Self =
+            serde_json::from_str(json).map_err(|err| CutSetError::Malformed(err.to_string()))?;
+        if cut_set.version > CUT_SET_VERSION {
+            return Err(CutSetError::UnsupportedVersion {
+                found: cut_set.version,
+                supported: CUT_SET_VERSION,
+            });
+        }
+        Ok(cut_set)
+    }
+
+    /// Reapplies this cut set onto a mesh with matching geometry, returning the
+    /// commands it ran so the caller can add them to its history. Cuts the mesh
+    /// already has are kept. Nothing is changed unless every cut and piece of
+    /// the set finds its edge or face.
+    pub fn apply(&self, state: &mut State, m_id: MeshId) -> Result<Vec<CommandType>, CutSetError> {
+        let mesh = state.meshes.get(m_id).ok_or(CutSetError::MissingMesh(m_id))?;
+        let cuts = self
+            .cuts
+            .iter()
+            .map(|cut| match_cut(mesh, cut).map(|(e_id, flap)| ((m_id, e_id), flap)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let pieces = self
+            .pieces
+            .iter()
+            .map(|piece| match_face(mesh, &piece.face).map(|f_id| (f_id, piece.transform)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut commands = Vec::new();
+        let edges = cuts.iter().map(|(id, _)| *id).collect();
+        let roots: Vec<_> = pieces.iter().map(|(f_id, _)| (m_id, *f_id)).collect();
+        let make_cuts = MakeCutsCommand::from_edges(state, edges, &roots);
+        if !make_cuts.edges.is_empty() {
+            commands.push(CommandType::MakeCuts(make_cuts));
+        }
+
+        let mesh = &mut state.meshes[m_id];
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+        for ((m_id, e_id), flap_position) in cuts {
+            let current = mesh.cuts[&e_id].flap_position;
+            if u8::from(current) != u8::from(flap_position) {
+                mesh.set_cut_flap(e_id, flap_position);
+                before.push(((m_id, e_id), current));
+                after.push(((m_id, e_id), flap_position));
+            }
+        }
+        if !after.is_empty() {
+            commands.push(CommandType::UpdateFlaps(UpdateFlapsCommand { before, after }));
+        }
+
+        for (f_id, transform) in pieces {
+            // A root which didn't end up a piece has no transform to restore
+            if mesh[f_id].p != Some(f_id) {
+                continue;
+            }
+            let current = mesh.pieces[&f_id].transform;
+            let Some(delta) = current.invert().map(|inverse| Matrix4::from(transform) * inverse)
+            else {
+                continue;
+            };
+            if delta == Matrix4::identity() {
+                continue;
+            }
+            mesh.transform_piece(&f_id, delta);
+            commands.push(CommandType::TransformPieces(TransformPiecesCommand {
+                pieces: vec![(m_id, f_id)],
+                delta,
+            }));
+        }
+        Ok(commands)
+    }
+}
+
+/// Every vertex within `MATCH_TOLERANCE` of `po`. Unwelded meshes can have
+/// several, only some of which share an edge with the other endpoint.
+fn matching_verts(mesh: &Mesh, po: [f32; 3]) -> Vec<VertexId> {
+    mesh.verts.indices().map(VertexId::from_usize).filter(|v| near(mesh[*v].po, po)).collect()
+}
+
+fn near(a: [f32; 3], b: [f32; 3]) -> bool {
+    a.iter().zip(b).all(|(a, b)| (a - b).abs() <= MATCH_TOLERANCE)
+}
+
+/// Finds the edge a cut was made on, along with its flap position relative to
+/// the matched edge's own vertex order.
+fn match_cut(mesh: &Mesh, cut: &CutSetCut) -> Result<(EdgeId, FlapPosition), CutSetError> {
+    let [a, b] = cut.vertices;
+    let (v_as, v_bs) = (matching_verts(mesh, a), matching_verts(mesh, b));
+    if v_as.is_empty() {
+        return Err(CutSetError::UnmatchedVertex(a));
+    }
+    if v_bs.is_empty() {
+        return Err(CutSetError::UnmatchedVertex(b));
+    }
+    v_as.iter()
+        .flat_map(|v_a| v_bs.iter().map(move |v_b| (*v_a, *v_b)))
+        .find_map(|(v_a, v_b)| {
+            let e_id = mesh.query_edge(v_a, v_b)?;
+            // The flap is relative to the edge's vertex order, which the
+            // re-exported mesh is free to have reversed
+            let flap = match mesh[e_id].v[0] == v_a {
+                true => cut.flap_position,
+                false => cut.flap_position.opposite(),
+            };
+            Some((e_id, flap))
+        })
+        .ok_or(CutSetError::UnmatchedEdge(a, b))
+}
+
+/// Finds the face whose corners are at `corners`, in any rotation
+fn match_face(mesh: &Mesh, corners: &[[f32; 3]]) -> Result<FaceId, CutSetError> {
+    mesh.faces
+        .indices()
+        .map(FaceId::from_usize)
+        .find(|f_id| {
+            let positions: Vec<_> =
+                mesh.iter_face_loops(*f_id).map(|l| mesh[mesh[l].v].po).collect();
+            positions.len() == corners.len()
+                && corners.iter().all(|corner| positions.iter().any(|po| near(*po, *corner)))
+        })
+        .ok_or_else(|| CutSetError::UnmatchedFace(corners.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Matrix4, Vector3};
+    use pp_core::{commands::Command, id::EdgeId};
+
+    use super::*;
+
+    /// The cube's ring of cuts from the save tests, with one flap swapped and
+    /// the resulting piece moved, so there's something beyond the defaults to
+    /// carry across.
+    fn cut_cube() -> State {
+        let mut state = State::default();
+        let m_id = state.meshes.insert(Mesh::cut_cube());
+        let mesh = &mut state.meshes[m_id];
+        let (e_id, cut) = mesh.cuts.iter().next().map(|(e, c)| (*e, *c)).unwrap();
+        mesh.set_cut_flap(e_id, cut.flap_position.opposite());
+        let root = *mesh.iter_pieces().next().unwrap();
+        mesh.transform_piece(&root, Matrix4::from_translation(Vector3::new(3.0, 0.0, 0.0)));
+        state
+    }
+
+    fn flaps(mesh: &Mesh) -> Vec<(EdgeId, u8)> {
+        let mut flaps: Vec<_> = mesh
+            .cuts
+            .iter()
+            .filter(|(_, cut)| !cut.is_dead)
+            .map(|(e_id, cut)| (*e_id, u8::from(cut.flap_position)))
+            .collect();
+        flaps.sort();
+        flaps
+    }
+
+    fn mesh(state: &State) -> &Mesh {
+        state.meshes.values().next().unwrap()
+    }
+
+    #[test]
+    fn cut_sets_round_trip_through_json() {
+        let cut_set = CutSet::from_mesh(mesh(&cut_cube()));
+        let json = cut_set.to_json().unwrap();
+        let loaded = CutSet::from_json(&json).unwrap();
+        assert_eq!(loaded.cuts.len(), 6);
+        assert_eq!(loaded.pieces.len(), 1);
+    }
+
+    #[test]
+    fn applying_a_cut_set_reproduces_the_cuts_flaps_and_pieces() {
+        let source = cut_cube();
+        let cut_set = CutSet::from_mesh(mesh(&source));
+        let mut target = State::with_cube();
+        let m_id = target.meshes.keys().next().unwrap();
+        let commands = cut_set.apply(&mut target, m_id).unwrap();
+        assert_eq!(commands.len(), 3, "cuts, a flap swap and a piece move");
+
+        assert_eq!(flaps(mesh(&target)), flaps(mesh(&source)));
+        let source_pieces: Vec<_> = mesh(&source).iter_pieces().copied().collect();
+        let target_pieces: Vec<_> = mesh(&target).iter_pieces().copied().collect();
+        assert_eq!(target_pieces, source_pieces);
+        let root = target_pieces[0];
+        assert_eq!(mesh(&target).pieces[&root].transform, mesh(&source).pieces[&root].transform);
+    }
+
+    /// The commands returned are what was run, so rolling them back in reverse
+    /// leaves the mesh uncut.
+    #[test]
+    fn applied_cut_sets_can_be_undone() {
+        let cut_set = CutSet::from_mesh(mesh(&cut_cube()));
+        let mut target = State::with_cube();
+        let m_id = target.meshes.keys().next().unwrap();
+        let commands = cut_set.apply(&mut target, m_id).unwrap();
+        commands.iter().rev().for_each(|cmd| cmd.rollback(&mut target).unwrap());
+        assert!(flaps(mesh(&target)).is_empty());
+        assert_eq!(mesh(&target).iter_pieces().count(), 0);
+    }
+
+    /// A DCC tool may renumber the mesh on export; the cut set only cares
+    /// about where things are.
+    #[test]
+    fn cut_sets_match_edges_by_position_not_id() {
+        let cut_set = CutSet::from_mesh(mesh(&cut_cube()));
+        let mut target = State::with_cube();
+        let m_id = target.meshes.keys().next().unwrap();
+        // Reverse every cut's vertex order, as a renumbered export might
+        let mut reversed = cut_set.clone();
+        reversed.cuts.iter_mut().for_each(|cut| {
+            cut.vertices.reverse();
+            cut.flap_position = cut.flap_position.opposite();
+        });
+        reversed.apply(&mut target, m_id).unwrap();
+        assert_eq!(flaps(mesh(&target)), flaps(mesh(&cut_cube())));
+    }
+
+    #[test]
+    fn cut_sets_for_other_geometry_change_nothing() {
+        let mut cut_set = CutSet::from_mesh(mesh(&cut_cube()));
+        cut_set.cuts[3].vertices[1] = [9.0, 9.0, 9.0];
+        let mut target = State::with_cube();
+        let m_id = target.meshes.keys().next().unwrap();
+        assert!(matches!(cut_set.apply(&mut target, m_id), Err(CutSetError::UnmatchedVertex(_))));
+        assert!(flaps(mesh(&target)).is_empty());
+    }
+
+    #[test]
+    fn cut_sets_from_a_newer_app_are_refused() {
+        let json = format!(r#"{{ "version": {}, "cuts": [], "pieces": [] }}"#, CUT_SET_VERSION + 1);
+        assert!(matches!(CutSet::from_json(&json), Err(CutSetError::UnsupportedVersion { .. })));
+    }
+}
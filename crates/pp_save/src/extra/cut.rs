@@ -3,10 +3,16 @@ use gltf_json::{
     accessor::{self, ComponentType, GenericComponentType},
     Index,
 };
-use pp_core::mesh::cut::FlapPosition;
+use pp_core::mesh::{
+    cut::FlapPosition,
+    flap::{FlapSettings, FlapStyle},
+};
 use serde::{Deserialize, Serialize};
 
-use crate::standard::buffers::{self, AccessorOptions, GltfBufferBuilder};
+use crate::{
+    load::{LoadError, MeshLocation},
+    standard::buffers::{self, AccessorOptions, GltfBufferBuilder},
+};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct CutPrimitiveAttributes {
@@ -27,6 +33,30 @@ pub struct SerializableCut {
     pub flap_position: FlapPosition,
 }
 
+/// A cut's own flap settings, in place of its mesh's
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct CutFlapSettings {
+    /// The cut's index among the mesh's saved cuts
+    pub cut: u32,
+    pub settings: FlapSettings,
+}
+
+/// The style a cut's flaps are cut in, for cuts not using the default
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct CutFlapStyle {
+    /// The cut's index among the mesh's saved cuts
+    pub cut: u32,
+    pub style: FlapStyle,
+}
+
+/// The number a cut is labelled with
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct CutLabel {
+    /// The cut's index among the mesh's saved cuts
+    pub cut: u32,
+    pub label: u32,
+}
+
 /// Builds the GLTF accessors that encode "cut"s into a GLTF file's buffers
 pub fn save_cuts(
     builder: &mut GltfBufferBuilder,
@@ -72,25 +102,66 @@ pub fn save_cuts(
 pub fn load_cuts(
     accessors: &[Accessor],
     buffers: &[Data],
-    cuts: CutPrimitiveAttributes,
-) -> Vec<SerializableCut> {
-    let Some(vertices) = accessors
-        .get(cuts.vertices.value())
-        .and_then(|accessor| buffers::read_accessor::<[u32; 2]>(buffers, accessor).ok())
-    else {
-        return Vec::new();
-    };
-
-    // Load flap positions if available, otherwise use default
-    let flap_positions = accessors
-        .get(cuts.flap_position.value())
-        .and_then(|accessor| buffers::read_accessor::<u8>(buffers, accessor).ok())
-        .map(|positions| positions.into_iter().map(FlapPosition::from).collect())
-        .unwrap_or_else(|| vec![FlapPosition::FirstFace; vertices.len()]);
+    cuts: &CutPrimitiveAttributes,
+    mesh: &str,
+) -> Result<Vec<SerializableCut>, LoadError> {
+    let vertices: Vec<[u32; 2]> =
+        read_extra_accessor(accessors, buffers, cuts.vertices, mesh, "VERTICES")?;
+    let flap_positions: Vec<u8> =
+        read_extra_accessor(accessors, buffers, cuts.flap_position, mesh, "FLAP_POSITION")?;
+    if flap_positions.len() != vertices.len() {
+        return Err(LoadError::ExtrasLengthMismatch {
+            mesh: mesh.to_string(),
+            what: "cut flap positions",
+            expected: vertices.len(),
+            found: flap_positions.len(),
+        });
+    }
 
-    vertices
+    Ok(vertices
         .into_iter()
         .zip(flap_positions)
-        .map(|(vertices, flap_position)| SerializableCut { vertices, flap_position })
-        .collect()
+        .map(|(vertices, flap_position)| SerializableCut {
+            vertices,
+            flap_position: FlapPosition::from(flap_position),
+        })
+        .collect())
+}
+
+/// Checks that every cut's edge is between two vertices the mesh actually has
+pub fn validate_cuts(
+    cuts: &[SerializableCut],
+    mesh: &str,
+    vertex_count: usize,
+) -> Result<(), LoadError> {
+    for (i, cut) in cuts.iter().enumerate() {
+        if let Some(vertex) = cut.vertices.iter().copied().find(|v| *v as usize >= vertex_count) {
+            return Err(LoadError::CutEdgeOutOfRange {
+                mesh: mesh.to_string(),
+                cut: i,
+                vertex,
+                vertex_count,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Reads one of the accessors a papercraft extra points at, attributing any
+/// problem with it to the mesh the extra belongs to.
+pub(super) fn read_extra_accessor<T: bytemuck::Pod>(
+    accessors: &[Accessor],
+    buffers: &[Data],
+    index: Index<accessor::Accessor>,
+    mesh: &str,
+    attribute: &'static str,
+) -> Result<Vec<T>, LoadError> {
+    let accessor = accessors
+        .get(index.value())
+        .ok_or(LoadError::MissingAccessor { at: MeshLocation::mesh(mesh), attribute })?;
+    buffers::read_accessor::<T>(buffers, accessor).map_err(|source| LoadError::BadAccessor {
+        at: MeshLocation::mesh(mesh),
+        attribute,
+        source,
+    })
 }
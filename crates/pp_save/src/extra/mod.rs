@@ -1,10 +1,15 @@
+use pp_core::mesh::flap::FlapSettings;
 use serde::{Deserialize, Serialize};
 
-use crate::extra::{cut::CutPrimitiveAttributes, piece::PiecePrimitiveAttributes};
+use crate::extra::{
+    cut::CutPrimitiveAttributes, piece::PiecePrimitiveAttributes,
+    polygon::PolygonPrimitiveAttributes,
+};
 
 pub(super) mod cut;
 pub(super) mod page;
 pub(super) mod piece;
+pub(super) mod polygon;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct MeshExtras {
@@ -22,22 +27,65 @@ pub struct PapercraftMeshExtra {
     pub cuts: CutPrimitiveAttributes,
     /// Pieces in the 3D geometry, indicated by a "root" face
     pub pieces: PiecePrimitiveAttributes,
-    /// The mesh's own affine transform (translation + rotation, column-major).
-    /// Absent on save files written before this field existed.
+    /// Faces with more than three corners, saved as triangle fans. Absent when
+    /// every face is a triangle.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub transform: Option<[[f32; 4]; 4]>,
-    /// The mesh's uniform scale factor. Absent on save files written before
-    /// this field existed.
+    pub polygons: Option<PolygonPrimitiveAttributes>,
+    /// The mesh's own affine transform (translation + rotation, column-major)
+    pub transform: [[f32; 4]; 4],
+    /// The mesh's uniform scale factor
+    pub scale: f32,
+    /// The shape limits of the mesh's flaps. Absent in files from before they
+    /// could be changed, which get the defaults.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub scale: Option<f32>,
+    pub flap_settings: Option<FlapSettings>,
+    /// How thick the mesh's paper is, in world units. Absent for paper with no
+    /// thickness to compensate for.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paper_thickness: Option<f32>,
+    /// The cuts with their own flap shape. Absent when no cut has one.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cut_flaps: Vec<cut::CutFlapSettings>,
+    /// The cuts whose flaps aren't plain trapezoids. Absent when none are.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cut_styles: Vec<cut::CutFlapStyle>,
+    /// The number each labelled cut is labelled with. Absent in files from
+    /// before cuts were labelled, whose cuts are numbered afresh in the order
+    /// of the saved cuts as they load, as are any cuts missing here.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cut_labels: Vec<cut::CutLabel>,
+    /// The order the user put the mesh's pieces in to assemble them, as
+    /// indices into `pieces`. Absent when it follows the suggested order.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub assembly: Vec<u32>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RootExtras {
+    /// Document-wide papercraft data, including the save format version
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub papercraft: Option<PapercraftExtra>,
 }
 
 /// Custom data for the papercraft unfolding system, stored inside the save file
 /// GLTF under the `extras` attribute at the `root`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct PapercraftExtra {
+    /// The save format version this file was written in. See `crate::migrate`.
+    pub version: u32,
+    /// A rendered preview of the document, among the file's images. See
+    /// `crate::thumbnail`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<gltf_json::Index<gltf_json::Image>>,
     /// An array of pages (used in the print layout).
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
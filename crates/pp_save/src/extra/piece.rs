@@ -5,7 +5,13 @@ use gltf_json::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::standard::buffers::{self, AccessorOptions, GltfBufferBuilder};
+use std::collections::HashSet;
+
+use crate::{
+    extra::cut::read_extra_accessor,
+    load::LoadError,
+    standard::buffers::{AccessorOptions, GltfBufferBuilder},
+};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct PiecePrimitiveAttributes {
@@ -72,28 +78,57 @@ pub fn save_pieces(
 pub fn load_pieces(
     accessors: &[Accessor],
     buffers: &[Data],
-    pieces: PiecePrimitiveAttributes,
-) -> Vec<SerializablePiece> {
-    let Some(face_indices) = accessors
-        .get(pieces.face.value())
-        .and_then(|accessor| buffers::read_accessor::<u32>(buffers, accessor).ok())
-    else {
-        return Vec::new();
-    };
-
-    let Some(transforms) = accessors
-        .get(pieces.transform.value())
-        .and_then(|accessor| buffers::read_accessor::<[[f32; 4]; 4]>(buffers, accessor).ok())
-    else {
-        return Vec::new();
-    };
+    pieces: &PiecePrimitiveAttributes,
+    mesh: &str,
+) -> Result<Vec<SerializablePiece>, LoadError> {
+    let face_indices: Vec<u32> =
+        read_extra_accessor(accessors, buffers, pieces.face, mesh, "FACE")?;
+    let transforms: Vec<[[f32; 4]; 4]> =
+        read_extra_accessor(accessors, buffers, pieces.transform, mesh, "TRANSFORM")?;
+    if transforms.len() != face_indices.len() {
+        return Err(LoadError::ExtrasLengthMismatch {
+            mesh: mesh.to_string(),
+            what: "piece transforms",
+            expected: face_indices.len(),
+            found: transforms.len(),
+        });
+    }
 
-    face_indices
+    Ok(face_indices
         .into_iter()
         .zip(transforms)
         .map(|(face_index, t)| SerializablePiece {
             face_index,
             transform: cgmath::Matrix4::from(t),
         })
-        .collect()
+        .collect())
+}
+
+/// Checks that every piece is rooted at a face the mesh actually has. A face is
+/// saved as the index of its first corner, so the root has to be in range *and*
+/// start one of the faces in `face_starts`.
+pub fn validate_pieces(
+    pieces: &[SerializablePiece],
+    mesh: &str,
+    vertex_count: usize,
+    face_starts: &HashSet<u32>,
+) -> Result<(), LoadError> {
+    for (i, piece) in pieces.iter().enumerate() {
+        if piece.face_index as usize >= vertex_count {
+            return Err(LoadError::PieceFaceOutOfRange {
+                mesh: mesh.to_string(),
+                piece: i,
+                face: piece.face_index,
+                vertex_count,
+            });
+        }
+        if !face_starts.contains(&piece.face_index) {
+            return Err(LoadError::PieceFaceInvalid {
+                mesh: mesh.to_string(),
+                piece: i,
+                face: piece.face_index,
+            });
+        }
+    }
+    Ok(())
 }
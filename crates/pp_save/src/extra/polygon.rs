@@ -0,0 +1,134 @@
+use gltf::{buffer::Data, Accessor};
+use gltf_json::{
+    accessor::{self, ComponentType, GenericComponentType},
+    Index,
+};
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+
+use crate::{
+    extra::cut::read_extra_accessor,
+    load::LoadError,
+    standard::buffers::{AccessorOptions, GltfBufferBuilder},
+};
+
+/// GLTF primitives are triangles, so faces with more than three corners are
+/// saved as a fan of triangles around their first corner. This records which
+/// fans to merge back into one face on load.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PolygonPrimitiveAttributes {
+    /// The GLTF index of each polygon's first corner, and its number of corners
+    #[serde(rename = "CORNERS")]
+    corners: Index<accessor::Accessor>,
+}
+
+/// Builds the GLTF accessor that encodes polygons into a GLTF file's buffers.
+/// `polygons` holds `[first corner, corner count]` for each face of more than
+/// three corners.
+pub fn save_polygons(
+    builder: &mut GltfBufferBuilder,
+    polygons: Vec<[u32; 2]>,
+) -> PolygonPrimitiveAttributes {
+    use gltf_json::buffer;
+    use gltf_json::validation::Checked::Valid;
+
+    PolygonPrimitiveAttributes {
+        corners: builder.add_accessor(
+            &polygons,
+            AccessorOptions {
+                component_type: Valid(GenericComponentType(ComponentType::U32)),
+                type_: Valid(accessor::Type::Vec2),
+                target: Some(Valid(buffer::Target::ArrayBuffer)),
+                normalized: false,
+                min: None,
+                max: None,
+            },
+        ),
+    }
+}
+
+/// Reads polygons from a GLTF file's buffers, as corner counts keyed by the
+/// GLTF index of each polygon's first corner
+pub fn load_polygons(
+    accessors: &[Accessor],
+    buffers: &[Data],
+    polygons: &PolygonPrimitiveAttributes,
+    mesh: &str,
+) -> Result<HashMap<u32, u32>, LoadError> {
+    let corners: Vec<[u32; 2]> =
+        read_extra_accessor(accessors, buffers, polygons.corners, mesh, "CORNERS")?;
+    Ok(corners.into_iter().map(|[first, count]| (first, count)).collect())
+}
+
+/// Regroups a primitive's triangles into faces, as lists of GLTF indices in
+/// winding order. Triangles which start a polygon in `polygons` take the next
+/// `count - 3` triangles with them, which must be the rest of its fan; every
+/// other triangle is a face of its own.
+pub fn group_faces(
+    indices: &[u32],
+    polygons: &HashMap<u32, u32>,
+    mesh: &str,
+) -> Result<Vec<Vec<u32>>, LoadError> {
+    let malformed = |first: u32, reason: &'static str| LoadError::MalformedPolygon {
+        mesh: mesh.to_string(),
+        first,
+        reason,
+    };
+    let mut faces = Vec::new();
+    let mut triangles = indices.chunks_exact(3);
+    while let Some(tri) = triangles.next() {
+        let mut face = tri.to_vec();
+        let count = polygons.get(&tri[0]).copied().unwrap_or(3);
+        if count < 3 {
+            return Err(malformed(tri[0], "has fewer than three corners"));
+        }
+        for _ in 3..count {
+            let next = triangles.next().ok_or(malformed(tri[0], "runs past its primitive"))?;
+            if next[0] != tri[0] || Some(&next[1]) != face.last() {
+                return Err(malformed(tri[0], "isn't saved as a fan of triangles"));
+            }
+            face.push(next[2]);
+        }
+        faces.push(face);
+    }
+    Ok(faces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triangles_without_polygons_are_faces_of_their_own() {
+        let faces = group_faces(&[0, 1, 2, 3, 4, 5], &HashMap::new(), "test").unwrap();
+        assert_eq!(faces, vec![vec![0, 1, 2], vec![3, 4, 5]]);
+    }
+
+    #[test]
+    fn fans_merge_back_into_polygons() {
+        let polygons = HashMap::from([(0, 5)]);
+        let indices = [0, 1, 2, 0, 2, 3, 0, 3, 4, 5, 6, 7];
+        let faces = group_faces(&indices, &polygons, "test").unwrap();
+        assert_eq!(faces, vec![vec![0, 1, 2, 3, 4], vec![5, 6, 7]]);
+    }
+
+    #[test]
+    fn a_polygon_which_is_not_a_fan_is_refused() {
+        let polygons = HashMap::from([(0, 4)]);
+        let indices = [0, 1, 2, 0, 3, 4];
+        assert!(matches!(
+            group_faces(&indices, &polygons, "test"),
+            Err(LoadError::MalformedPolygon { first: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn a_polygon_running_off_its_primitive_is_refused() {
+        let polygons = HashMap::from([(0, 4)]);
+        assert!(matches!(
+            group_faces(&[0, 1, 2], &polygons, "test"),
+            Err(LoadError::MalformedPolygon { first: 0, .. })
+        ));
+    }
+}
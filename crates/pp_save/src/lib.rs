@@ -1,33 +1,146 @@
 use std::io;
 
+use extra::{PapercraftExtra, RootExtras};
 use gltf::Gltf;
+use load::LoadError;
+use save::SaveError;
+use serde_json::value::RawValue;
 
 mod extra;
 mod standard;
 
+pub mod cut_set;
 pub mod load;
+pub mod migrate;
+pub mod pattern;
 pub mod pdf;
 pub mod save;
+pub mod thumbnail;
 
 /// A GLTF file with a `papercraft` extension containing the app state
 pub struct SaveFile(gltf::Gltf);
 
 impl SaveFile {
-    /// Validates that the current save file matches the app state schema
-    pub fn validate(&self) -> anyhow::Result<()> {
+    /// Validates that the current save file matches the app state schema.
+    ///
+    /// This checks everything `Loadable::load` would otherwise trip over
+    /// part-way through: the accessors each primitive needs, its index type and
+    /// range, the formats of its images, and the papercraft extras against the
+    /// geometry they point into.
+    pub fn validate(&self) -> Result<(), LoadError> {
+        let buffers = gltf::import_buffers(&self.0.document, None, self.0.blob.clone())
+            .map_err(|err| LoadError::FailedToLoadBuffers(err.to_string()))?;
+        for (i, gltf_image) in self.0.images().enumerate() {
+            standard::image::check_image(&gltf_image, i)?;
+        }
+        let accessors: Vec<_> = self.0.document.accessors().collect();
+        for gltf_mesh in self.0.meshes() {
+            standard::mesh::validate_mesh(&gltf_mesh, &accessors, &buffers)?;
+        }
         Ok(())
     }
 
+    /// Runs any migrations needed to bring the papercraft extras of an older
+    /// save file up to the current format version.
+    fn upgraded(self) -> Result<Self, LoadError> {
+        let malformed = |err: &dyn std::fmt::Display| LoadError::Malformed(err.to_string());
+        let mut json = serde_json::to_value(self.0.as_json()).map_err(|err| malformed(&err))?;
+        if migrate::migrate(&mut json)? == migrate::FORMAT_VERSION {
+            return Ok(self);
+        }
+        let root = serde_json::from_value(json).map_err(|err| malformed(&err))?;
+        let document = gltf::Document::from_json(root).map_err(|err| malformed(&err))?;
+        Ok(Self(Gltf { document, blob: self.0.blob }))
+    }
+
     /// Validates and interprets a GLTF / GLB as a `SaveFile`
-    pub fn from_reader<R>(reader: R) -> anyhow::Result<Self>
+    pub fn from_reader<R>(reader: R) -> Result<Self, LoadError>
     where
         R: io::Read + io::Seek,
     {
-        let save = Self(Gltf::from_reader(reader)?);
+        let gltf =
+            Gltf::from_reader(reader).map_err(|err| LoadError::Malformed(err.to_string()))?;
+        let save = Self(gltf).upgraded()?;
         save.validate()?;
         Ok(save)
     }
 
+    /// The document-wide papercraft extras, if the file has any
+    fn root_extras(&self) -> Option<PapercraftExtra> {
+        let extras = self.0.as_json().extras.as_ref()?;
+        serde_json::from_str::<RootExtras>(extras.get()).ok()?.papercraft
+    }
+
+    /// The index of the image holding the document's thumbnail, if it has one
+    pub(crate) fn thumbnail_index(&self) -> Option<usize> {
+        self.root_extras()?.thumbnail.map(|index| index.value())
+    }
+
+    /// The PNG preview saved with the document, if it has one. This reads the
+    /// image without loading anything else, so it's cheap enough for a list of
+    /// documents.
+    pub fn thumbnail(&self) -> Option<Vec<u8>> {
+        let image = self.0.images().nth(self.thumbnail_index()?)?;
+        // Thumbnails are written as data URIs, so the buffers are only read
+        // for one which something else put in a buffer view
+        let buffers = match image.source() {
+            gltf::image::Source::View { .. } => {
+                gltf::import_buffers(&self.0.document, None, self.0.blob.clone()).ok()?
+            }
+            gltf::image::Source::Uri { .. } => Vec::new(),
+        };
+        standard::image::encoded_image_data(&image, &buffers).ok()
+    }
+
+    /// Reads just the PNG preview out of a GLTF / GLB, if it has one. Unlike
+    /// `from_reader`, nothing is upgraded or validated, so a document which
+    /// wouldn't open here can still be shown in a list.
+    pub fn read_thumbnail<R>(reader: R) -> Result<Option<Vec<u8>>, LoadError>
+    where
+        R: io::Read + io::Seek,
+    {
+        let gltf =
+            Gltf::from_reader(reader).map_err(|err| LoadError::Malformed(err.to_string()))?;
+        Ok(Self(gltf).thumbnail())
+    }
+
+    /// Attaches a PNG preview of the document, replacing any it already had.
+    /// See `thumbnail::Thumbnail` for rendering one.
+    pub fn with_thumbnail(self, png: &[u8]) -> Result<Self, SaveError> {
+        let mut root = self.0.as_json().clone();
+        let mut papercraft = self.root_extras().unwrap_or(PapercraftExtra {
+            version: migrate::FORMAT_VERSION,
+            thumbnail: None,
+            pages: Default::default(),
+        });
+        let image = gltf_json::Image {
+            name: Some("Thumbnail".to_string()),
+            uri: Some(standard::image::png_data_uri(png)),
+            mime_type: Some(gltf_json::image::MimeType("image/png".to_string())),
+            buffer_view: None,
+            extensions: Default::default(),
+            extras: Default::default(),
+        };
+        let index = match papercraft.thumbnail {
+            Some(index) if index.value() < root.images.len() => {
+                root.images[index.value()] = image;
+                index
+            }
+            _ => {
+                root.images.push(image);
+                gltf_json::Index::new(root.images.len() as u32 - 1)
+            }
+        };
+        papercraft.thumbnail = Some(index);
+        let extras = serde_json::to_string(&RootExtras { papercraft: Some(papercraft) })
+            .and_then(RawValue::from_string)
+            .map_err(|err| SaveError::Json(err.to_string()))?;
+        root.extras = Some(extras);
+        let document =
+            gltf::Document::from_json(root).map_err(|err| SaveError::Json(err.to_string()))?;
+        Ok(Self(Gltf { document, blob: self.0.blob }))
+    }
+
     /// Returns the GLTF JSON blob as a JSON string
     pub fn to_json_string(&self) -> std::result::Result<std::string::String, gltf_json::Error> {
         self.0.as_json().to_string()
@@ -40,7 +153,7 @@ impl SaveFile {
         self.0.as_json().to_string_pretty()
     }
 
-    fn to_glb(&'_ self) -> anyhow::Result<gltf::binary::Glb<'_>> {
+    fn to_glb(&'_ self) -> Result<gltf::binary::Glb<'_>, SaveError> {
         use std::borrow::Cow;
         Ok(gltf::binary::Glb {
             header: gltf::binary::Header {
@@ -48,13 +161,497 @@ impl SaveFile {
                 version: 2,
                 length: 0, // Will be calculated by to_writer
             },
-            json: Cow::Owned(self.0.as_json().to_string()?.into_bytes()),
+            json: Cow::Owned(
+                self.0
+                    .as_json()
+                    .to_string()
+                    .map_err(|err| SaveError::Json(err.to_string()))?
+                    .into_bytes(),
+            ),
             bin: self.0.blob.as_ref().map(|blob| Cow::Borrowed(blob.as_slice())),
         })
     }
 
     /// Exports the save file as a GLB binary blob
-    pub fn to_binary(&self) -> anyhow::Result<Vec<u8>> {
-        Ok(self.to_glb()?.to_vec()?)
+    pub fn to_binary(&self) -> Result<Vec<u8>, SaveError> {
+        self.to_glb()?.to_vec().map_err(|err| SaveError::Glb(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use pp_core::{
+        id::{FaceId, Id, VertexId},
+        mesh::cut::CutUpdate,
+        State,
+    };
+
+    use super::*;
+    use crate::{
+        load::{ImportOptions, Loadable},
+        save::Saveable,
+    };
+
+    /// A cube cut open along a ring, so that its save carries both cuts and
+    /// pieces in its extras.
+    fn cut_cube() -> State {
+        let mut state = State::default();
+        state.meshes.insert(pp_core::mesh::Mesh::cut_cube());
+        state
+    }
+
+    /// Re-encodes a save as a GLB after letting `edit` tamper with its JSON.
+    fn tampered(state: &State, edit: impl FnOnce(&mut serde_json::Value)) -> Vec<u8> {
+        tampered_save(&state.save().unwrap(), edit)
+    }
+
+    fn tampered_save(save: &SaveFile, edit: impl FnOnce(&mut serde_json::Value)) -> Vec<u8> {
+        let mut json = serde_json::to_value(save.0.as_json()).unwrap();
+        edit(&mut json);
+        let glb = gltf::binary::Glb {
+            header: gltf::binary::Header { magic: *b"glTF", version: 2, length: 0 },
+            json: serde_json::to_vec(&json).unwrap().into(),
+            bin: save.0.blob.as_deref().map(Into::into),
+        };
+        glb.to_vec().unwrap()
+    }
+
+    fn open(bytes: Vec<u8>) -> Result<SaveFile, LoadError> {
+        SaveFile::from_reader(Cursor::new(bytes))
+    }
+
+    #[test]
+    fn a_saved_document_validates_and_loads() {
+        let state = cut_cube();
+        let bytes = state.save().unwrap().to_binary().unwrap();
+        let loaded = State::load(open(bytes).unwrap()).unwrap();
+        let mesh = loaded.meshes.values().next().unwrap();
+        assert_eq!(mesh.cuts.len(), 6);
+        assert!(mesh.iter_pieces().next().is_some(), "the cut cube should have pieces");
+    }
+
+    /// The mesh's flap settings and a cut's own come back as they were saved
+    #[test]
+    fn flap_settings_survive_a_round_trip() {
+        use pp_core::mesh::flap::{FlapSettings, FlapStyle};
+
+        let mut state = cut_cube();
+        let mesh = state.meshes.values_mut().next().unwrap();
+        let thick = FlapSettings { height: 1.0, ..Default::default() };
+        let tiny = FlapSettings { height: 0.1, min_base: 0.5, ..Default::default() };
+        mesh.flap_settings = thick;
+        let e_id = mesh.query_edge(VertexId::from_usize(0), VertexId::from_usize(4)).unwrap();
+        mesh.set_cut_flap_settings(e_id, Some(tiny));
+        mesh.set_cut_flap_style(e_id, FlapStyle::Zigzag);
+
+        let bytes = state.save().unwrap().to_binary().unwrap();
+        let loaded = State::load(open(bytes).unwrap()).unwrap();
+        let mesh = loaded.meshes.values().next().unwrap();
+        assert_eq!(mesh.flap_settings, thick);
+        let e_id = mesh.query_edge(VertexId::from_usize(0), VertexId::from_usize(4)).unwrap();
+        assert_eq!(mesh.cut_flap_settings(e_id), tiny);
+        assert_eq!(mesh.cuts.values().filter(|cut| cut.flap.is_some()).count(), 1);
+        assert_eq!(mesh.cut_flap_style(e_id), FlapStyle::Zigzag);
+        assert_eq!(mesh.cuts.values().filter(|cut| cut.style != FlapStyle::default()).count(), 1);
+    }
+
+    #[test]
+    fn paper_thickness_survives_a_round_trip() {
+        let mut state = cut_cube();
+        state.meshes.values_mut().next().unwrap().paper_thickness = 0.02;
+
+        let bytes = state.save().unwrap().to_binary().unwrap();
+        let loaded = State::load(open(bytes).unwrap()).unwrap();
+        assert_eq!(loaded.meshes.values().next().unwrap().paper_thickness, 0.02);
+    }
+
+    /// A reordered assembly comes back in the order it was put in
+    #[test]
+    fn assembly_order_survives_a_round_trip() {
+        let mut mesh = pp_core::mesh::Mesh::cut_quad_cube();
+        // The ring of sides is suggested first, so this puts it last
+        mesh.assembly = mesh.suggested_assembly_order().into_iter().rev().collect();
+        let mut state = State::default();
+        state.meshes.insert(mesh);
+
+        let bytes = state.save().unwrap().to_binary().unwrap();
+        let loaded = State::load(open(bytes).unwrap()).unwrap();
+        let mesh = loaded.meshes.values().next().unwrap();
+        assert_eq!(mesh.assembly.len(), 3);
+        let sizes: Vec<usize> = mesh
+            .assembly_order()
+            .iter()
+            .map(|root| mesh.iter_connected_faces(*root).count())
+            .collect();
+        assert_eq!(sizes, vec![1, 1, 4]);
+    }
+
+    #[test]
+    fn cut_labels_survive_a_round_trip() {
+        let mut state = cut_cube();
+        let mesh = state.meshes.values_mut().next().unwrap();
+        let e_id = mesh.query_edge(VertexId::from_usize(0), VertexId::from_usize(4)).unwrap();
+        mesh.set_cut_label(e_id, 42);
+        let mut labels: Vec<_> = mesh.cuts.values().map(|cut| cut.label).collect();
+
+        let bytes = state.save().unwrap().to_binary().unwrap();
+        let loaded = State::load(open(bytes).unwrap()).unwrap();
+        let mesh = loaded.meshes.values().next().unwrap();
+        let e_id = mesh.query_edge(VertexId::from_usize(0), VertexId::from_usize(4)).unwrap();
+        assert_eq!(mesh.cut_label(e_id), Some(42));
+        let mut loaded_labels: Vec<_> = mesh.cuts.values().map(|cut| cut.label).collect();
+        loaded_labels.sort();
+        labels.sort();
+        assert_eq!(loaded_labels, labels);
+    }
+
+    /// Each label loads on its own, so one cut missing its label costs the
+    /// others nothing
+    #[test]
+    fn cut_labels_load_without_every_cut_labelled() {
+        let mut state = cut_cube();
+        let mesh = state.meshes.values_mut().next().unwrap();
+        let e_id = mesh.query_edge(VertexId::from_usize(0), VertexId::from_usize(4)).unwrap();
+        mesh.set_cut_label(e_id, 42);
+        let unlabelled = *mesh.cuts.keys().find(|other| **other != e_id).unwrap();
+        mesh.cuts.get_mut(&unlabelled).unwrap().label = None;
+
+        let bytes = state.save().unwrap().to_binary().unwrap();
+        let loaded = State::load(open(bytes).unwrap()).unwrap();
+        let mesh = loaded.meshes.values().next().unwrap();
+        let e_id = mesh.query_edge(VertexId::from_usize(0), VertexId::from_usize(4)).unwrap();
+        assert_eq!(mesh.cut_label(e_id), Some(42));
+        assert!(mesh.cuts.values().all(|cut| cut.label.is_some()));
+    }
+
+    /// A cube of quads cut open into a net, with its piece moved, so that the
+    /// polygons have to come back with the cuts and piece still on them.
+    #[test]
+    fn quad_faces_survive_a_round_trip() {
+        let mut state = State::default();
+        let mut mesh = pp_core::mesh::Mesh::new_quad_cube();
+        for (a, b) in [(4, 5), (5, 6), (6, 7), (0, 4), (1, 5), (2, 6), (3, 7)] {
+            let e_id = mesh.query_edge(VertexId::from_usize(a), VertexId::from_usize(b)).unwrap();
+            mesh.make_cut(e_id, CutUpdate::PiecesAndFlaps);
+        }
+        let root = *mesh.iter_pieces().next().unwrap();
+        mesh.pieces.get_mut(&root).unwrap().transform.w.x = 4.0;
+        state.meshes.insert(mesh);
+
+        let bytes = state.save().unwrap().to_binary().unwrap();
+        let loaded = State::load(open(bytes).unwrap()).unwrap();
+        let mesh = loaded.meshes.values().next().unwrap();
+        assert_eq!(mesh.faces.num_elements(), 6);
+        assert!(mesh.faces.indices().all(|f| mesh.iter_face_loops(Id::from_usize(f)).count() == 4));
+        assert_eq!(mesh.cuts.len(), 7);
+        let roots: Vec<_> = mesh.iter_pieces().collect();
+        assert_eq!(roots.len(), 1);
+        assert_eq!(mesh.pieces[roots[0]].transform.w.x, 4.0);
+    }
+
+    /// Skipping the first triangle of the indices leaves the first polygon's
+    /// fan starting at the wrong corner, which should be caught rather than
+    /// stitched into a face that was never saved.
+    #[test]
+    fn a_polygon_which_is_not_a_fan_is_refused() {
+        let mut state = State::default();
+        state.meshes.insert(pp_core::mesh::Mesh::new_quad_cube());
+        let bytes = tampered(&state, |json| {
+            let i = json["meshes"][0]["primitives"][0]["indices"].as_u64().unwrap() as usize;
+            let indices = &mut json["accessors"][i];
+            indices["byteOffset"] = 12.into();
+            indices["count"] = (indices["count"].as_u64().unwrap() - 3).into();
+        });
+        assert!(matches!(open(bytes), Err(LoadError::MalformedPolygon { first: 0, .. })));
+    }
+
+    fn document(name: &str) -> Vec<u8> {
+        std::fs::read(format!("{}/../../documents/{name}", env!("CARGO_MANIFEST_DIR"))).unwrap()
+    }
+
+    fn version(save: &SaveFile) -> u32 {
+        migrate::version_of(&serde_json::to_value(save.0.as_json()).unwrap()).unwrap()
+    }
+
+    /// A plain Blender export has no papercraft extras at all, which is fine:
+    /// it's upgraded from version 0 like any other unversioned file.
+    #[test]
+    fn a_plain_gltf_export_loads() {
+        let save = open(document("Rock.glb")).unwrap();
+        assert_eq!(version(&save), migrate::FORMAT_VERSION);
+        let loaded = State::load(save).unwrap();
+        assert_eq!(loaded.meshes.len(), 1);
+    }
+
+    /// Without papercraft extras, a triangulated cube is taken for an export
+    /// from elsewhere and its squares come back as quads.
+    #[test]
+    fn a_plain_triangulated_export_is_merged_into_polygons() {
+        let bytes = tampered(&State::with_cube(), |json| {
+            json["meshes"][0]["extras"] = serde_json::Value::Null;
+        });
+        let loaded = State::load(open(bytes).unwrap()).unwrap();
+        let mesh = loaded.meshes.values().next().unwrap();
+        assert_eq!(mesh.faces.num_elements(), 6);
+        assert_eq!(mesh.edges.num_elements(), 12);
+    }
+
+    /// A square of two triangles whose shared edge was exported a hair apart,
+    /// plus a sliver with all three corners in a line, as a plain import
+    fn jittered_square() -> Vec<u8> {
+        use pp_core::mesh::face::FaceDescriptor;
+
+        let mut mesh = pp_core::mesh::Mesh::new("JITTER".to_string());
+        let v: Vec<_> = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]]
+            .into_iter()
+            .chain([[1.0, 1.0, 1e-6], [0.0, 1.0, 0.0], [1e-6, 0.0, 0.0]])
+            .chain([[2.0, 0.0, 0.0], [3.0, 0.0, 0.0], [4.0, 0.0, 0.0]])
+            .map(|p| mesh.add_vertex(p))
+            .collect();
+        mesh.add_face(&[v[0], v[1], v[2]], &FaceDescriptor::default());
+        mesh.add_face(&[v[5], v[3], v[4]], &FaceDescriptor::default());
+        mesh.add_face(&[v[6], v[7], v[8]], &FaceDescriptor::default());
+        let mut state = State::default();
+        state.meshes.insert(mesh);
+        tampered(&state, |json| json["meshes"][0]["extras"] = serde_json::Value::Null)
+    }
+
+    #[test]
+    fn a_plain_import_is_welded_and_cleaned_up() {
+        let save = open(jittered_square()).unwrap();
+        let (loaded, report) = State::load_with_options(save, &ImportOptions::default()).unwrap();
+        let mesh = loaded.meshes.values().next().unwrap();
+        // The two triangles are joined along their diagonal, and merged
+        assert_eq!(mesh.faces.num_elements(), 1);
+        assert_eq!(mesh.verts.num_elements(), 4);
+        assert_eq!(report.meshes.len(), 1);
+        assert_eq!(report.meshes[0].welded_verts, 2);
+        assert_eq!(report.meshes[0].dropped_faces, 1);
+    }
+
+    #[test]
+    fn a_plain_import_can_be_loaded_as_it_is() {
+        let options = ImportOptions {
+            weld_distance: 0.0,
+            remove_degenerate_faces: false,
+            ..Default::default()
+        };
+        let save = open(jittered_square()).unwrap();
+        let (loaded, report) = State::load_with_options(save, &options).unwrap();
+        let mesh = loaded.meshes.values().next().unwrap();
+        assert_eq!(mesh.faces.num_elements(), 3);
+        assert!(report.meshes.is_empty());
+        // The sliver loads with a zero normal, rather than a NaN one
+        assert!(mesh
+            .faces
+            .indices()
+            .all(|f| !mesh[FaceId::from_usize(f)].no.iter().any(|c| c.is_nan())));
+    }
+
+    #[test]
+    fn a_new_save_is_stamped_with_the_current_version() {
+        let save = cut_cube().save().unwrap();
+        assert_eq!(version(&save), migrate::FORMAT_VERSION);
+    }
+
+    /// `CutCube.v0.glb` was written before save files were versioned: a cube
+    /// cut open along a ring, moved to (1, 2, 3) at scale 2, with its piece
+    /// moved 4 along X. It has to keep opening, and say the same thing.
+    #[test]
+    fn a_v0_save_file_is_upgraded_and_loads() {
+        let save = open(document("CutCube.v0.glb")).unwrap();
+        assert_eq!(version(&save), migrate::FORMAT_VERSION);
+        let loaded = State::load(save).unwrap();
+        let mesh = loaded.meshes.values().next().unwrap();
+        assert_eq!(mesh.scale, 2.0);
+        assert_eq!(mesh.transform.w.truncate(), cgmath::Vector3::new(1.0, 2.0, 3.0));
+        assert_eq!(mesh.cuts.len(), 6);
+        let roots: Vec<_> = mesh.iter_pieces().collect();
+        assert_eq!(roots.len(), 1);
+        assert_eq!(mesh.pieces[roots[0]].transform.w.x, 4.0);
+    }
+
+    #[test]
+    fn a_thumbnail_survives_a_round_trip() {
+        let png = thumbnail::Thumbnail::render(&cut_cube(), None, 16).to_png().unwrap();
+        let save = cut_cube().save().unwrap().with_thumbnail(&png).unwrap();
+        let reopened = open(save.to_binary().unwrap()).unwrap();
+        assert_eq!(reopened.thumbnail(), Some(png));
+    }
+
+    /// The thumbnail is a preview of the document, not one of its textures.
+    #[test]
+    fn a_thumbnail_is_not_loaded_as_an_image() {
+        let state = cut_cube();
+        let images = state.images.len();
+        let save = state.save().unwrap().with_thumbnail(&[0x89, b'P', b'N', b'G']).unwrap();
+        assert_eq!(State::load(save).unwrap().images.len(), images);
+    }
+
+    #[test]
+    fn a_new_thumbnail_replaces_the_old_one() {
+        let save = cut_cube().save().unwrap();
+        let images = save.0.images().count();
+        let save = save.with_thumbnail(b"first").unwrap().with_thumbnail(b"second").unwrap();
+        assert_eq!(save.0.images().count(), images + 1);
+        assert_eq!(save.thumbnail().as_deref(), Some(&b"second"[..]));
+    }
+
+    /// `CutQuadCube.v3.glb` is a quad cube cut into its lids and the ring of
+    /// sides, saved with a 16 pixel thumbnail when thumbnails came in
+    #[test]
+    fn a_v3_save_file_keeps_its_thumbnail() {
+        let save = open(document("CutQuadCube.v3.glb")).unwrap();
+        assert!(save.thumbnail().unwrap().starts_with(b"\x89PNG"));
+        let loaded = State::load(save).unwrap();
+        assert_eq!(loaded.images.len(), State::default().images.len());
+        assert_eq!(loaded.meshes.values().next().unwrap().pieces.len(), 3);
+    }
+
+    /// `CutQuadCube.v4.glb` is the same cube saved when flap shapes came in:
+    /// its flaps are 1 high, but the cut `v0-v4`'s are 0.1 high on a base of
+    /// at least 0.5
+    #[test]
+    fn a_v4_save_file_keeps_its_flap_shapes() {
+        use pp_core::mesh::flap::FlapSettings;
+
+        let loaded = State::load(open(document("CutQuadCube.v4.glb")).unwrap()).unwrap();
+        let mesh = loaded.meshes.values().next().unwrap();
+        assert_eq!(mesh.flap_settings, FlapSettings { height: 1.0, ..Default::default() });
+        let e_id = mesh.query_edge(VertexId::from_usize(0), VertexId::from_usize(4)).unwrap();
+        let tiny = FlapSettings { height: 0.1, min_base: 0.5, ..Default::default() };
+        assert_eq!(mesh.cut_flap_settings(e_id), tiny);
+        assert_eq!(mesh.cuts.values().filter(|cut| cut.flap.is_some()).count(), 1);
+    }
+
+    /// `CutQuadCube.v5.glb` is the same cube saved when flap styles came in,
+    /// with a zigzag flap on the cut `v0-v4`
+    #[test]
+    fn a_v5_save_file_keeps_its_flap_styles() {
+        use pp_core::mesh::flap::FlapStyle;
+
+        let loaded = State::load(open(document("CutQuadCube.v5.glb")).unwrap()).unwrap();
+        let mesh = loaded.meshes.values().next().unwrap();
+        let e_id = mesh.query_edge(VertexId::from_usize(0), VertexId::from_usize(4)).unwrap();
+        assert_eq!(mesh.cut_flap_style(e_id), FlapStyle::Zigzag);
+        assert_eq!(mesh.cuts.values().filter(|cut| cut.style != FlapStyle::default()).count(), 1);
+    }
+
+    /// `CutQuadCube.v6.glb` is the same cube saved when cut labels came in,
+    /// with the cut `v0-v4` labelled 42
+    #[test]
+    fn a_v6_save_file_keeps_its_cut_labels() {
+        let loaded = State::load(open(document("CutQuadCube.v6.glb")).unwrap()).unwrap();
+        let mesh = loaded.meshes.values().next().unwrap();
+        let e_id = mesh.query_edge(VertexId::from_usize(0), VertexId::from_usize(4)).unwrap();
+        assert_eq!(mesh.cut_label(e_id), Some(42));
+        assert!(mesh.cuts.values().all(|cut| cut.label.is_some()));
+    }
+
+    /// `CutQuadCube.v7.glb` is the same cube saved when paper thickness came
+    /// in, on paper 0.02 thick
+    #[test]
+    fn a_v7_save_file_keeps_its_paper_thickness() {
+        let loaded = State::load(open(document("CutQuadCube.v7.glb")).unwrap()).unwrap();
+        assert_eq!(loaded.meshes.values().next().unwrap().paper_thickness, 0.02);
+    }
+
+    /// `CutQuadCube.v8.glb` is the same cube saved when assembly orders came
+    /// in, ordered the reverse of how it's suggested: the lids, then the ring
+    #[test]
+    fn a_v8_save_file_keeps_its_assembly_order() {
+        let loaded = State::load(open(document("CutQuadCube.v8.glb")).unwrap()).unwrap();
+        let mesh = loaded.meshes.values().next().unwrap();
+        assert_eq!(mesh.assembly.len(), 3);
+        let sizes: Vec<usize> = mesh
+            .assembly_order()
+            .iter()
+            .map(|root| mesh.iter_connected_faces(*root).count())
+            .collect();
+        assert_eq!(sizes, vec![1, 1, 4]);
+    }
+
+    /// A listing reads the preview of documents it couldn't open, too
+    #[test]
+    fn a_thumbnail_reads_without_the_rest_of_the_document() {
+        let save = cut_cube().save().unwrap().with_thumbnail(b"preview").unwrap();
+        let bytes = tampered_save(&save, |json| {
+            json["extras"]["papercraft"]["version"] = (migrate::FORMAT_VERSION + 1).into();
+            json["meshes"][0]["extras"]["papercraft"]["pieces"] = "nonsense".into();
+        });
+        assert!(open(bytes.clone()).is_err());
+        let thumbnail = SaveFile::read_thumbnail(Cursor::new(bytes)).unwrap();
+        assert_eq!(thumbnail.as_deref(), Some(&b"preview"[..]));
+    }
+
+    #[test]
+    fn older_documents_have_no_thumbnail() {
+        assert_eq!(open(document("CutCube.v0.glb")).unwrap().thumbnail(), None);
+    }
+
+    #[test]
+    fn garbage_is_not_a_save_file() {
+        assert!(matches!(open(b"not a glb".to_vec()), Err(LoadError::Malformed(_))));
+    }
+
+    #[test]
+    fn extras_pointing_at_a_missing_accessor_are_refused() {
+        let bytes = tampered(&cut_cube(), |json| {
+            json["meshes"][0]["extras"]["papercraft"]["cuts"]["VERTICES"] = 999.into();
+        });
+        assert!(matches!(
+            open(bytes),
+            Err(LoadError::MissingAccessor { attribute: "VERTICES", .. })
+        ));
+    }
+
+    #[test]
+    fn extras_which_are_not_papercraft_data_are_refused() {
+        let bytes = tampered(&cut_cube(), |json| {
+            json["meshes"][0]["extras"]["papercraft"] = "scissors".into();
+        });
+        assert!(matches!(open(bytes), Err(LoadError::MalformedExtras { .. })));
+    }
+
+    /// The flap positions are one byte per cut, so reading them as the
+    /// four-byte vertex pairs should be caught rather than reinterpreted.
+    #[test]
+    fn extras_reading_the_wrong_element_size_are_refused() {
+        let bytes = tampered(&cut_cube(), |json| {
+            let cuts = &mut json["meshes"][0]["extras"]["papercraft"]["cuts"];
+            cuts["VERTICES"] = cuts["FLAP_POSITION"].clone();
+        });
+        let err = open(bytes).err().unwrap();
+        assert!(
+            matches!(
+                err,
+                LoadError::BadAccessor {
+                    attribute: "VERTICES",
+                    source: load::AccessorError::WrongElementSize { .. },
+                    ..
+                }
+            ),
+            "got {err}"
+        );
+    }
+
+    #[test]
+    fn non_triangle_primitives_are_refused() {
+        let bytes = tampered(&cut_cube(), |json| {
+            json["meshes"][0]["primitives"][0]["mode"] = 1.into();
+        });
+        assert!(matches!(open(bytes), Err(LoadError::NotTriangles { .. })));
+    }
+
+    #[test]
+    fn images_outside_the_file_are_refused() {
+        let bytes = tampered(&cut_cube(), |json| {
+            json["images"] = serde_json::json!([{ "uri": "texture.png" }]);
+        });
+        let err = open(bytes).err().unwrap();
+        assert!(matches!(err, LoadError::UnsupportedImageSource { .. }), "got {err}");
+        assert!(err.to_string().contains("texture.png"), "the reason should name the file");
     }
 }
@@ -1,37 +1,205 @@
 use crate::{standard, SaveFile};
-use pp_core::{material::texture::Texture, State};
+use pp_core::{
+    material::texture::Texture,
+    mesh::{manifold::ManifoldRepair, winding::WindingRepair},
+    State,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use thiserror::Error;
 
+/// Where in the document a problem was found: a mesh by name, and the
+/// primitive within it when the problem is specific to one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeshLocation {
+    pub mesh: String,
+    pub primitive: Option<usize>,
+}
+
+impl MeshLocation {
+    pub fn mesh(mesh: &str) -> Self {
+        Self { mesh: mesh.to_string(), primitive: None }
+    }
+
+    pub fn primitive(mesh: &str, primitive: usize) -> Self {
+        Self { mesh: mesh.to_string(), primitive: Some(primitive) }
+    }
+}
+
+impl fmt::Display for MeshLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "mesh \"{}\"", self.mesh)?;
+        if let Some(primitive) = self.primitive {
+            write!(f, " primitive {primitive}")?;
+        }
+        Ok(())
+    }
+}
+
+/// What was wrong with an accessor, independent of which mesh reads it
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AccessorError {
+    #[error("is sparse or has no buffer view")]
+    NoBufferView,
+    #[error("points at buffer {0}, which doesn't exist")]
+    MissingBuffer(usize),
+    #[error("reads past the end of its buffer")]
+    OutOfBounds,
+    #[error("holds {found}-byte elements where {expected}-byte elements were expected")]
+    WrongElementSize { expected: usize, found: usize },
+}
+
 /// Possible errors that can occur while loading a file
-#[derive(Debug, Clone, Copy, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum LoadError {
-    #[error("unknown data store error")]
-    Unknown,
-    #[error("failed to load buffers")]
-    FailedToLoadBuffers,
+    #[error("not a valid glTF file: {0}")]
+    Malformed(String),
+    #[error("the save file's format version {0} is not a number")]
+    MalformedVersion(String),
+    #[error("the save file is format version {found}, but this app only reads up to {supported}")]
+    UnsupportedVersion { found: u32, supported: u32 },
+    #[error("the save file could not be upgraded from format version {from}: {reason}")]
+    FailedMigration { from: u32, reason: String },
+    #[error("failed to load buffers: {0}")]
+    FailedToLoadBuffers(String),
+    #[error("{at} has no {attribute} accessor")]
+    MissingAccessor { at: MeshLocation, attribute: &'static str },
+    #[error("the {attribute} accessor of {at} {source}")]
+    BadAccessor {
+        at: MeshLocation,
+        attribute: &'static str,
+        #[source]
+        source: AccessorError,
+    },
+    #[error("{at} has indices of type {data_type}, expected an unsigned integer")]
+    BadIndexType { at: MeshLocation, data_type: String },
+    #[error("{at} is drawn as {mode}, only triangles are supported")]
+    NotTriangles { at: MeshLocation, mode: String },
+    #[error("{at} has {count} indices, which isn't a whole number of triangles")]
+    IncompleteTriangle { at: MeshLocation, count: usize },
+    #[error("{at} references vertex {index}, but only has {vertex_count}")]
+    IndexOutOfRange { at: MeshLocation, index: u32, vertex_count: usize },
+    #[error("the papercraft extras of mesh \"{mesh}\" could not be read: {reason}")]
+    MalformedExtras { mesh: String, reason: String },
+    #[error("mesh \"{mesh}\" has {found} {what}, expected {expected}")]
+    ExtrasLengthMismatch { mesh: String, what: &'static str, expected: usize, found: usize },
+    #[error("cut {cut} of mesh \"{mesh}\" references vertex {vertex}, but the mesh only has {vertex_count}")]
+    CutEdgeOutOfRange { mesh: String, cut: usize, vertex: u32, vertex_count: usize },
+    #[error("cut {cut} of mesh \"{mesh}\" joins vertices {vertices:?}, which share no edge")]
+    CutEdgeMissing { mesh: String, cut: usize, vertices: [u32; 2] },
+    #[error("piece {piece} of mesh \"{mesh}\" is rooted at vertex {face}, but the mesh only has {vertex_count}")]
+    PieceFaceOutOfRange { mesh: String, piece: usize, face: u32, vertex_count: usize },
+    #[error("piece {piece} of mesh \"{mesh}\" is rooted at vertex {face}, which starts no face")]
+    PieceFaceInvalid { mesh: String, piece: usize, face: u32 },
+    #[error("the polygon at vertex {first} of mesh \"{mesh}\" {reason}")]
+    MalformedPolygon { mesh: String, first: u32, reason: &'static str },
+    #[error("piece {piece} of mesh \"{mesh}\" contains a cycle, so it can't be unfolded")]
+    PieceHasCycle { mesh: String, piece: usize },
+    #[error("image \"{image}\" is {mime_type}, only PNG and JPEG are supported")]
+    UnsupportedImageFormat { image: String, mime_type: String },
+    #[error("image \"{image}\" is stored outside the file, at {uri}")]
+    UnsupportedImageSource { image: String, uri: String },
+    #[error("image \"{image}\" could not be decoded: {reason}")]
+    UndecodableImage { image: String, reason: String },
+}
+
+/// How the geometry of a plain import is cleaned up as it loads. Meshes from
+/// our own saves are loaded exactly as they were saved, whatever these say.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportOptions {
+    /// Vertices closer together than this, in model units, are welded into
+    /// one. Zero only welds vertices at exactly the same position.
+    pub weld_distance: f32,
+    /// Whether to drop faces with no area, like triangles with all three
+    /// corners in a line, which have no normal to unfold along
+    pub remove_degenerate_faces: bool,
+    /// Whether to split edges with more than two faces apart
+    pub split_non_manifold: bool,
+    /// Whether to wind faces consistently, facing outward
+    pub orient_faces: bool,
+    /// Whether to merge patches of coplanar triangles back into polygons
+    pub merge_coplanar_faces: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            weld_distance: 1e-5,
+            remove_degenerate_faces: true,
+            split_non_manifold: true,
+            orient_faces: true,
+            merge_coplanar_faces: true,
+        }
+    }
+}
+
+/// What cleaning up the geometry of a load changed, for each mesh
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ImportReport {
+    pub meshes: Vec<MeshImportReport>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MeshImportReport {
+    pub mesh: String,
+    /// Vertices which were welded to a nearby vertex at a different position
+    pub welded_verts: usize,
+    /// Faces which were dropped for having no area, or repeated corners
+    pub dropped_faces: usize,
+    pub manifold: ManifoldRepair,
+    pub winding: WindingRepair,
+}
+
+impl MeshImportReport {
+    /// Whether the mesh loaded exactly as it was in the file
+    pub fn is_empty(&self) -> bool {
+        self.welded_verts == 0
+            && self.dropped_faces == 0
+            && self.manifold.is_empty()
+            && self.winding.flipped == 0
+    }
 }
 
 pub trait Loadable {
-    fn load(save: SaveFile) -> Result<pp_core::State, LoadError>;
+    fn load(save: SaveFile) -> Result<pp_core::State, LoadError> {
+        Self::load_with_options(save, &ImportOptions::default()).map(|(state, _)| state)
+    }
+
+    /// Loads a save file, cleaning up any plain imports in it as `options`
+    /// say, and reports what was changed
+    fn load_with_options(
+        save: SaveFile,
+        options: &ImportOptions,
+    ) -> Result<(pp_core::State, ImportReport), LoadError>;
 }
 
 impl Loadable for pp_core::State {
-    fn load(save: SaveFile) -> Result<pp_core::State, LoadError> {
+    fn load_with_options(
+        save: SaveFile,
+        options: &ImportOptions,
+    ) -> Result<(pp_core::State, ImportReport), LoadError> {
         let mut state = State::default();
+        let thumbnail = save.thumbnail_index();
 
         // Extract buffer data (basically, all geometry data) out of the GLTF
         let mut gltf = save.0;
         let buffers = gltf::import_buffers(&gltf.document, None, gltf.blob.take())
-            .map_err(|_| LoadError::FailedToLoadBuffers)?;
+            .map_err(|err| LoadError::FailedToLoadBuffers(err.to_string()))?;
 
-        // Step 1: Load images
-        let image_ids: Vec<_> = gltf
+        // Step 1: Load images. The thumbnail is only a preview of the document,
+        // not part of it, so it's left out of the editor's images.
+        let image_ids = gltf
             .images()
             .enumerate()
             .map(|(i, gltf_image)| {
-                state.images.insert(standard::image::load_image(&gltf_image, &buffers, i))
+                if Some(i) == thumbnail {
+                    return Ok(state.defaults.image);
+                }
+                let image = standard::image::load_image(&gltf_image, &buffers, i)?;
+                Ok(state.images.insert(image))
             })
-            .collect();
+            .collect::<Result<Vec<_>, LoadError>>()?;
 
         // Step 2: Load samplers
         let sampler_ids: Vec<_> = gltf
@@ -78,15 +246,21 @@ impl Loadable for pp_core::State {
 
         // Step 5: Load meshes
         let accessors: Vec<_> = gltf.document.accessors().collect();
-        let _mesh_ids: Vec<_> = gltf
-            .meshes()
-            .filter_map(|gltf_mesh| {
-                // Add mesh from the GLTF to the state we're building
-                standard::mesh::load_mesh(&gltf_mesh, &accessors, &buffers, &material_ids).ok()
-            })
-            .map(|mesh| state.meshes.insert(mesh))
-            .collect();
+        let mut report = ImportReport::default();
+        for gltf_mesh in gltf.meshes() {
+            let (mesh, mesh_report) = standard::mesh::load_mesh(
+                &gltf_mesh,
+                &accessors,
+                &buffers,
+                &material_ids,
+                options,
+            )?;
+            state.meshes.insert(mesh);
+            if !mesh_report.is_empty() {
+                report.meshes.push(mesh_report);
+            }
+        }
 
-        Ok(state)
+        Ok((state, report))
     }
 }
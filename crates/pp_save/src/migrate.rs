@@ -0,0 +1,299 @@
+//! Upgrades the papercraft extras of older save files to the current format.
+//!
+//! Every save file records the format it was written in as `version` on the
+//! root `papercraft` extras. Files written before that field existed, and
+//! plain glTFs with no papercraft data at all, are version 0. Each entry of
+//! `MIGRATIONS` takes the document's JSON from one version to the next, so an
+//! old file is walked forward step by step until it matches `FORMAT_VERSION`,
+//! before `SaveFile::validate` and `Loadable::load` ever see it.
+
+use serde_json::{Map, Value};
+
+use crate::load::LoadError;
+
+/// The format version written by this build of the app
+pub const FORMAT_VERSION: u32 = 8;
+
+/// A single upgrade step, taking the document's JSON from version `i` to `i + 1`
+type Migration = fn(&mut Value) -> Result<(), String>;
+
+/// Upgrade steps, indexed by the version they upgrade *from*
+const MIGRATIONS: [Migration; FORMAT_VERSION as usize] =
+    [v0_to_v1, v1_to_v2, v2_to_v3, v3_to_v4, v4_to_v5, v5_to_v6, v6_to_v7, v7_to_v8];
+
+/// Reads the format version of a document's JSON. Documents without root
+/// papercraft extras predate versioning, and are version 0.
+pub fn version_of(json: &Value) -> Result<u32, LoadError> {
+    let Some(version) = json.pointer("/extras/papercraft/version") else {
+        return Ok(0);
+    };
+    version
+        .as_u64()
+        .and_then(|v| u32::try_from(v).ok())
+        .ok_or_else(|| LoadError::MalformedVersion(version.to_string()))
+}
+
+/// Upgrades a document's JSON in place to `FORMAT_VERSION`, returning the
+/// version it started at.
+pub fn migrate(json: &mut Value) -> Result<u32, LoadError> {
+    let found = version_of(json)?;
+    if found > FORMAT_VERSION {
+        return Err(LoadError::UnsupportedVersion { found, supported: FORMAT_VERSION });
+    }
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(found as usize) {
+        let from = from as u32;
+        migration(json).map_err(|reason| LoadError::FailedMigration { from, reason })?;
+        set_version(json, from + 1)?;
+    }
+    Ok(found)
+}
+
+/// Stamps the root papercraft extras with `version`, creating them if needed
+fn set_version(json: &mut Value, version: u32) -> Result<(), LoadError> {
+    let unrecordable = |v: &Value| LoadError::FailedMigration {
+        from: version.saturating_sub(1),
+        reason: format!("format version {version} can't be recorded in extras of {v}"),
+    };
+    let root = json.as_object_mut().ok_or_else(|| LoadError::Malformed("not an object".into()))?;
+    let extras = root.entry("extras").or_insert_with(|| Value::Object(Map::new()));
+    if extras.is_null() {
+        *extras = Value::Object(Map::new());
+    }
+    let extras = match extras {
+        Value::Object(extras) => extras,
+        other => return Err(unrecordable(other)),
+    };
+    let papercraft = match extras.entry("papercraft").or_insert_with(|| Value::Object(Map::new())) {
+        Value::Object(papercraft) => papercraft,
+        other => return Err(unrecordable(other)),
+    };
+    papercraft.insert("version".to_string(), version.into());
+    Ok(())
+}
+
+/// Iterates the papercraft extras of every mesh which has them
+fn mesh_extras_mut(json: &mut Value) -> impl Iterator<Item = &mut Map<String, Value>> {
+    json.get_mut("meshes")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(|mesh| mesh.pointer_mut("/extras/papercraft"))
+        .filter_map(Value::as_object_mut)
+}
+
+// --- Section: Migrations ---
+
+/// Version 0 files written before meshes could be moved have no `transform` or
+/// `scale` on their mesh extras. Fill in the identity so that version 1 can
+/// rely on both being there.
+fn v0_to_v1(json: &mut Value) -> Result<(), String> {
+    const IDENTITY: [[f32; 4]; 4] =
+        [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+    for extras in mesh_extras_mut(json) {
+        if extras.get("transform").is_none_or(Value::is_null) {
+            extras.insert("transform".to_string(), serde_json::json!(IDENTITY));
+        }
+        if extras.get("scale").is_none_or(Value::is_null) {
+            extras.insert("scale".to_string(), serde_json::json!(1.0));
+        }
+    }
+    Ok(())
+}
+
+/// Version 2 saves faces of more than three corners as triangle fans, listed
+/// in each mesh's `polygons` extras. Every face of a version 1 file is a
+/// triangle, which is what a missing `polygons` means, so there is nothing to
+/// change. The version still moves on so that older apps refuse version 2
+/// files rather than loading their polygons as loose triangles.
+fn v1_to_v2(_json: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+/// Version 3 can point the root extras at a rendered thumbnail among the
+/// file's images. A version 2 file has none, which is what a missing
+/// `thumbnail` means. Older apps would load a thumbnail as one of the
+/// document's textures, so they're made to refuse version 3 files instead.
+fn v2_to_v3(_json: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+/// Version 4 saves each mesh's `flap_settings`, and the cuts with flaps of
+/// their own shape in `cut_flaps`. A version 3 mesh has neither, and its flaps
+/// take the default shape, which is what both missing means. Older apps
+/// would quietly reshape every flap, so they're made to refuse version 4.
+fn v3_to_v4(_json: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+/// Version 5 lists the cuts whose flaps aren't plain trapezoids in each
+/// mesh's `cut_styles`. Every flap of a version 4 file is a trapezoid, which
+/// is what a missing `cut_styles` means. Older apps would turn every styled
+/// flap back into one, so they're made to refuse version 5.
+fn v4_to_v5(_json: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+/// Version 6 saves the number each cut is labelled with in each mesh's
+/// `cut_labels`. Version 5 cuts have no labels saved, and a missing
+/// `cut_labels` means they're numbered afresh as they load. Older apps would
+/// renumber cuts a user had already printed, so they're made to refuse
+/// version 6.
+fn v5_to_v6(_json: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+/// Version 7 saves how thick each mesh's paper is as its `paper_thickness`.
+/// Version 6 paper has no thickness to compensate for, which is what a
+/// missing `paper_thickness` means. Older apps would print thick paper as
+/// though it had none, so they're made to refuse version 7.
+fn v6_to_v7(_json: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+/// Version 8 saves the order the user put each mesh's pieces in to assemble
+/// them as its `assembly`. Version 7 pieces follow the suggested order, which
+/// is what a missing `assembly` means. Older apps would lose the user's
+/// order, so they're made to refuse version 8.
+fn v7_to_v8(_json: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn legacy_mesh(extras: Value) -> Value {
+        json!({ "asset": { "version": "2.0" }, "meshes": [{ "primitives": [], "extras": extras }] })
+    }
+
+    #[test]
+    fn a_document_without_root_extras_is_version_zero() {
+        assert_eq!(version_of(&json!({ "asset": { "version": "2.0" } })).unwrap(), 0);
+    }
+
+    #[test]
+    fn migrating_stamps_the_current_version() {
+        let mut json = json!({ "asset": { "version": "2.0" } });
+        assert_eq!(migrate(&mut json).unwrap(), 0);
+        assert_eq!(version_of(&json).unwrap(), FORMAT_VERSION);
+    }
+
+    #[test]
+    fn a_current_document_is_left_alone() {
+        let mut json = legacy_mesh(json!({ "papercraft": { "cuts": {}, "pieces": {} } }));
+        set_version(&mut json, FORMAT_VERSION).unwrap();
+        let before = json.clone();
+        assert_eq!(migrate(&mut json).unwrap(), FORMAT_VERSION);
+        assert_eq!(json, before, "no migration should have run");
+    }
+
+    /// Files from a newer app may rely on data this one would silently drop.
+    #[test]
+    fn a_document_from_the_future_is_refused() {
+        let mut json = json!({ "extras": { "papercraft": { "version": FORMAT_VERSION + 1 } } });
+        assert!(matches!(
+            migrate(&mut json),
+            Err(LoadError::UnsupportedVersion { found, .. }) if found == FORMAT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn a_version_which_is_not_a_number_is_refused() {
+        let json = json!({ "extras": { "papercraft": { "version": "one" } } });
+        assert!(matches!(version_of(&json), Err(LoadError::MalformedVersion(_))));
+    }
+
+    /// Root extras with no room for a version name both ends of the upgrade
+    #[test]
+    fn a_version_with_nowhere_to_go_is_refused() {
+        let mut json = json!({ "extras": 5 });
+        let error = migrate(&mut json).unwrap_err();
+        assert!(matches!(error, LoadError::FailedMigration { from: 0, .. }));
+        assert!(error.to_string().contains("format version 1"));
+    }
+
+    #[test]
+    fn v0_meshes_without_a_transform_get_the_identity() {
+        let mut json = legacy_mesh(json!({ "papercraft": { "cuts": {}, "pieces": {} } }));
+        migrate(&mut json).unwrap();
+        let extras = &json["meshes"][0]["extras"]["papercraft"];
+        assert_eq!(extras["scale"], json!(1.0));
+        assert_eq!(extras["transform"][3], json!([0.0, 0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn v0_meshes_with_a_transform_keep_it() {
+        let mut json = legacy_mesh(json!({
+            "papercraft": { "cuts": {}, "pieces": {}, "scale": 2.0, "transform": [[2.0]] }
+        }));
+        migrate(&mut json).unwrap();
+        let extras = &json["meshes"][0]["extras"]["papercraft"];
+        assert_eq!(extras["scale"], json!(2.0));
+        assert_eq!(extras["transform"], json!([[2.0]]));
+    }
+
+    #[test]
+    fn v1_meshes_are_all_triangles() {
+        let mut json = legacy_mesh(json!({ "papercraft": { "cuts": {}, "pieces": {} } }));
+        set_version(&mut json, 1).unwrap();
+        let before = json["meshes"].clone();
+        assert_eq!(migrate(&mut json).unwrap(), 1);
+        assert_eq!(json["meshes"], before);
+        assert_eq!(version_of(&json).unwrap(), FORMAT_VERSION);
+    }
+
+    #[test]
+    fn v2_documents_have_no_thumbnail() {
+        let mut json = legacy_mesh(json!({ "papercraft": { "cuts": {}, "pieces": {} } }));
+        set_version(&mut json, 2).unwrap();
+        assert_eq!(migrate(&mut json).unwrap(), 2);
+        assert_eq!(json["extras"]["papercraft"], json!({ "version": FORMAT_VERSION }));
+    }
+
+    #[test]
+    fn v3_meshes_keep_the_default_flaps() {
+        let mut json = legacy_mesh(json!({ "papercraft": { "cuts": {}, "pieces": {} } }));
+        set_version(&mut json, 3).unwrap();
+        let before = json["meshes"].clone();
+        assert_eq!(migrate(&mut json).unwrap(), 3);
+        assert_eq!(json["meshes"], before);
+    }
+
+    #[test]
+    fn v4_flaps_are_all_trapezoids() {
+        let mut json = legacy_mesh(json!({ "papercraft": { "cuts": {}, "pieces": {} } }));
+        set_version(&mut json, 4).unwrap();
+        let before = json["meshes"].clone();
+        assert_eq!(migrate(&mut json).unwrap(), 4);
+        assert_eq!(json["meshes"], before);
+    }
+
+    #[test]
+    fn v5_cuts_are_numbered_as_they_load() {
+        let mut json = legacy_mesh(json!({ "papercraft": { "cuts": {}, "pieces": {} } }));
+        set_version(&mut json, 5).unwrap();
+        let before = json["meshes"].clone();
+        assert_eq!(migrate(&mut json).unwrap(), 5);
+        assert_eq!(json["meshes"], before);
+    }
+
+    #[test]
+    fn v6_paper_has_no_thickness() {
+        let mut json = legacy_mesh(json!({ "papercraft": { "cuts": {}, "pieces": {} } }));
+        set_version(&mut json, 6).unwrap();
+        let before = json["meshes"].clone();
+        assert_eq!(migrate(&mut json).unwrap(), 6);
+        assert_eq!(json["meshes"], before);
+    }
+
+    #[test]
+    fn v7_pieces_follow_the_suggested_order() {
+        let mut json = legacy_mesh(json!({ "papercraft": { "cuts": {}, "pieces": {} } }));
+        set_version(&mut json, 7).unwrap();
+        let before = json["meshes"].clone();
+        assert_eq!(migrate(&mut json).unwrap(), 7);
+        assert_eq!(json["meshes"], before);
+    }
+}
@@ -0,0 +1,364 @@
+//! Exporting the unfolded pattern as flat geometry.
+//!
+//! Where the PDF is the pattern as paper, this is the pattern as a mesh: every
+//! piece laid out exactly where it sits on the cutting viewport's sheet, its
+//! glue tabs included, with the model's own UVs. Loaded into Blender or a game
+//! engine it can preview the printed sheet, or be texture-painted directly on
+//! the layout and the result mapped straight back onto the model.
+//!
+//! Positions are in the same centimeters as the layout, on the ground plane of
+//! the output's Y-up space, so one unit is one centimeter of paper.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use cgmath::{EuclideanSpace, Point3, Transform};
+use pp_core::{
+    id::{FaceId, LoopId},
+    mesh::{flap::FLAP_OUTLINE_POINTS, Mesh},
+    MaterialId, State,
+};
+
+use crate::{
+    save::{save_materials, SaveError, SavedMaterials},
+    standard::buffers::{AccessorOptions, GltfBufferBuilder},
+};
+
+/// One piece of the pattern, as triangles on the sheet.
+pub struct PatternPiece {
+    /// Names the piece after its mesh, so pieces can be told apart in an outliner
+    pub name: String,
+    /// Positions on the sheet, in the layout's centimeters, Z up
+    pub positions: Vec<[f32; 3]>,
+    /// The model's UV for each position
+    pub uvs: Vec<[f32; 2]>,
+    /// Indices into `positions`, grouped by the material of the face they came
+    /// from. Tabs are grouped with the face they hang off.
+    pub triangles: BTreeMap<Option<MaterialId>, Vec<[u32; 3]>>,
+}
+
+/// The whole unfolded pattern, piece by piece.
+pub struct FlatPattern {
+    pub pieces: Vec<PatternPiece>,
+}
+
+impl FlatPattern {
+    /// Lays out every piece of every mesh as it currently sits on the sheet
+    pub fn from_state(state: &State) -> Self {
+        let mut meshes: Vec<_> = state.meshes.iter().collect();
+        meshes.sort_by_key(|(m_id, _)| *m_id);
+        let pieces = meshes
+            .into_iter()
+            .flat_map(|(_, mesh)| {
+                let label = mesh.label.as_deref().unwrap_or("Mesh");
+                mesh.iter_pieces()
+                    .enumerate()
+                    .map(move |(i, root)| piece(mesh, *root, format!("{label} piece {i}")))
+            })
+            .collect();
+        Self { pieces }
+    }
+
+    /// Writes the pattern as a Wavefront OBJ, one object per piece. OBJ has no
+    /// materials without a separate MTL file, so faces are left ungrouped.
+    pub fn to_obj(&self) -> String {
+        let mut obj = String::from("# Unfolded papercraft pattern, in centimeters\n");
+        // OBJ indices are 1-based and shared across the whole file
+        let mut offset = 1;
+        for piece in &self.pieces {
+            let _ = writeln!(obj, "o {}", piece.name.replace(char::is_whitespace, "_"));
+            for p in &piece.positions {
+                let [x, y, z] = to_y_up(*p);
+                let _ = writeln!(obj, "v {x} {y} {z}");
+            }
+            for [u, v] in &piece.uvs {
+                // OBJ's V runs up the image, glTF's runs down
+                let _ = writeln!(obj, "vt {u} {}", 1.0 - v);
+            }
+            for [a, b, c] in piece.triangles.values().flatten() {
+                let (a, b, c) = (a + offset, b + offset, c + offset);
+                let _ = writeln!(obj, "f {a}/{a} {b}/{b} {c}/{c}");
+            }
+            offset += piece.positions.len() as u32;
+        }
+        obj
+    }
+
+    /// Writes the pattern as a GLB, one mesh per piece, with the document's own
+    /// materials so that textured models keep their textures.
+    pub fn to_glb(&self, state: &State) -> Result<Vec<u8>, SaveError> {
+        use gltf_json::validation::Checked::Valid;
+        use gltf_json::{accessor, buffer, mesh};
+
+        let SavedMaterials { images, samplers, textures, materials, material_ids } =
+            save_materials(state)?;
+        let mut builder = GltfBufferBuilder::new();
+        let f32_accessor = |type_, min: Option<[f32; 3]>, max: Option<[f32; 3]>| AccessorOptions {
+            component_type: Valid(accessor::GenericComponentType(accessor::ComponentType::F32)),
+            type_: Valid(type_),
+            target: Some(Valid(buffer::Target::ArrayBuffer)),
+            normalized: false,
+            min: min.map(|min| gltf_json::Value::from(Vec::from(min))),
+            max: max.map(|max| gltf_json::Value::from(Vec::from(max))),
+        };
+
+        let mut meshes = Vec::new();
+        for piece in &self.pieces {
+            let positions: Vec<_> = piece.positions.iter().copied().map(to_y_up).collect();
+            let (min, max) = bounds(&positions);
+            let positions =
+                builder.add_accessor(&positions, f32_accessor(accessor::Type::Vec3, min, max));
+            let uvs =
+                builder.add_accessor(&piece.uvs, f32_accessor(accessor::Type::Vec2, None, None));
+            let primitives = piece
+                .triangles
+                .iter()
+                .map(|(m_id, triangles)| mesh::Primitive {
+                    indices: Some(builder.add_accessor(
+                        triangles,
+                        AccessorOptions {
+                            component_type: Valid(accessor::GenericComponentType(
+                                accessor::ComponentType::U32,
+                            )),
+                            type_: Valid(accessor::Type::Scalar),
+                            target: Some(Valid(buffer::Target::ElementArrayBuffer)),
+                            normalized: false,
+                            min: None,
+                            max: None,
+                        },
+                    )),
+                    attributes: BTreeMap::from([
+                        (Valid(mesh::Semantic::Positions), positions),
+                        (Valid(mesh::Semantic::TexCoords(0)), uvs),
+                    ]),
+                    material: m_id.as_ref().and_then(|m_id| material_ids.get(m_id)).copied(),
+                    mode: Valid(mesh::Mode::Triangles),
+                    targets: None,
+                    extensions: Default::default(),
+                    extras: Default::default(),
+                })
+                .collect();
+            meshes.push(mesh::Mesh {
+                name: Some(piece.name.clone()),
+                primitives,
+                weights: None,
+                extensions: Default::default(),
+                extras: Default::default(),
+            });
+        }
+
+        let nodes: Vec<_> = (0..meshes.len() as u32)
+            .map(|i| gltf_json::Node {
+                name: meshes[i as usize].name.clone(),
+                mesh: Some(gltf_json::Index::new(i)),
+                ..Default::default()
+            })
+            .collect();
+        let scenes = vec![gltf_json::Scene {
+            name: Some("Pattern".to_string()),
+            nodes: (0..nodes.len() as u32).map(gltf_json::Index::new).collect(),
+            extensions: Default::default(),
+            extras: Default::default(),
+        }];
+
+        let (buffers, buffer_views, accessors) = builder.build();
+        let root = gltf_json::Root {
+            accessors,
+            buffers,
+            buffer_views,
+            scene: Some(gltf_json::Index::new(0)),
+            scenes,
+            meshes,
+            nodes,
+            samplers,
+            textures,
+            images,
+            materials,
+            ..Default::default()
+        };
+        let json = root.to_vec().map_err(|err| SaveError::Json(err.to_string()))?;
+        gltf::binary::Glb {
+            header: gltf::binary::Header { magic: *b"glTF", version: 2, length: 0 },
+            json: json.into(),
+            bin: None,
+        }
+        .to_vec()
+        .map_err(|err| SaveError::Glb(err.to_string()))
+    }
+}
+
+/// Collects the faces and tabs of the piece rooted at `root`, placed on the
+/// sheet as `piece_lines` in `pp_core::print::vector` places them.
+fn piece(mesh: &Mesh, root: FaceId, name: String) -> PatternPiece {
+    let mut out =
+        PatternPiece { name, positions: Vec::new(), uvs: Vec::new(), triangles: BTreeMap::new() };
+    let Some(piece) = mesh.pieces.get(&root) else { return out };
+    let walker = mesh.iter_piece_faces_unfolded(root);
+    let t = walker.t;
+
+    for face in walker {
+        let place = |p: Point3<f32>| piece.transform.transform_point(p);
+        let material = mesh[face.f].m;
+        // Corners are per-face rather than shared, since a vertex's UV can
+        // differ between the faces around it.
+        let corners: Vec<u32> = mesh
+            .iter_face_loops(face.f)
+            .map(|l_id| {
+                let l = mesh[l_id];
+                let p = face.affine.transform_point(Point3::from_vec(mesh.vert_pos(l.v)));
+                out.push(place(p), l.uv)
+            })
+            .collect();
+        let triangles = out.triangles.entry(material).or_default();
+        for i in 1..corners.len().saturating_sub(1) {
+            triangles.push([corners[0], corners[i], corners[i + 1]]);
+        }
+
+        for l_id in mesh.iter_face_loops(face.f) {
+            let Some(outline) = mesh.piece_flap_outline(l_id, face.affine, t) else { continue };
+            // A tab has no UVs of its own, so it stretches the texture along
+            // the seam it hangs from: each point of its outline takes the UV
+            // of the base beneath it.
+            let [uv0, uv1] = edge_uvs(mesh, l_id);
+            let uv = |i: usize| {
+                let s = outline.profile[i][0];
+                [uv0[0] + (uv1[0] - uv0[0]) * s, uv0[1] + (uv1[1] - uv0[1]) * s]
+            };
+            // The tab is a column per segment of its outline, standing on the
+            // base beneath it. A tab collapsed onto its base, and the padding
+            // at the end of every outline, give columns with nothing to cut out.
+            for i in 0..FLAP_OUTLINE_POINTS - 1 {
+                let column = [
+                    (place(outline.base_point(i)), uv(i)),
+                    (place(outline.base_point(i + 1)), uv(i + 1)),
+                    (place(outline.point(i + 1)), uv(i + 1)),
+                    (place(outline.point(i)), uv(i)),
+                ];
+                for [a, b, c] in [[0, 1, 2], [0, 2, 3]].map(|tri| tri.map(|k| column[k])) {
+                    let twice_area = area(a.0, b.0, c.0);
+                    if twice_area.abs() < f32::EPSILON {
+                        continue;
+                    }
+                    // Wind the tab the same way round as the face it hangs off
+                    let [a, b, c] = if twice_area < 0.0 { [a, c, b] } else { [a, b, c] };
+                    let ids = [a, b, c].map(|(p, uv)| out.push(p, uv));
+                    out.triangles.entry(material).or_default().push(ids);
+                }
+            }
+        }
+    }
+    out
+}
+
+impl PatternPiece {
+    fn push(&mut self, p: Point3<f32>, uv: [f32; 2]) -> u32 {
+        self.positions.push(p.into());
+        self.uvs.push(uv);
+        self.positions.len() as u32 - 1
+    }
+}
+
+/// The UVs at the two ends of `l_id`'s edge, in the edge's own vertex order -
+/// the order `piece_flap_outline` puts the base of a tab in.
+fn edge_uvs(mesh: &Mesh, l_id: LoopId) -> [[f32; 2]; 2] {
+    let l = mesh[l_id];
+    let next = mesh[l.next];
+    if mesh[l.e].v[0] == l.v {
+        [l.uv, next.uv]
+    } else {
+        [next.uv, l.uv]
+    }
+}
+
+/// Twice the signed area of a triangle on the sheet, positive if it runs
+/// anticlockwise seen from above
+fn area(a: Point3<f32>, b: Point3<f32>, c: Point3<f32>) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+/// The layout is Z up, like the rest of the app; glTF and OBJ are Y up
+fn to_y_up([x, y, z]: [f32; 3]) -> [f32; 3] {
+    [x, z, -y]
+}
+
+/// The min / max corners glTF requires on a position accessor
+fn bounds(positions: &[[f32; 3]]) -> (Option<[f32; 3]>, Option<[f32; 3]>) {
+    let mut iter = positions.iter();
+    let Some(first) = iter.next() else { return (Some([0.0; 3]), Some([0.0; 3])) };
+    let (min, max) = iter.fold((*first, *first), |(mut min, mut max), p| {
+        for i in 0..3 {
+            min[i] = min[i].min(p[i]);
+            max[i] = max[i].max(p[i]);
+        }
+        (min, max)
+    });
+    (Some(min), Some(max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A cube cut open along a ring, which gives it pieces with tabs.
+    fn cut_cube() -> State {
+        let mut state = State::default();
+        state.meshes.insert(Mesh::cut_cube());
+        state
+    }
+
+    fn triangle_count(pattern: &FlatPattern) -> usize {
+        pattern.pieces.iter().flat_map(|piece| piece.triangles.values()).map(Vec::len).sum()
+    }
+
+    #[test]
+    fn a_model_without_pieces_has_no_pattern() {
+        assert!(FlatPattern::from_state(&State::with_cube()).pieces.is_empty());
+    }
+
+    /// Every face in a piece is in the pattern, plus four triangles for each
+    /// tab: one under each side, and two under its top. Faces outside any
+    /// piece aren't part of the layout, so they're left out.
+    #[test]
+    fn every_face_and_tab_is_exported() {
+        let state = cut_cube();
+        let mesh = state.meshes.values().next().unwrap();
+        let loops: Vec<_> = mesh.iter_piece_loops().collect();
+        let tabs = loops.iter().filter(|l_id| mesh.loop_has_flap(**l_id)).count();
+        assert!(tabs > 0, "the cut cube should have tabs");
+        let pattern = FlatPattern::from_state(&state);
+        assert_eq!(triangle_count(&pattern), loops.len() / 3 + 4 * tabs);
+    }
+
+    /// The pattern is flat: pieces lie in the sheet's plane.
+    #[test]
+    fn the_pattern_lies_on_the_sheet() {
+        let pattern = FlatPattern::from_state(&cut_cube());
+        for p in pattern.pieces.iter().flat_map(|piece| &piece.positions) {
+            assert!(p[2].abs() < 1e-4, "expected a flat pattern, got z = {}", p[2]);
+        }
+    }
+
+    #[test]
+    fn the_obj_has_a_vertex_and_uv_per_position() {
+        let pattern = FlatPattern::from_state(&cut_cube());
+        let obj = pattern.to_obj();
+        let positions: usize = pattern.pieces.iter().map(|piece| piece.positions.len()).sum();
+        assert_eq!(obj.lines().filter(|l| l.starts_with("v ")).count(), positions);
+        assert_eq!(obj.lines().filter(|l| l.starts_with("vt ")).count(), positions);
+        assert_eq!(obj.lines().filter(|l| l.starts_with("f ")).count(), triangle_count(&pattern));
+    }
+
+    /// The GLB is a valid glTF that other tools can open.
+    #[test]
+    fn the_glb_is_valid_gltf() {
+        let state = cut_cube();
+        let pattern = FlatPattern::from_state(&state);
+        let glb = pattern.to_glb(&state).unwrap();
+        let gltf = gltf::Gltf::from_slice(&glb).unwrap();
+        assert_eq!(gltf.meshes().count(), pattern.pieces.len());
+        let buffers = gltf::import_buffers(&gltf.document, None, None).unwrap();
+        let primitive = gltf.meshes().next().unwrap().primitives().next().unwrap();
+        let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+        let heights: Vec<f32> = reader.read_positions().unwrap().map(|p| p[1]).collect();
+        assert!(heights.iter().all(|y| y.abs() < 1e-4), "the sheet should be the ground plane");
+    }
+}
@@ -1,7 +1,9 @@
 //! Writing the print layout out as a PDF.
 //!
 //! Each sheet becomes one page carrying a single full-page image: the cutting
-//! viewport's own render of that sheet, cut lines and folds included.
+//! viewport's own render of that sheet, cut lines and folds included. Pages of
+//! plain text, such as the assembly instructions, follow them, set in one of
+//! the fonts every PDF reader carries so that none need be embedded.
 //!
 //! Rasterizing the lines rather than stroking them as PDF vector is a deliberate
 //! choice. Vector strokes would print crisper, but they cost more than they
@@ -20,20 +22,46 @@
 //! cut geometry is a separate concern, computed rather than drawn — see
 //! `docs/cut-contours.md`.
 
-use pdf_writer::{Filter, Finish, Name, Pdf, Ref, TextStr};
+use pdf_writer::{Filter, Finish, Name, Pdf, Ref, Str, TextStr};
 use pp_core::measures::Dimensions;
 
 /// Centimeters per PDF point. PDF user space is 1/72 inch by definition.
 const CM_PER_POINT: f32 = 2.54 / 72.0;
 
+/// The margin around a page of text, in centimeters
+const TEXT_MARGIN: f32 = 2.0;
+/// The size a text page's title is set in, in points
+const TITLE_SIZE: f32 = 16.0;
+/// The size a text page's lines are set in, in points
+const TEXT_SIZE: f32 = 11.0;
+/// How far apart a text page's lines are, in points
+const TEXT_LEADING: f32 = 15.0;
+/// Roughly how wide an average Helvetica character is, as a fraction of the
+/// size it is set in, which is what lines are wrapped by
+const AVERAGE_CHAR_WIDTH: f32 = 0.5;
+
 /// One sheet, ready to be written out.
 pub struct PdfPage {
     /// The sheet's own size, in centimeters.
     pub size: Dimensions<f32>,
     /// The page's name in the layout, used as its PDF page label.
     pub label: Option<String>,
+    pub body: PageBody,
+}
+
+/// What a page carries
+pub enum PageBody {
     /// The rendered sheet, covering the whole page.
-    pub raster: RasterPage,
+    Raster(RasterPage),
+    /// Lines of text set down the page under a title.
+    Text(TextPage),
+}
+
+/// A page of text, already broken into lines which fit across it. See
+/// [`text_pages`].
+pub struct TextPage {
+    pub title: String,
+    pub lines: Vec<String>,
 }
 
 /// A rendered sheet, as opaque 8-bit RGB.
@@ -78,7 +106,8 @@ impl std::fmt::Display for PdfError {
 const CATALOG_ID: Ref = Ref::new(1);
 const PAGE_TREE_ID: Ref = Ref::new(2);
 const PAGE_LABELS_ID: Ref = Ref::new(3);
-/// Ids from here on are per page: the page, its content, and its image.
+/// Ids from here on are per page: the page, its content, and its image or
+/// font.
 const FIRST_PAGE_ID: i32 = 4;
 const REFS_PER_PAGE: i32 = 3;
 
@@ -88,7 +117,7 @@ pub fn write_pdf(pages: &[PdfPage]) -> Result<Vec<u8>, PdfError> {
         return Err(PdfError::NoPages);
     }
     for (i, page) in pages.iter().enumerate() {
-        if !page.raster.is_valid() {
+        if matches!(&page.body, PageBody::Raster(raster) if !raster.is_valid()) {
             return Err(PdfError::MalformedRaster { page: i });
         }
     }
@@ -111,7 +140,7 @@ pub fn write_pdf(pages: &[PdfPage]) -> Result<Vec<u8>, PdfError> {
     for (i, page) in pages.iter().enumerate() {
         let page_id = page_ids[i];
         let content_id = Ref::new(page_id.get() + 1);
-        let image_id = Ref::new(page_id.get() + 2);
+        let resource_id = Ref::new(page_id.get() + 2);
 
         let width_pt = page.size.width / CM_PER_POINT;
         let height_pt = page.size.height / CM_PER_POINT;
@@ -122,36 +151,146 @@ pub fn write_pdf(pages: &[PdfPage]) -> Result<Vec<u8>, PdfError> {
                 .parent(PAGE_TREE_ID)
                 .media_box(pdf_writer::Rect::new(0.0, 0.0, width_pt, height_pt))
                 .contents(content_id);
-            writer.resources().x_objects().pair(Name(b"Im0"), image_id);
+            match &page.body {
+                PageBody::Raster(_) => {
+                    writer.resources().x_objects().pair(Name(b"Im0"), resource_id);
+                }
+                PageBody::Text(_) => {
+                    writer.resources().fonts().pair(Name(b"F0"), resource_id);
+                }
+            }
             writer.finish();
         }
 
-        // The image fills the sheet, placed in untransformed PDF space so it
-        // lands the right way up.
-        let mut content = pdf_writer::Content::new();
-        content.save_state();
-        content.transform([width_pt, 0.0, 0.0, height_pt, 0.0, 0.0]);
-        content.x_object(Name(b"Im0"));
-        content.restore_state();
-        pdf.stream(content_id, &content.finish());
-
-        // FlateDecode rather than DCTDecode: the page is flat-shaded regions,
-        // hard texture edges and thin lines, all of which JPEG rings around, and
-        // lossless costs little on the kind of image a papercraft page is.
-        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&page.raster.rgb, 7);
-        let mut image = pdf.image_xobject(image_id, &compressed);
-        image
-            .width(page.raster.width as i32)
-            .height(page.raster.height as i32)
-            .color_space()
-            .device_rgb();
-        image.bits_per_component(8).filter(Filter::FlateDecode);
-        image.finish();
+        match &page.body {
+            PageBody::Raster(raster) => {
+                write_raster(&mut pdf, content_id, resource_id, raster, width_pt, height_pt)
+            }
+            PageBody::Text(text) => write_text(&mut pdf, content_id, resource_id, text, height_pt),
+        }
     }
 
     Ok(pdf.finish())
 }
 
+/// Writes a sheet's render, filling the page, as its content and image
+fn write_raster(
+    pdf: &mut Pdf,
+    content_id: Ref,
+    image_id: Ref,
+    raster: &RasterPage,
+    width_pt: f32,
+    height_pt: f32,
+) {
+    // The image fills the sheet, placed in untransformed PDF space so it
+    // lands the right way up.
+    let mut content = pdf_writer::Content::new();
+    content.save_state();
+    content.transform([width_pt, 0.0, 0.0, height_pt, 0.0, 0.0]);
+    content.x_object(Name(b"Im0"));
+    content.restore_state();
+    pdf.stream(content_id, &content.finish());
+
+    // FlateDecode rather than DCTDecode: the page is flat-shaded regions,
+    // hard texture edges and thin lines, all of which JPEG rings around, and
+    // lossless costs little on the kind of image a papercraft page is.
+    let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&raster.rgb, 7);
+    let mut image = pdf.image_xobject(image_id, &compressed);
+    image.width(raster.width as i32).height(raster.height as i32).color_space().device_rgb();
+    image.bits_per_component(8).filter(Filter::FlateDecode);
+    image.finish();
+}
+
+/// Writes a page of text as its content and font: the title, then each line
+/// beneath it, from the top left margin down
+fn write_text(pdf: &mut Pdf, content_id: Ref, font_id: Ref, text: &TextPage, height_pt: f32) {
+    let margin = TEXT_MARGIN / CM_PER_POINT;
+    let mut content = pdf_writer::Content::new();
+    let mut y = height_pt - margin - TITLE_SIZE;
+    let line = |content: &mut pdf_writer::Content, size: f32, y: f32, text: &str| {
+        content.begin_text();
+        content.set_font(Name(b"F0"), size);
+        content.next_line(margin, y);
+        content.show(Str(&win_ansi(text)));
+        content.end_text();
+    };
+    line(&mut content, TITLE_SIZE, y, &text.title);
+    y -= TITLE_SIZE;
+    for text in &text.lines {
+        y -= TEXT_LEADING;
+        line(&mut content, TEXT_SIZE, y, text);
+    }
+    pdf.stream(content_id, &content.finish());
+
+    // One of the standard fonts, which readers supply themselves
+    pdf.type1_font(font_id)
+        .base_font(Name(b"Helvetica"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+}
+
+/// `text` in the encoding the text font is set up with. Only ASCII is carried
+/// over as is: anything else prints as a question mark.
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| if c.is_ascii() && !c.is_ascii_control() { c as u8 } else { b'?' })
+        .collect()
+}
+
+/// `lines` of text set on pages of `size` under `title`, which repeats on each.
+/// Lines too long for the page wrap between words, with the rest indented, and
+/// as many pages follow as it takes. The first is labelled `label`, and the
+/// rest after it with a count.
+pub fn text_pages(
+    size: Dimensions<f32>,
+    label: &str,
+    title: &str,
+    lines: &[String],
+) -> Vec<PdfPage> {
+    let margin = TEXT_MARGIN / CM_PER_POINT;
+    let width = size.width / CM_PER_POINT - 2.0 * margin;
+    let height = size.height / CM_PER_POINT - 2.0 * margin - 2.0 * TITLE_SIZE;
+    let columns = ((width / (TEXT_SIZE * AVERAGE_CHAR_WIDTH)) as usize).max(8);
+    let rows = ((height / TEXT_LEADING) as usize).max(1);
+
+    let wrapped: Vec<String> = lines.iter().flat_map(|line| wrap(line, columns)).collect();
+    let chunks: Vec<&[String]> = match wrapped.is_empty() {
+        true => vec![&[]],
+        false => wrapped.chunks(rows).collect(),
+    };
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, lines)| PdfPage {
+            size,
+            label: Some(match i {
+                0 => label.to_string(),
+                _ => format!("{label} {}", i + 1),
+            }),
+            body: PageBody::Text(TextPage { title: title.to_string(), lines: lines.to_vec() }),
+        })
+        .collect()
+}
+
+/// Breaks `line` between words into lines at most `columns` characters long,
+/// indenting all but the first. A word longer than a line is left whole.
+fn wrap(line: &str, columns: usize) -> Vec<String> {
+    const INDENT: &str = "    ";
+    let mut lines = vec![String::new()];
+    for word in line.split(' ') {
+        let current = lines.last_mut().unwrap();
+        let is_start = current.is_empty() || current.as_str() == INDENT;
+        if !is_start && current.len() + 1 + word.len() > columns {
+            lines.push(format!("{INDENT}{word}"));
+        } else {
+            if !is_start {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+    }
+    lines
+}
+
 /// Names each page after the sheet it came from, when the layout named it.
 ///
 /// These are the labels the old archive spent on filenames. A viewer shows them
@@ -185,7 +324,7 @@ mod tests {
         PdfPage {
             size: Dimensions { width, height },
             label: None,
-            raster: RasterPage { width: 2, height: 2, rgb: vec![255; 2 * 2 * 3] },
+            body: PageBody::Raster(RasterPage { width: 2, height: 2, rgb: vec![255; 2 * 2 * 3] }),
         }
     }
 
@@ -217,7 +356,11 @@ mod tests {
     fn the_page_image_is_compressed() {
         let mut page = blank_page(21.0, 29.7);
         let (w, h) = (256u32, 256u32);
-        page.raster = RasterPage { width: w, height: h, rgb: vec![255; (w * h * 3) as usize] };
+        page.body = PageBody::Raster(RasterPage {
+            width: w,
+            height: h,
+            rgb: vec![255; (w * h * 3) as usize],
+        });
         let bytes = write_pdf(&[page]).unwrap();
         assert!(
             bytes.len() < (w * h * 3) as usize / 10,
@@ -252,10 +395,35 @@ mod tests {
     #[test]
     fn a_malformed_raster_is_rejected() {
         let mut page = blank_page(21.0, 29.7);
-        page.raster.rgb.truncate(5);
+        if let PageBody::Raster(raster) = &mut page.body {
+            raster.rgb.truncate(5);
+        }
         assert!(matches!(write_pdf(&[page]), Err(PdfError::MalformedRaster { page: 0 })));
     }
 
+    /// Text is set in a standard font, wrapped to the page, and carried on
+    /// over as many pages as it needs
+    #[test]
+    fn text_wraps_and_runs_onto_more_pages() {
+        let size = Dimensions { width: 21.0, height: 29.7 };
+        let long = "word ".repeat(40);
+        let pages = text_pages(size, "Assembly", "How to build it", std::slice::from_ref(&long));
+        assert_eq!(pages.len(), 1);
+        let PageBody::Text(text) = &pages[0].body else { panic!("a page of text") };
+        assert!(text.lines.len() > 1, "a long line wraps");
+        assert!(text.lines[1].starts_with("    "), "and its rest is indented");
+
+        let many: Vec<String> = (1..=200).map(|i| format!("{i}. A step")).collect();
+        let pages = text_pages(size, "Assembly", "How to build it", &many);
+        assert!(pages.len() > 1);
+        assert_eq!(pages[1].label.as_deref(), Some("Assembly 2"));
+
+        let bytes = write_pdf(&pages).unwrap();
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.contains("/Helvetica"));
+        assert!(text.contains("(200. A step)"));
+    }
+
     /// Nothing to print is an error, not an empty file a viewer would reject.
     #[test]
     fn an_empty_document_is_refused() {
@@ -1,90 +1,40 @@
 use gltf::Gltf;
+use serde_json::value::RawValue;
 use std::collections::HashMap;
 use thiserror::Error;
 
-use crate::{standard, SaveFile};
+use crate::{
+    extra::{PapercraftExtra, RootExtras},
+    migrate, standard, SaveFile,
+};
 
 /// Possible errors that can occur while saving a file
-#[derive(Debug, Clone, Copy, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum SaveError {
-    #[error("unknown data store error")]
-    Unknown,
+    #[error("texture \"{texture}\" has no image to save")]
+    TextureWithoutImage { texture: String },
+    #[error("image \"{image}\" is stored as {format}, which can't be saved as PNG")]
+    UnsupportedImageFormat { image: String, format: String },
+    #[error("image \"{image}\" could not be encoded: {reason}")]
+    ImageEncoding { image: String, reason: String },
+    #[error("the document could not be written as glTF: {0}")]
+    Json(String),
+    #[error("the document could not be written as GLB: {0}")]
+    Glb(String),
 }
 
 pub trait Saveable {
-    fn save(&self) -> anyhow::Result<SaveFile>;
+    fn save(&self) -> Result<SaveFile, SaveError>;
 }
 
 impl Saveable for pp_core::State {
-    fn save(&self) -> anyhow::Result<SaveFile> {
+    fn save(&self) -> Result<SaveFile, SaveError> {
         // We will be *building* a single large buffer with our data
         let mut gltf_builder = standard::buffers::GltfBufferBuilder::new();
 
-        // Step 1: Save images (skip default image)
-        let mut image_ids = HashMap::new();
-        let mut images = Vec::new();
-        let mut sorted_images: Vec<_> = self.images.iter().collect();
-        sorted_images.sort_by_key(|(id, _)| *id);
-        for (img_id, img) in sorted_images {
-            if img_id == self.defaults.image {
-                continue;
-            }
-            let gltf_image = standard::image::save_image(img);
-            let idx = gltf_json::Index::new(images.len() as u32);
-            images.push(gltf_image);
-            image_ids.insert(img_id, idx);
-        }
-
-        // Step 2: Save samplers (skip default sampler)
-        let mut sampler_ids = HashMap::new();
-        let mut samplers = Vec::new();
-        let mut sorted_samplers: Vec<_> = self.samplers.iter().collect();
-        sorted_samplers.sort_by_key(|(id, _)| *id);
-        for (samp_id, samp) in sorted_samplers {
-            if samp_id == self.defaults.sampler {
-                continue;
-            }
-            let gltf_sampler = standard::sampler::save_sampler(samp);
-            let idx = gltf_json::Index::new(samplers.len() as u32);
-            samplers.push(gltf_sampler);
-            sampler_ids.insert(samp_id, idx);
-        }
-
-        // Step 3: Save textures (skip default texture)
-        let mut texture_ids = HashMap::new();
-        let mut textures = Vec::new();
-        let mut sorted_textures: Vec<_> = self.textures.iter().collect();
-        sorted_textures.sort_by_key(|(id, _)| *id);
-        for (tex_id, tex) in sorted_textures {
-            if tex_id == self.defaults.texture {
-                continue;
-            }
-            let gltf_texture = gltf_json::Texture {
-                name: Some(tex.label.clone()),
-                sampler: sampler_ids.get(&tex.sampler).copied(),
-                source: image_ids.get(&tex.image).copied().unwrap(),
-                extensions: Default::default(),
-                extras: Default::default(),
-            };
-            let idx = gltf_json::Index::new(textures.len() as u32);
-            textures.push(gltf_texture);
-            texture_ids.insert(tex_id, idx);
-        }
-
-        // Step 4: Save materials (skip default material)
-        let mut material_ids = HashMap::new();
-        let mut materials = Vec::new();
-        let mut sorted_materials: Vec<_> = self.materials.iter().collect();
-        sorted_materials.sort_by_key(|(id, _)| *id);
-        for (mat_id, mat) in sorted_materials {
-            if mat_id == self.defaults.material {
-                continue;
-            }
-            let gltf_material = standard::material::save_material(mat, &texture_ids);
-            let idx = gltf_json::Index::new(materials.len() as u32);
-            materials.push(gltf_material);
-            material_ids.insert(mat_id, idx);
-        }
+        // Steps 1-4: Save images, samplers, textures and materials
+        let SavedMaterials { images, samplers, textures, materials, material_ids } =
+            save_materials(self)?;
 
         // Step 5: Save mesh geometries
         let mut meshes = Vec::new();
@@ -120,6 +70,18 @@ impl Saveable for pp_core::State {
             })
         }
 
+        // Step 8: Stamp the format version, so that later versions of the app
+        // know which migrations this file needs
+        let extras = serde_json::to_string(&RootExtras {
+            papercraft: Some(PapercraftExtra {
+                version: migrate::FORMAT_VERSION,
+                thumbnail: None,
+                pages: Default::default(),
+            }),
+        })
+        .and_then(RawValue::from_string)
+        .map_err(|err| SaveError::Json(err.to_string()))?;
+
         // Build final buffers, buffer views, and accessors
         let (buffers, buffer_views, accessors) = gltf_builder.build();
         Ok(SaveFile(Gltf {
@@ -135,9 +97,98 @@ impl Saveable for pp_core::State {
                 textures,
                 images,
                 materials,
+                extras: Some(extras),
                 ..Default::default()
-            })?,
+            })
+            .map_err(|err| SaveError::Json(err.to_string()))?,
             blob: None,
         }))
     }
 }
+
+/// Everything a mesh needs to reference its materials: the GLTF images,
+/// samplers, textures and materials of a document, plus where each of its
+/// materials ended up.
+pub(crate) struct SavedMaterials {
+    pub images: Vec<gltf_json::Image>,
+    pub samplers: Vec<gltf_json::texture::Sampler>,
+    pub textures: Vec<gltf_json::Texture>,
+    pub materials: Vec<gltf_json::Material>,
+    pub material_ids: HashMap<pp_core::MaterialId, gltf_json::Index<gltf_json::Material>>,
+}
+
+/// Saves the images, samplers, textures and materials of a document, skipping
+/// the defaults every document already has.
+pub(crate) fn save_materials(state: &pp_core::State) -> Result<SavedMaterials, SaveError> {
+    // Step 1: Save images (skip default image)
+    let mut image_ids = HashMap::new();
+    let mut images = Vec::new();
+    let mut sorted_images: Vec<_> = state.images.iter().collect();
+    sorted_images.sort_by_key(|(id, _)| *id);
+    for (img_id, img) in sorted_images {
+        if img_id == state.defaults.image {
+            continue;
+        }
+        let gltf_image = standard::image::save_image(img)?;
+        let idx = gltf_json::Index::new(images.len() as u32);
+        images.push(gltf_image);
+        image_ids.insert(img_id, idx);
+    }
+
+    // Step 2: Save samplers (skip default sampler)
+    let mut sampler_ids = HashMap::new();
+    let mut samplers = Vec::new();
+    let mut sorted_samplers: Vec<_> = state.samplers.iter().collect();
+    sorted_samplers.sort_by_key(|(id, _)| *id);
+    for (samp_id, samp) in sorted_samplers {
+        if samp_id == state.defaults.sampler {
+            continue;
+        }
+        let gltf_sampler = standard::sampler::save_sampler(samp);
+        let idx = gltf_json::Index::new(samplers.len() as u32);
+        samplers.push(gltf_sampler);
+        sampler_ids.insert(samp_id, idx);
+    }
+
+    // Step 3: Save textures (skip default texture)
+    let mut texture_ids = HashMap::new();
+    let mut textures = Vec::new();
+    let mut sorted_textures: Vec<_> = state.textures.iter().collect();
+    sorted_textures.sort_by_key(|(id, _)| *id);
+    for (tex_id, tex) in sorted_textures {
+        if tex_id == state.defaults.texture {
+            continue;
+        }
+        let source = image_ids
+            .get(&tex.image)
+            .copied()
+            .ok_or_else(|| SaveError::TextureWithoutImage { texture: tex.label.clone() })?;
+        let gltf_texture = gltf_json::Texture {
+            name: Some(tex.label.clone()),
+            sampler: sampler_ids.get(&tex.sampler).copied(),
+            source,
+            extensions: Default::default(),
+            extras: Default::default(),
+        };
+        let idx = gltf_json::Index::new(textures.len() as u32);
+        textures.push(gltf_texture);
+        texture_ids.insert(tex_id, idx);
+    }
+
+    // Step 4: Save materials (skip default material)
+    let mut material_ids = HashMap::new();
+    let mut materials = Vec::new();
+    let mut sorted_materials: Vec<_> = state.materials.iter().collect();
+    sorted_materials.sort_by_key(|(id, _)| *id);
+    for (mat_id, mat) in sorted_materials {
+        if mat_id == state.defaults.material {
+            continue;
+        }
+        let gltf_material = standard::material::save_material(mat, &texture_ids);
+        let idx = gltf_json::Index::new(materials.len() as u32);
+        materials.push(gltf_material);
+        material_ids.insert(mat_id, idx);
+    }
+
+    Ok(SavedMaterials { images, samplers, textures, materials, material_ids })
+}
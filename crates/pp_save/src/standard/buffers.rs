@@ -116,32 +116,48 @@ fn base64_encode(data: &[u8]) -> String {
 
 // ========== LOAD FUNCTIONS ==========
 
-use crate::load::LoadError;
+use crate::load::AccessorError;
 
 /// Reads typed data from a GLTF accessor
 pub fn read_accessor<T: bytemuck::Pod + Copy>(
     buffers: &[gltf::buffer::Data],
     accessor: &gltf::Accessor,
-) -> Result<Vec<T>, LoadError> {
-    let buffer_view = accessor.view().ok_or(LoadError::Unknown)?;
+) -> Result<Vec<T>, AccessorError> {
+    let elem_size = std::mem::size_of::<T>();
+    if accessor.size() != elem_size {
+        return Err(AccessorError::WrongElementSize {
+            expected: elem_size,
+            found: accessor.size(),
+        });
+    }
+    let buffer_view = accessor.view().ok_or(AccessorError::NoBufferView)?;
     let offset = buffer_view.offset() + accessor.offset();
     let count = accessor.count();
-    let stride = buffer_view.stride().unwrap_or(std::mem::size_of::<T>());
-
-    let buffer = buffers.get(buffer_view.buffer().index()).ok_or(LoadError::Unknown)?;
+    let stride = buffer_view.stride().unwrap_or(elem_size);
+
+    let buffer_index = buffer_view.buffer().index();
+    let buffer = buffers.get(buffer_index).ok_or(AccessorError::MissingBuffer(buffer_index))?;
+    // The last element only needs its own bytes, not a whole stride
+    let end = match count {
+        0 => offset,
+        _ => offset + (count - 1) * stride + elem_size,
+    };
+    if end > buffer.len() || end > buffer_view.offset() + buffer_view.length() {
+        return Err(AccessorError::OutOfBounds);
+    }
     let mut result = Vec::with_capacity(count);
 
-    if stride == std::mem::size_of::<T>() {
-        // Tightly packed, can read directly
-        let end = offset + count * std::mem::size_of::<T>();
+    if stride == elem_size {
+        // Tightly packed, can read directly. The slice isn't necessarily
+        // aligned for `T`, so copy rather than cast in place.
         let slice = &buffer[offset..end];
-        result.extend_from_slice(bytemuck::cast_slice(slice));
+        result.extend(slice.chunks_exact(elem_size).map(bytemuck::pod_read_unaligned::<T>));
     } else {
         // Strided data, need to read element by element
         for i in 0..count {
             let elem_offset = offset + i * stride;
-            let elem_bytes = &buffer[elem_offset..elem_offset + std::mem::size_of::<T>()];
-            result.push(*bytemuck::from_bytes(elem_bytes));
+            let elem_bytes = &buffer[elem_offset..elem_offset + elem_size];
+            result.push(bytemuck::pod_read_unaligned(elem_bytes));
         }
     }
 
@@ -1,101 +1,126 @@
 use image::ImageEncoder;
 use pp_core::material::image::{Format, Image};
 
+use crate::{load::LoadError, save::SaveError};
+
 /// Converts a pp_core Image to GLTF Image with embedded base64 data
-pub fn save_image(image: &Image) -> gltf_json::Image {
+pub fn save_image(image: &Image) -> Result<gltf_json::Image, SaveError> {
     use gltf_json::image;
 
     // Convert image to PNG format for embedding
-    let uri = image_to_data_uri(image);
-    image::Image {
+    let uri = image_to_data_uri(image)?;
+    Ok(image::Image {
         name: Some(image.label.clone()),
         uri: Some(uri),
         mime_type: Some(image::MimeType("image/png".to_string())),
         buffer_view: None,
         extensions: Default::default(),
         extras: Default::default(),
+    })
+}
+
+/// The MIME types `image` is built to decode here. Anything else is refused
+/// rather than swapped for a placeholder, so a texture never silently goes white.
+const SUPPORTED_MIME_TYPES: [&str; 2] = ["image/png", "image/jpeg"];
+
+/// The name an image is known by in errors and in the editor
+pub(crate) fn image_label(gltf_image: &gltf::Image, index: usize) -> String {
+    gltf_image.name().map(|s| s.to_string()).unwrap_or_else(|| format!("Image{}", index))
+}
+
+/// Checks that an image is stored somewhere and in a format we can decode,
+/// without decoding it. Used by `SaveFile::validate`, ahead of `load_image`.
+pub(crate) fn check_image(gltf_image: &gltf::Image, index: usize) -> Result<(), LoadError> {
+    let mime_type = match gltf_image.source() {
+        gltf::image::Source::View { mime_type, .. } => mime_type.to_string(),
+        gltf::image::Source::Uri { uri, mime_type } => {
+            let Some(data) = uri.strip_prefix("data:") else {
+                return Err(LoadError::UnsupportedImageSource {
+                    image: image_label(gltf_image, index),
+                    uri: uri.to_string(),
+                });
+            };
+            // `data:<mime>;base64,<payload>` carries its own type, which wins
+            // over the optional one on the image itself.
+            data.split([';', ',']).next().or(mime_type).unwrap_or_default().to_string()
+        }
+    };
+    if !SUPPORTED_MIME_TYPES.contains(&mime_type.as_str()) {
+        return Err(LoadError::UnsupportedImageFormat {
+            image: image_label(gltf_image, index),
+            mime_type,
+        });
     }
+    Ok(())
+}
+
+pub fn load_image(
+    gltf_image: &gltf::Image,
+    buffers: &[gltf::buffer::Data],
+    index: usize,
+) -> Result<Image, LoadError> {
+    check_image(gltf_image, index)?;
+    let label = image_label(gltf_image, index);
+    let undecodable = |reason: String| LoadError::UndecodableImage { image: label.clone(), reason };
+    let data = encoded_image_data(gltf_image, buffers).map_err(undecodable)?;
+    decode_image_data(&data, label.clone()).map_err(undecodable)
 }
 
-pub fn load_image(gltf_image: &gltf::Image, buffers: &[gltf::buffer::Data], index: usize) -> Image {
-    // Get the image data from the GLTF image source
-    let image_data = match gltf_image.source() {
+/// The still-encoded bytes of an image, e.g. the PNG file itself, wherever in
+/// the GLTF they're stored.
+pub(crate) fn encoded_image_data(
+    gltf_image: &gltf::Image,
+    buffers: &[gltf::buffer::Data],
+) -> Result<Vec<u8>, String> {
+    match gltf_image.source() {
         gltf::image::Source::View { view, mime_type: _ } => {
             // Image is stored in a buffer view
-            let buffer = &buffers[view.buffer().index()];
-            let start = view.offset();
-            let end = start + view.length();
-            &buffer[start..end]
+            let buffer =
+                buffers.get(view.buffer().index()).ok_or("its buffer doesn't exist".to_string())?;
+            let (start, end) = (view.offset(), view.offset() + view.length());
+            let data =
+                buffer.get(start..end).ok_or("its buffer view is out of bounds".to_string())?;
+            Ok(data.to_vec())
         }
         gltf::image::Source::Uri { uri, mime_type: _ } => {
-            // For data URIs, parse the base64 data
-            if let Some(base64_data) = uri
-                .strip_prefix("data:image/png;base64,")
-                .or_else(|| uri.strip_prefix("data:image/jpeg;base64,"))
-            {
-                // Decode base64 - we'll need to allocate here
-                match base64::Engine::decode(
-                    &base64::engine::general_purpose::STANDARD,
-                    base64_data,
-                ) {
-                    Ok(decoded) => {
-                        // We need a static reference, so we'll process immediately
-                        return decode_image_data(&decoded, gltf_image.name(), index);
-                    }
-                    Err(_) => {
-                        // Failed to decode, return placeholder
-                        return create_placeholder_image(gltf_image.name(), index);
-                    }
-                }
-            } else {
-                // External file reference - not supported yet, return placeholder
-                return create_placeholder_image(gltf_image.name(), index);
-            }
+            // `check_image` already refused anything but a data URI
+            let payload = uri
+                .split_once(";base64,")
+                .map(|(_, payload)| payload)
+                .ok_or("its data URI isn't base64".to_string())?;
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, payload)
+                .map_err(|err| err.to_string())
         }
-    };
+    }
+}
 
-    decode_image_data(image_data, gltf_image.name(), index)
+/// Embeds already-encoded PNG data as a data URI
+pub(crate) fn png_data_uri(png: &[u8]) -> String {
+    format!(
+        "data:image/png;base64,{}",
+        base64::Engine::encode(&base64::engine::general_purpose::STANDARD, png)
+    )
 }
 
-fn decode_image_data(data: &[u8], name: Option<&str>, index: usize) -> Image {
-    // Try to decode the image
-    let img = match image::load_from_memory(data) {
-        Ok(img) => img,
-        Err(_) => return create_placeholder_image(name, index),
-    };
+fn decode_image_data(data: &[u8], label: String) -> Result<Image, String> {
+    let img = image::load_from_memory(data).map_err(|err| err.to_string())?;
 
     // Convert to RGBA8 for consistency
     let rgba = img.to_rgba8();
     let (width, height) = rgba.dimensions();
 
-    Image {
-        label: name.map(|s| s.to_string()).unwrap_or_else(|| format!("Image{}", index)),
-        pixels: rgba.into_raw(),
-        width,
-        height,
-        format: Format::R8G8B8A8,
-    }
-}
-
-fn create_placeholder_image(name: Option<&str>, index: usize) -> Image {
-    // Create a 1x1 white pixel as placeholder
-    Image {
-        label: name.map(|s| s.to_string()).unwrap_or_else(|| format!("Image{}", index)),
-        pixels: vec![255u8; 4],
-        width: 1,
-        height: 1,
-        format: Format::R8G8B8A8,
-    }
+    Ok(Image { label, pixels: rgba.into_raw(), width, height, format: Format::R8G8B8A8 })
 }
 
 /// Converts image data to a data URI
-fn image_to_data_uri(image: &Image) -> String {
+fn image_to_data_uri(image: &Image) -> Result<String, SaveError> {
     use std::io::Cursor;
 
     let mut buffer = Vec::new();
     let encoder = image::codecs::png::PngEncoder::new(Cursor::new(&mut buffer));
 
-    // Convert our format to image crate's ColorType
+    // Convert our format to image crate's ColorType. PNG has no float
+    // channels, and squeezing them into 8 bits would misread the buffer.
     let color_type = match image.format {
         Format::R8 => image::ExtendedColorType::L8,
         Format::R8G8 => image::ExtendedColorType::La8,
@@ -105,17 +130,17 @@ fn image_to_data_uri(image: &Image) -> String {
         Format::R16G16 => image::ExtendedColorType::La16,
         Format::R16G16B16 => image::ExtendedColorType::Rgb16,
         Format::R16G16B16A16 => image::ExtendedColorType::Rgba16,
-        // For float formats, we'll convert to 8-bit (this is a simplification)
-        Format::R32G32B32FLOAT => image::ExtendedColorType::Rgb8,
-        Format::R32G32B32A32FLOAT => image::ExtendedColorType::Rgba8,
+        Format::R32G32B32FLOAT | Format::R32G32B32A32FLOAT => {
+            return Err(SaveError::UnsupportedImageFormat {
+                image: image.label.clone(),
+                format: format!("{:?}", image.format),
+            })
+        }
     };
 
-    encoder
-        .write_image(&image.pixels, image.width, image.height, color_type)
-        .expect("Failed to encode image");
+    encoder.write_image(&image.pixels, image.width, image.height, color_type).map_err(|err| {
+        SaveError::ImageEncoding { image: image.label.clone(), reason: err.to_string() }
+    })?;
 
-    format!(
-        "data:image/png;base64,{}",
-        base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &buffer)
-    )
+    Ok(png_data_uri(&buffer))
 }
@@ -2,17 +2,18 @@ use gltf::{buffer::Data, Accessor, Semantic};
 use ordered_float::OrderedFloat;
 use pp_core::{
     id::{self, FaceId, VertexId},
-    mesh::{cut::CutUpdate, face::FaceDescriptor, Mesh},
+    mesh::{cut::CutUpdate, face, face::FaceDescriptor, flap::FlapStyle, merge, Mesh},
     MaterialId,
 };
 use serde_json::value::RawValue;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use crate::{
     extra::{
-        self, cut::SerializableCut, piece::SerializablePiece, MeshExtras, PapercraftMeshExtra,
+        self, cut::SerializableCut, piece::SerializablePiece, polygon, MeshExtras,
+        PapercraftMeshExtra,
     },
-    load::LoadError,
+    load::{AccessorError, ImportOptions, LoadError, MeshImportReport, MeshLocation},
     standard::buffers::{AccessorOptions, GltfBufferBuilder},
 };
 
@@ -41,20 +42,25 @@ pub fn save_mesh(
     // indices by its vertex ID, necessary for identifying the verts in an edge
     let mut v_indices: HashMap<VertexId, u32> = HashMap::new();
     let mut f_indices: HashMap<FaceId, u32> = HashMap::new();
+    // Each face's corners, by GLTF index. `iter_loops` visits a face's loops
+    // consecutively, so a face's corners are a run of indices.
+    let mut face_corners: Vec<(FaceId, Vec<u32>)> = Vec::new();
 
     // GLTFs are just triangles, a lot of the time - meaning we should take a
     // per-face per-vertex approach to building our GLTF.
     for (loop_id, i) in loops.iter().zip(0u32..) {
         let loop_ = &mesh[*loop_id];
         let vertex = &mesh[loop_.v];
-        let face = &mesh[loop_.f];
 
         // Transform from OpenGL/WebGL coordinate system to GLTF coordinate system
         // Obviously, UV coordinates don't need transformation
         positions.push([vertex.po[0], vertex.po[2], -vertex.po[1]]);
         normals.push([loop_.no[0], loop_.no[2], -loop_.no[1]]);
         tex_coords.push(loop_.uv);
-        primitives_by_material.entry(face.m).or_default().push(i);
+        match face_corners.last_mut() {
+            Some((f_id, corners)) if *f_id == loop_.f => corners.push(i),
+            _ => face_corners.push((loop_.f, vec![i])),
+        }
 
         // Store the to-be indices of the current vert and face. We'll use these
         // to consistently identify edges and pieces in the GLTF.
@@ -62,6 +68,20 @@ pub fn save_mesh(
         f_indices.entry(loop_.f).or_insert(i);
     }
 
+    // Faces with more than three corners are saved as fans around their first
+    // corner, and recorded in our extras so that they're merged back on load.
+    // Other viewers will see the fan, which is only wrong for concave faces.
+    let mut polygons = Vec::new();
+    for (f_id, corners) in &face_corners {
+        let indices = primitives_by_material.entry(mesh[*f_id].m).or_default();
+        for k in 1..corners.len() - 1 {
+            indices.extend([corners[0], corners[k], corners[k + 1]]);
+        }
+        if corners.len() > 3 {
+            polygons.push([corners[0], corners.len() as u32]);
+        }
+    }
+
     // Calculate min/max bounds for positions (required by GLTF spec for position accessors)
     let (min_pos, max_pos) = if positions.is_empty() {
         ([0.0, 0.0, 0.0], [0.0, 0.0, 0.0])
@@ -152,12 +172,30 @@ pub fn save_mesh(
     // In our `extras` property, we need to indicate all the cut edges in this
     // mesh. However, edges are not a concept in GLTF-land. We instead identify
     // edges with pairs of vertices.
+    let saved_cuts: Vec<_> = mesh.cuts.iter().filter(|(_, cut)| !cut.is_dead).collect();
+    let cut_flaps = saved_cuts
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (_, cut))| {
+            Some(extra::cut::CutFlapSettings { cut: i as u32, settings: cut.flap? })
+        })
+        .collect();
+    let cut_styles = saved_cuts
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, cut))| cut.style != FlapStyle::default())
+        .map(|(i, (_, cut))| extra::cut::CutFlapStyle { cut: i as u32, style: cut.style })
+        .collect();
+    let cut_labels = saved_cuts
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (_, cut))| Some(extra::cut::CutLabel { cut: i as u32, label: cut.label? }))
+        .collect();
     let cuts = extra::cut::save_cuts(
         builder,
-        mesh.cuts
+        saved_cuts
             .iter()
-            .filter(|(_, cut)| !cut.is_dead)
-            .map(|(e_id, cut)| SerializableCut {
+            .map(|&(e_id, cut)| SerializableCut {
                 vertices: [
                     *v_indices.get(&mesh[*e_id].v[0]).unwrap(),
                     *v_indices.get(&mesh[*e_id].v[1]).unwrap(),
@@ -167,6 +205,14 @@ pub fn save_mesh(
             .collect(),
     );
 
+    // The assembly order refers to pieces by where they are saved
+    let roots: Vec<FaceId> = mesh.iter_pieces().copied().collect();
+    let assembly = mesh
+        .assembly
+        .iter()
+        .filter_map(|root| roots.iter().position(|r| r == root).map(|i| i as u32))
+        .collect();
+
     // In our `extras` property, we also need to include each of the pieces,
     // as each piece has metadata attached to it.
     let pieces = extra::piece::save_pieces(
@@ -178,6 +224,7 @@ pub fn save_mesh(
             })
             .collect(),
     );
+    let polygons = (!polygons.is_empty()).then(|| polygon::save_polygons(builder, polygons));
 
     gltf_json::mesh::Mesh {
         name: mesh.label.clone(),
@@ -188,8 +235,15 @@ pub fn save_mesh(
             papercraft: Some(PapercraftMeshExtra {
                 cuts,
                 pieces,
-                transform: Some(mesh.transform.into()),
-                scale: Some(mesh.scale),
+                polygons,
+                transform: mesh.transform.into(),
+                scale: mesh.scale,
+                flap_settings: Some(mesh.flap_settings),
+                paper_thickness: (mesh.paper_thickness > 0.0).then_some(mesh.paper_thickness),
+                cut_flaps,
+                cut_styles,
+                cut_labels,
+                assembly,
             }),
         })
         .ok()
@@ -206,17 +260,259 @@ impl From<[f32; 3]> for VertPos {
     }
 }
 
+/// Finds the vertex each position loads as, adding vertices as needed. Every
+/// position is first matched exactly; with a weld distance, it is then
+/// matched to the first vertex within that distance, found through a grid of
+/// cells as wide as the distance.
+struct VertexWelder {
+    distance: f32,
+    exact: HashMap<VertPos, id::VertexId>,
+    cells: HashMap<[i64; 3], Vec<id::VertexId>>,
+    welded: usize,
+}
+
+impl VertexWelder {
+    fn new(distance: f32) -> Self {
+        Self { distance, exact: HashMap::new(), cells: HashMap::new(), welded: 0 }
+    }
+
+    fn cell(&self, po: [f32; 3]) -> [i64; 3] {
+        po.map(|c| (c / self.distance).floor() as i64)
+    }
+
+    fn vertex(&mut self, mesh: &mut Mesh, po: [f32; 3]) -> id::VertexId {
+        if let Some(v_id) = self.exact.get(&po.into()) {
+            return *v_id;
+        }
+        if self.distance <= 0.0 {
+            let v_id = mesh.add_vertex(po);
+            self.exact.insert(po.into(), v_id);
+            return v_id;
+        }
+
+        let [x, y, z] = self.cell(po);
+        let near = (-1..=1)
+            .flat_map(|dx| {
+                (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| [x + dx, y + dy, z + dz]))
+            })
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .find(|v_id| {
+                let other = mesh[**v_id].po;
+                (0..3).map(|i| (other[i] - po[i]).powi(2)).sum::<f32>() <= self.distance.powi(2)
+            })
+            .copied();
+        let v_id = match near {
+            Some(v_id) => {
+                self.welded += 1;
+                v_id
+            }
+            None => {
+                let v_id = mesh.add_vertex(po);
+                self.cells.entry([x, y, z]).or_default().push(v_id);
+                v_id
+            }
+        };
+        self.exact.insert(po.into(), v_id);
+        v_id
+    }
+}
+
+/// Whether a face has no area to speak of, measured against its longest edge
+/// so that it doesn't matter what units the model is in
+fn is_degenerate(positions: &[[f32; 3]]) -> bool {
+    let longest_squared = positions
+        .iter()
+        .zip(positions.iter().cycle().skip(1))
+        .map(|(a, b)| (0..3).map(|i| (a[i] - b[i]).powi(2)).sum::<f32>())
+        .fold(0.0, f32::max);
+    face::polygon_area(positions) <= 1e-6 * longest_squared
+}
+
+/// The name a mesh is known by, in the editor and in load errors
+fn mesh_label(mesh: &gltf::mesh::Mesh) -> String {
+    mesh.name().map(|e| e.to_string()).unwrap_or_else(|| "ImportedMesh".to_string())
+}
+
+/// The geometry of a single primitive, read and checked: a whole number of
+/// triangles, every index in range of the positions.
+struct PrimitiveGeometry {
+    positions: Vec<[f32; 3]>,
+    indices: Vec<u32>,
+    normals: Option<Vec<[f32; 3]>>,
+    uvs: Option<Vec<[f32; 2]>>,
+}
+
+fn read_primitive(
+    primitive: &gltf::Primitive,
+    at: &MeshLocation,
+    buffers: &[Data],
+) -> Result<PrimitiveGeometry, LoadError> {
+    use crate::standard::buffers;
+    let bad_accessor = |attribute: &'static str| {
+        move |source| LoadError::BadAccessor { at: at.clone(), attribute, source }
+    };
+    if primitive.mode() != gltf::mesh::Mode::Triangles {
+        return Err(LoadError::NotTriangles {
+            at: at.clone(),
+            mode: format!("{:?}", primitive.mode()),
+        });
+    }
+    let attrs: HashMap<_, _> = primitive.attributes().collect();
+
+    // Read vertex attributes from the buffers
+    let pos_acc = attrs
+        .get(&Semantic::Positions)
+        .ok_or(LoadError::MissingAccessor { at: at.clone(), attribute: "POSITION" })?;
+    let positions =
+        buffers::read_accessor::<[f32; 3]>(buffers, pos_acc).map_err(bad_accessor("POSITION"))?;
+
+    // Read indices - GLTF supports u8, u16, or u32 indices
+    let ind_acc = primitive
+        .indices()
+        .ok_or(LoadError::MissingAccessor { at: at.clone(), attribute: "indices" })?;
+    let indices: Vec<u32> = match ind_acc.data_type() {
+        gltf::accessor::DataType::U8 => buffers::read_accessor::<u8>(buffers, &ind_acc)
+            .map_err(bad_accessor("indices"))?
+            .into_iter()
+            .map(|i| i as u32)
+            .collect(),
+        gltf::accessor::DataType::U16 => buffers::read_accessor::<u16>(buffers, &ind_acc)
+            .map_err(bad_accessor("indices"))?
+            .into_iter()
+            .map(|i| i as u32)
+            .collect(),
+        gltf::accessor::DataType::U32 => {
+            buffers::read_accessor::<u32>(buffers, &ind_acc).map_err(bad_accessor("indices"))?
+        }
+        data_type => {
+            return Err(LoadError::BadIndexType {
+                at: at.clone(),
+                data_type: format!("{:?}", data_type),
+            })
+        }
+    };
+    if !indices.len().is_multiple_of(3) {
+        return Err(LoadError::IncompleteTriangle { at: at.clone(), count: indices.len() });
+    }
+    if let Some(index) = indices.iter().copied().find(|i| *i as usize >= positions.len()) {
+        return Err(LoadError::IndexOutOfRange {
+            at: at.clone(),
+            index,
+            vertex_count: positions.len(),
+        });
+    }
+
+    // Normals and UVs are not strictly required like position / indices, but
+    // when they are there they have to cover every vertex.
+    let covers_positions = |len: usize, attribute: &'static str| {
+        if len < positions.len() {
+            Err(LoadError::BadAccessor {
+                at: at.clone(),
+                attribute,
+                source: AccessorError::OutOfBounds,
+            })
+        } else {
+            Ok(())
+        }
+    };
+    let normals = attrs
+        .get(&Semantic::Normals)
+        .map(|accessor| buffers::read_accessor::<[f32; 3]>(buffers, accessor))
+        .transpose()
+        .map_err(bad_accessor("NORMAL"))?;
+    if let Some(normals) = &normals {
+        covers_positions(normals.len(), "NORMAL")?;
+    }
+    let uvs = attrs
+        .get(&Semantic::TexCoords(0))
+        .map(|accessor| buffers::read_accessor::<[f32; 2]>(buffers, accessor))
+        .transpose()
+        .map_err(bad_accessor("TEXCOORD_0"))?;
+    if let Some(uvs) = &uvs {
+        covers_positions(uvs.len(), "TEXCOORD_0")?;
+    }
+
+    Ok(PrimitiveGeometry { positions, indices, normals, uvs })
+}
+
+/// Reads a mesh's papercraft extras, if it has any. Extras which are there
+/// but don't parse are an error rather than a mesh with no cuts: loading them
+/// as plain geometry would throw the user's work away on the next save.
+fn read_papercraft_extras(
+    mesh: &gltf::mesh::Mesh,
+) -> Result<Option<PapercraftMeshExtra>, LoadError> {
+    let Some(extras) = mesh.extras() else {
+        return Ok(None);
+    };
+    serde_json::from_str::<MeshExtras>(extras.get()).map(|extras| extras.papercraft).map_err(
+        |err| LoadError::MalformedExtras { mesh: mesh_label(mesh), reason: err.to_string() },
+    )
+}
+
+/// Reads which of a mesh's triangle fans are polygons. Files from before
+/// polygons were saved, or with only triangles, have none.
+fn load_polygon_corners(
+    extras: Option<&PapercraftMeshExtra>,
+    accessors: &[Accessor],
+    buffers: &[Data],
+    mesh: &str,
+) -> Result<HashMap<u32, u32>, LoadError> {
+    match extras.and_then(|extras| extras.polygons.as_ref()) {
+        Some(polygons) => polygon::load_polygons(accessors, buffers, polygons, mesh),
+        None => Ok(HashMap::new()),
+    }
+}
+
+/// Checks a mesh's geometry and papercraft extras without building it, so that
+/// `SaveFile::validate` can refuse a file before anything is loaded.
+pub fn validate_mesh(
+    mesh: &gltf::mesh::Mesh,
+    accessors: &[Accessor],
+    buffers: &[Data],
+) -> Result<(), LoadError> {
+    let label = mesh_label(mesh);
+    let extras = read_papercraft_extras(mesh)?;
+    let polygons = load_polygon_corners(extras.as_ref(), accessors, buffers, &label)?;
+    // Cuts and pieces index into the primitives' shared vertex space, so that
+    // is as large as the largest primitive's positions.
+    let mut vertex_count = 0;
+    let mut face_starts = HashSet::new();
+    for (i, primitive) in mesh.primitives().enumerate() {
+        let geometry = read_primitive(&primitive, &MeshLocation::primitive(&label, i), buffers)?;
+        vertex_count = vertex_count.max(geometry.positions.len());
+        let faces = polygon::group_faces(&geometry.indices, &polygons, &label)?;
+        face_starts.extend(faces.iter().map(|face| face[0]));
+    }
+
+    let Some(extras) = extras else {
+        return Ok(());
+    };
+    let cuts = extra::cut::load_cuts(accessors, buffers, &extras.cuts, &label)?;
+    extra::cut::validate_cuts(&cuts, &label, vertex_count)?;
+    let pieces = extra::piece::load_pieces(accessors, buffers, &extras.pieces, &label)?;
+    extra::piece::validate_pieces(&pieces, &label, vertex_count, &face_starts)?;
+    Ok(())
+}
+
 /// Loads a mesh into runtime memory from its GLTF representation.
 pub fn load_mesh(
     mesh: &gltf::mesh::Mesh,
     accessors: &[Accessor],
     buffers: &[Data],
     materials: &[MaterialId],
-) -> anyhow::Result<pp_core::mesh::Mesh, LoadError> {
-    let mut pp_mesh = pp_core::mesh::Mesh::new(
-        mesh.name().map(|e| e.to_string()).unwrap_or_else(|| "ImportedMesh".to_string()),
-    );
-    let mut vertices: HashMap<VertPos, id::VertexId> = HashMap::new();
+    options: &ImportOptions,
+) -> Result<(pp_core::mesh::Mesh, MeshImportReport), LoadError> {
+    let label = mesh_label(mesh);
+    let extras = read_papercraft_extras(mesh)?;
+    let polygons = load_polygon_corners(extras.as_ref(), accessors, buffers, &label)?;
+    let mut pp_mesh = pp_core::mesh::Mesh::new(label.clone());
+    let mut report = MeshImportReport { mesh: label.clone(), ..Default::default() };
+    // A mesh without our extras is a plain import, cleaned up as the options
+    // say. Our own saves load exactly, as their cuts and pieces refer to
+    // exactly the vertices and faces that were saved.
+    let plain = extras.is_none();
+    let mut vertices = VertexWelder::new(if plain { options.weld_distance } else { 0.0 });
 
     // Build mappings from GLTF buffer indices to our runtime IDs
     // Key: GLTF buffer index, Value: our vertex/face ID
@@ -224,40 +520,15 @@ pub fn load_mesh(
     let mut gltf_index_to_face_id: HashMap<u32, id::FaceId> = HashMap::new();
 
     // Primitive corresponds to a set of faces with the same material
-    for primitive in mesh.primitives() {
-        use crate::standard::buffers;
-        let attrs: HashMap<_, _> = primitive.attributes().collect();
-
-        // Read vertex attributes from the buffers
-        let pos_acc = attrs.get(&Semantic::Positions).ok_or(LoadError::Unknown)?;
-        let positions = buffers::read_accessor::<[f32; 3]>(buffers, pos_acc)?;
-
-        // Read indices - GLTF supports u8, u16, or u32 indices
-        let ind_acc = primitive.indices().ok_or(LoadError::Unknown)?;
-        let indices: Vec<u32> = match ind_acc.data_type() {
-            gltf::accessor::DataType::U8 => buffers::read_accessor::<u8>(buffers, &ind_acc)?
-                .into_iter()
-                .map(|i| i as u32)
-                .collect(),
-            gltf::accessor::DataType::U16 => buffers::read_accessor::<u16>(buffers, &ind_acc)?
-                .into_iter()
-                .map(|i| i as u32)
-                .collect(),
-            gltf::accessor::DataType::U32 => buffers::read_accessor::<u32>(buffers, &ind_acc)?,
-            _ => return Err(LoadError::Unknown),
-        };
-
-        // Normals and UVs are not strictly required like position / indices
-        let normals = attrs
-            .get(&Semantic::Normals)
-            .and_then(|accessor| buffers::read_accessor::<[f32; 3]>(buffers, accessor).ok());
-        let uvs = attrs
-            .get(&Semantic::TexCoords(0))
-            .and_then(|accessor| buffers::read_accessor::<[f32; 2]>(buffers, accessor).ok());
+    for (primitive_i, primitive) in mesh.primitives().enumerate() {
+        let PrimitiveGeometry { positions, indices, normals, uvs } =
+            read_primitive(&primitive, &MeshLocation::primitive(&label, primitive_i), buffers)?;
 
         // Transform positions from GLTF (Y-up) back to internal (Z-up) coordinate system,
         // and deduplicate the vertices on their positions within the mesh itself. This
         // allows us to reconstruct adjacencies instead of treating all tris distinctly.
+        // Plain imports are also welded within the weld distance, closing seams
+        // which an exporter left a little apart.
         // Note that we preserve the per-vertex normals / UVs by applying that data
         // to our BMesh `loops`.
         let v_ids: Vec<_> = positions
@@ -265,9 +536,7 @@ pub fn load_mesh(
             .enumerate()
             .map(|(gltf_idx, pos)| {
                 let transformed = [pos[0], -pos[2], pos[1]];
-                let v_id = *vertices
-                    .entry(transformed.into())
-                    .or_insert_with(|| pp_mesh.add_vertex(transformed));
+                let v_id = vertices.vertex(&mut pp_mesh, transformed);
 
                 // Store mapping from GLTF buffer index to vertex ID
                 gltf_index_to_vertex_id.insert(gltf_idx as u32, v_id);
@@ -275,86 +544,151 @@ pub fn load_mesh(
             })
             .collect();
 
-        // Create our adjacency map of tris from the indices by adding primitives
-        // as faces.
-        for i in (0..indices.len()).step_by(3) {
-            let idx = [indices[i] as usize, indices[i + 1] as usize, indices[i + 2] as usize];
+        // Create our adjacency map of faces from the indices, merging the
+        // triangle fans that were saved from polygons back into one face.
+        for corners in polygon::group_faces(&indices, &polygons, &label)? {
+            let idx: Vec<_> = corners.iter().map(|i| *i as usize).collect();
+            let verts: Vec<_> = idx.iter().map(|i| v_ids[*i]).collect();
+            if plain {
+                // Welding can leave a face with the same vertex twice, which
+                // can't be linked up, and a face with no area has no normal
+                let repeated = verts.iter().enumerate().any(|(k, v)| verts[..k].contains(v));
+                let positions: Vec<_> = verts.iter().map(|v| pp_mesh[*v].po).collect();
+                if repeated || (options.remove_degenerate_faces && is_degenerate(&positions)) {
+                    report.dropped_faces += 1;
+                    continue;
+                }
+            }
             let f_id = pp_mesh.add_face(
-                &[v_ids[idx[0]], v_ids[idx[1]], v_ids[idx[2]]],
+                &verts,
                 &FaceDescriptor {
                     m: primitive
                         .material()
                         .index()
                         .and_then(|mat_idx| materials.get(mat_idx).cloned()),
-                    uvs: uvs.as_ref().map(|uvs| [uvs[idx[0]], uvs[idx[1]], uvs[idx[2]]]).as_ref(),
+                    uvs: uvs
+                        .as_ref()
+                        .map(|uvs| idx.iter().map(|i| uvs[*i]).collect::<Vec<_>>())
+                        .as_deref(),
                     nos: normals
                         .as_ref()
                         .map(|no| {
-                            [
-                                [no[idx[0]][0], -no[idx[0]][2], no[idx[0]][1]],
-                                [no[idx[1]][0], -no[idx[1]][2], no[idx[1]][1]],
-                                [no[idx[2]][0], -no[idx[2]][2], no[idx[2]][1]],
-                            ]
+                            idx.iter()
+                                .map(|i| [no[*i][0], -no[*i][2], no[*i][1]])
+                                .collect::<Vec<_>>()
                         })
-                        .as_ref(),
+                        .as_deref(),
                 },
             );
 
             // Store mapping from GLTF buffer index to face ID
             // Use the first vertex's index as the face's index
-            gltf_index_to_face_id.insert(indices[i], f_id);
+            gltf_index_to_face_id.insert(corners[0], f_id);
         }
     }
 
     // Now we have our base geometry loaded in. It's time to load in all the cuts
     // and pieces contained in the mesh.
-    let Some(extras) = mesh
-        .extras()
-        .clone()
-        .and_then(|extras| serde_json::from_str::<crate::extra::MeshExtras>(extras.get()).ok())
-        .and_then(|extras| extras.papercraft)
-    else {
-        return Ok(pp_mesh);
+    let Some(extras) = extras else {
+        report.welded_verts = vertices.welded;
+        // Edges with more than two faces can't be cut, so they're split apart
+        // before anything is merged across them
+        if options.split_non_manifold {
+            report.manifold = pp_mesh.split_non_manifold();
+        }
+        // Fold directions are read off the winding, so it has to be consistent
+        // and outward before anything is unfolded
+        if options.orient_faces {
+            report.winding = pp_mesh.orient_faces();
+        }
+        if !report.is_empty() {
+            log::warn!(
+                "Cleaned up \"{label}\": welded {} vertices, dropped {} faces, split {} \
+                 non-manifold edges and {} vertices ({} couldn't be split), flipped {} faces",
+                report.welded_verts,
+                report.dropped_faces,
+                report.manifold.edges.len(),
+                report.manifold.verts.len(),
+                report.manifold.unresolved.len(),
+                report.winding.flipped,
+            );
+        }
+        // Exporters triangulate, so coplanar triangles are merged back into
+        // the polygons they were
+        if options.merge_coplanar_faces {
+            pp_mesh = pp_mesh.merge_coplanar_faces(merge::COPLANAR_ANGLE);
+        }
+        return Ok((pp_mesh, report));
     };
 
-    // 0. Restore the mesh's own transform/scale, if present (absent on save
-    // files written before these fields existed).
-    if let Some(t) = extras.transform {
-        pp_mesh.transform = cgmath::Matrix4::from(t);
-    }
-    if let Some(s) = extras.scale {
-        pp_mesh.scale = s;
-    }
+    // 0. Restore the mesh's own transform/scale
+    pp_mesh.transform = cgmath::Matrix4::from(extras.transform);
+    pp_mesh.scale = extras.scale;
+    pp_mesh.flap_settings = extras.flap_settings.unwrap_or_default();
+    pp_mesh.paper_thickness = extras.paper_thickness.unwrap_or_default();
 
     // 1. Load cuts and apply them to real edges in the model. Do *not* use our
     // internal functions which also create pieces / edges - we'll do that manually.
-    crate::extra::cut::load_cuts(accessors, buffers, extras.cuts).iter().for_each(|cut| {
-        if let (Some(&v0_id), Some(&v1_id)) = (
-            gltf_index_to_vertex_id.get(&cut.vertices[0]),
-            gltf_index_to_vertex_id.get(&cut.vertices[1]),
-        ) {
-            if let Some(e_id) = pp_mesh.query_edge(v0_id, v1_id) {
-                pp_mesh.make_cut(e_id, CutUpdate::Nothing);
-                if let Some(new_cut) = pp_mesh.cuts.get_mut(&e_id) {
-                    new_cut.flap_position = cut.flap_position;
-                }
-            };
-        };
-    });
+    let cuts = extra::cut::load_cuts(accessors, buffers, &extras.cuts, &label)?;
+    extra::cut::validate_cuts(&cuts, &label, gltf_index_to_vertex_id.len())?;
+    let mut cut_edges = Vec::with_capacity(cuts.len());
+    for (i, cut) in cuts.iter().enumerate() {
+        let e_id = gltf_index_to_vertex_id
+            .get(&cut.vertices[0])
+            .zip(gltf_index_to_vertex_id.get(&cut.vertices[1]))
+            .and_then(|(v0_id, v1_id)| pp_mesh.query_edge(*v0_id, *v1_id))
+            .ok_or_else(|| LoadError::CutEdgeMissing {
+                mesh: label.clone(),
+                cut: i,
+                vertices: cut.vertices,
+            })?;
+        pp_mesh.make_cut(e_id, CutUpdate::Nothing);
+        if let Some(new_cut) = pp_mesh.cuts.get_mut(&e_id) {
+            new_cut.flap_position = cut.flap_position;
+        }
+        cut_edges.push(e_id);
+    }
+    for cut_flap in &extras.cut_flaps {
+        if let Some(e_id) = cut_edges.get(cut_flap.cut as usize) {
+            pp_mesh.set_cut_flap_settings(*e_id, Some(cut_flap.settings));
+        }
+    }
+    for cut_style in &extras.cut_styles {
+        if let Some(e_id) = cut_edges.get(cut_style.cut as usize) {
+            pp_mesh.set_cut_flap_style(*e_id, cut_style.style);
+        }
+    }
+    for cut_label in &extras.cut_labels {
+        if let Some(e_id) = cut_edges.get(cut_label.cut as usize) {
+            pp_mesh.set_cut_label(*e_id, cut_label.label);
+        }
+    }
 
     // 2. Load pieces based on face IDs - we need to be able to consistently
     // refer to pieces such that we can load in their transforms / metadata.
-    crate::extra::piece::load_pieces(accessors, buffers, extras.pieces).iter().for_each(
-        |piece_data| {
-            if let Some(f_id) = gltf_index_to_face_id.get(&piece_data.face_index) {
-                pp_mesh.expand_piece(*f_id).unwrap();
-                if let Some(piece) = pp_mesh.pieces.get_mut(f_id) {
-                    piece.transform = piece_data.transform;
-                    piece.elem_dirty = true;
-                }
-            };
-        },
-    );
+    let pieces = extra::piece::load_pieces(accessors, buffers, &extras.pieces, &label)?;
+    for (i, piece_data) in pieces.iter().enumerate() {
+        let f_id = gltf_index_to_face_id.get(&piece_data.face_index).ok_or_else(|| {
+            LoadError::PieceFaceInvalid {
+                mesh: label.clone(),
+                piece: i,
+                face: piece_data.face_index,
+            }
+        })?;
+        pp_mesh
+            .expand_piece(*f_id)
+            .map_err(|_| LoadError::PieceHasCycle { mesh: label.clone(), piece: i })?;
+        if let Some(piece) = pp_mesh.pieces.get_mut(f_id) {
+            piece.transform = piece_data.transform;
+            piece.elem_dirty = true;
+        }
+    }
+    pp_mesh.assembly = extras
+        .assembly
+        .iter()
+        .filter_map(|i| pieces.get(*i as usize))
+        .filter_map(|piece| gltf_index_to_face_id.get(&piece.face_index).copied())
+        .collect();
 
-    Ok(pp_mesh)
+    Ok((pp_mesh, report))
 }
@@ -0,0 +1,208 @@
+//! A small rendered preview of the folded model, saved alongside it.
+//!
+//! The preview is stored as an ordinary glTF image, pointed at by the root
+//! papercraft extras (see `SaveFile::with_thumbnail`), so a document list or a
+//! file browser can show what a project is without building its mesh.
+//!
+//! Rendering happens here, on the CPU, rather than in `pp_draw`: the server has
+//! no GPU, and reading a frame back from one in the browser is an async round
+//! trip that a save shouldn't have to wait on. A few thousand flat-shaded
+//! triangles at thumbnail size are cheap enough either way. The client passes
+//! its folding viewport's camera, so the preview matches what the user last
+//! saw; the server frames the whole model from a fixed three-quarter view.
+
+use std::io::Cursor;
+
+use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Point3, Transform, Vector3, Vector4};
+use image::ImageEncoder;
+use pp_core::{
+    id::{FaceId, Id},
+    State,
+};
+
+use crate::save::SaveError;
+
+/// The width and height of a saved thumbnail, in pixels
+pub const THUMBNAIL_SIZE: u32 = 256;
+
+/// The colour of an unlit face: a warm off-white, like card stock
+const PAPER: [f32; 3] = [0.94, 0.92, 0.87];
+/// How much light a face turned away from the light still gets
+const AMBIENT: f32 = 0.35;
+
+/// A rendered preview, as 8-bit RGBA. Pixels no face covers are transparent.
+pub struct Thumbnail {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl Thumbnail {
+    /// Renders every mesh of `state`, folded, into a `size` x `size` image.
+    ///
+    /// `view_proj` takes world space to clip space, as a viewport camera's
+    /// does. Without one, the model is framed from a fixed three-quarter view.
+    pub fn render(state: &State, view_proj: Option<Matrix4<f32>>, size: u32) -> Self {
+        let view_proj = view_proj.unwrap_or_else(|| default_view_proj(state));
+        let mut rgba = vec![0; (size * size * 4) as usize];
+        let mut depth = vec![f32::INFINITY; (size * size) as usize];
+        let light = Vector3::new(0.4, -0.6, 1.0).normalize();
+
+        for mesh in state.meshes.values() {
+            for f_id in mesh.faces.indices().map(FaceId::from_usize) {
+                let corners: Vec<_> = mesh
+                    .iter_face_loops(f_id)
+                    .map(|l_id| {
+                        let p = Point3::from_vec(mesh.vert_pos(mesh[l_id].v));
+                        let world = mesh.transform.transform_point(p);
+                        view_proj * world.to_homogeneous()
+                    })
+                    .collect();
+                let normal = mesh.transform.transform_vector(Vector3::from(mesh[f_id].no));
+                // Two-sided, so an open mesh seen from behind isn't black
+                let lit = AMBIENT + (1.0 - AMBIENT) * normal.normalize().dot(light).abs();
+                let colour = PAPER.map(|c| (c * lit * 255.0).round() as u8);
+                // Faces are fans around their first corner
+                for i in 1..corners.len().saturating_sub(1) {
+                    let tri = [corners[0], corners[i], corners[i + 1]];
+                    fill_triangle(tri, colour, size, &mut rgba, &mut depth);
+                }
+            }
+        }
+
+        Self { width: size, height: size, rgba }
+    }
+
+    /// Encodes the preview as a PNG, the format it's saved in
+    pub fn to_png(&self) -> Result<Vec<u8>, SaveError> {
+        let mut buffer = Vec::new();
+        image::codecs::png::PngEncoder::new(Cursor::new(&mut buffer))
+            .write_image(&self.rgba, self.width, self.height, image::ExtendedColorType::Rgba8)
+            .map_err(|err| SaveError::ImageEncoding {
+                image: "Thumbnail".to_string(),
+                reason: err.to_string(),
+            })?;
+        Ok(buffer)
+    }
+}
+
+/// Looks at the whole document from above and in front, far enough back that
+/// its bounding sphere fits the frame.
+fn default_view_proj(state: &State) -> Matrix4<f32> {
+    let bounds = state.world_bounds();
+    let (target, radius) = match bounds.is_empty() {
+        true => (Point3::origin(), 1.0),
+        false => (Point3::from_vec(bounds.center()), bounds.bounding_radius().max(1e-3)),
+    };
+    let fovy = cgmath::Deg(30.0_f32);
+    // Distance at which a sphere of `radius` just fills a `fovy` cone
+    let distance = radius / (fovy.0.to_radians() / 2.0).sin();
+    let eye = target + Vector3::new(1.0, -1.4, 0.9).normalize() * distance;
+    let view = Matrix4::look_at_rh(eye, target, Vector3::unit_z());
+    let proj = cgmath::perspective(fovy, 1.0, distance - radius * 1.1, distance + radius * 1.1);
+    proj * view
+}
+
+/// Rasterizes one clip-space triangle into the image, keeping the nearest
+/// fragment at each pixel. Triangles with a corner behind the eye are skipped
+/// rather than clipped, which only matters for a camera inside the model.
+fn fill_triangle(
+    clip: [Vector4<f32>; 3],
+    colour: [u8; 3],
+    size: u32,
+    rgba: &mut [u8],
+    depth: &mut [f32],
+) {
+    if clip.iter().any(|c| c.w <= 1e-6) {
+        return;
+    }
+    let extent = size as f32;
+    // Normalized device coordinates to pixels, y running down the image
+    let screen = clip.map(|c| {
+        let ndc = c.truncate() / c.w;
+        Vector3::new((ndc.x + 1.0) * 0.5 * extent, (1.0 - ndc.y) * 0.5 * extent, ndc.z)
+    });
+    let edge = |a: Vector3<f32>, b: Vector3<f32>, x: f32, y: f32| {
+        (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x)
+    };
+    let area = edge(screen[0], screen[1], screen[2].x, screen[2].y);
+    if area.abs() < f32::EPSILON {
+        return;
+    }
+
+    let min_x = screen.iter().map(|p| p.x).fold(f32::INFINITY, f32::min).max(0.0) as u32;
+    let min_y = screen.iter().map(|p| p.y).fold(f32::INFINITY, f32::min).max(0.0) as u32;
+    let max_x = screen.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max).min(extent - 1.0);
+    let max_y = screen.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max).min(extent - 1.0);
+    if max_x < 0.0 || max_y < 0.0 {
+        return;
+    }
+
+    for y in min_y..=max_y as u32 {
+        for x in min_x..=max_x as u32 {
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+            // Barycentric weights, signed by the triangle's winding so that
+            // faces are drawn whichever way round they project
+            let w0 = edge(screen[1], screen[2], px, py) / area;
+            let w1 = edge(screen[2], screen[0], px, py) / area;
+            let w2 = 1.0 - w0 - w1;
+            if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                continue;
+            }
+            let z = w0 * screen[0].z + w1 * screen[1].z + w2 * screen[2].z;
+            let i = (y * size + x) as usize;
+            if z >= depth[i] || !(-1.0..=1.0).contains(&z) {
+                continue;
+            }
+            depth[i] = z;
+            rgba[i * 4..i * 4 + 4].copy_from_slice(&[colour[0], colour[1], colour[2], 255]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coverage(thumbnail: &Thumbnail) -> usize {
+        thumbnail.rgba.chunks(4).filter(|px| px[3] == 255).count()
+    }
+
+    #[test]
+    fn an_empty_document_renders_transparent() {
+        let thumbnail = Thumbnail::render(&State::default(), None, 32);
+        assert_eq!(thumbnail.rgba.len(), 32 * 32 * 4);
+        assert_eq!(coverage(&thumbnail), 0);
+    }
+
+    /// The default view frames the model, so it should land in the middle of
+    /// the image and leave the corners empty.
+    #[test]
+    fn the_default_view_frames_the_model() {
+        let thumbnail = Thumbnail::render(&State::with_cube(), None, 64);
+        let covered = coverage(&thumbnail);
+        assert!(covered > 64 * 64 / 10, "the cube should fill a good part of the frame");
+        let centre = ((32 * 64 + 32) * 4) as usize;
+        assert_eq!(thumbnail.rgba[centre + 3], 255, "the centre should be the cube");
+        assert_eq!(thumbnail.rgba[3], 0, "the corner should be background");
+    }
+
+    /// A camera looking away from the model sees nothing of it.
+    #[test]
+    fn a_given_camera_is_used() {
+        let view = Matrix4::look_at_rh(
+            Point3::new(0.0, 0.0, 10.0),
+            Point3::new(0.0, 0.0, 20.0),
+            Vector3::unit_y(),
+        );
+        let proj = cgmath::perspective(cgmath::Deg(45.0), 1.0, 0.1, 100.0);
+        let thumbnail = Thumbnail::render(&State::with_cube(), Some(proj * view), 32);
+        assert_eq!(coverage(&thumbnail), 0);
+    }
+
+    #[test]
+    fn thumbnails_encode_as_png() {
+        let png = Thumbnail::render(&State::with_cube(), None, 16).to_png().unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
@@ -3,15 +3,18 @@ pub mod store;
 
 use axum::{
     extract::{
-        ws::{WebSocket, WebSocketUpgrade},
+        ws::{Message, WebSocket, WebSocketUpgrade},
         Path, State,
     },
+    http::{header, StatusCode},
     response::IntoResponse,
     routing::get,
     Router,
 };
+use pp_protocol::ServerMessage;
+use pp_save::SaveFile;
 use session::DocumentSession;
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{collections::HashMap, io::Cursor, sync::Arc, time::Duration};
 use store::DocumentStore;
 use tokio::sync::RwLock;
 use tower_http::cors::CorsLayer;
@@ -48,6 +51,7 @@ impl Server {
         let app = Router::new()
             .route("/health", get(health_check))
             .route("/documents/{doc_id}", get(websocket_handler))
+            .route("/documents/{doc_id}/thumbnail", get(thumbnail_handler))
             .layer(CorsLayer::permissive())
             .with_state(Arc::clone(&server));
 
@@ -83,6 +87,29 @@ async fn health_check() -> &'static str {
     "OK"
 }
 
+/// Serves the PNG preview saved with a document, so a document list can show
+/// what a project is without opening a session on it
+async fn thumbnail_handler(
+    Path(doc_id): Path<String>,
+    State(server): State<Arc<Server>>,
+) -> impl IntoResponse {
+    let thumbnail = async {
+        if !server.store.exists(&doc_id).await? {
+            return anyhow::Ok(None);
+        }
+        let bytes = server.store.load(&doc_id).await?;
+        Ok(SaveFile::read_thumbnail(Cursor::new(bytes))?)
+    };
+    match thumbnail.await {
+        Ok(Some(png)) => ([(header::CONTENT_TYPE, "image/png")], png).into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!("Failed to read thumbnail of {}: {:?}", doc_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
 /// WebSocket handler
 async fn websocket_handler(
     ws: WebSocketUpgrade,
@@ -102,6 +129,12 @@ async fn handle_socket(socket: WebSocket, doc_id: String, server: Arc<Server>) {
         Ok(session) => session,
         Err(e) => {
             tracing::error!("Failed to get or create session: {:?}", e);
+            // Tell the client why, so it can show more than a dropped connection
+            let reason = format!("{:#}", e);
+            if let Ok(msg) = serde_json::to_string(&ServerMessage::Refused { doc_id, reason }) {
+                let mut socket = socket;
+                let _ = socket.send(Message::Text(msg.into())).await;
+            }
             return;
         }
     };
@@ -1,5 +1,5 @@
 use crate::store::DocumentStore;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use axum::extract::ws::{Message, Utf8Bytes, WebSocket};
 use futures::{stream::StreamExt, SinkExt};
 use pp_protocol::{ClientMessage, ServerMessage};
@@ -11,6 +11,7 @@ use tracing::{error, info, warn};
 
 use pp_core::{Command, State};
 use pp_save::save::Saveable;
+use pp_save::thumbnail::{Thumbnail, THUMBNAIL_SIZE};
 use pp_save::{load::Loadable, SaveFile};
 
 #[derive(Debug, Copy, Clone, Error)]
@@ -45,8 +46,10 @@ impl DocumentSession {
         let state = if store.exists(&doc_id).await? {
             info!("Loading existing document: {}", doc_id);
             let bytes = store.load(&doc_id).await?;
-            let save_file = SaveFile::from_reader(Cursor::new(bytes))?;
-            State::load(save_file)?
+            let save_file = SaveFile::from_reader(Cursor::new(bytes))
+                .with_context(|| format!("Document {} is not a valid save file", doc_id))?;
+            State::load(save_file)
+                .with_context(|| format!("Document {} could not be loaded", doc_id))?
         } else {
             info!("Creating new document: {}", doc_id);
             State::default()
@@ -255,7 +258,9 @@ impl DocumentSession {
     /// Persist the current state to storage
     pub async fn persist(&self) -> Result<()> {
         let state = self.state.read().await;
-        let save_file = state.save()?;
+        // No camera on the server, so the thumbnail frames the whole model
+        let thumbnail = Thumbnail::render(&state, None, THUMBNAIL_SIZE).to_png()?;
+        let save_file = state.save()?.with_thumbnail(&thumbnail)?;
         let bytes = save_file.to_binary()?;
         // self.store.save(&self.doc_id, &bytes).await?;
         // info!("Persisted document {} ({} bytes)", self.doc_id, bytes.len());
@@ -0,0 +1,92 @@
+import { useEffect, useRef, useState } from "react";
+
+import { Button } from "@/components/ui/button";
+import { Slider } from "@/components/ui/slider";
+import { useEditor } from "@/contexts/EditorContext";
+import { useEngine } from "@/contexts/EngineContext";
+
+// Scrubbing snaps to tenths of a step: fine enough to pause mid-fold.
+const SCRUB_STEP = 0.1;
+
+export default function FoldTimeline() {
+  const engine = useEngine();
+  const editor = useEditor();
+  const timeline = editor?.state.timeline;
+  const isOpen = !!timeline;
+  const isPlaying = !!timeline?.is_playing;
+
+  const [position, setPosition] = useState(0);
+  const [steps, setSteps] = useState(0);
+  const lastKey = useRef<string>("");
+
+  // Playback doesn't send editor snapshots every frame, so the playhead (and
+  // the step count, which recutting changes) is polled while the timeline is
+  // open.
+  useEffect(() => {
+    if (!engine || !isOpen) return;
+    let raf: number;
+    const tick = () => {
+      try {
+        const nextPosition = engine.get_timeline_position() ?? 0;
+        const nextSteps = engine.get_assembly_step_count();
+        const key = `${nextPosition}|${nextSteps}`;
+        if (key !== lastKey.current) {
+          lastKey.current = key;
+          setPosition(nextPosition);
+          setSteps(nextSteps);
+        }
+      } catch {
+        // engine not fully attached yet; ignore, retry next frame
+      }
+      raf = requestAnimationFrame(tick);
+    };
+    raf = requestAnimationFrame(tick);
+    return () => cancelAnimationFrame(raf);
+  }, [engine, isOpen]);
+
+  const handlePlayPause = () => {
+    if (!engine) return;
+    if (isPlaying) engine.pause_timeline();
+    else engine.play_timeline();
+  };
+
+  return (
+    <div
+      className="fixed bottom-4 left-4 z-50 flex items-center gap-3 px-3 py-2 bg-card border shadow-lg text-xs text-muted-foreground tabular-nums"
+      aria-label="Fold Timeline"
+    >
+      <Button
+        size="sm"
+        variant="outline"
+        onClick={handlePlayPause}
+        disabled={!engine}
+      >
+        {isPlaying ? "Pause" : "Play folding"}
+      </Button>
+      {isOpen && (
+        <>
+          <Slider
+            className="w-48"
+            min={0}
+            max={Math.max(steps, SCRUB_STEP)}
+            step={SCRUB_STEP}
+            value={[position]}
+            onValueChange={([value]) => engine?.seek_timeline(value)}
+            aria-label="Fold progress"
+          />
+          <span>
+            {Math.min(Math.floor(position) + 1, steps)} / {steps}
+          </span>
+          <Button
+            size="sm"
+            variant="ghost"
+            onClick={() => engine?.close_timeline()}
+            aria-label="Close timeline"
+          >
+            ×
+          </Button>
+        </>
+      )}
+    </div>
+  );
+}
@@ -110,6 +110,28 @@ export default function HelpButton() {
                   <Kbd>Scroll</Kbd>
                 </KbdGroup>
               </div>
+              <div className="flex items-center justify-between">
+                <span className="text-muted-foreground">
+                  Step through assembly
+                </span>
+                <KbdGroup>
+                  <Kbd>[</Kbd>
+                  <span className="text-muted-foreground">/</span>
+                  <Kbd>]</Kbd>
+                </KbdGroup>
+              </div>
+              <div className="flex items-center justify-between">
+                <span className="text-muted-foreground">
+                  Reorder selected pieces
+                </span>
+                <KbdGroup>
+                  <Kbd>⌥</Kbd>
+                  <span className="text-muted-foreground">+</span>
+                  <Kbd>[</Kbd>
+                  <span className="text-muted-foreground">/</span>
+                  <Kbd>]</Kbd>
+                </KbdGroup>
+              </div>
             </div>
           </div>
         </PopoverContent>
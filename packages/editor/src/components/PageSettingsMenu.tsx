@@ -32,13 +32,26 @@ function dimensionsOf(size: PageSize) {
 }
 
 /// The numeric fields, keyed by the part of `PrintLayoutSettings` they edit.
-type Field = "width" | "height" | "marginX" | "marginY";
+type Field =
+  | "width"
+  | "height"
+  | "marginX"
+  | "marginY"
+  | "bleed"
+  | "thickness";
 
 const NUMERIC_INPUT_CLASS =
   "w-full rounded-none border bg-background px-1.5 py-1 text-xs tabular-nums focus:outline-none focus:ring-1 focus:ring-ring";
 
+/// The bleed and the paper's thickness are edited in millimeters, the unit
+/// printers and paper makers quote them in, but stored in centimeters like
+/// every other length in the document.
+const MM_PER_CM = 10;
+
 /// Settings for the 2D (cutting / printing) side of the document: whether fold
-/// lines are drawn, and the size and margins of the pages the pieces lay out on.
+/// lines are drawn, the size and margins of the pages the pieces lay out on,
+/// how the pieces' artwork is printed past their edges, and how thick the paper
+/// is that unfolding compensates for.
 ///
 /// Fold lines are an editor preference, so they read off the pushed editor
 /// snapshot. The page layout lives on the Rust document state instead, which
@@ -50,11 +63,14 @@ export default function PageSettingsMenu() {
 
   const [open, setOpen] = useState(false);
   const [layout, setLayout] = useState<PrintLayoutSettings | null>(null);
+  const [thickness, setThickness] = useState(0);
   const [drafts, setDrafts] = useState<Record<Field, string>>({
     width: "",
     height: "",
     marginX: "",
     marginY: "",
+    bleed: "",
+    thickness: "",
   });
   // Which field the user is actively typing in, so the poll below doesn't
   // clobber an in-progress edit.
@@ -67,16 +83,20 @@ export default function PageSettingsMenu() {
     const tick = () => {
       try {
         const next = engine.get_print_layout();
+        const nextThickness = engine.get_paper_thickness();
         const dims = dimensionsOf(next.page_size);
-        const key = `${kindOf(next.page_size)}|${dims.width}|${dims.height}|${next.margin_x}|${next.margin_y}`;
+        const key = `${kindOf(next.page_size)}|${dims.width}|${dims.height}|${next.margin_x}|${next.margin_y}|${next.textured_flaps}|${next.bleed}|${nextThickness}`;
         if (key !== lastKey.current) {
           lastKey.current = key;
           setLayout(next);
+          setThickness(nextThickness);
           const values: Record<Field, number> = {
             width: dims.width,
             height: dims.height,
             marginX: next.margin_x,
             marginY: next.margin_y,
+            bleed: next.bleed * MM_PER_CM,
+            thickness: nextThickness * MM_PER_CM,
           };
           setDrafts((prev) => {
             const updated = { ...prev };
@@ -130,17 +150,25 @@ export default function PageSettingsMenu() {
       height: dims.height,
       marginX: layout.margin_x,
       marginY: layout.margin_y,
+      bleed: layout.bleed * MM_PER_CM,
+      thickness: thickness * MM_PER_CM,
     };
-    // Page dimensions must be positive; a margin of zero is perfectly valid.
+    // Page dimensions must be positive; a margin, bleed or thickness of zero is
+    // perfectly valid.
     const min =
-      field === "marginX" || field === "marginY" ? 0 : Number.MIN_VALUE;
+      field === "width" || field === "height" ? Number.MIN_VALUE : 0;
     if (!Number.isFinite(value) || value < min) {
       setDrafts((d) => ({ ...d, [field]: current[field].toFixed(2) }));
       return;
     }
     if (Math.abs(value - current[field]) < 1e-6) return;
 
-    if (field === "marginX" || field === "marginY") {
+    if (field === "thickness") {
+      // A document setting rather than a print one, with its own command
+      engine?.set_paper_thickness(value / MM_PER_CM);
+    } else if (field === "bleed") {
+      apply({ ...layout, bleed: value / MM_PER_CM });
+    } else if (field === "marginX" || field === "marginY") {
       apply({
         ...layout,
         [field === "marginX" ? "margin_x" : "margin_y"]: value,
@@ -213,6 +241,20 @@ export default function PageSettingsMenu() {
                 onCheckedChange={(checked) => engine?.set_fold_lines(checked)}
               />
             </label>
+            <label
+              htmlFor="page-settings-textured-tabs"
+              className="flex items-center justify-between cursor-pointer"
+            >
+              <span className="text-muted-foreground">Textured tabs</span>
+              <Switch
+                id="page-settings-textured-tabs"
+                checked={layout?.textured_flaps ?? false}
+                disabled={!layout}
+                onCheckedChange={(checked) =>
+                  layout && apply({ ...layout, textured_flaps: checked })
+                }
+              />
+            </label>
             <div>
               <span className="text-muted-foreground">Page size</span>
               <div
@@ -247,6 +289,18 @@ export default function PageSettingsMenu() {
                 {numericInput("marginY", "y")}
               </div>
             </div>
+            <div>
+              <span className="text-muted-foreground">Bleed (mm)</span>
+              <div className="mt-2 grid grid-cols-2 gap-1.5">
+                {numericInput("bleed", "past cut edges")}
+              </div>
+            </div>
+            <div>
+              <span className="text-muted-foreground">Paper (mm)</span>
+              <div className="mt-2 grid grid-cols-2 gap-1.5">
+                {numericInput("thickness", "thickness")}
+              </div>
+            </div>
           </div>
         </div>
       </PopoverContent>
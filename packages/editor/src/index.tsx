@@ -3,6 +3,7 @@ import { useMemo } from "react";
 
 import BoundsPanel from "./components/BoundsPanel";
 import Viewport from "./components/Editor";
+import FoldTimeline from "./components/FoldTimeline";
 import HelpButton from "./components/HelpButton";
 import MainControls from "./components/MainControls";
 import { EditorProvider } from "./contexts/EditorContext";
@@ -23,6 +24,7 @@ export function Engine() {
                 <MainControls />
               </div>
               <HelpButton />
+              <FoldTimeline />
               <BoundsPanel />
             </section>
           </EditorProvider>